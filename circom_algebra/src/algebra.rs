@@ -1269,6 +1269,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
         raw_substitution(&mut constraint.a, substitution, field);
         raw_substitution(&mut constraint.b, substitution, field);
         raw_substitution(&mut constraint.c, substitution, field);
+        Constraint::remove_zero_value_coefficients(constraint);
         //Constraint::fix_constraint(constraint, field);
     }
 
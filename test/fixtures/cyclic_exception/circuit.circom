@@ -0,0 +1,20 @@
+pragma circom 2.0.0;
+
+// Compiled with -p 11 to keep the field small. The === constraints on (a1, a2) and on (b1, b2) are
+//  each assigned from the other one through <==, so neither can be solved first.
+template Cyclic() {
+    signal input in;
+    signal output out;
+    signal a1;
+    signal a2;
+    signal b1;
+    signal b2;
+
+    a1 * a1 === a2;
+    b1 * b1 === b2;
+    a1 <== b2 * in;
+    b1 <== a2 * in;
+    out <== a1 + b1;
+}
+
+component main = Cyclic();
@@ -0,0 +1,14 @@
+pragma circom 2.0.0;
+
+// Compiled with -p 11 to keep the field small. The input b is constrained to be binary, but the
+//  constraint using it to compute out has been forgotten, so b does not influence the output.
+template ForgottenInput() {
+    signal input a;
+    signal input b;
+    signal output out;
+
+    b * (b - 1) === 0;
+    out <== a;
+}
+
+component main = ForgottenInput();
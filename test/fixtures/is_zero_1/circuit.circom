@@ -0,0 +1,19 @@
+pragma circom 2.0.0;
+
+// Compiled with -p 11 to keep the field small. IsZero gadget applied to a square root x of the
+//  input, which is not unique. The === constraint x*out === 0 is assigned through <== from the
+//  hint inv, which is only constrained by that <==, so it looks like a cyclic dependency. Still,
+//  out is uniquely determined by a.
+template IsZeroOfRoot() {
+    signal input a;
+    signal output out;
+    signal x;
+    signal inv;
+
+    x * x === a;
+    inv <-- x != 0 ? 1 / x : 0;
+    out <== -x * inv + 1;
+    x * out === 0;
+}
+
+component main = IsZeroOfRoot();
@@ -0,0 +1,16 @@
+pragma circom 2.0.0;
+
+// Compiled with -p 11 to keep the field small. The only binary decomposition of in = 3 with two bits
+//  is 1 + 2*1, so the outputs are fixed.
+template Num2Bits2() {
+    signal input in;
+    signal output out[2];
+
+    out[0] <-- in & 1;
+    out[1] <-- (in >> 1) & 1;
+    out[0] * (out[0] - 1) === 0;
+    out[1] * (out[1] - 1) === 0;
+    out[0] + 2*out[1] === in;
+}
+
+component main = Num2Bits2();
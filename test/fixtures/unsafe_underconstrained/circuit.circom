@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+// Compiled with -p 11 to keep the field small. out[0] is not restricted to be a bit, so in = 3 is
+//  both 1 + 2*1 and 3 + 2*0, and the outputs are not fixed.
+template Num2Bits2() {
+    signal input in;
+    signal output out[2];
+
+    out[0] <-- in & 1;
+    out[1] <-- (in >> 1) & 1;
+    out[1] * (out[1] - 1) === 0;
+    out[0] + 2*out[1] === in;
+}
+
+component main = Num2Bits2();
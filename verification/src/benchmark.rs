@@ -0,0 +1,299 @@
+use crate::cli::Options;
+use crate::error::{VerifierError, VerifierResult};
+use crate::output_format::AnalysisOutput;
+use crate::polynomial_system_fixer::{get_pol_system_stats, OptimizationPass, PolSystemStats};
+use crate::verifier::{
+    collect_polynomial_systems, solve_polynomial_systems, PolynomialSystemFixedSignal,
+};
+use crate::{InputDataContext, InputDataContextView};
+use colored::Colorize;
+use itertools::Itertools;
+use serde::Serialize;
+use std::path::PathBuf;
+use which::which;
+
+// Set of optimization passes compared in the benchmark, applied in the given order
+#[derive(Clone, Debug)]
+pub struct BenchmarkConfiguration {
+    pub name: String,
+    pub passes: Vec<OptimizationPass>,
+}
+
+// Configurations of the benchmark matrix: no passes, all of them, each pass alone and all passes
+//  but one. They are derived from OptimizationPass::ALL, so new passes are benchmarked as soon as
+//  they are added there
+pub fn get_benchmark_configurations() -> Vec<BenchmarkConfiguration> {
+    let mut configurations = vec![
+        BenchmarkConfiguration {
+            name: "none".to_string(),
+            passes: vec![],
+        },
+        BenchmarkConfiguration {
+            name: "all".to_string(),
+            passes: OptimizationPass::ALL.to_vec(),
+        },
+    ];
+
+    for pass in OptimizationPass::ALL {
+        configurations.push(BenchmarkConfiguration {
+            name: format!("only-{}", pass.name()),
+            passes: vec![pass],
+        });
+    }
+    for pass in OptimizationPass::ALL {
+        configurations.push(BenchmarkConfiguration {
+            name: format!("no-{}", pass.name()),
+            passes: OptimizationPass::ALL
+                .into_iter()
+                .filter(|other| *other != pass)
+                .collect(),
+        });
+    }
+
+    configurations
+}
+
+// Size of the polynomial systems of a circuit before and after applying the passes of a
+//  configuration, added over all of them
+#[derive(Serialize, Debug)]
+pub struct BenchmarkEntry {
+    pub circuit: String,
+    pub configuration: String,
+    pub passes: Vec<&'static str>,
+    pub num_systems: usize,
+    pub constraints_before: usize,
+    pub signals_before: usize,
+    pub constraints_after: usize,
+    pub signals_after: usize,
+    pub prohibition_vars: u32,
+
+    // Time CoCoA has spent on the systems, if they have been solved
+    pub solve_seconds: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct BenchmarkResults {
+    pub entries: Vec<BenchmarkEntry>,
+}
+
+// Benchmarks the optimization passes on the polynomial systems of the circuit in each artifacts
+//  folder. If solve is true and a solver is available (CoCoA or the one given in the options), the
+//  systems are also solved with every configuration to time them. Circuits whose traversal fails are skipped with a warning
+pub fn benchmark_optimization_passes(
+    folders: &[PathBuf],
+    options: &Options,
+    solve: bool,
+) -> VerifierResult<BenchmarkResults> {
+    let solve = solve && {
+        let cocoa_found = options.solver_cmd.is_some() || which("CoCoAInterpreter").is_ok();
+        if !cocoa_found {
+            println!(
+                "{}",
+                "[Warning] CoCoA not found, the polynomial systems will not be solved".yellow()
+            );
+        }
+        cocoa_found
+    };
+
+    let mut entries = Vec::new();
+    for folder in folders {
+        let (context, mut constraint_storage) =
+            InputDataContext::parse_from_files(folder, options.clone())?;
+        let context = context.get_context_view();
+
+        let pol_systems = match collect_polynomial_systems(&context, &mut constraint_storage) {
+            Ok(pol_systems) => pol_systems,
+            Err(VerifierError::TraversalFailed(e)) => {
+                println!(
+                    "{}",
+                    format!("[Warning] Skipping '{}': {}", folder.display(), e).yellow()
+                );
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        entries.extend(benchmark_pol_systems(
+            &folder.display().to_string(),
+            &pol_systems,
+            &context,
+            solve,
+        )?);
+    }
+
+    Ok(BenchmarkResults { entries })
+}
+
+// Returns one entry per configuration of the benchmark matrix for the given polynomial systems
+pub fn benchmark_pol_systems(
+    circuit: &str,
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    solve: bool,
+) -> VerifierResult<Vec<BenchmarkEntry>> {
+    let get_total_stats = |context: &InputDataContextView| -> PolSystemStats {
+        pol_systems
+            .iter()
+            .map(|pol_system| get_pol_system_stats(pol_system, context))
+            .fold(
+                PolSystemStats {
+                    num_constraints: 0,
+                    num_signals: 0,
+                    num_prohibition_vars: 0,
+                },
+                |total, stats| PolSystemStats {
+                    num_constraints: total.num_constraints + stats.num_constraints,
+                    num_signals: total.num_signals + stats.num_signals,
+                    num_prohibition_vars: total.num_prohibition_vars + stats.num_prohibition_vars,
+                },
+            )
+    };
+
+    let mut entries = Vec::new();
+    let mut stats_before = None;
+    for configuration in get_benchmark_configurations() {
+        let options = Options {
+            optimization_passes: configuration.passes.clone(),
+            ..context.options.clone()
+        };
+        let configuration_context = InputDataContextView {
+            options: &options,
+            field: context.field.clone(),
+            ..*context
+        };
+
+        let stats = get_total_stats(&configuration_context);
+        let (constraints_before, signals_before) =
+            *stats_before.get_or_insert((stats.num_constraints, stats.num_signals));
+
+        let solve_seconds = if solve && !pol_systems.is_empty() {
+            let results = solve_polynomial_systems(pol_systems, &configuration_context)?;
+            Some(
+                results
+                    .iter()
+                    .filter_map(|result| result.duration)
+                    .map(|duration| duration.as_secs_f64())
+                    .fold(0.0, |total, seconds| total + seconds),
+            )
+        } else {
+            None
+        };
+
+        entries.push(BenchmarkEntry {
+            circuit: circuit.to_string(),
+            configuration: configuration.name,
+            passes: configuration
+                .passes
+                .iter()
+                .map(|pass| pass.name())
+                .collect(),
+            num_systems: pol_systems.len(),
+            constraints_before,
+            signals_before,
+            constraints_after: stats.num_constraints,
+            signals_after: stats.num_signals,
+            prohibition_vars: stats.num_prohibition_vars,
+            solve_seconds,
+        });
+    }
+
+    Ok(entries)
+}
+
+impl AnalysisOutput for BenchmarkResults {
+    // One table per circuit, with a line per configuration
+    fn print_table(&self) {
+        for (circuit, entries) in &self.entries.iter().group_by(|entry| &entry.circuit) {
+            let entries: Vec<_> = entries.collect();
+            println!(
+                "{} ({} polynomial systems, {} constraints and {} signals before the passes):",
+                circuit,
+                entries[0].num_systems,
+                entries[0].constraints_before,
+                entries[0].signals_before
+            );
+            println!(
+                "  {:<16} {:>11} {:>8} {:>16} {:>10}",
+                "configuration", "constraints", "signals", "prohibition vars", "solve time"
+            );
+            for entry in entries {
+                println!(
+                    "  {:<16} {:>11} {:>8} {:>16} {:>10}",
+                    entry.configuration,
+                    entry.constraints_after,
+                    entry.signals_after,
+                    entry.prohibition_vars,
+                    entry
+                        .solve_seconds
+                        .map_or("-".to_string(), |seconds| format!("{:.3}s", seconds))
+                );
+            }
+        }
+    }
+
+    fn csv_header(&self) -> Vec<&'static str> {
+        vec![
+            "circuit",
+            "configuration",
+            "passes",
+            "num_systems",
+            "constraints_before",
+            "signals_before",
+            "constraints_after",
+            "signals_after",
+            "prohibition_vars",
+            "solve_seconds",
+        ]
+    }
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        self.entries
+            .iter()
+            .map(|e| {
+                vec![
+                    e.circuit.clone(),
+                    e.configuration.clone(),
+                    e.passes.join(" "),
+                    e.num_systems.to_string(),
+                    e.constraints_before.to_string(),
+                    e.signals_before.to_string(),
+                    e.constraints_after.to_string(),
+                    e.signals_after.to_string(),
+                    e.prohibition_vars.to_string(),
+                    e.solve_seconds.map_or(String::new(), |s| s.to_string()),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_benchmark_configurations() {
+    let configurations = get_benchmark_configurations();
+    assert_eq!(configurations.len(), 2 + 2 * OptimizationPass::ALL.len());
+    assert!(configurations[0].passes.is_empty());
+
+    let no_gauss = configurations
+        .iter()
+        .find(|c| c.name == "no-gauss")
+        .unwrap();
+    assert_eq!(no_gauss.passes.len(), OptimizationPass::ALL.len() - 1);
+    assert!(!no_gauss.passes.contains(&OptimizationPass::Gauss));
+}
+
+#[test]
+fn test_benchmark_optimization_passes() {
+    use std::path::Path;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let results = benchmark_optimization_passes(&[path], &Options::default(), false).unwrap();
+    assert_eq!(results.entries.len(), get_benchmark_configurations().len());
+
+    let none = &results.entries[0];
+    let all = &results.entries[1];
+    assert_eq!(none.num_systems, 1);
+    assert_eq!(none.constraints_after, none.constraints_before);
+    assert!(all.constraints_after <= none.constraints_after);
+    assert!(all.solve_seconds.is_none());
+}
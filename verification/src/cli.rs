@@ -1,8 +1,16 @@
-use clap::{arg, command, value_parser};
+use crate::output_format::OutputFormat;
+use crate::polynomial_system_fixer::{
+    BooleanProhibition, CocoaStrategy, CocoaVersionRange, MonomialOrder, OptimizationPass,
+    ProhibitionMode, SUPPORTED_COCOA_VERSIONS,
+};
+use clap::{arg, command, value_parser, Arg, ArgAction, ArgMatches, Command};
+use num_bigint_dig::BigInt;
+use std::env;
 use std::ffi::OsString;
+use std::fs;
 use std::path::PathBuf;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Options {
     // Value in seconds use as a timeout for each Cocoa Groebner basis computation
     pub groebner_cocoa_timeout_seconds: u32,
@@ -12,12 +20,262 @@ pub struct Options {
     //  in the prohibition polynomial (with large amounts of variables)
     pub max_vars_prohibition_polynomial_before_timeout: u32,
 
+    // Maximum number of signals a polynomial system may contain. Larger systems are not sent to
+    //  Cocoa and are reported as too large to verify
+    pub max_system_signals: Option<u32>,
+
+    // Maximum total degree of the constraints of a polynomial system. Systems with constraints of
+    //  higher degree are not sent to Cocoa and are reported separately
+    pub max_degree: Option<u32>,
+
+    // Maximum number of propagation steps, each fixing a signal or merging a === connected
+    //  component, when verifying a single component. It guards against propagation loops that
+    //  never converge, reporting the component as an exception once exceeded
+    pub propagation_max_iterations: u64,
+
+    // Optimization passes applied to each polynomial system before sending it to Cocoa, in order
+    pub optimization_passes: Vec<OptimizationPass>,
+
+    // How the witness is excluded from the solutions of each polynomial system sent to Cocoa
+    pub prohibition_mode: ProhibitionMode,
+
+    // How the witness is excluded from polynomial systems whose signals to fix are all boolean
+    pub boolean_prohibition: BooleanProhibition,
+
+    // How CoCoA decides whether the signals of each polynomial system are fixed
+    pub cocoa_strategy: CocoaStrategy,
+
+    // Monomial ordering of the rings of the polynomial systems sent to Cocoa
+    pub monomial_order: MonomialOrder,
+
     // Boolean that specifies whether SVG diagrams should be drawn
     pub generate_svg_diagrams: bool,
 
     // True if only the last frame of the propagation process should be converted into an SVG,
     //  for better performance
     pub generate_only_last_propagation_svg: bool,
+
+    // If true, the SVG diagrams of a component drawn one after another keep the same layout, with
+    //  the signals and constraints removed since the first one drawn as invisible, and the signals
+    //  fixed since the previous one highlighted
+    pub stable_svg_layout: bool,
+
+    // Names of templates whose components are trusted to be safe. They are treated as black boxes
+    //  whose outputs are fixed when their inputs are, and they are not verified. Each name is either
+    //  a bare template name or one with its parameters, as in the component tree. The main
+    //  component is always verified
+    pub trusted_templates: Vec<String>,
+
+    // Artifacts folders of circuits verified in previous runs. Components structurally identical
+    //  to their main component are considered safe without being verified
+    pub reference_paths: Vec<PathBuf>,
+
+    // Glob patterns, with '*' and '?' wildcards. If any is given, only components whose
+    //  fully-qualified name or template name matches one of them are verified
+    pub component_filters: Vec<String>,
+
+    // Glob patterns of component names, with '*' and '?' wildcards. The polynomial systems of
+    //  components matching them are sent to CoCoA before the rest, in the order of the patterns
+    pub priority_components: Vec<String>,
+
+    // If true, the generated CoCoA script is written to a new file with a timestamped name instead
+    //  of overwriting 'groebner.cocoa5', so scripts from previous runs are kept
+    pub keep_scripts: bool,
+
+    // If true, the subscript of each polynomial system in the CoCoA script is preceded by comments
+    //  with the witness value of each signal to fix
+    pub annotate_scripts: bool,
+
+    // If true, malformed input artifacts (such as constraints with extra terms) are rejected instead
+    //  of being read with a warning
+    pub strict: bool,
+
+    // Paths of the artifacts given individually, instead of their conventional names inside the
+    //  artifacts folder
+    pub constraints_path: Option<PathBuf>,
+    pub witness_path: Option<PathBuf>,
+    pub sym_path: Option<PathBuf>,
+    pub tree_path: Option<PathBuf>,
+    pub r1cs_path: Option<PathBuf>,
+
+    // Witnesses given after the first one. Each polynomial system is checked at every witness at
+    //  once, with a copy of its signals per witness
+    pub extra_witness_paths: Vec<PathBuf>,
+
+    // Prime of the field Z_p given explicitly. It takes precedence over the prime found in the
+    //  header of the .r1cs file and in the tree constraints
+    pub prime: Option<BigInt>,
+
+    // Maximum amount of memory in megabytes CoCoA may use. If it is exceeded, the polynomial system
+    //  being checked is reported as out of memory and CoCoA is restarted on the remaining ones
+    pub cocoa_memory_limit_mb: Option<u64>,
+
+    // If present, the whole verification of a circuit is stopped after this number of seconds,
+    //  killing CoCoA. The polynomial systems not verified by then are left unchecked, so the
+    //  verdict is partial
+    pub total_timeout_seconds: Option<u64>,
+
+    // CoCoA code prepended to every generated script, for example to load packages
+    pub cocoa_prelude: Option<String>,
+
+    // Extra command line arguments passed to the CoCoA interpreter
+    pub cocoa_args: Vec<String>,
+
+    // Versions of CoCoA accepted without a warning
+    pub allowed_cocoa_versions: CocoaVersionRange,
+
+    // If true, the verification is aborted when the version of CoCoA is not allowed or cannot be
+    //  detected, instead of warning about it
+    pub abort_on_unsupported_cocoa: bool,
+
+    // If present, shell command that checks the polynomial systems instead of CoCoA, following the
+    //  protocol described in polynomial_system_fixer::Solver
+    pub solver_cmd: Option<String>,
+
+    // If present, a JSON report with the verdict of each component is written to this path
+    pub report_path: Option<PathBuf>,
+
+    // If present, a JSON report of a previous run that the current run is compared against
+    pub baseline_report_path: Option<PathBuf>,
+
+    // If true, the run fails, as if the circuit were unsafe, when the comparison against the
+    //  baseline report finds regressions
+    pub fail_on_regression: bool,
+
+    // If present, the unsafe components, exceptions and polynomial systems that have not been
+    //  fixed are written to this path as a SARIF log, for code scanning tools
+    pub sarif_path: Option<PathBuf>,
+
+    // If present, the component hierarchy is drawn to this path as an SVG diagram, with each
+    //  component colored by its verdict
+    pub tree_svg_path: Option<PathBuf>,
+
+    // If true, the component hierarchy is printed instead of verifying the circuit. Does not
+    //  require a witness
+    pub list_components: bool,
+
+    // Format of the results of the inspection and analysis modes (the component list, the graph
+    //  metrics, the linear ranks and the redundant constraints)
+    pub output_format: OutputFormat,
+
+    // If true, metrics of the verification graph of the main component are printed instead of
+    //  verifying the circuit. Does not require a witness
+    pub graph_metrics: bool,
+
+    // If true, the rank of the linear constraints of every component is printed instead of
+    //  verifying the circuit, next to its number of non-input signals. Does not require a witness
+    pub linear_rank: bool,
+
+    // If true, the linear constraints of every component that are implied by its other
+    //  constraints are printed instead of verifying the circuit. Does not require a witness
+    pub report_redundant: bool,
+
+    // If true, the constraints involved in fixing each output of every verified component are
+    //  printed after traversing the circuit
+    pub coverage: bool,
+
+    // If true, every constraint of the circuit is also checked as a single polynomial system,
+    //  ignoring the subcomponents, to cross-check the verdict of the modular verification
+    pub monolithic: bool,
+
+    // If true, the inputs of each component that none of its outputs depends on are reported
+    pub report_dead_inputs: bool,
+
+    // If true, the witness values of the inputs and outputs of each failing component are printed
+    //  under its failure message
+    pub print_witness_for_failures: bool,
+
+    // If true, verification fails right away, without checking polynomial systems nor writing
+    //  reports, if no constraint can fix some output of the main component
+    pub require_outputs_fixed: bool,
+
+    // If present, the verification graph of the main component is written to this path in GraphML
+    //  format before propagating fixed nodes, so only the signals fixed from the start are marked
+    //  as fixed
+    pub graphml_path: Option<PathBuf>,
+
+    // If present, the verification graph of the main component is written to this path as a
+    //  Mermaid flowchart before propagating fixed nodes
+    pub mermaid_path: Option<PathBuf>,
+
+    // If true, only components that fail verification are printed and written to the report.
+    //  Components that pass are summarized as a count
+    pub only_failures: bool,
+
+    // If true, the artifacts folder is watched and the circuit is verified again every time its
+    //  constraints or witness change
+    pub watch: bool,
+
+    // If present, instead of checking weak safety, the verifier checks that the outputs of the
+    //  component with this name are determined by its inputs, for any input. Does not require a
+    //  witness
+    pub functional_uniqueness_component: Option<String>,
+
+    // Names of signals that are checked to be fixed by the inputs of the main component, instead
+    //  of its outputs. If not empty, the circuit is not verified
+    pub fix_signals: Vec<String>,
+
+    // If true, components that raise an exception (and therefore cannot be verified) are reported
+    //  as warnings, and only unsafe components make the verification fail
+    pub exceptions_as_warnings: bool,
+
+    // Maximum number of CoCoA processes running at the same time. If greater than 1, the polynomial
+    //  systems of each component are verified as soon as the component has been traversed
+    pub jobs: usize,
+
+    // If present, the ideal of each polynomial system is written to a file inside this folder, to
+    //  be checked with external tools
+    pub export_ideals_path: Option<PathBuf>,
+
+    // If present, every polynomial system is written to this file in readable form, using signal
+    //  names
+    pub dump_systems_path: Option<PathBuf>,
+
+    // If present, the exact input sent to CoCoA for every polynomial system is written to this
+    //  file as JSON, to reproduce a verdict with another Computer Algebra System
+    pub dump_cas_json_path: Option<PathBuf>,
+
+    // If true, signal names in readable polynomial systems are turned into valid identifiers for
+    //  Computer Algebra Systems, so they can be pasted into Sage, whose comments the legend uses
+    pub sanitized_names: bool,
+
+    // If true, the product A * B of each constraint in readable polynomial systems is expanded into
+    //  a sum of monomials
+    pub expand_products: bool,
+
+    // If present, this many assignments of the signals to fix other than the witness are sampled
+    //  for each polynomial system, and CoCoA checks that none of them is a second solution
+    pub sample_inputs: Option<u32>,
+
+    // If true, the signals of polynomial systems with many solutions are checked one by one, to
+    //  report which of them are not fixed and the constraints they appear in
+    pub minimize_failure: bool,
+
+    // If true, the polynomial system CoCoA is working on is not shown. It is never shown when
+    //  stdout is not a terminal
+    pub no_progress: bool,
+
+    // If true, the output of the verification of each circuit is suppressed, and only a line with
+    //  its verdict is printed, followed by a total over all the circuits
+    pub summary_only: bool,
+
+    // If true, the output of the verification is not printed, and only the exit code tells whether
+    //  the circuit is safe. Implied by summary_only, which prints its own lines instead
+    pub quiet: bool,
+
+    // If true, polynomial systems whose verdict was recorded by a previous interrupted run on the
+    //  same circuit and witness are not verified again
+    pub resume: bool,
+
+    // If true, components recorded as safe in the verified components file of a previous run are
+    //  not verified again unless their constraints change, and the components found safe are
+    //  added to it
+    pub skip_verified: bool,
+
+    // If present, generated files (CoCoA scripts, SVG diagrams, the progress file, the verified
+    //  components file and reports given with a relative path) are written to this folder, created
+    //  if needed, instead of the artifacts folder
+    pub out_dir: Option<PathBuf>,
 }
 
 impl Default for Options {
@@ -25,18 +283,329 @@ impl Default for Options {
         Options {
             groebner_cocoa_timeout_seconds: 5,
             max_vars_prohibition_polynomial_before_timeout: 75,
+            max_system_signals: None,
+            max_degree: None,
+            propagation_max_iterations: 10_000_000,
+            optimization_passes: OptimizationPass::ALL.to_vec(),
+            prohibition_mode: ProhibitionMode::UVar,
+            boolean_prohibition: BooleanProhibition::Product,
+            cocoa_strategy: CocoaStrategy::GBasis,
+            monomial_order: MonomialOrder::DegRevLex,
             generate_svg_diagrams: false,
             generate_only_last_propagation_svg: false,
+            stable_svg_layout: false,
+            trusted_templates: vec![],
+            reference_paths: vec![],
+            component_filters: vec![],
+            priority_components: vec![],
+            keep_scripts: false,
+            annotate_scripts: false,
+            strict: false,
+            constraints_path: None,
+            witness_path: None,
+            extra_witness_paths: vec![],
+            sym_path: None,
+            tree_path: None,
+            r1cs_path: None,
+            prime: None,
+            cocoa_memory_limit_mb: None,
+            total_timeout_seconds: None,
+            cocoa_prelude: None,
+            cocoa_args: Vec::new(),
+            allowed_cocoa_versions: SUPPORTED_COCOA_VERSIONS.parse().unwrap(),
+            abort_on_unsupported_cocoa: false,
+            solver_cmd: None,
+            report_path: None,
+            baseline_report_path: None,
+            fail_on_regression: false,
+            sarif_path: None,
+            tree_svg_path: None,
+            list_components: false,
+            output_format: OutputFormat::Table,
+            graph_metrics: false,
+            linear_rank: false,
+            report_redundant: false,
+            coverage: false,
+            monolithic: false,
+            report_dead_inputs: false,
+            print_witness_for_failures: false,
+            require_outputs_fixed: false,
+            graphml_path: None,
+            mermaid_path: None,
+            only_failures: false,
+            watch: false,
+            functional_uniqueness_component: None,
+            fix_signals: vec![],
+            exceptions_as_warnings: false,
+            jobs: 1,
+            export_ideals_path: None,
+            dump_systems_path: None,
+            dump_cas_json_path: None,
+            sanitized_names: false,
+            expand_products: false,
+            sample_inputs: None,
+            minimize_failure: false,
+            no_progress: false,
+            summary_only: false,
+            quiet: false,
+            resume: false,
+            skip_verified: false,
+            out_dir: None,
         }
     }
 }
 
-pub fn parse_command_line_arguments() -> (Option<PathBuf>, Options) {
+// Command selected in the command line, together with the artifacts folder it operates on
+pub enum CliCommand {
+    // Verify the safety of the circuit. The folder is None if the hardcoded path in main.rs must
+    //  be used instead. Circuits in extra_folders are verified afterwards, one after another
+    Verify {
+        folder: Option<PathBuf>,
+        extra_folders: Vec<PathBuf>,
+        options: Options,
+    },
+
+    // Print the component hierarchy of the circuit. Does not require a witness
+    Inspect {
+        folder: PathBuf,
+        options: Options,
+    },
+
+    // Write the verification graph of the main component to a GraphML file, a Mermaid flowchart
+    //  or both, without verifying the circuit. Does not require a witness
+    Export {
+        folder: PathBuf,
+        graphml_path: Option<PathBuf>,
+        mermaid_path: Option<PathBuf>,
+        options: Options,
+    },
+
+    // Report the size of the polynomial systems of each circuit with every combination of
+    //  optimization passes in the benchmark matrix and, if solve is true, the time CoCoA spends on
+    //  them
+    Benchmark {
+        folders: Vec<PathBuf>,
+        solve: bool,
+        options: Options,
+    },
+}
+
+const SUBCOMMAND_NAMES: [&str; 4] = ["verify", "inspect", "export", "benchmark"];
+
+pub fn parse_command_line_arguments() -> CliCommand {
+    parse_command_line_arguments_from(env::args_os())
+}
+
+// Parses the given arguments, the first of which is the binary name. If they do not start with a
+//  subcommand (for example, if the first argument is the artifacts folder, as in previous versions
+//  of the verifier), they are parsed as arguments of the verify subcommand.
+pub fn parse_command_line_arguments_from<I>(args: I) -> CliCommand
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut args: Vec<OsString> = args.into_iter().collect();
+    let starts_with_subcommand = args.get(1).and_then(|arg| arg.to_str()).is_some_and(|arg| {
+        SUBCOMMAND_NAMES.contains(&arg)
+            || ["help", "-h", "--help", "-V", "--version"].contains(&arg)
+    });
+    if !starts_with_subcommand {
+        args.insert(args.len().min(1), OsString::from("verify"));
+    }
+
     let matches = command!()
+        .subcommand_required(true)
+        .subcommand(get_verify_subcommand())
+        .subcommand(
+            Command::new("inspect")
+                .about("Print the component hierarchy of the circuit. Does not require a witness")
+                .arg(get_folder_arg().required(true))
+                .arg(get_format_arg())
+                .arg(get_strict_arg())
+                .arg(get_prime_arg())
+                .args(get_artifact_path_args()),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export the verification graph of the main component without verifying the circuit")
+                .arg(get_folder_arg().required(true))
+                .arg(
+                    arg!(
+                        --graphml <FILE> "Write the verification graph of the main component, as built before propagation, to the given GraphML file. Its initially_fixed attribute marks the inputs and the signals fixed to constants"
+                    )
+                        .required_unless_present("mermaid")
+                        .value_parser(value_parser!(PathBuf))
+                )
+                .arg(
+                    arg!(
+                        --mermaid <FILE> "Write the verification graph of the main component to the given file as a Mermaid flowchart"
+                    )
+                        .required(false)
+                        .value_parser(value_parser!(PathBuf))
+                )
+                .arg(get_strict_arg())
+                .arg(get_prime_arg())
+                .args(get_artifact_path_args()),
+        )
+        .subcommand(
+            Command::new("benchmark")
+                .about("Measure the effect of the optimization passes on the polynomial systems of the given circuits")
+                .arg(
+                    arg!(<folders> ... "Artifacts folders of the circuits to benchmark")
+                        .value_parser(value_parser!(PathBuf))
+                )
+                .arg(arg!(
+                    --solve "Also solve the polynomial systems with CoCoA with each configuration, to time them"
+                ))
+                .arg(get_format_arg())
+                .arg(get_boolean_prohibition_arg())
+                .arg(get_cocoa_strategy_arg())
+                .arg(get_strict_arg())
+                .arg(get_prime_arg()),
+        )
+        .get_matches_from(args);
+
+    match matches.subcommand() {
+        Some(("verify", matches)) => {
+            let use_hardcoded_path = matches.get_flag("usehardcodedpath");
+
+            let mut folders: Vec<PathBuf> = matches
+                .get_many::<PathBuf>("folder")
+                .map_or(vec![], |folders| folders.cloned().collect());
+            let folder = if use_hardcoded_path || folders.is_empty() {
+                None
+            } else {
+                Some(folders.remove(0))
+            };
+
+            CliCommand::Verify {
+                folder,
+                extra_folders: folders,
+                options: parse_verify_options(matches),
+            }
+        }
+        Some(("inspect", matches)) => CliCommand::Inspect {
+            folder: matches.get_one::<PathBuf>("folder").unwrap().clone(),
+            options: Options {
+                output_format: *matches.get_one::<OutputFormat>("format").unwrap(),
+                ..parse_artifact_options(matches)
+            },
+        },
+        Some(("export", matches)) => CliCommand::Export {
+            folder: matches.get_one::<PathBuf>("folder").unwrap().clone(),
+            graphml_path: matches.get_one::<PathBuf>("graphml").cloned(),
+            mermaid_path: matches.get_one::<PathBuf>("mermaid").cloned(),
+            options: parse_artifact_options(matches),
+        },
+        Some(("benchmark", matches)) => CliCommand::Benchmark {
+            folders: matches
+                .get_many::<PathBuf>("folders")
+                .unwrap()
+                .cloned()
+                .collect(),
+            solve: matches.get_flag("solve"),
+            options: Options {
+                output_format: *matches.get_one::<OutputFormat>("format").unwrap(),
+                boolean_prohibition: *matches
+                    .get_one::<BooleanProhibition>("boolean-prohibition")
+                    .unwrap(),
+                cocoa_strategy: *matches.get_one::<CocoaStrategy>("cocoa-strategy").unwrap(),
+                strict: matches.get_flag("strict"),
+                prime: matches.get_one::<BigInt>("prime").cloned(),
+                ..Default::default()
+            },
+        },
+        _ => unreachable!("A subcommand is required"),
+    }
+}
+
+fn get_folder_arg() -> Arg {
+    arg!([folder] "Artifacts folder to operate on").value_parser(value_parser!(PathBuf))
+}
+
+fn get_strict_arg() -> Arg {
+    arg!(
+        --strict "Reject constraints with more than 3 terms instead of ignoring the extra terms"
+    )
+}
+
+fn get_format_arg() -> Arg {
+    arg!(
+        --format <FORMAT> "Format of the component hierarchy, the graph metrics, the linear ranks and the redundant constraints (table, json or csv)"
+    )
+        .required(false)
+        .value_parser(|s: &str| s.parse::<OutputFormat>())
+        .default_value("table")
+}
+
+fn get_boolean_prohibition_arg() -> Arg {
+    arg!(
+        --"boolean-prohibition" <ENCODING> "How the witness is excluded from polynomial systems whose signals to fix are all boolean, with the uvar prohibition mode: 'product' multiplies the flips of every signal, 'separate' checks each flip in its own ideal"
+    )
+        .required(false)
+        .value_parser(|s: &str| s.parse::<BooleanProhibition>())
+        .default_value("product")
+}
+
+fn get_cocoa_strategy_arg() -> Arg {
+    arg!(
+        --"cocoa-strategy" <STRATEGY> "How CoCoA decides whether the signals of each polynomial system are fixed: 'gbasis' checks that the ideal with the witness excluded contains 1, 'dimension' first checks that the constraints only have the witness as solution, from the dimension of their quotient ring and its number of points, and falls back to saturation otherwise. With 'dimension', the CoCoA timeout only bounds the Groebner basis of the constraints, and not the radical nor the saturation computed from it, which are only bounded by --total-timeout"
+    )
+        .required(false)
+        .value_parser(|s: &str| s.parse::<CocoaStrategy>())
+        .default_value("gbasis")
+}
+
+fn get_prime_arg() -> Arg {
+    arg!(
+        --prime <P> "Prime of the field Z_p. By default it is read from the .r1cs file if present, and otherwise from the tree constraints"
+    )
+        .required(false)
+        .value_parser(|s: &str| s.parse::<BigInt>())
+}
+
+// Arguments giving the path of each artifact individually
+fn get_artifact_path_args() -> [Arg; 5] {
+    [
+        arg!(
+            --constraints <FILE> "Read the constraints from the given file instead of 'circuit_constraints.json' inside the artifacts folder"
+        ),
+        arg!(
+            --witness <FILE> "Read the witness from the given file instead of 'witness.json' inside the artifacts folder"
+        ),
+        arg!(
+            --sym <FILE> "Read the signal names from the given file instead of 'circuit_signals.sym' inside the artifacts folder"
+        ),
+        arg!(
+            --tree <FILE> "Read the component tree from the given file instead of 'circuit_treeconstraints.json' inside the artifacts folder"
+        ),
+        arg!(
+            --r1cs <FILE> "Read the field prime from the header of the given file instead of 'circuit.r1cs' inside the artifacts folder"
+        ),
+    ]
+    .map(|arg| arg.required(false).value_parser(value_parser!(PathBuf)))
+}
+
+// Options of the subcommands that only read the artifacts, without verifying the circuit
+fn parse_artifact_options(matches: &ArgMatches) -> Options {
+    Options {
+        strict: matches.get_flag("strict"),
+        constraints_path: matches.get_one::<PathBuf>("constraints").cloned(),
+        witness_path: matches.get_one::<PathBuf>("witness").cloned(),
+        sym_path: matches.get_one::<PathBuf>("sym").cloned(),
+        tree_path: matches.get_one::<PathBuf>("tree").cloned(),
+        r1cs_path: matches.get_one::<PathBuf>("r1cs").cloned(),
+        prime: matches.get_one::<BigInt>("prime").cloned(),
+        ..Default::default()
+    }
+}
+
+fn get_verify_subcommand() -> Command {
+    Command::new("verify")
+        .about("Verify the safety of the circuit. This is the default subcommand")
         .arg(
-            arg!([folder] "Artifacts folder to operate on")
+            arg!([folder] ... "Artifacts folders of the circuits to verify, one after another")
+                .value_parser(value_parser!(PathBuf))
                 .required_unless_present("usehardcodedpath")
-                .value_parser(value_parser!(PathBuf)),
         )
         .arg(
             arg!(
@@ -55,40 +624,713 @@ pub fn parse_command_line_arguments() -> (Option<PathBuf>, Options) {
                 .value_parser(value_parser!(u32))
                 .default_value(OsString::from(Options::default().max_vars_prohibition_polynomial_before_timeout.to_string()))
         )
+        .arg(
+            arg!(
+                --"max-system-signals" <N> "Report polynomial systems with more than N signals as too large instead of verifying them"
+            )
+                .required(false)
+                .value_parser(value_parser!(u32))
+        )
+        .arg(
+            arg!(
+                --"max-degree" <D> "Report polynomial systems with constraints of total degree higher than D instead of verifying them"
+            )
+                .required(false)
+                .value_parser(value_parser!(u32))
+        )
+        .arg(
+            arg!(
+                --"propagation-max-iters" <N> "Report a component as an exception if its graph propagation takes more than N steps, each fixing a signal or merging a === connected component"
+            )
+                .required(false)
+                .value_parser(value_parser!(u64))
+                .default_value(OsString::from(Options::default().propagation_max_iterations.to_string()))
+        )
+        .arg(
+            arg!(
+                --"opt-passes" <PASSES> "Comma-separated list of optimization passes to apply to polynomial systems, in order (tautology, binary, gauss, dedup, reach). Defaults to all"
+            )
+                .required(false)
+                .value_delimiter(',')
+                .value_parser(|s: &str| s.parse::<OptimizationPass>())
+        )
+        .arg(
+            arg!(
+                --"prohibition-mode" <MODE> "How the witness is excluded from the solutions of each polynomial system: 'uvar' adds an auxiliary variable per signal, 'saturate' uses ideal saturation instead"
+            )
+                .required(false)
+                .value_parser(|s: &str| s.parse::<ProhibitionMode>())
+                .default_value("uvar")
+        )
+        .arg(get_boolean_prohibition_arg())
+        .arg(get_cocoa_strategy_arg())
+        .arg(
+            arg!(
+                --"monomial-order" <ORDER> "Monomial ordering of the rings of the polynomial systems sent to CoCoA (degrevlex, deglex, lex, or elim to eliminate every variable but the signals to fix)"
+            )
+                .required(false)
+                .value_parser(|s: &str| s.parse::<MonomialOrder>())
+                .default_value("degrevlex")
+        )
+        .arg(
+            arg!(
+                --graphml <FILE> "Write the verification graph of the main component, as built before propagation, to the given GraphML file. Its initially_fixed attribute marks the inputs and the signals fixed to constants"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --mermaid <FILE> "Write the verification graph of the main component to the given file as a Mermaid flowchart"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
         .arg(arg!(
             -s --svg "Turn SVG debug output"
         ))
         .arg(arg!(
             -p --propagationsvg "Generate all propagation steps SVG, not only one SVG after all propagations steps have been executed. Also enables SVG debug output"
         ))
+        .arg(arg!(
+            --"stable-svg" "Keep the layout of the SVG diagrams of each component stable across propagation steps, highlighting the signals fixed since the previous step"
+        ))
+        .arg(
+            arg!(
+                --"functional-uniqueness" <COMPONENT> "Check that the outputs of the given component are determined by its inputs for any input, instead of checking weak safety. Does not require a witness. Needs CoCoA, so it cannot be used with --solver-cmd"
+            )
+                .required(false)
+                .conflicts_with("solver-cmd")
+        )
+        .arg(
+            arg!(
+                --"fix-signals" <NAMES> "Comma-separated list of signals to check that are fixed by the inputs of the main component, instead of its outputs"
+            )
+                .required(false)
+                .value_delimiter(',')
+        )
+        .arg(
+            arg!(
+                --"mem-limit" <MB> "Limit the memory CoCoA may use, in megabytes"
+            )
+                .required(false)
+                .value_parser(value_parser!(u64))
+        )
+        .arg(
+            arg!(
+                --"total-timeout" <SECONDS> "Stop verifying the circuit after SECONDS, killing CoCoA and reporting the polynomial systems not verified by then as not checked. The traversal of the circuit is not interrupted"
+            )
+                .required(false)
+                .value_parser(value_parser!(u64))
+        )
+        .arg(
+            arg!(
+                --"cocoa-prelude" <FILE> "Prepend the CoCoA code in FILE to every generated script, for example to load packages"
+            )
+                .required(false)
+                .value_parser(|path: &str| {
+                    fs::read_to_string(path)
+                        .map_err(|e| format!("Couldn't read CoCoA prelude '{}': {}", path, e))
+                })
+        )
+        .arg(
+            arg!(
+                --"cocoa-arg" <ARG> "Pass an extra argument to the CoCoA interpreter. Can be repeated"
+            )
+                .required(false)
+                .allow_hyphen_values(true)
+                .action(ArgAction::Append)
+        )
+        .arg(
+            arg!(
+                --"allow-cocoa-version" <RANGE> "Range of CoCoA versions accepted without a warning, as MIN..MAX with MAX excluded"
+            )
+                .required(false)
+                .value_parser(|s: &str| s.parse::<CocoaVersionRange>())
+                .default_value(SUPPORTED_COCOA_VERSIONS)
+        )
+        .arg(
+            arg!(
+                --"abort-on-unsupported-cocoa" "Abort the verification if the version of CoCoA is not accepted or cannot be detected"
+            )
+        )
+        .arg(
+            arg!(
+                --"solver-cmd" <CMD> "Check the polynomial systems with the shell command CMD instead of CoCoA. It receives them on stdin as the JSON of --dump-cas-json and answers with a line 'OK: <index>', 'ERROR: <index>' or 'TIMEOUT: <index>' per system, followed by 'FINISHED'"
+            )
+                .required(false)
+        )
+        .arg(
+            arg!(
+                -j --jobs <N> "Run up to N CoCoA processes in parallel, verifying each component as soon as it has been traversed"
+            )
+                .required(false)
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value(OsString::from(Options::default().jobs.to_string()))
+        )
+        .arg(
+            arg!(
+                --"export-ideals" <DIR> "Write the ideal of each polynomial system to its own file inside the given folder, for external Groebner basis tools"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --"dump-systems" <FILE> "Write every optimized polynomial system in readable form, grouped by component, to the given file"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --"dump-cas-json" <FILE> "Write the variables, generators, prime and timeout sent to CoCoA for every polynomial system to the given JSON file"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --"sanitized-names" "Write signal names in readable polynomial systems as valid CAS identifiers ('c.in[0]' as 'c_in_0_'), preceded by a legend with the original names as Sage comments. Names that would collide once sanitized are followed by their signal index"
+            )
+        )
+        .arg(
+            arg!(
+                --"expand-products" "Expand the product A * B of each constraint in readable polynomial systems into a sum of monomials"
+            )
+        )
+        .arg(
+            arg!(
+                --"sample-inputs" <N> "After verifying, check that N random assignments of the signals of each polynomial system other than the witness are not a second solution. Needs CoCoA, so it cannot be used with --solver-cmd"
+            )
+                .required(false)
+                .conflicts_with("solver-cmd")
+                .value_parser(value_parser!(u32).range(1..))
+        )
+        .arg(
+            arg!(
+                --"out-dir" <DIR> "Write generated files, such as CoCoA scripts, SVG diagrams and reports with a relative path, to the given folder instead of the artifacts folder"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --report <FILE> "Write a JSON report with the verdict of each component to the given file"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --baseline <FILE> "Compare the verdicts and timings of this run against a previous JSON report"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --"fail-on-regression" "Exit with a non-zero code if the comparison against the baseline report finds components that no longer pass or polynomial systems that are noticeably slower"
+            )
+                .requires("baseline")
+        )
+        .arg(
+            arg!(
+                --sarif <FILE> "Write the unsafe components, exceptions and unfixed polynomial systems to the given file as SARIF 2.1.0, for code scanning tools"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --"tree-svg" <FILE> "Draw the component hierarchy to the given SVG file, with each component colored by its verdict: green if safe, red if unsafe, orange if it raised an exception and gray if undetermined"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+        )
+        .arg(
+            arg!(
+                --"trust-template" <NAME> "Trust components of the given template as safe without verifying them. NAME is either the bare template name, such as 'Num2Bits', which matches every instance, or the name with its parameters as shown by the inspect subcommand, such as 'Num2Bits(4)'. The main component is always verified. Can be repeated"
+            )
+                .required(false)
+                .action(ArgAction::Append)
+        )
+        .arg(
+            arg!(
+                --reference <DIR> "Consider safe, without verifying them, the components with the same constraints up to signal renaming as the main component of the already verified circuit in DIR. Can be repeated"
+            )
+                .required(false)
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Append)
+        )
+        .arg(
+            arg!(
+                --filter <GLOB> "Only verify components whose name (such as 'main.adders[*]') or template (such as '*Bits*') matches GLOB, assuming the outputs of the rest are fixed. Can be repeated"
+            )
+                .required(false)
+                .action(ArgAction::Append)
+        )
+        .arg(
+            arg!(
+                --priority <GLOB> "Send the polynomial systems of components whose name matches GLOB to CoCoA before the rest. Can be repeated, in decreasing order of priority"
+            )
+                .required(false)
+                .action(ArgAction::Append)
+        )
+        .arg(arg!(
+            --"keep-scripts" "Write the CoCoA script to a new timestamped file instead of overwriting 'groebner.cocoa5'"
+        ))
+        .arg(arg!(
+            --"annotate-scripts" "Precede each polynomial system of the CoCoA script by comments with the witness value of its signals to fix"
+        ))
+        .arg(get_strict_arg())
+        .arg(get_prime_arg())
+        .args(get_artifact_path_args())
+        .mut_arg("witness", |arg| {
+            arg.action(ArgAction::Append).help(
+                "Read the witness from the given file instead of 'witness.json' inside the artifacts folder. Can be repeated to check every polynomial system at all the given witnesses in a single CoCoA run",
+            )
+        })
+        .arg(arg!(
+            --"list-components" "Print the component hierarchy and exit. Does not require a witness"
+        ))
+        .arg(get_format_arg())
+        .arg(arg!(
+            --"graph-metrics" "Print the number of signals, edges and connected components of '===' constraints and the rank of the linear constraints of the verification graph of the main component and exit. Does not require a witness"
+        ))
+        .arg(arg!(
+            --rank "Print the rank of the linear constraints of every component over the field and its number of non-input signals, flagging the components whose linear constraints cannot determine them, and exit. Does not require a witness"
+        ))
+        .arg(arg!(
+            --"report-redundant" "Print the linear constraints of every component that are linear combinations of its other constraints, and so can be removed without changing the circuit, and exit. Does not require a witness"
+        ))
+        .arg(arg!(
+            --coverage "Print the constraints involved in fixing each output of every verified component"
+        ))
+        .arg(arg!(
+            --"report-dead-inputs" "Report the inputs of each component that none of its outputs depends on, which usually means a constraint is missing"
+        ))
+        .arg(arg!(
+            --"print-witness-for-failures" "Print the witness values of the inputs and outputs of each failing component under its failure message"
+        ))
+        .arg(arg!(
+            --"require-outputs-fixed" "Fail right away, without CoCoA or reports, if no constraint can fix some output of the main component. Meant as a fast check for CI"
+        ))
+        .arg(arg!(
+            --monolithic "Also verify every constraint of the circuit as a single polynomial system, ignoring the subcomponents, and warn if its verdict disagrees with the modular one. Only feasible for small circuits"
+        ))
+        .arg(arg!(
+            --"only-failures" "Only print and report components that fail verification, summarizing the rest as a count"
+        ))
+        .arg(arg!(
+            --"minimize-failure" "For each polynomial system with many solutions, check its signals one by one with the solver and report those that are not fixed, with the constraints they appear in"
+        ))
+        .arg(arg!(
+            --resume "Reuse the verdicts of polynomial systems already verified by a previous interrupted run, verifying only timed out and pending ones"
+        ))
+        .arg(arg!(
+            --"skip-verified" "Skip components found safe by previous runs whose constraints have not changed, as recorded in verified.json, and record there the components found safe"
+        ))
+        .arg(arg!(
+            --"no-progress" "Do not show the polynomial system being fixed, only the result of each one. Implied when the output is not a terminal"
+        ))
+        .arg(arg!(
+            --"summary-only" "Only print a line with the verdict of each circuit and a total at the end, for batches of circuits"
+        ))
+        .arg(arg!(
+            --"quiet" "Do not print the output of the verification. The exit code tells whether the circuit is safe"
+        ))
+        .arg(arg!(
+            --"exceptions-as-warnings" "Report components that cannot be verified due to an exception as warnings, failing only on unsafe components"
+        ))
+        .arg(arg!(
+            --watch "Verify the circuit again every time its constraints or witness change, until interrupted with Ctrl-C"
+        ))
         .arg(arg!(
             --usehardcodedpath "Use hard coded folder path from main.rs for debug purposes"
         ))
-        .get_matches();
+}
+
+// Returns the path of a generated file given in the argument with the given id. Relative paths are
+//  resolved against the output directory, if one is given
+fn get_output_path_arg(matches: &ArgMatches, id: &str) -> Option<PathBuf> {
+    let path = matches.get_one::<PathBuf>(id)?;
+    match matches.get_one::<PathBuf>("out-dir") {
+        Some(out_dir) if path.is_relative() => Some(out_dir.join(path)),
+        _ => Some(path.clone()),
+    }
+}
 
+fn parse_verify_options(matches: &ArgMatches) -> Options {
     let generate_only_last_propagation_svg = !matches.get_flag("propagationsvg");
     let generate_svg_diagrams = !generate_only_last_propagation_svg || matches.get_flag("svg");
+    let stable_svg_layout = matches.get_flag("stable-svg");
     let groebner_cocoa_timeout_seconds = *matches.get_one::<u32>("timeout").unwrap();
     let max_vars_prohibition_polynomial_before_timeout =
         *matches.get_one::<u32>("maxvars").unwrap();
+    let max_system_signals = matches.get_one::<u32>("max-system-signals").copied();
+    let max_degree = matches.get_one::<u32>("max-degree").copied();
+    let propagation_max_iterations = *matches.get_one::<u64>("propagation-max-iters").unwrap();
+    let optimization_passes = match matches.get_many::<OptimizationPass>("opt-passes") {
+        Some(passes) => passes.copied().collect(),
+        None => Options::default().optimization_passes,
+    };
+    let prohibition_mode = *matches
+        .get_one::<ProhibitionMode>("prohibition-mode")
+        .unwrap();
+    let boolean_prohibition = *matches
+        .get_one::<BooleanProhibition>("boolean-prohibition")
+        .unwrap();
+    let cocoa_strategy = *matches.get_one::<CocoaStrategy>("cocoa-strategy").unwrap();
+    let monomial_order = *matches.get_one::<MonomialOrder>("monomial-order").unwrap();
+    let trusted_templates = matches
+        .get_many::<String>("trust-template")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let reference_paths = matches
+        .get_many::<PathBuf>("reference")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let component_filters = matches
+        .get_many::<String>("filter")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let priority_components = matches
+        .get_many::<String>("priority")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let keep_scripts = matches.get_flag("keep-scripts");
+    let annotate_scripts = matches.get_flag("annotate-scripts");
+    let strict = matches.get_flag("strict");
+    let constraints_path = matches.get_one::<PathBuf>("constraints").cloned();
+    let mut witness_paths = matches
+        .get_many::<PathBuf>("witness")
+        .unwrap_or_default()
+        .cloned();
+    let witness_path = witness_paths.next();
+    let extra_witness_paths = witness_paths.collect();
+    let sym_path = matches.get_one::<PathBuf>("sym").cloned();
+    let tree_path = matches.get_one::<PathBuf>("tree").cloned();
+    let r1cs_path = matches.get_one::<PathBuf>("r1cs").cloned();
+    let prime = matches.get_one::<BigInt>("prime").cloned();
+    let cocoa_memory_limit_mb = matches.get_one::<u64>("mem-limit").copied();
+    let total_timeout_seconds = matches.get_one::<u64>("total-timeout").copied();
+    let cocoa_prelude = matches.get_one::<String>("cocoa-prelude").cloned();
+    let cocoa_args = matches
+        .get_many::<String>("cocoa-arg")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let allowed_cocoa_versions = *matches
+        .get_one::<CocoaVersionRange>("allow-cocoa-version")
+        .unwrap();
+    let abort_on_unsupported_cocoa = matches.get_flag("abort-on-unsupported-cocoa");
+    let solver_cmd = matches.get_one::<String>("solver-cmd").cloned();
+    let report_path = get_output_path_arg(matches, "report");
+    let baseline_report_path = matches.get_one::<PathBuf>("baseline").cloned();
+    let fail_on_regression = matches.get_flag("fail-on-regression");
+    let sarif_path = get_output_path_arg(matches, "sarif");
+    let tree_svg_path = get_output_path_arg(matches, "tree-svg");
+    let list_components = matches.get_flag("list-components");
+    let output_format = *matches.get_one::<OutputFormat>("format").unwrap();
+    let graph_metrics = matches.get_flag("graph-metrics");
+    let linear_rank = matches.get_flag("rank");
+    let report_redundant = matches.get_flag("report-redundant");
+    let coverage = matches.get_flag("coverage");
+    let monolithic = matches.get_flag("monolithic");
+    let report_dead_inputs = matches.get_flag("report-dead-inputs");
+    let print_witness_for_failures = matches.get_flag("print-witness-for-failures");
+    let require_outputs_fixed = matches.get_flag("require-outputs-fixed");
+    let graphml_path = get_output_path_arg(matches, "graphml");
+    let mermaid_path = get_output_path_arg(matches, "mermaid");
+    let only_failures = matches.get_flag("only-failures");
+    let watch = matches.get_flag("watch");
+    let functional_uniqueness_component =
+        matches.get_one::<String>("functional-uniqueness").cloned();
+    let fix_signals = matches
+        .get_many::<String>("fix-signals")
+        .map_or(vec![], |names| names.cloned().collect());
+    let exceptions_as_warnings = matches.get_flag("exceptions-as-warnings");
+    let jobs = *matches.get_one::<u32>("jobs").unwrap() as usize;
+    let export_ideals_path = get_output_path_arg(matches, "export-ideals");
+    let dump_systems_path = get_output_path_arg(matches, "dump-systems");
+    let dump_cas_json_path = get_output_path_arg(matches, "dump-cas-json");
+    let sanitized_names = matches.get_flag("sanitized-names");
+    let expand_products = matches.get_flag("expand-products");
+    let sample_inputs = matches.get_one::<u32>("sample-inputs").copied();
+    let minimize_failure = matches.get_flag("minimize-failure");
+    let no_progress = matches.get_flag("no-progress");
+    let summary_only = matches.get_flag("summary-only");
+    let quiet = matches.get_flag("quiet") || summary_only;
+    let resume = matches.get_flag("resume");
+    let skip_verified = matches.get_flag("skip-verified");
+    let out_dir = matches.get_one::<PathBuf>("out-dir").cloned();
 
-    let options = Options {
+    Options {
         groebner_cocoa_timeout_seconds,
         max_vars_prohibition_polynomial_before_timeout,
+        max_system_signals,
+        max_degree,
+        propagation_max_iterations,
+        optimization_passes,
+        prohibition_mode,
+        boolean_prohibition,
+        cocoa_strategy,
+        monomial_order,
         generate_svg_diagrams,
         generate_only_last_propagation_svg,
-    };
+        stable_svg_layout,
+        trusted_templates,
+        reference_paths,
+        component_filters,
+        priority_components,
+        keep_scripts,
+        annotate_scripts,
+        strict,
+        constraints_path,
+        witness_path,
+        extra_witness_paths,
+        sym_path,
+        tree_path,
+        r1cs_path,
+        prime,
+        cocoa_memory_limit_mb,
+        total_timeout_seconds,
+        cocoa_prelude,
+        cocoa_args,
+        allowed_cocoa_versions,
+        abort_on_unsupported_cocoa,
+        solver_cmd,
+        report_path,
+        baseline_report_path,
+        fail_on_regression,
+        sarif_path,
+        tree_svg_path,
+        list_components,
+        output_format,
+        graph_metrics,
+        linear_rank,
+        report_redundant,
+        coverage,
+        monolithic,
+        report_dead_inputs,
+        print_witness_for_failures,
+        require_outputs_fixed,
+        graphml_path,
+        mermaid_path,
+        only_failures,
+        watch,
+        functional_uniqueness_component,
+        fix_signals,
+        exceptions_as_warnings,
+        jobs,
+        export_ideals_path,
+        dump_systems_path,
+        dump_cas_json_path,
+        sanitized_names,
+        expand_products,
+        sample_inputs,
+        minimize_failure,
+        no_progress,
+        summary_only,
+        quiet,
+        resume,
+        skip_verified,
+        out_dir,
+    }
+}
 
-    let use_hardcoded_path = matches.get_flag("usehardcodedpath");
+#[test]
+fn test_parse_command_line_arguments() {
+    let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
 
-    let folder_path = if use_hardcoded_path {
-        None
-    } else {
-        Some(matches.get_one::<PathBuf>("folder").unwrap().clone())
-    };
+    // Without a subcommand, arguments are parsed as in the verify subcommand
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "circuit",
+        "-t",
+        "7",
+        "-j",
+        "4",
+        "--sample-inputs",
+        "10",
+        "--cocoa-arg",
+        "--packages",
+        "--cocoa-arg",
+        "-q",
+        "--allow-cocoa-version",
+        "5.3..6.0",
+    ])) {
+        CliCommand::Verify {
+            folder,
+            extra_folders,
+            options,
+        } => {
+            assert_eq!(folder, Some(PathBuf::from("circuit")));
+            assert!(extra_folders.is_empty());
+            assert_eq!(options.groebner_cocoa_timeout_seconds, 7);
+            assert_eq!(options.jobs, 4);
+            assert_eq!(options.sample_inputs, Some(10));
+            assert_eq!(options.cocoa_args, vec!["--packages", "-q"]);
+            assert_eq!(
+                options.allowed_cocoa_versions,
+                "5.3.0..6.0.0".parse().unwrap()
+            );
+            assert!(!options.abort_on_unsupported_cocoa);
+            assert_eq!(options.out_dir, None);
+        }
+        _ => panic!("Expected the verify subcommand"),
+    }
 
-    // println!("{:?}", folder_path);
-    // println!("{:?}", options);
+    // Generated files given with a relative path are written inside the output directory
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "circuit",
+        "--out-dir",
+        "out",
+        "--report",
+        "report.json",
+        "--dump-systems",
+        "/tmp/systems.txt",
+    ])) {
+        CliCommand::Verify { options, .. } => {
+            assert_eq!(options.out_dir, Some(PathBuf::from("out")));
+            assert_eq!(options.report_path, Some(PathBuf::from("out/report.json")));
+            assert_eq!(
+                options.dump_systems_path,
+                Some(PathBuf::from("/tmp/systems.txt"))
+            );
+        }
+        _ => panic!("Expected the verify subcommand"),
+    }
 
-    (folder_path, options)
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "circuit1",
+        "circuit2",
+        "circuit3",
+        "--summary-only",
+    ])) {
+        CliCommand::Verify {
+            folder,
+            extra_folders,
+            options,
+        } => {
+            assert_eq!(folder, Some(PathBuf::from("circuit1")));
+            assert_eq!(
+                extra_folders,
+                vec![PathBuf::from("circuit2"), PathBuf::from("circuit3")]
+            );
+            assert!(options.summary_only);
+            assert!(options.quiet);
+        }
+        _ => panic!("Expected the verify subcommand"),
+    }
+
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "circuit",
+        "--witness",
+        "w1.json",
+        "--witness",
+        "w2.json",
+        "--witness",
+        "w3.json",
+    ])) {
+        CliCommand::Verify { options, .. } => {
+            assert_eq!(options.witness_path, Some(PathBuf::from("w1.json")));
+            assert_eq!(
+                options.extra_witness_paths,
+                vec![PathBuf::from("w2.json"), PathBuf::from("w3.json")]
+            );
+        }
+        _ => panic!("Expected the verify subcommand"),
+    }
+
+    match parse_command_line_arguments_from(args(&["verification", "verify", "--usehardcodedpath"]))
+    {
+        CliCommand::Verify { folder, .. } => assert_eq!(folder, None),
+        _ => panic!("Expected the verify subcommand"),
+    }
+
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "export",
+        "circuit",
+        "--graphml",
+        "graph.graphml",
+        "--strict",
+        "--constraints",
+        "build/mycircuit_constraints.json",
+        "--prime",
+        "17",
+    ])) {
+        CliCommand::Export {
+            folder,
+            graphml_path,
+            mermaid_path,
+            options,
+        } => {
+            assert_eq!(folder, PathBuf::from("circuit"));
+            assert_eq!(graphml_path, Some(PathBuf::from("graph.graphml")));
+            assert_eq!(mermaid_path, None);
+            assert!(options.strict);
+            assert_eq!(
+                options.constraints_path,
+                Some(PathBuf::from("build/mycircuit_constraints.json"))
+            );
+            assert_eq!(options.witness_path, None);
+            assert_eq!(options.prime, Some(BigInt::from(17)));
+        }
+        _ => panic!("Expected the export subcommand"),
+    }
+
+    match parse_command_line_arguments_from(args(&["verification", "inspect", "circuit"])) {
+        CliCommand::Inspect { options, .. } => {
+            assert_eq!(options.output_format, OutputFormat::Table)
+        }
+        _ => panic!("Expected the inspect subcommand"),
+    }
+
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "inspect",
+        "circuit",
+        "--format",
+        "csv",
+    ])) {
+        CliCommand::Inspect { options, .. } => assert_eq!(options.output_format, OutputFormat::Csv),
+        _ => panic!("Expected the inspect subcommand"),
+    }
+    match parse_command_line_arguments_from(args(&[
+        "verification",
+        "benchmark",
+        "circuit1",
+        "circuit2",
+        "--solve",
+        "--boolean-prohibition",
+        "separate",
+        "--cocoa-strategy",
+        "dimension",
+    ])) {
+        CliCommand::Benchmark {
+            folders,
+            solve,
+            options,
+        } => {
+            assert_eq!(
+                folders,
+                vec![PathBuf::from("circuit1"), PathBuf::from("circuit2")]
+            );
+            assert!(solve);
+            assert_eq!(options.output_format, OutputFormat::Table);
+            assert_eq!(options.boolean_prohibition, BooleanProhibition::Separate);
+            assert_eq!(options.cocoa_strategy, CocoaStrategy::Dimension);
+        }
+        _ => panic!("Expected the benchmark subcommand"),
+    }
 }
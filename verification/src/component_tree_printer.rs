@@ -0,0 +1,155 @@
+use crate::error::VerifierResult;
+use crate::input_data::TreeConstraints;
+use crate::report::{ComponentReport, Verdict};
+use graphviz_rust::cmd::Format;
+use graphviz_rust::dot_generator::*;
+use graphviz_rust::dot_structures::*;
+use graphviz_rust::exec;
+use graphviz_rust::printer::PrinterContext;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Draws the component hierarchy of the circuit as an SVG diagram, with a node per component
+//  colored by its verdict. Unlike the diagrams of the verification graph, it does not show any
+//  signal, so it gives an overview of where the failures are in large circuits.
+pub fn write_component_tree_svg(
+    tree_constraints: &TreeConstraints,
+    components: &[ComponentReport],
+    path: &Path,
+) -> VerifierResult<()> {
+    let graph_svg = exec(
+        construct_component_tree_graph(tree_constraints, components),
+        &mut PrinterContext::default(),
+        vec![Format::Svg.into()],
+    )?;
+    fs::write(path, graph_svg)?;
+
+    Ok(())
+}
+
+// Fill color of the node of a component with the given verdict. Components without a verdict have
+//  not been verified, for example because they are inside an unsafe component
+fn get_verdict_color(verdict: Option<Verdict>) -> &'static str {
+    match verdict {
+        Some(Verdict::Safe | Verdict::Trusted | Verdict::SafeIfTrusted) => "green",
+        Some(Verdict::Unsafe | Verdict::ManySolutions | Verdict::UnsafeSubcomponent) => "red",
+        Some(Verdict::Exception) => "orange",
+        Some(
+            Verdict::Timeout
+            | Verdict::OutOfMemory
+            | Verdict::TooLarge
+            | Verdict::TooHighDegree
+            | Verdict::NotChecked,
+        )
+        | None => "gray",
+    }
+}
+
+// The verdicts of the components are those of the verification report, which are computed from
+//  the tree of verification results
+fn construct_component_tree_graph(
+    tree_constraints: &TreeConstraints,
+    components: &[ComponentReport],
+) -> Graph {
+    fn add_component(
+        g: &mut Graph,
+        tree_constraints: &TreeConstraints,
+        verdicts: &HashMap<&str, Verdict>,
+        next_node: &mut usize,
+    ) -> String {
+        let node_id = format!("cmp{}", *next_node);
+        *next_node += 1;
+
+        let verdict = verdicts
+            .get(tree_constraints.component_name.as_str())
+            .copied();
+        let label = format!(
+            "{}: {}",
+            tree_constraints.component_name, tree_constraints.template_name
+        );
+        let tooltip = match verdict {
+            Some(verdict) => format!("{:?}", verdict),
+            None => "Not verified".to_string(),
+        };
+        let color = get_verdict_color(verdict);
+        let attrs = vec![
+            attr!("label", esc label),
+            attr!("tooltip", esc tooltip),
+            attr!("fillcolor", color),
+        ];
+        g.add_stmt(Stmt::Node(node!(node_id, attrs)));
+
+        for subcomponent in &tree_constraints.subcomponents {
+            let subcomponent_node_id = add_component(g, subcomponent, verdicts, next_node);
+            g.add_stmt(Stmt::Edge(
+                edge!(node_id!(node_id) => node_id!(subcomponent_node_id)),
+            ));
+        }
+
+        node_id
+    }
+
+    let verdicts: HashMap<&str, Verdict> = components
+        .iter()
+        .map(|c| (c.component_name.as_str(), c.verdict))
+        .collect();
+
+    let mut g = graph!(di id!("id"));
+    g.add_stmt(Stmt::GAttribute(GraphAttributes::Node(vec![
+        attr!("shape", "box"),
+        attr!("style", "filled"),
+    ])));
+    add_component(&mut g, tree_constraints, &verdicts, &mut 0);
+
+    g
+}
+
+#[test]
+fn test_component_tree_graph() {
+    let tree_constraints = TreeConstraints {
+        component_name: "main".to_string(),
+        template_name: "Main()".to_string(),
+        subcomponents: ["main.a", "main.b", "main.c"]
+            .into_iter()
+            .map(|name| TreeConstraints {
+                component_name: name.to_string(),
+                template_name: "Sub()".to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let components: Vec<_> = [
+        ("main", Verdict::UnsafeSubcomponent),
+        ("main.a", Verdict::Safe),
+        ("main.b", Verdict::Exception),
+    ]
+    .into_iter()
+    .map(|(name, verdict)| ComponentReport {
+        component_name: name.to_string(),
+        verdict,
+        dead_inputs: vec![],
+    })
+    .collect();
+
+    let dot = graphviz_rust::print(
+        construct_component_tree_graph(&tree_constraints, &components),
+        &mut PrinterContext::default(),
+    );
+    let node_line = |node_id: &str| {
+        dot.lines()
+            .find(|line| line.trim_start().starts_with(&format!("{}[", node_id)))
+            .unwrap()
+            .to_string()
+    };
+
+    assert!(node_line("cmp0").contains("\"main: Main()\""));
+    assert!(node_line("cmp0").contains("fillcolor=red"));
+    assert!(node_line("cmp1").contains("fillcolor=green"));
+    assert!(node_line("cmp2").contains("fillcolor=orange"));
+
+    // main.c has not been verified
+    assert!(node_line("cmp3").contains("fillcolor=gray"));
+    assert!(dot.contains("cmp0 -> cmp3"));
+}
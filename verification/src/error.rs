@@ -0,0 +1,62 @@
+use num_bigint_dig::ParseBigIntError;
+use std::io;
+use std::num::ParseIntError;
+use thiserror::Error;
+
+// Errors returned by the verifier. Library users can match on the kind of failure, for example to
+//  tell a malformed artifact apart from a missing CoCoA installation.
+#[derive(Debug, Error)]
+pub enum VerifierError {
+    // An input artifact or report is not valid JSON, or does not have the expected format
+    #[error("{0}")]
+    Parse(String),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    // The computer algebra system used to verify polynomial systems (CoCoA) cannot be found
+    #[error("Couldn't find CocoA 5 interpreter in PATH: {0}")]
+    CasNotFound(#[from] which::Error),
+
+    // The computer algebra system has crashed, has been interrupted or cannot be run as requested
+    #[error("{0}")]
+    CasFailure(String),
+
+    // An input artifact has the expected format but is inconsistent, for example because it
+    //  references constraints that do not exist
+    #[error("{0}")]
+    MalformedArtifact(String),
+
+    // A name or option given by the user is invalid for the circuit being verified
+    #[error("{0}")]
+    InvalidArgument(String),
+
+    // Some component is unsafe or raises an exception when traversing the circuit, so there are no
+    //  polynomial systems that could prove it safe
+    #[error("{0}")]
+    TraversalFailed(String),
+}
+
+pub type VerifierResult<T> = Result<T, VerifierError>;
+
+impl From<serde_json::Error> for VerifierError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            VerifierError::Io(e.into())
+        } else {
+            VerifierError::Parse(e.to_string())
+        }
+    }
+}
+
+impl From<ParseIntError> for VerifierError {
+    fn from(e: ParseIntError) -> Self {
+        VerifierError::Parse(e.to_string())
+    }
+}
+
+impl From<ParseBigIntError> for VerifierError {
+    fn from(e: ParseBigIntError) -> Self {
+        VerifierError::Parse(e.to_string())
+    }
+}
@@ -0,0 +1,231 @@
+use crate::error::VerifierResult;
+use crate::verification_graph::{Node, VerificationGraph};
+use crate::InputDataContextView;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// Writes the verification graph in GraphML format, to be explored in interactive tools such as
+//  Gephi or Cytoscape. Signals are nodes; '<==' assignments are directed edges from each RHS signal
+//  to the LHS signal. '===' constraints and subcomponents are hyperedges, so they are exported as
+//  an extra node connected to all their signals.
+// The graph is exported as built, before propagation, which removes fixed signals and the
+//  constraints they satisfy. So the initially_fixed attribute only marks the signals known to be
+//  fixed from the start: inputs, outputs of subcomponents without inputs and signals set to a
+//  constant. Signals fixed later by propagation are not marked.
+pub fn write_verification_graph_graphml(
+    verification_graph: &VerificationGraph,
+    context: &InputDataContextView,
+    path: &Path,
+) -> VerifierResult<()> {
+    let mut w = BufWriter::new(File::create(path)?);
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="kind" for="node" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="initially_fixed" for="node" attr.name="initially_fixed" attr.type="boolean"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="component" for="node" attr.name="component" attr.type="string"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="edge_kind" for="edge" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="constraint" for="edge" attr.name="constraint" attr.type="long"/>"#
+    )?;
+    writeln!(w, r#"  <graph id="G" edgedefault="directed">"#)?;
+
+    let main_component_name = &context.tree_constraints.component_name;
+    let get_subcomponent_name = |cmp_index: usize| -> &str {
+        context.tree_constraints.subcomponents[cmp_index]
+            .component_name
+            .as_str()
+    };
+
+    // Signals
+    for (signal, node) in &verification_graph.nodes {
+        let (kind, component) = match node {
+            Node::InputSignal => ("input", main_component_name.as_str()),
+            Node::OutputSignal => ("output", main_component_name.as_str()),
+            Node::IntermediateSignal => ("intermediate", main_component_name.as_str()),
+            Node::SubComponentInputSignal(cmp_index) => {
+                ("subcomponent_input", get_subcomponent_name(*cmp_index))
+            }
+            Node::SubComponentOutputSignal(cmp_index) => {
+                ("subcomponent_output", get_subcomponent_name(*cmp_index))
+            }
+        };
+
+        write_node(
+            &mut w,
+            &format!("s{}", signal),
+            &context.signal_name(*signal),
+            kind,
+            Some(verification_graph.fixed_nodes.contains(signal)),
+            component,
+        )?;
+    }
+
+    // Safe assignments '<=='
+    for safe_assignment in verification_graph
+        .safe_assignments
+        .iter()
+        .filter(|a| a.active)
+    {
+        for rhs_signal in &safe_assignment.rhs_signals {
+            write_edge(
+                &mut w,
+                &format!("s{}", rhs_signal),
+                &format!("s{}", safe_assignment.lhs_signal),
+                "safe_assignment",
+                Some(safe_assignment.associated_constraint),
+            )?;
+        }
+    }
+
+    // Unsafe constraints '==='
+    for (idx, unsafe_constraint) in verification_graph
+        .unsafe_constraints
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.active)
+    {
+        let constraint_node = format!("c{}", idx);
+        write_node(
+            &mut w,
+            &constraint_node,
+            &format!("constraint {}", unsafe_constraint.associated_constraint),
+            "unsafe_constraint",
+            None,
+            main_component_name,
+        )?;
+
+        for signal in &unsafe_constraint.signals {
+            write_edge(
+                &mut w,
+                &format!("s{}", signal),
+                &constraint_node,
+                "unsafe_constraint",
+                Some(unsafe_constraint.associated_constraint),
+            )?;
+        }
+    }
+
+    // Subcomponents, with edges from their inputs and to their outputs
+    for (cmp_index, subcomponent) in &verification_graph.subcomponents {
+        let component_node = format!("cmp{}", cmp_index);
+        let tree_constraints = &context.tree_constraints.subcomponents[*cmp_index];
+        write_node(
+            &mut w,
+            &component_node,
+            &format!(
+                "{}: {}",
+                tree_constraints.component_name, tree_constraints.template_name
+            ),
+            "subcomponent",
+            None,
+            &tree_constraints.component_name,
+        )?;
+
+        for input in &subcomponent.input_signals {
+            write_edge(
+                &mut w,
+                &format!("s{}", input),
+                &component_node,
+                "subcomponent_input",
+                None,
+            )?;
+        }
+
+        for output in &subcomponent.output_signals {
+            write_edge(
+                &mut w,
+                &component_node,
+                &format!("s{}", output),
+                "subcomponent_output",
+                None,
+            )?;
+        }
+    }
+
+    writeln!(w, "  </graph>")?;
+    writeln!(w, "</graphml>")?;
+    w.flush()?;
+
+    Ok(())
+}
+
+fn write_node(
+    w: &mut impl Write,
+    id: &str,
+    label: &str,
+    kind: &str,
+    initially_fixed: Option<bool>,
+    component: &str,
+) -> VerifierResult<()> {
+    writeln!(w, r#"    <node id="{}">"#, id)?;
+    writeln!(w, r#"      <data key="label">{}</data>"#, escape_xml(label))?;
+    writeln!(w, r#"      <data key="kind">{}</data>"#, kind)?;
+    if let Some(initially_fixed) = initially_fixed {
+        writeln!(
+            w,
+            r#"      <data key="initially_fixed">{}</data>"#,
+            initially_fixed
+        )?;
+    }
+    writeln!(
+        w,
+        r#"      <data key="component">{}</data>"#,
+        escape_xml(component)
+    )?;
+    writeln!(w, "    </node>")?;
+    Ok(())
+}
+
+fn write_edge(
+    w: &mut impl Write,
+    source: &str,
+    target: &str,
+    kind: &str,
+    constraint: Option<usize>,
+) -> VerifierResult<()> {
+    writeln!(w, r#"    <edge source="{}" target="{}">"#, source, target)?;
+    writeln!(w, r#"      <data key="edge_kind">{}</data>"#, kind)?;
+    if let Some(constraint) = constraint {
+        writeln!(w, r#"      <data key="constraint">{}</data>"#, constraint)?;
+    }
+    writeln!(w, "    </edge>")?;
+    Ok(())
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[test]
+fn test_escape_xml() {
+    assert_eq!(
+        escape_xml(r#"a[0] <"x" & 'y'>"#),
+        "a[0] &lt;&quot;x&quot; &amp; &apos;y&apos;&gt;"
+    );
+}
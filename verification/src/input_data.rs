@@ -1,112 +1,532 @@
 use crate::cli::Options;
+use crate::error::{VerifierError, VerifierResult};
+use crate::output_format::AnalysisOutput;
+use crate::reference::{load_reference_components, CanonicalComponent, ReferenceComponent};
+use crate::verified_components::{load_verified_components, VerifiedComponents};
 use crate::DebugSVGPrinter;
 use circom_algebra::algebra::Constraint;
 use circom_algebra::constraint_storage::ConstraintStorage;
+use colored::Colorize;
+use flate2::bufread::GzDecoder;
 use itertools::Itertools;
-use num_bigint_dig::BigInt;
-use serde::{Deserialize, Serialize};
+use num_bigint_dig::{BigInt, Sign};
+use num_traits::Zero;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
-use std::error::Error;
-use std::fs::File;
+use std::cmp::Reverse;
+use std::fmt;
+use std::fs::{self, File};
 use std::io::BufRead;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::{collections::HashMap, io};
-
-fn parse_constraint_list(path: &Path) -> Result<ConstraintStorage, Box<dyn Error>> {
-    let f = File::open(path)?;
-    let data: Value = serde_json::from_reader(f)?;
-
-    let o = data
-        .as_object()
-        .ok_or("constraint.json main value is not an object")?;
-    let json_constraint_list = o
-        .get("constraints")
-        .ok_or("constraint.json main object does not contain a constraints array")?;
-
-    let v = json_constraint_list
-        .as_array()
-        .ok_or("constraint.json 'constraints' value is not an array")?;
-    let mut storage = ConstraintStorage::new();
+use std::{
+    collections::{HashMap, HashSet},
+    io, iter,
+};
+use wildmatch::WildMatch;
+
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
+// Opens an artifact file for reading. Files with a .gz extension or starting with the gzip magic
+//  bytes are decompressed transparently
+fn open_artifact(path: &Path) -> VerifierResult<Box<dyn BufRead>> {
+    let mut reader = io::BufReader::new(File::open(path)?);
+    let is_gzipped = path.extension().is_some_and(|extension| extension == "gz")
+        || reader.fill_buf()?.starts_with(&GZIP_MAGIC_BYTES);
+
+    if is_gzipped {
+        Ok(Box::new(io::BufReader::new(GzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+// Returns the constraints of the file, together with the field prime if the file gives it (as
+//  the JSON export of snarkjs does)
+fn parse_constraint_list(
+    path: &Path,
+    strict: bool,
+) -> VerifierResult<(ConstraintStorage, Option<BigInt>)> {
+    let mut deserializer = serde_json::Deserializer::from_reader(open_artifact(path)?);
+    let constraint_list = parse_constraint_list_from_deserializer(&mut deserializer, strict)?;
+    deserializer.end()?;
+    Ok(constraint_list)
+}
+
+fn parse_linear_combination(x: &Value) -> VerifierResult<HashMap<SignalIndex, BigInt>> {
+    let m = x.as_object().ok_or_else(|| {
+        VerifierError::Parse("Constraint in 'constraint.json' has a non-object".to_string())
+    })?;
+    m.iter()
+        .map(|(k, v)| -> VerifierResult<(SignalIndex, BigInt)> {
+            let s = v.as_str().ok_or_else(|| {
+                VerifierError::Parse("Coefficient in 'constraint.json' is not a string".to_string())
+            })?;
+            Ok((k.parse::<usize>()?, s.parse::<BigInt>()?))
+        })
+        .collect()
+}
+
+// Each constraint is usually an array [A, B, C] of linear combinations. Some exporters append
+//  extra metadata elements after C: those are ignored with a warning, or rejected if strict is
+//  true. A 2-element array [A, C] is read as a constraint with an empty B.
+fn parse_constraint(
+    idx: usize,
+    val: &Value,
+    strict: bool,
+    num_constraints_with_extra_terms: &mut usize,
+) -> VerifierResult<Constraint<usize>> {
+    let arr = val.as_array().ok_or_else(|| {
+        VerifierError::Parse("constraint.json contains a non-array in constraint list".to_string())
+    })?;
+
+    let constraint = match arr.as_slice() {
+        [a, c] => {
+            // As B is empty, A*B vanishes and the constraint is linear. A is dropped so the
+            //  constraint is recognized as such by Constraint::is_linear
+            let _ = parse_linear_combination(a)?;
+            Constraint::new(HashMap::new(), HashMap::new(), parse_linear_combination(c)?)
+        }
+        [a, b, c, extra @ ..] => {
+            if !extra.is_empty() {
+                if strict {
+                    return Err(VerifierError::Parse(format!(
+                        "Constraint {} in constraint.json has {} terms, expected 3",
+                        idx,
+                        arr.len()
+                    )));
+                }
+                *num_constraints_with_extra_terms += 1;
+            }
+
+            Constraint::new(
+                parse_linear_combination(a)?,
+                parse_linear_combination(b)?,
+                parse_linear_combination(c)?,
+            )
+        }
+        _ => {
+            return Err(VerifierError::Parse(format!(
+                "Constraint {} in constraint.json has {} terms, expected 2 or 3",
+                idx,
+                arr.len()
+            )))
+        }
+    };
+
+    Ok(constraint)
+}
+
+// Reduces every coefficient of the stored constraints modulo the field prime, so they are always
+//  in [0, p) regardless of how the exporter wrote them. Coefficients that become zero are removed,
+//  as the signal does not really appear in the constraint.
+fn normalize_constraint_coefficients(constraint_storage: &mut ConstraintStorage, field: &BigInt) {
+    let normalize = |linear_combination: &HashMap<SignalIndex, BigInt>| {
+        linear_combination
+            .iter()
+            .map(|(signal, coefficient)| (*signal, ((coefficient % field) + field) % field))
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+            .collect()
+    };
+
+    for id in constraint_storage.get_ids() {
+        let constraint = constraint_storage.read_constraint(id).unwrap();
+        constraint_storage.replace(
+            id,
+            Constraint::new(
+                normalize(constraint.a()),
+                normalize(constraint.b()),
+                normalize(constraint.c()),
+            ),
+        );
+    }
+}
+
+// State of the streaming constraint list parser
+struct ConstraintListReader {
+    storage: ConstraintStorage,
+    strict: bool,
+
+    // Number of constraints whose extra elements have been ignored
+    num_constraints_with_extra_terms: usize,
+
+    // Entries only present in the JSON export of snarkjs: the field prime and the map from each
+    //  wire index used in the constraints to its signal index
+    prime: Option<BigInt>,
+    wire_map: Option<Vec<SignalIndex>>,
+
+    // Error found while checking the contents of the file. Serde errors can only carry a message,
+    //  so the original error is kept here to be returned unchanged
+    error: Option<VerifierError>,
+}
+
+impl ConstraintListReader {
+    fn fail<E: de::Error>(&mut self, error: VerifierError) -> E {
+        let e = E::custom(&error);
+        self.error = Some(error);
+        e
+    }
+}
+
+// Visitor of the main object of constraint.json. Only the "constraints" entry is read, and the
+//  "prime" and "map" entries of snarkjs exports
+struct ConstraintFileVisitor<'a> {
+    reader: &'a mut ConstraintListReader,
+}
+
+impl<'de, 'a> Visitor<'de> for ConstraintFileVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("constraint.json main value to be an object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut found_constraints = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "constraints" {
+                map.next_value_seed(ConstraintListVisitor {
+                    reader: &mut *self.reader,
+                })?;
+                found_constraints = true;
+            } else if key == "prime" {
+                let prime = map.next_value::<String>()?;
+                match prime.parse::<BigInt>() {
+                    Ok(prime) => self.reader.prime = Some(prime),
+                    Err(_) => {
+                        let msg = format!("Invalid field prime '{}' in constraint.json", prime);
+                        return Err(self.reader.fail(VerifierError::Parse(msg)));
+                    }
+                }
+            } else if key == "map" {
+                self.reader.wire_map = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        if !found_constraints {
+            let msg = "constraint.json main object does not contain a constraints array";
+            return Err(self.reader.fail(VerifierError::Parse(msg.to_string())));
+        }
+
+        Ok(())
+    }
+}
+
+// Visitor of the constraints array. Each constraint is read and added to the storage on its own,
+//  so the whole array is never held in memory as JSON values
+struct ConstraintListVisitor<'a> {
+    reader: &'a mut ConstraintListReader,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ConstraintListVisitor<'a> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
 
-    for val in v {
-        // Read one constraint
-        let arr = val
-            .as_array()
-            .ok_or("constraint.json contains a non-array in constraint list")?;
-        if arr.len() != 3 {
-            return Err("Constraint in constraint.json has more than 3 terms".into());
+impl<'de, 'a> Visitor<'de> for ConstraintListVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("constraint.json 'constraints' value to be an array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut idx = 0;
+        while let Some(val) = seq.next_element::<Value>()? {
+            let reader = &mut *self.reader;
+            match parse_constraint(
+                idx,
+                &val,
+                reader.strict,
+                &mut reader.num_constraints_with_extra_terms,
+            ) {
+                Ok(constraint) => {
+                    reader.storage.add_constraint(constraint);
+                }
+                Err(e) => return Err(reader.fail(e)),
+            }
+            idx += 1;
         }
 
-        let maybe_cs: Result<Vec<_>, _> = arr
+        Ok(())
+    }
+}
+
+// Converts the constraints of a snarkjs export to the form used by circom's JSON output. snarkjs
+//  writes each constraint as A*B - C = 0 over wire indices, while circom writes A*B + C = 0 over
+//  signal indices, so C is negated and every wire is replaced by its signal in wire_map (if any).
+//  The coefficients of C are reduced to the field later, with the rest of the coefficients.
+fn convert_snarkjs_constraints(
+    constraint_storage: &mut ConstraintStorage,
+    wire_map: Option<&[SignalIndex]>,
+) -> VerifierResult<()> {
+    let map_wires = |linear_combination: &HashMap<SignalIndex, BigInt>, negate: bool| {
+        linear_combination
             .iter()
-            .map(
-                |x| -> Result<HashMap<SignalIndex, BigInt>, Box<dyn Error>> {
-                    let m = x
-                        .as_object()
-                        .ok_or("Constraint in 'constraint.json' has a non-object")?;
-                    m.iter()
-                        .map(|(k, v)| -> Result<(SignalIndex, BigInt), Box<dyn Error>> {
-                            let s = v
-                                .as_str()
-                                .ok_or("Coefficient in 'constraint.json' is not a string")?;
-                            Ok((k.parse::<usize>()?, s.parse::<BigInt>()?))
-                        })
-                        .collect()
-                },
+            .map(|(wire, coefficient)| {
+                let signal = match wire_map {
+                    Some(wire_map) => *wire_map.get(*wire).ok_or_else(|| {
+                        VerifierError::Parse(format!(
+                            "Wire {} in constraint.json is not in its map of {} wires",
+                            wire,
+                            wire_map.len()
+                        ))
+                    })?,
+                    None => *wire,
+                };
+                let coefficient = if negate {
+                    -coefficient
+                } else {
+                    coefficient.clone()
+                };
+                Ok((signal, coefficient))
+            })
+            .collect::<VerifierResult<HashMap<_, _>>>()
+    };
+
+    for id in constraint_storage.get_ids() {
+        let constraint = constraint_storage.read_constraint(id).unwrap();
+        constraint_storage.replace(
+            id,
+            Constraint::new(
+                map_wires(constraint.a(), false)?,
+                map_wires(constraint.b(), false)?,
+                map_wires(constraint.c(), true)?,
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+// Reads the constraint list from any JSON deserializer, adding constraints to the storage as they
+//  are read instead of building the whole JSON document first. Files with a "prime" entry are
+//  JSON exports of snarkjs, whose constraints are converted to the form of circom's JSON output.
+//  Their prime is returned along with the constraints
+fn parse_constraint_list_from_deserializer<'de, D>(
+    deserializer: D,
+    strict: bool,
+) -> VerifierResult<(ConstraintStorage, Option<BigInt>)>
+where
+    D: Deserializer<'de>,
+    D::Error: Into<VerifierError>,
+{
+    let mut reader = ConstraintListReader {
+        storage: ConstraintStorage::new(),
+        strict,
+        num_constraints_with_extra_terms: 0,
+        prime: None,
+        wire_map: None,
+        error: None,
+    };
+
+    let result = deserializer.deserialize_map(ConstraintFileVisitor {
+        reader: &mut reader,
+    });
+    if let Some(e) = reader.error.take() {
+        return Err(e);
+    }
+    result.map_err(Into::into)?;
+
+    if reader.num_constraints_with_extra_terms > 0 {
+        println!(
+            "{}",
+            format!(
+                "Warning: ignored extra terms after the third one in {} constraints of constraint.json",
+                reader.num_constraints_with_extra_terms
             )
-            .collect();
+            .yellow()
+        );
+    }
 
-        let (a, b, c) = maybe_cs?.into_iter().collect_tuple().unwrap();
-        storage.add_constraint(Constraint::new(a, b, c));
+    if reader.prime.is_some() {
+        convert_snarkjs_constraints(&mut reader.storage, reader.wire_map.as_deref())?;
     }
 
-    Ok(storage)
+    Ok((reader.storage, reader.prime))
 }
 
 pub type ConstraintIndex = usize;
 pub type Witness = HashMap<SignalIndex, BigInt>;
 
-fn parse_witness(path: &Path) -> Result<Witness, Box<dyn Error>> {
-    let f = File::open(path)?;
-    let data: Value = serde_json::from_reader(f)?;
+fn parse_witness(path: &Path, number_signals: usize) -> VerifierResult<Witness> {
+    parse_witness_from_reader(open_artifact(path)?, number_signals)
+}
 
-    let o = data
-        .as_object()
-        .ok_or("witness.json main value is not an object")?;
-    let map = o
-        .iter()
-        .map(|(k, v)| -> Result<(usize, BigInt), Box<dyn Error>> {
-            let s = v
-                .as_str()
-                .ok_or("witness.json has a witness value that is not a string")?;
-            Ok((k.parse::<usize>()?, s.parse::<BigInt>()?))
-        })
-        .collect::<Result<Witness, Box<dyn Error>>>()?;
+// Parses a witness given either as an object mapping each signal index to its value, or as a list
+//  of values indexed by position (as exported by some versions of snarkjs). A list must contain the
+//  value of each of the number_signals signals of the circuit, starting with the constant signal.
+fn parse_witness_from_reader(
+    reader: impl io::Read,
+    number_signals: usize,
+) -> VerifierResult<Witness> {
+    let data: Value = serde_json::from_reader(reader)?;
+
+    let parse_value = |v: &Value| -> VerifierResult<BigInt> {
+        let s = v.as_str().ok_or_else(|| {
+            VerifierError::Parse(
+                "witness.json has a witness value that is not a string".to_string(),
+            )
+        })?;
+        Ok(s.parse::<BigInt>()?)
+    };
+
+    match data {
+        Value::Object(o) => o
+            .iter()
+            .map(|(k, v)| -> VerifierResult<(usize, BigInt)> {
+                Ok((k.parse::<usize>()?, parse_value(v)?))
+            })
+            .collect(),
+        Value::Array(values) => {
+            if values.len() < number_signals {
+                return Err(VerifierError::MalformedArtifact(format!(
+                    "witness.json has {} values, but the circuit has {} signals",
+                    values.len(),
+                    number_signals
+                )));
+            }
 
-    Ok(map)
+            values
+                .iter()
+                .enumerate()
+                .map(|(idx, v)| -> VerifierResult<(usize, BigInt)> { Ok((idx, parse_value(v)?)) })
+                .collect()
+        }
+        _ => Err(VerifierError::Parse(
+            "witness.json main value is not an object or a list".to_string(),
+        )),
+    }
 }
 
 pub type SignalIndex = usize;
 pub type SignalNameMap = HashMap<SignalIndex, String>;
 
-fn parse_signal_name_map(path: &Path) -> Result<SignalNameMap, Box<dyn Error>> {
-    let f = File::open(path)?;
-    let mut map = SignalNameMap::new();
+// Inverse of SignalNameMap, keyed by fully-qualified signal name (for example, "main.a"). A name
+//  may be associated to more than one index in malformed .sym files.
+pub type SignalIndexMap = HashMap<String, Vec<SignalIndex>>;
 
-    for maybe_line in io::BufReader::new(f).lines() {
-        let line = maybe_line.unwrap();
-        let (id, _, _, fully_qualified_name) = line
+// Position of the columns used by the verifier in a .sym file. By default the columns are signal
+//  index, witness index, component index and fully-qualified name, but the file may start with a
+//  header row declaring a different order, such as "signal,component,witness,name".
+#[derive(Debug, PartialEq)]
+struct SymColumns {
+    number_of_columns: usize,
+    signal_column: usize,
+    name_column: usize,
+}
+
+impl Default for SymColumns {
+    fn default() -> Self {
+        SymColumns {
+            number_of_columns: 4,
+            signal_column: 0,
+            name_column: 3,
+        }
+    }
+}
+
+impl SymColumns {
+    // Returns None if the line is not a header row, that is, if it starts with a signal index
+    fn from_header(line: &str) -> VerifierResult<Option<Self>> {
+        let column_names: Vec<_> = line
             .split(',')
-            .collect_tuple()
-            .ok_or("Invalid number of entries per line in 'circuit_signals.sym'")?;
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+        if column_names[0].parse::<SignalIndex>().is_ok() {
+            return Ok(None);
+        }
+
+        let find_column = |name: &str| {
+            column_names
+                .iter()
+                .position(|column| column == name)
+                .ok_or_else(|| {
+                    VerifierError::Parse(format!(
+                        "Header row of 'circuit_signals.sym' has no '{}' column",
+                        name
+                    ))
+                })
+        };
+
+        Ok(Some(SymColumns {
+            number_of_columns: column_names.len(),
+            signal_column: find_column("signal")?,
+            name_column: find_column("name")?,
+        }))
+    }
+
+    // Returns the signal index and the fully-qualified name of a line. Names may contain commas
+    //  (for example, "main.a[1,2]"), so the name takes all the text between the columns before it
+    //  and the columns after it.
+    fn parse_line<'a>(&self, line: &'a str) -> VerifierResult<(&'a str, &'a str)> {
+        let invalid_line = || {
+            VerifierError::Parse(format!(
+                "Invalid number of entries in line '{}' of 'circuit_signals.sym'",
+                line
+            ))
+        };
+
+        let columns_after_name = self.number_of_columns - self.name_column - 1;
+        let mut columns: Vec<_> = line.splitn(self.name_column + 1, ',').collect();
+        if columns.len() != self.name_column + 1 {
+            return Err(invalid_line());
+        }
+
+        let name_and_rest = columns.pop().unwrap();
+        let mut rest: Vec<_> = name_and_rest.rsplitn(columns_after_name + 1, ',').collect();
+        if rest.len() != columns_after_name + 1 {
+            return Err(invalid_line());
+        }
+        rest.reverse();
+        columns.extend(rest);
+
+        Ok((columns[self.signal_column], columns[self.name_column]))
+    }
+}
+
+fn parse_signal_name_map(path: &Path) -> VerifierResult<(SignalNameMap, SignalIndexMap)> {
+    parse_signal_name_map_from_reader(open_artifact(path)?)
+}
+
+fn parse_signal_name_map_from_reader(
+    reader: impl BufRead,
+) -> VerifierResult<(SignalNameMap, SignalIndexMap)> {
+    let mut map = SignalNameMap::new();
+    let mut inverse_map = SignalIndexMap::new();
+    let mut columns = SymColumns::default();
+
+    for (line_number, maybe_line) in reader.lines().enumerate() {
+        let line = maybe_line?;
+        if line_number == 0 {
+            if let Some(header_columns) = SymColumns::from_header(&line)? {
+                columns = header_columns;
+                continue;
+            }
+        }
+
+        let (id, fully_qualified_name) = columns.parse_line(&line)?;
+        let signal_index = id.parse::<SignalIndex>()?;
 
         // Remove first component path from name, that is, remove the initial "main."
         let (_, name) = fully_qualified_name.split_once('.').unwrap();
-        map.insert(id.parse::<SignalIndex>()?, name.to_string());
+        map.insert(signal_index, name.to_string());
+
+        inverse_map
+            .entry(fully_qualified_name.to_string())
+            .or_default()
+            .push(signal_index);
     }
 
-    Ok(map)
+    Ok((map, inverse_map))
 }
 
 pub type ComponentIndex = usize;
@@ -127,93 +547,853 @@ pub struct TreeConstraints {
     pub are_double_arrow: Vec<(ConstraintIndex, SignalIndex)>,
     // first number constraint, second number assigned signal
     pub subcomponents: Vec<TreeConstraints>,
+
+    // Optional origin of the constraints of this component (for example, their source location),
+    //  keyed by constraint index. Only used for debugging output
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub constraint_origins: HashMap<ConstraintIndex, String>,
+}
+
+fn parse_field(tree_constraints: &TreeConstraints) -> VerifierResult<BigInt> {
+    BigInt::from_str(&tree_constraints.field).map_err(|_| {
+        VerifierError::Parse(format!(
+            "Invalid field prime '{}' in tree constraints",
+            tree_constraints.field
+        ))
+    })
+}
+
+// Reads the field prime from the header of a .r1cs file. The file starts with the magic "r1cs",
+//  the version and the number of sections, followed by each section as its type, its size and its
+//  contents. The header section (type 1) starts with the size in bytes of the field elements,
+//  followed by the prime in little endian.
+fn parse_r1cs_prime(mut reader: impl io::Read + io::Seek) -> VerifierResult<BigInt> {
+    const R1CS_HEADER_SECTION: u32 = 1;
+
+    let read_u32 = |reader: &mut dyn io::Read| -> io::Result<u32> {
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    };
+
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"r1cs" {
+        return Err(VerifierError::MalformedArtifact(
+            "The .r1cs file does not start with the magic 'r1cs'".to_string(),
+        ));
+    }
+
+    let _version = read_u32(&mut reader)?;
+    let number_sections = read_u32(&mut reader)?;
+    for _ in 0..number_sections {
+        let section_type = read_u32(&mut reader)?;
+        let mut size_bytes = [0u8; 8];
+        reader.read_exact(&mut size_bytes)?;
+        let section_size = u64::from_le_bytes(size_bytes);
+
+        if section_type == R1CS_HEADER_SECTION {
+            let field_size = read_u32(&mut reader)?;
+            let mut prime_bytes = vec![0u8; field_size as usize];
+            reader.read_exact(&mut prime_bytes)?;
+            return Ok(BigInt::from_bytes_le(Sign::Plus, &prime_bytes));
+        }
+
+        reader.seek(io::SeekFrom::Current(section_size as i64))?;
+    }
+
+    Err(VerifierError::MalformedArtifact(
+        "The .r1cs file has no header section".to_string(),
+    ))
 }
 
-fn parse_tree_constraints(path: &Path) -> Result<TreeConstraints, Box<dyn Error>> {
-    let f = File::open(path)?;
-    let constraints: TreeConstraints = serde_json::from_reader(f)?;
+// Resolves the prime of the field. In order of precedence, it is the prime given in the options,
+//  the one in the header of the .r1cs file (if present), the one in the constraints file (if it
+//  is a snarkjs export) or the one in the tree constraints. A warning is printed for every other
+//  source that disagrees with the resolved prime.
+fn resolve_field(
+    prime_option: Option<&BigInt>,
+    r1cs_prime: Option<BigInt>,
+    constraints_prime: Option<BigInt>,
+    tree_constraints: &TreeConstraints,
+) -> VerifierResult<BigInt> {
+    let tree_prime = match (prime_option, &r1cs_prime, &constraints_prime) {
+        (None, None, None) => return parse_field(tree_constraints),
+        // The tree constraints prime is only needed to warn about a mismatch
+        _ => parse_field(tree_constraints).ok(),
+    };
+
+    let sources = [
+        ("--prime", prime_option.cloned()),
+        (".r1cs header", r1cs_prime),
+        ("constraints file", constraints_prime),
+        ("tree constraints", tree_prime),
+    ];
+    let mut sources = sources
+        .into_iter()
+        .filter_map(|(name, prime)| prime.map(|p| (name, p)));
+
+    let (resolved_name, resolved) = sources.next().unwrap();
+    for (name, prime) in sources {
+        if prime != resolved {
+            println!(
+                "{}",
+                format!(
+                    "Warning: the field prime in the {} ({}) differs from the one in the {} ({}), which is used",
+                    name, prime, resolved_name, resolved
+                )
+                .yellow()
+            );
+        }
+    }
+
+    Ok(resolved)
+}
+
+fn parse_tree_constraints(path: &Path) -> VerifierResult<TreeConstraints> {
+    let constraints: TreeConstraints = serde_json::from_reader(open_artifact(path)?)?;
 
     Ok(constraints)
 }
 
+// Checks that every (constraint, lhs_signal) pair in are_double_arrow references an existing
+//  constraint of its component in which lhs_signal appears, and that lhs_signal is not an input of
+//  the component. Inputs are fixed from the start, so an assignment to one of them would corrupt
+//  the verification graph. Checks all subcomponents recursively.
+fn validate_double_arrow_constraints(
+    tree_constraints: &TreeConstraints,
+    signal_name_map: &SignalNameMap,
+    constraint_storage: &ConstraintStorage,
+) -> VerifierResult<()> {
+    let component_constraints = tree_constraints.initial_constraint
+        ..(tree_constraints.initial_constraint + tree_constraints.no_constraints);
+    let first_input = tree_constraints.initial_signal + tree_constraints.number_outputs;
+    let component_inputs = first_input..(first_input + tree_constraints.number_inputs);
+
+    for (constraint_idx, lhs_signal) in &tree_constraints.are_double_arrow {
+        let constraint = constraint_storage
+            .read_constraint(*constraint_idx)
+            .ok_or_else(|| {
+                VerifierError::MalformedArtifact(format!(
+                    "Safe assignment in component '{}' references constraint {}, which does not exist",
+                    tree_constraints.component_name, constraint_idx
+                ))
+            })?;
+
+        if !component_constraints.contains(constraint_idx) {
+            return Err(VerifierError::MalformedArtifact(format!(
+                "Safe assignment in component '{}' references constraint {}, which is outside of the component constraints [{}, {})",
+                tree_constraints.component_name,
+                constraint_idx,
+                component_constraints.start,
+                component_constraints.end
+            )));
+        }
+
+        if !constraint.take_cloned_signals().contains(lhs_signal) {
+            return Err(VerifierError::MalformedArtifact(format!(
+                "Safe assignment in component '{}' has LHS signal {}, which does not appear in constraint {}",
+                tree_constraints.component_name, lhs_signal, constraint_idx
+            )));
+        }
+
+        if component_inputs.contains(lhs_signal) {
+            return Err(VerifierError::MalformedArtifact(format!(
+                "Safe assignment in component '{}' has LHS signal {} ({}), which is an input of the component",
+                tree_constraints.component_name,
+                lhs_signal,
+                get_signal_name(signal_name_map, *lhs_signal)
+            )));
+        }
+    }
+
+    for subcomponent in &tree_constraints.subcomponents {
+        validate_double_arrow_constraints(subcomponent, signal_name_map, constraint_storage)?;
+    }
+
+    Ok(())
+}
+
+// Range of signals or constraints of a component. The preorder range contains the position of the
+//  component in a preorder traversal of the component tree, followed by the positions of all its
+//  descendants, so it tells whether a component is an ancestor of another one
+struct ComponentRange<'a> {
+    range: Range<usize>,
+    component_name: &'a str,
+    preorder: Range<usize>,
+}
+
+// Collects the signal and constraint ranges of a component and all its subcomponents. Returns the
+//  preorder position following the last descendant of the component
+fn collect_component_ranges<'a>(
+    tree_constraints: &'a TreeConstraints,
+    preorder_start: usize,
+    signal_ranges: &mut Vec<ComponentRange<'a>>,
+    constraint_ranges: &mut Vec<ComponentRange<'a>>,
+) -> usize {
+    let mut preorder_end = preorder_start + 1;
+    for subcomponent in &tree_constraints.subcomponents {
+        preorder_end =
+            collect_component_ranges(subcomponent, preorder_end, signal_ranges, constraint_ranges);
+    }
+
+    let component_name = tree_constraints.component_name.as_str();
+    let preorder = preorder_start..preorder_end;
+    signal_ranges.push(ComponentRange {
+        range: tree_constraints.initial_signal
+            ..(tree_constraints.initial_signal + tree_constraints.number_signals),
+        component_name,
+        preorder: preorder.clone(),
+    });
+    constraint_ranges.push(ComponentRange {
+        range: tree_constraints.initial_constraint
+            ..(tree_constraints.initial_constraint + tree_constraints.no_constraints),
+        component_name,
+        preorder,
+    });
+
+    preorder_end
+}
+
+// Checks that any two of the given ranges are either disjoint, or nested with the inner range
+//  belonging to a descendant of the component of the outer one. kind names the ranges in errors.
+fn check_component_ranges(
+    mut ranges: Vec<ComponentRange>,
+    kind: &str,
+) -> VerifierResult<()> {
+    ranges.retain(|r| !r.range.is_empty());
+
+    // Outer ranges are sorted before the ranges nested in them
+    ranges.sort_by_key(|r| (r.range.start, Reverse(r.range.end), r.preorder.start));
+
+    // Chain of ranges containing the current one, from the outermost to the innermost
+    let mut enclosing: Vec<&ComponentRange> = Vec::new();
+    for r in &ranges {
+        while enclosing
+            .last()
+            .is_some_and(|e| e.range.end <= r.range.start)
+        {
+            enclosing.pop();
+        }
+
+        if let Some(e) = enclosing.last() {
+            let is_nested = r.range.end <= e.range.end
+                && e.preorder.start < r.preorder.start
+                && r.preorder.end <= e.preorder.end;
+
+            if !is_nested {
+                return Err(VerifierError::MalformedArtifact(format!(
+                    "The {} of component '{}' [{}, {}) overlap with those of component '{}' [{}, {})",
+                    kind,
+                    e.component_name,
+                    e.range.start,
+                    e.range.end,
+                    r.component_name,
+                    r.range.start,
+                    r.range.end
+                )));
+            }
+        }
+
+        enclosing.push(r);
+    }
+
+    Ok(())
+}
+
+// Checks that the signal and constraint ranges of different components do not collide, as the
+//  verification graph of each component is built assuming so. Ranges of a subcomponent may only be
+//  nested inside the ranges of its ancestors.
+fn validate_component_ranges(tree_constraints: &TreeConstraints) -> VerifierResult<()> {
+    let mut signal_ranges = Vec::new();
+    let mut constraint_ranges = Vec::new();
+    collect_component_ranges(
+        tree_constraints,
+        0,
+        &mut signal_ranges,
+        &mut constraint_ranges,
+    );
+
+    check_component_ranges(signal_ranges, "signals")?;
+    check_component_ranges(constraint_ranges, "constraints")
+}
+
+// Formats a list of signals for error messages, showing only the first ones of long lists
+fn format_signal_list(signals: &[String]) -> String {
+    const MAX_SHOWN: usize = 10;
+    let mut list = signals.iter().take(MAX_SHOWN).join(", ");
+    if signals.len() > MAX_SHOWN {
+        list.push_str(&format!(" and {} more", signals.len() - MAX_SHOWN));
+    }
+    list
+}
+
+// Returns the number of signals of the circuit, including the constant signal 0, that is, the end
+//  of the last signal range of the component and its subcomponents. The ranges of subcomponents
+//  are not necessarily nested in the range of the main component
+fn get_total_number_signals(tree_constraints: &TreeConstraints) -> usize {
+    tree_constraints
+        .subcomponents
+        .iter()
+        .map(get_total_number_signals)
+        .fold(
+            tree_constraints.initial_signal + tree_constraints.number_signals,
+            usize::max,
+        )
+}
+
+// Checks that every signal index used by the constraints or given a value by the witnesses belongs
+//  to the signals of the circuit. A corrupt artifact could otherwise contain huge indices, which
+//  would only make the verification panic much later. All out of range signals are reported at
+//  once, with the constraint they appear in
+fn validate_signal_indices(
+    tree_constraints: &TreeConstraints,
+    constraint_storage: &ConstraintStorage,
+    witnesses: &[&Witness],
+) -> VerifierResult<()> {
+    let number_signals = get_total_number_signals(tree_constraints);
+
+    let out_of_range_constraint_signals: Vec<String> = constraint_storage
+        .get_ids()
+        .into_iter()
+        .flat_map(|constraint_idx| {
+            let constraint = constraint_storage.read_constraint(constraint_idx).unwrap();
+            constraint
+                .take_cloned_signals_ordered()
+                .into_iter()
+                .filter(|signal| *signal >= number_signals)
+                .map(move |signal| format!("{} (constraint {})", signal, constraint_idx))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    if !out_of_range_constraint_signals.is_empty() {
+        return Err(VerifierError::MalformedArtifact(format!(
+            "Constraints use signals {}, but the circuit only has signals [0, {})",
+            format_signal_list(&out_of_range_constraint_signals),
+            number_signals
+        )));
+    }
+
+    let out_of_range_witness_signals: Vec<String> = witnesses
+        .iter()
+        .flat_map(|witness| witness.keys())
+        .filter(|signal| **signal >= number_signals)
+        .sorted()
+        .dedup()
+        .map(|signal| signal.to_string())
+        .collect();
+    if !out_of_range_witness_signals.is_empty() {
+        return Err(VerifierError::MalformedArtifact(format!(
+            "The witness has values for signals {}, but the circuit only has signals [0, {})",
+            format_signal_list(&out_of_range_witness_signals),
+            number_signals
+        )));
+    }
+
+    Ok(())
+}
+
+// Checks that the signals of each component match the signals that actually belong to it. The
+//  verification graph takes them from the contiguous range [initial_signal, initial_signal +
+//  number_signals), with the outputs first, then the inputs and then the intermediates, so a wrong
+//  range in the tree constraints would silently misplace signals. Every signal of the range must
+//  have a name inside the component in the .sym file, and the constraints of the component may
+//  only use its own signals and the inputs and outputs of its subcomponents. Checks all
+//  subcomponents recursively.
+fn validate_component_signals(
+    tree_constraints: &TreeConstraints,
+    main_component_name: &str,
+    signal_name_map: &SignalNameMap,
+    constraint_storage: &ConstraintStorage,
+) -> VerifierResult<()> {
+    let component_name = &tree_constraints.component_name;
+    let signals = tree_constraints.initial_signal
+        ..(tree_constraints.initial_signal + tree_constraints.number_signals);
+
+    if tree_constraints.number_outputs + tree_constraints.number_inputs > signals.len() {
+        return Err(VerifierError::MalformedArtifact(format!(
+            "Component '{}' has {} outputs and {} inputs, but only {} signals",
+            component_name,
+            tree_constraints.number_outputs,
+            tree_constraints.number_inputs,
+            signals.len()
+        )));
+    }
+
+    // Names in signal_name_map are relative to the main component. Signals in the range of a
+    //  subcomponent nested in this one are checked with that subcomponent, and signals without a
+    //  name (see get_signal_name) cannot be checked
+    let component_prefix = format!("{}.", component_name);
+    let subcomponent_prefixes: Vec<String> = tree_constraints
+        .subcomponents
+        .iter()
+        .map(|c| format!("{}.", c.component_name))
+        .collect();
+    let nested_signals: Vec<Range<SignalIndex>> = tree_constraints
+        .subcomponents
+        .iter()
+        .map(|c| c.initial_signal..(c.initial_signal + c.number_signals))
+        .filter(|r| signals.start <= r.start && r.end <= signals.end)
+        .collect();
+    let foreign_signals: Vec<String> = signals
+        .clone()
+        .filter(|signal| !nested_signals.iter().any(|r| r.contains(signal)))
+        .filter_map(|signal| {
+            let qualified_name =
+                format!("{}.{}", main_component_name, signal_name_map.get(&signal)?);
+            let belongs = qualified_name.starts_with(&component_prefix)
+                && !subcomponent_prefixes
+                    .iter()
+                    .any(|prefix| qualified_name.starts_with(prefix));
+            (!belongs).then(|| format!("{} ({})", signal, qualified_name))
+        })
+        .collect();
+    if !foreign_signals.is_empty() {
+        return Err(VerifierError::MalformedArtifact(format!(
+            "Signals {} are in the range [{}, {}) of component '{}', but do not belong to it",
+            format_signal_list(&foreign_signals),
+            signals.start,
+            signals.end,
+            component_name
+        )));
+    }
+
+    let subcomponent_signals: Vec<Range<SignalIndex>> = tree_constraints
+        .subcomponents
+        .iter()
+        .map(|c| c.initial_signal..(c.initial_signal + c.number_outputs + c.number_inputs))
+        .collect();
+    let is_known_signal = |signal: &SignalIndex| {
+        *signal == 0
+            || signals.contains(signal)
+            || subcomponent_signals.iter().any(|r| r.contains(signal))
+    };
+
+    for constraint_idx in tree_constraints.initial_constraint
+        ..(tree_constraints.initial_constraint + tree_constraints.no_constraints)
+    {
+        let constraint = constraint_storage
+            .read_constraint(constraint_idx)
+            .ok_or_else(|| {
+                VerifierError::MalformedArtifact(format!(
+                    "Component '{}' has constraint {}, which does not exist",
+                    component_name, constraint_idx
+                ))
+            })?;
+
+        if let Some(signal) = constraint
+            .take_cloned_signals_ordered()
+            .into_iter()
+            .find(|signal| !is_known_signal(signal))
+        {
+            return Err(VerifierError::MalformedArtifact(format!(
+                "Constraint {} of component '{}' uses signal {} ({}), which is neither one of its signals [{}, {}) nor an input or output of its subcomponents",
+                constraint_idx,
+                component_name,
+                signal,
+                get_signal_name(signal_name_map, signal),
+                signals.start,
+                signals.end
+            )));
+        }
+    }
+
+    for subcomponent in &tree_constraints.subcomponents {
+        validate_component_signals(
+            subcomponent,
+            main_component_name,
+            signal_name_map,
+            constraint_storage,
+        )?;
+    }
+
+    Ok(())
+}
+
 pub struct InputDataContext {
-    pub witness: Witness,
+    // The witness is only needed for weak safety verification, so it may be missing for
+    //  structural-only checks
+    pub witness: Option<Witness>,
+
+    // Witnesses given in the options after the first one, to check polynomial systems at several
+    //  points at once
+    pub extra_witnesses: Vec<Witness>,
     pub signal_name_map: SignalNameMap,
+    pub signal_index_map: SignalIndexMap,
     pub tree_constraints: TreeConstraints,
+
+    // Prime of the field Z_p, resolved from the options and the artifacts
+    pub field: BigInt,
     pub base_path: String,
+
+    // Folder where generated files, such as CoCoA scripts and SVG diagrams, are written. It is
+    //  the artifacts folder unless another one is given in the options
+    pub output_path: String,
     pub svg_printer: DebugSVGPrinter,
     pub options: Options,
+
+    // Reference components loaded from the folders given in the options
+    pub reference_components: Vec<ReferenceComponent>,
+
+    // Components found safe by previous runs, only loaded with the skip_verified option
+    pub verified_components: VerifiedComponents,
 }
 
 pub struct InputDataContextView<'a> {
-    pub witness: &'a Witness,
+    pub witness: Option<&'a Witness>,
+    pub extra_witnesses: &'a [Witness],
     pub signal_name_map: &'a SignalNameMap,
+    pub signal_index_map: &'a SignalIndexMap,
     pub tree_constraints: &'a TreeConstraints,
     pub field: BigInt,
     pub base_path: &'a String,
+    pub output_path: &'a String,
     pub svg_printer: &'a DebugSVGPrinter,
     pub options: &'a Options,
+    pub reference_components: &'a [ReferenceComponent],
+    pub verified_components: &'a VerifiedComponents,
+}
+
+// Paths of the artifacts read by the verifier. Each of them may be given individually in the
+//  options, and otherwise it has its conventional name inside the artifacts folder, or that name
+//  followed by .gz if only the compressed artifact exists
+pub struct ArtifactPaths {
+    pub constraints: PathBuf,
+    pub witness: PathBuf,
+    pub signal_names: PathBuf,
+    pub tree_constraints: PathBuf,
+
+    // Only read for the field prime in its header. It is optional, as circom only writes it when
+    //  asked to
+    pub r1cs: PathBuf,
+}
+
+impl ArtifactPaths {
+    pub fn new(folder_base_path: &Path, options: &Options) -> ArtifactPaths {
+        let get_path = |path: &Option<PathBuf>, default_name: &str| -> PathBuf {
+            path.clone().unwrap_or_else(|| {
+                let default_path = folder_base_path.join(default_name);
+                let gzipped_path = folder_base_path.join(format!("{}.gz", default_name));
+                if !default_path.exists() && gzipped_path.exists() {
+                    gzipped_path
+                } else {
+                    default_path
+                }
+            })
+        };
+
+        ArtifactPaths {
+            constraints: get_path(&options.constraints_path, "circuit_constraints.json"),
+            witness: get_path(&options.witness_path, "witness.json"),
+            signal_names: get_path(&options.sym_path, "circuit_signals.sym"),
+            tree_constraints: get_path(&options.tree_path, "circuit_treeconstraints.json"),
+            r1cs: get_path(&options.r1cs_path, "circuit.r1cs"),
+        }
+    }
 }
 
 impl InputDataContext {
-    //noinspection SpellCheckingInspection
+    // Parses the artifacts of the circuit in the given folder, except those whose path is given in
+    //  the options
     pub fn parse_from_files(
         folder_base_path: &Path,
         options: Options,
-    ) -> Result<(InputDataContext, ConstraintStorage), Box<dyn Error>> {
-        let constraint_storage =
-            parse_constraint_list(folder_base_path.join("circuit_constraints.json").as_path())?;
-        let witness = parse_witness(folder_base_path.join("witness.json").as_path())?;
-        let signal_name_map =
-            parse_signal_name_map(folder_base_path.join("circuit_signals.sym").as_path())?;
-        let tree_constraints = parse_tree_constraints(
-            folder_base_path
-                .join("circuit_treeconstraints.json")
-                .as_path(),
+    ) -> VerifierResult<(InputDataContext, ConstraintStorage)> {
+        let artifact_paths = ArtifactPaths::new(folder_base_path, &options);
+        InputDataContext::parse_from_artifact_paths(folder_base_path, &artifact_paths, options)
+    }
+
+    // Parses the given artifacts. Files generated by the verifier, such as CoCoA scripts, are
+    //  written to the output directory given in the options, or to folder_base_path otherwise
+    //noinspection SpellCheckingInspection
+    pub fn parse_from_artifact_paths(
+        folder_base_path: &Path,
+        artifact_paths: &ArtifactPaths,
+        options: Options,
+    ) -> VerifierResult<(InputDataContext, ConstraintStorage)> {
+        let (mut constraint_storage, constraints_prime) =
+            parse_constraint_list(&artifact_paths.constraints, options.strict)?;
+        let tree_constraints = parse_tree_constraints(&artifact_paths.tree_constraints)?;
+        // The constant signal 0 comes before the signals of the main component
+        let number_signals = tree_constraints.initial_signal + tree_constraints.number_signals;
+        let witness = if artifact_paths.witness.exists() {
+            Some(parse_witness(&artifact_paths.witness, number_signals)?)
+        } else {
+            None
+        };
+        let extra_witnesses = options
+            .extra_witness_paths
+            .iter()
+            .map(|path| parse_witness(path, number_signals))
+            .collect::<VerifierResult<Vec<_>>>()?;
+        let (signal_name_map, signal_index_map) =
+            parse_signal_name_map(&artifact_paths.signal_names)?;
+        let r1cs_prime = if artifact_paths.r1cs.exists() {
+            let f = File::open(&artifact_paths.r1cs)?;
+            Some(parse_r1cs_prime(io::BufReader::new(f))?)
+        } else {
+            None
+        };
+        let field = resolve_field(
+            options.prime.as_ref(),
+            r1cs_prime,
+            constraints_prime,
+            &tree_constraints,
+        )?;
+        normalize_constraint_coefficients(&mut constraint_storage, &field);
+        validate_signal_indices(
+            &tree_constraints,
+            &constraint_storage,
+            &witness.iter().chain(&extra_witnesses).collect::<Vec<_>>(),
+        )?;
+        validate_component_ranges(&tree_constraints)?;
+        validate_component_signals(
+            &tree_constraints,
+            &tree_constraints.component_name,
+            &signal_name_map,
+            &constraint_storage,
+        )?;
+        let num_unnamed_signals =
+            count_unnamed_signals(&tree_constraints, &signal_name_map, &constraint_storage);
+        if num_unnamed_signals > 0 {
+            println!(
+                "{}",
+                format!(
+                    "[Warning] {} signals do not appear in '{}', they are named signal_<index>",
+                    num_unnamed_signals,
+                    artifact_paths.signal_names.display()
+                )
+                .yellow()
+            );
+        }
+        validate_double_arrow_constraints(
+            &tree_constraints,
+            &signal_name_map,
+            &constraint_storage,
         )?;
+        let reference_components = load_reference_components(&options)?;
+        let output_path = options
+            .out_dir
+            .clone()
+            .unwrap_or_else(|| folder_base_path.to_path_buf());
+        fs::create_dir_all(&output_path)?;
+        let verified_components = load_verified_components(&options, &output_path)?;
 
         Ok((
             InputDataContext {
                 witness,
+                extra_witnesses,
                 signal_name_map,
+                signal_index_map,
                 tree_constraints,
+                field,
                 base_path: folder_base_path.to_str().unwrap().to_string(),
-                svg_printer: DebugSVGPrinter::new(folder_base_path.join("svg").to_str().unwrap()),
+                output_path: output_path.to_str().unwrap().to_string(),
+                svg_printer: DebugSVGPrinter::new(output_path.join("svg").to_str().unwrap()),
                 options,
+                reference_components,
+                verified_components,
             },
             constraint_storage,
         ))
     }
 
-    pub fn get_context_view(&self) -> InputDataContextView {
-        // FIXME: Use another better .json format in order not to store the field prime
-        //  number in every subtree
+    // Builds the context from already constructed data structures, without touching the
+    //  filesystem (except to load the reference components given in the options). SVG diagrams are
+    //  never drawn, and the base path (where the CoCoA script is written if needed) is the
+    //  temporary directory.
+    pub fn from_data(
+        mut constraint_storage: ConstraintStorage,
+        witness: Option<Witness>,
+        signal_name_map: SignalNameMap,
+        tree_constraints: TreeConstraints,
+        options: Options,
+    ) -> VerifierResult<(InputDataContext, ConstraintStorage)> {
+        let field = resolve_field(options.prime.as_ref(), None, None, &tree_constraints)?;
+        normalize_constraint_coefficients(&mut constraint_storage, &field);
+        validate_component_ranges(&tree_constraints)?;
+        validate_double_arrow_constraints(
+            &tree_constraints,
+            &signal_name_map,
+            &constraint_storage,
+        )?;
+        let reference_components = load_reference_components(&options)?;
+
+        // Names in signal_name_map are relative to the main component
+        let mut signal_index_map = SignalIndexMap::new();
+        for (signal_index, name) in &signal_name_map {
+            signal_index_map
+                .entry(format!("{}.{}", tree_constraints.component_name, name))
+                .or_default()
+                .push(*signal_index);
+        }
 
-        let field = BigInt::from_str(self.tree_constraints.field.as_str()).unwrap();
+        Ok((
+            InputDataContext {
+                witness,
+                extra_witnesses: vec![],
+                signal_name_map,
+                signal_index_map,
+                tree_constraints,
+                field,
+                base_path: std::env::temp_dir().to_str().unwrap().to_string(),
+                output_path: std::env::temp_dir().to_str().unwrap().to_string(),
+                svg_printer: DebugSVGPrinter::disabled(),
+                options,
+                reference_components,
+                verified_components: VerifiedComponents::default(),
+            },
+            constraint_storage,
+        ))
+    }
+
+    pub fn get_context_view(&self) -> InputDataContextView<'_> {
         InputDataContextView {
-            witness: &self.witness,
+            witness: self.witness.as_ref(),
+            extra_witnesses: &self.extra_witnesses,
             signal_name_map: &self.signal_name_map,
+            signal_index_map: &self.signal_index_map,
             tree_constraints: &self.tree_constraints,
-            field,
+            field: self.field.clone(),
             base_path: &self.base_path,
+            output_path: &self.output_path,
             svg_printer: &self.svg_printer,
             options: &self.options,
+            reference_components: &self.reference_components,
+            verified_components: &self.verified_components,
         }
     }
 }
 
 /* Represents a view of the context. tree_constraints might be a subcomponent instead of main component */
 impl<'a> InputDataContextView<'a> {
-    pub fn get_subcomponent_context_view(&self, idx: ComponentIndex) -> InputDataContextView {
+    pub fn get_subcomponent_context_view(&self, idx: ComponentIndex) -> InputDataContextView<'_> {
         InputDataContextView {
             witness: self.witness,
+            extra_witnesses: self.extra_witnesses,
             signal_name_map: self.signal_name_map,
+            signal_index_map: self.signal_index_map,
             tree_constraints: self.tree_constraints.subcomponents.get(idx).unwrap(),
             field: self.field.clone(),
             base_path: self.base_path,
+            output_path: self.output_path,
             svg_printer: self.svg_printer,
             options: self.options,
+            reference_components: self.reference_components,
+            verified_components: self.verified_components,
+        }
+    }
+
+    // Returns the witness value of a signal. Only modes that require a witness may call this, and
+    //  they must check that it has been provided before starting.
+    pub fn witness_value(&self, signal: SignalIndex) -> &'a BigInt {
+        &self
+            .witness
+            .expect("Witness value requested, but no witness has been provided")[&signal]
+    }
+
+    // Returns the name of a signal relative to the main component, or signal_<index> if the
+    //  signal names do not include it
+    pub fn signal_name(&self, signal: SignalIndex) -> String {
+        get_signal_name(self.signal_name_map, signal)
+    }
+
+    // Returns the index of the signal with the given name. The name can be fully-qualified
+    //  ("main.a") or relative to the main component ("a"), like the names in signal_name_map.
+    pub fn signal_index_by_name(&self, name: &str) -> VerifierResult<SignalIndex> {
+        let indices = self
+            .signal_index_map
+            .get(name)
+            .or_else(|| self.signal_index_map.get(&format!("main.{}", name)))
+            .ok_or_else(|| {
+                VerifierError::InvalidArgument(format!("Unknown signal name '{}'", name))
+            })?;
+
+        match indices.as_slice() {
+            [index] => Ok(*index),
+            _ => Err(VerifierError::InvalidArgument(format!(
+                "Ambiguous signal name '{}', it refers to signals {}",
+                name,
+                indices.iter().join(", ")
+            ))),
+        }
+    }
+
+    // Returns the component with the given name. Like in signal_index_by_name, the name can be
+    //  fully-qualified ("main.sub") or relative to the main component ("sub").
+    pub fn component_by_name(&self, name: &str) -> VerifierResult<&'a TreeConstraints> {
+        fn find<'t>(
+            tree_constraints: &'t TreeConstraints,
+            name: &str,
+        ) -> Option<&'t TreeConstraints> {
+            if tree_constraints.component_name == name {
+                return Some(tree_constraints);
+            }
+
+            tree_constraints
+                .subcomponents
+                .iter()
+                .find_map(|subcomponent| find(subcomponent, name))
+        }
+
+        find(self.tree_constraints, name)
+            .or_else(|| find(self.tree_constraints, &format!("main.{}", name)))
+            .ok_or_else(|| {
+                VerifierError::InvalidArgument(format!("Unknown component name '{}'", name))
+            })
+    }
+
+    // Returns true if the component of this view has been marked as trusted by the user, so it
+    //  must not be verified. A template trusted by its bare name, such as 'Num2Bits', matches all
+    //  its instances, while 'Num2Bits(4)' only matches the instance with those parameters
+    pub fn is_template_trusted(&self) -> bool {
+        let template_name = &self.tree_constraints.template_name;
+        let bare_template_name = template_name
+            .split_once('(')
+            .map_or(template_name.as_str(), |(name, _)| name);
+
+        self.options
+            .trusted_templates
+            .iter()
+            .any(|trusted| trusted == template_name || trusted == bare_template_name)
+    }
+
+    // Returns the reference component with the same constraints as the component of this view, up
+    //  to signal renaming, if any
+    pub fn find_equivalent_reference(
+        &self,
+        constraint_storage: &ConstraintStorage,
+    ) -> Option<&'a ReferenceComponent> {
+        if self.reference_components.is_empty() {
+            return None;
         }
+
+        let canonical_component =
+            CanonicalComponent::new(self.tree_constraints, constraint_storage, &self.field);
+        self.reference_components
+            .iter()
+            .find(|reference| reference.canonical_component == canonical_component)
+    }
+
+    // Returns the hash under which the component of this view is recorded as verified, computed
+    //  from its constraints as they are now, or None if the skip_verified option is not set
+    pub fn get_component_hash(&self, constraint_storage: &ConstraintStorage) -> Option<String> {
+        self.options.skip_verified.then(|| {
+            CanonicalComponent::new(self.tree_constraints, constraint_storage, &self.field)
+                .get_hash()
+        })
+    }
+
+    // Returns true if no component filters have been given, or the name or the template of the
+    //  component of this view matches one of them
+    pub fn matches_component_filters(&self) -> bool {
+        let filters = &self.options.component_filters;
+        filters.is_empty()
+            || filters.iter().any(|filter| {
+                let pattern = WildMatch::new(filter);
+                pattern.matches(&self.tree_constraints.component_name)
+                    || pattern.matches(&self.tree_constraints.template_name)
+            })
     }
 
     pub fn is_signal_public(&self, signal: ConstraintIndex) -> bool {
@@ -224,6 +1404,40 @@ impl<'a> InputDataContextView<'a> {
         signal >= initial_signal + number_outputs
             && signal < initial_signal + number_outputs + number_inputs
     }
+
+    // Returns a table with the witness values of the inputs and outputs of the component with the
+    //  given name, to be printed under its failure message. Returns none if the component is not
+    //  part of this view
+    pub fn get_component_witness_table(&self, component_name: &str) -> Option<String> {
+        let component = self.component_by_name(component_name).ok()?;
+        let first_input = component.initial_signal + component.number_outputs;
+        let inputs = first_input..(first_input + component.number_inputs);
+        let outputs = component.initial_signal..first_input;
+
+        let rows: Vec<_> = inputs
+            .map(|signal| ("input", signal))
+            .chain(outputs.map(|signal| ("output", signal)))
+            .map(|(kind, signal)| {
+                let value = self
+                    .witness
+                    .and_then(|witness| witness.get(&signal))
+                    .map_or("-".to_string(), |value| value.to_string());
+                (kind, self.signal_name(signal), value)
+            })
+            .collect();
+
+        let width = rows
+            .iter()
+            .map(|(_, name, _)| name.len())
+            .chain(iter::once("signal".len()))
+            .max()
+            .unwrap();
+        let mut table = format!("    {:<6}  {:<width$}  witness value", "", "signal");
+        for (kind, name, value) in rows {
+            table.push_str(&format!("\n    {:<6}  {:<width$}  {}", kind, name, value));
+        }
+        Some(table)
+    }
 }
 
 /* Printer functions to print parsed Input Data */
@@ -254,6 +1468,42 @@ pub fn print_constraint_storage(storage: &ConstraintStorage) {
     }
 }
 
+// Returns the name of a signal relative to the main component, or signal_<index> if it does not
+//  have one. Incomplete .sym files may lack some signals, such as anonymous signals introduced by
+//  circom
+pub fn get_signal_name(signal_name_map: &SignalNameMap, signal: SignalIndex) -> String {
+    signal_name_map
+        .get(&signal)
+        .cloned()
+        .unwrap_or_else(|| format!("signal_{}", signal))
+}
+
+// Returns the number of signals of the components, or used in their constraints, without a name
+fn count_unnamed_signals(
+    tree_constraints: &TreeConstraints,
+    signal_name_map: &SignalNameMap,
+    constraint_storage: &ConstraintStorage,
+) -> usize {
+    let mut unnamed_signals = HashSet::new();
+    let mut pending = vec![tree_constraints];
+    while let Some(component) = pending.pop() {
+        let signals =
+            component.initial_signal..(component.initial_signal + component.number_signals);
+        let constraint_signals = (component.initial_constraint
+            ..(component.initial_constraint + component.no_constraints))
+            .filter_map(|idx| constraint_storage.read_constraint(idx))
+            .flat_map(|constraint| constraint.take_cloned_signals_ordered());
+
+        unnamed_signals.extend(
+            signals
+                .chain(constraint_signals)
+                .filter(|signal| *signal != 0 && !signal_name_map.contains_key(signal)),
+        );
+        pending.extend(&component.subcomponents);
+    }
+    unnamed_signals.len()
+}
+
 pub fn print_witness(witness: &Witness) {
     for (id, val) in witness {
         println!("Id: {id}, val: {val}");
@@ -266,6 +1516,769 @@ pub fn print_signal_name_map(map: &SignalNameMap) {
     }
 }
 
+// Component of the hierarchy printed by the inspection modes
+#[derive(Serialize)]
+pub struct ComponentListEntry {
+    // Depth of the component in the hierarchy, the main component has depth 0
+    pub depth: usize,
+    pub component_name: String,
+    pub template_name: String,
+    pub number_inputs: usize,
+    pub number_outputs: usize,
+    pub number_signals: usize,
+    pub number_constraints: usize,
+}
+
+// Component hierarchy, listing each component after its parent
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct ComponentList {
+    pub components: Vec<ComponentListEntry>,
+}
+
+impl ComponentList {
+    pub fn new(tree_constraints: &TreeConstraints) -> ComponentList {
+        fn add_component(
+            tree_constraints: &TreeConstraints,
+            depth: usize,
+            components: &mut Vec<ComponentListEntry>,
+        ) {
+            components.push(ComponentListEntry {
+                depth,
+                component_name: tree_constraints.component_name.clone(),
+                template_name: tree_constraints.template_name.clone(),
+                number_inputs: tree_constraints.number_inputs,
+                number_outputs: tree_constraints.number_outputs,
+                number_signals: tree_constraints.number_signals,
+                number_constraints: tree_constraints.no_constraints,
+            });
+
+            for subcomponent in &tree_constraints.subcomponents {
+                add_component(subcomponent, depth + 1, components);
+            }
+        }
+
+        let mut components = Vec::new();
+        add_component(tree_constraints, 0, &mut components);
+        ComponentList { components }
+    }
+}
+
+impl AnalysisOutput for ComponentList {
+    // One component per line, indented by depth
+    fn print_table(&self) {
+        for component in &self.components {
+            println!(
+                "{}{}: {}",
+                "  ".repeat(component.depth),
+                component.component_name,
+                component.template_name
+            );
+        }
+    }
+
+    fn csv_header(&self) -> Vec<&'static str> {
+        vec![
+            "depth",
+            "component_name",
+            "template_name",
+            "number_inputs",
+            "number_outputs",
+            "number_signals",
+            "number_constraints",
+        ]
+    }
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        self.components
+            .iter()
+            .map(|c| {
+                vec![
+                    c.depth.to_string(),
+                    c.component_name.clone(),
+                    c.template_name.clone(),
+                    c.number_inputs.to_string(),
+                    c.number_outputs.to_string(),
+                    c.number_signals.to_string(),
+                    c.number_constraints.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
 pub fn print_tree_constraints(tree_constraints: &TreeConstraints) {
     println!("{}", serde_json::to_string(&tree_constraints).unwrap());
 }
+
+#[cfg(test)]
+fn parse_single_constraint(
+    constraint: Value,
+    strict: bool,
+) -> VerifierResult<Constraint<usize>> {
+    let (storage, _) = parse_constraint_list_from_deserializer(
+        serde_json::json!({ "constraints": [constraint] }),
+        strict,
+    )?;
+    let id = storage.get_ids().into_iter().next().unwrap();
+    Ok(storage.read_constraint(id).unwrap())
+}
+
+#[test]
+fn test_parse_constraint_three_terms() {
+    let constraint = parse_single_constraint(
+        serde_json::json!([{ "1": "2" }, { "2": "1" }, { "0": "5", "3": "1" }]),
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(constraint.a(), &HashMap::from([(1, BigInt::from(2))]));
+    assert_eq!(constraint.b(), &HashMap::from([(2, BigInt::from(1))]));
+    assert_eq!(
+        constraint.c(),
+        &HashMap::from([(0, BigInt::from(5)), (3, BigInt::from(1))])
+    );
+}
+
+#[test]
+fn test_parse_constraint_two_terms() {
+    let constraint =
+        parse_single_constraint(serde_json::json!([{}, { "0": "5", "3": "1" }]), true).unwrap();
+
+    assert!(Constraint::is_linear(&constraint));
+    assert_eq!(
+        constraint.c(),
+        &HashMap::from([(0, BigInt::from(5)), (3, BigInt::from(1))])
+    );
+}
+
+#[test]
+fn test_parse_constraint_four_terms() {
+    let json = serde_json::json!([{ "1": "1" }, { "2": "1" }, { "3": "1" }, { "meta": "x" }]);
+
+    let constraint = parse_single_constraint(json.clone(), false).unwrap();
+    assert_eq!(constraint.a(), &HashMap::from([(1, BigInt::from(1))]));
+    assert_eq!(constraint.b(), &HashMap::from([(2, BigInt::from(1))]));
+    assert_eq!(constraint.c(), &HashMap::from([(3, BigInt::from(1))]));
+
+    assert!(parse_single_constraint(json, true).is_err());
+}
+
+#[test]
+fn test_validate_double_arrow_constraints() {
+    let json = serde_json::json!({
+        "constraints": [[{ "1": "1" }, { "2": "1" }, { "3": "1" }]]
+    });
+    let (storage, _) = parse_constraint_list_from_deserializer(json, true).unwrap();
+    let tree_constraints =
+        |are_double_arrow: Vec<(ConstraintIndex, SignalIndex)>| TreeConstraints {
+            no_constraints: 1,
+            component_name: "main".to_string(),
+            are_double_arrow,
+            ..Default::default()
+        };
+
+    let validate = |are_double_arrow: Vec<(ConstraintIndex, SignalIndex)>| {
+        validate_double_arrow_constraints(
+            &tree_constraints(are_double_arrow),
+            &SignalNameMap::new(),
+            &storage,
+        )
+    };
+
+    assert!(validate(vec![(0, 3)]).is_ok());
+
+    // Signal 4 does not appear in constraint 0, and constraint 1 does not exist
+    assert!(validate(vec![(0, 4)]).is_err());
+    assert!(validate(vec![(1, 3)]).is_err());
+
+    // The input a of the fixture is the LHS of a safe assignment
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/malformed/double_arrow_to_input");
+    match InputDataContext::parse_from_files(&path, Options::default()) {
+        Err(VerifierError::MalformedArtifact(msg)) => {
+            assert!(msg.contains("LHS signal 2 (a), which is an input"))
+        }
+        _ => panic!("Expected a malformed artifact error"),
+    }
+}
+
+#[test]
+fn test_validate_component_ranges() {
+    let component =
+        |name: &str, initial_signal, number_signals, initial_constraint| TreeConstraints {
+            component_name: name.to_string(),
+            initial_signal,
+            number_signals,
+            initial_constraint,
+            no_constraints: 2,
+            ..Default::default()
+        };
+
+    let mut main = component("main", 1, 3, 0);
+    main.subcomponents = vec![component("main.a", 4, 2, 2), component("main.b", 6, 2, 4)];
+    assert!(validate_component_ranges(&main).is_ok());
+
+    // Ranges of a subcomponent may be nested inside the ranges of its parent
+    main.subcomponents[1].subcomponents = vec![component("main.b.c", 6, 1, 4)];
+    assert!(validate_component_ranges(&main).is_ok());
+
+    // But not inside the ranges of a sibling
+    main.subcomponents[1].subcomponents.clear();
+    main.subcomponents[0].initial_signal = 5;
+    let err = validate_component_ranges(&main).unwrap_err().to_string();
+    assert!(
+        err.contains("'main.a'") && err.contains("'main.b'"),
+        "{}",
+        err
+    );
+
+    main.subcomponents[0].initial_signal = 4;
+    main.subcomponents[1].initial_constraint = 1;
+    assert!(validate_component_ranges(&main).is_err());
+}
+
+#[test]
+fn test_validate_signal_indices() {
+    // Signals [1, 4) of main and [4, 6) of its subcomponent, which is not nested in main
+    let main = TreeConstraints {
+        initial_signal: 1,
+        number_signals: 3,
+        subcomponents: vec![TreeConstraints {
+            initial_signal: 4,
+            number_signals: 2,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert_eq!(get_total_number_signals(&main), 6);
+
+    let mut storage = ConstraintStorage::new();
+    storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::from(1)), (5, BigInt::from(-1))]),
+    ));
+    let witness = Witness::from([(0, BigInt::from(1)), (5, BigInt::from(2))]);
+    assert!(validate_signal_indices(&main, &storage, &[&witness]).is_ok());
+
+    // Every out of range signal of the constraints is reported
+    storage.add_constraint(Constraint::new(
+        HashMap::from([(usize::MAX, BigInt::from(1))]),
+        HashMap::from([(2, BigInt::from(1))]),
+        HashMap::from([(6, BigInt::from(1))]),
+    ));
+    let err = validate_signal_indices(&main, &storage, &[&witness])
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(&format!(
+            "signals 6 (constraint 1), {} (constraint 1), but the circuit only has signals [0, 6)",
+            usize::MAX
+        )),
+        "{}",
+        err
+    );
+
+    let storage = ConstraintStorage::new();
+    let extra_witness = Witness::from([(7, BigInt::from(1)), (6, BigInt::from(1))]);
+    let err = validate_signal_indices(&main, &storage, &[&witness, &extra_witness])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("values for signals 6, 7"), "{}", err);
+}
+
+#[test]
+fn test_validate_component_signals() {
+    let mut storage = ConstraintStorage::new();
+    // c.in === a, out === c.out
+    storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(4, BigInt::from(1)), (2, BigInt::from(-1))]),
+    ));
+    storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::from(1)), (3, BigInt::from(-1))]),
+    ));
+
+    let signal_name_map: SignalNameMap = [(1, "out"), (2, "a"), (3, "c.out"), (4, "c.in")]
+        .into_iter()
+        .map(|(signal, name)| (signal, name.to_string()))
+        .collect();
+
+    let mut main = TreeConstraints {
+        component_name: "main".to_string(),
+        number_outputs: 1,
+        number_inputs: 1,
+        number_signals: 2,
+        initial_signal: 1,
+        no_constraints: 2,
+        subcomponents: vec![TreeConstraints {
+            component_name: "main.c".to_string(),
+            number_outputs: 1,
+            number_inputs: 1,
+            number_signals: 2,
+            initial_signal: 3,
+            initial_constraint: 2,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert!(validate_component_signals(&main, "main", &signal_name_map, &storage).is_ok());
+
+    // An off-by-one in the signal count of main includes the first signal of main.c
+    main.number_signals = 3;
+    let err = validate_component_signals(&main, "main", &signal_name_map, &storage)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("3 (main.c.out)"), "{}", err);
+
+    // Signal 5 does not have a name, as anonymous signals in incomplete .sym files
+    main.number_signals = 2;
+    main.subcomponents[0].number_signals = 3;
+    assert!(validate_component_signals(&main, "main", &signal_name_map, &storage).is_ok());
+    assert_eq!(count_unnamed_signals(&main, &signal_name_map, &storage), 1);
+    assert_eq!(get_signal_name(&signal_name_map, 3), "c.out");
+    assert_eq!(get_signal_name(&signal_name_map, 5), "signal_5");
+    main.subcomponents[0].number_signals = 2;
+
+    // Without the subcomponent, c.in and c.out are unknown signals of the constraints of main
+    main.subcomponents.clear();
+    let err = validate_component_signals(&main, "main", &signal_name_map, &storage)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("signal 4 (c.in)"), "{}", err);
+}
+
+#[test]
+fn test_error_kinds() {
+    let missing_folder = Path::new("this-folder-does-not-exist");
+    assert!(matches!(
+        InputDataContext::parse_from_files(missing_folder, Options::default()),
+        Err(VerifierError::Io(_))
+    ));
+
+    assert!(matches!(
+        parse_single_constraint(serde_json::json!([{ "1": "x" }, {}, {}]), true),
+        Err(VerifierError::Parse(_))
+    ));
+
+    let tree_constraints = TreeConstraints {
+        field: "101".to_string(),
+        no_constraints: 1,
+        component_name: "main".to_string(),
+        are_double_arrow: vec![(0, 1)],
+        ..Default::default()
+    };
+    assert!(matches!(
+        InputDataContext::from_data(
+            ConstraintStorage::new(),
+            None,
+            SignalNameMap::new(),
+            tree_constraints,
+            Options::default(),
+        ),
+        Err(VerifierError::MalformedArtifact(_))
+    ));
+}
+
+#[test]
+fn test_normalize_constraint_coefficients() {
+    let field = BigInt::from(101);
+    let mut constraint_storage = ConstraintStorage::new();
+    let id = constraint_storage.add_constraint(Constraint::new(
+        HashMap::from([(1, BigInt::from(-1))]),
+        HashMap::from([(2, BigInt::from(104))]),
+        HashMap::from([(0, BigInt::from(5)), (3, BigInt::from(202))]),
+    ));
+
+    normalize_constraint_coefficients(&mut constraint_storage, &field);
+
+    let constraint = constraint_storage.read_constraint(id).unwrap();
+    assert_eq!(constraint.a(), &HashMap::from([(1, BigInt::from(100))]));
+    assert_eq!(constraint.b(), &HashMap::from([(2, BigInt::from(3))]));
+    assert_eq!(constraint.c(), &HashMap::from([(0, BigInt::from(5))]));
+}
+
+#[test]
+fn test_parse_constraint_origins() {
+    let tree_constraints: TreeConstraints = serde_json::from_value(serde_json::json!({
+        "field": "101",
+        "no_constraints": 1,
+        "initial_constraint": 0,
+        "node_id": 0,
+        "template_name": "Main()",
+        "component_name": "main",
+        "number_inputs": 1,
+        "number_outputs": 1,
+        "number_signals": 2,
+        "initial_signal": 1,
+        "are_double_arrow": [],
+        "subcomponents": [],
+        "constraint_origins": { "0": "main.circom:5" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        tree_constraints.constraint_origins,
+        HashMap::from([(0, "main.circom:5".to_string())])
+    );
+}
+
+#[test]
+fn test_parse_signal_names_with_commas() {
+    let sym = "1,1,0,main.out\n2,-1,0,main.a[1,2]\n3,2,1,main.c.in[0,1,2]\n";
+    let (map, inverse_map) = parse_signal_name_map_from_reader(sym.as_bytes()).unwrap();
+
+    assert_eq!(map[&1], "out");
+    assert_eq!(map[&2], "a[1,2]");
+    assert_eq!(map[&3], "c.in[0,1,2]");
+    assert_eq!(inverse_map["main.a[1,2]"], vec![2]);
+
+    // Lines with fewer than four columns are still rejected
+    assert!(parse_signal_name_map_from_reader("1,0,main.out\n".as_bytes()).is_err());
+}
+
+#[test]
+fn test_parse_signal_names_with_header() {
+    // The name column is not the last one, so it takes the text between the columns around it
+    let sym = "Signal,Witness,Name,Component\n1,1,main.out,0\n2,-1,main.a[1,2],0\n";
+    let (map, _) = parse_signal_name_map_from_reader(sym.as_bytes()).unwrap();
+    assert_eq!(map[&1], "out");
+    assert_eq!(map[&2], "a[1,2]");
+
+    assert_eq!(
+        SymColumns::from_header("signal,name").unwrap(),
+        Some(SymColumns {
+            number_of_columns: 2,
+            signal_column: 0,
+            name_column: 1,
+        })
+    );
+    assert_eq!(SymColumns::from_header("1,1,0,main.out").unwrap(), None);
+    assert!(SymColumns::from_header("witness,name").is_err());
+}
+
+#[test]
+fn test_parse_witness_list() {
+    let object = r#"{"0": "1", "1": "7", "2": "0", "3": "100"}"#;
+    let list = r#"["1", "7", "0", "100"]"#;
+
+    let witness = parse_witness_from_reader(object.as_bytes(), 4).unwrap();
+    assert_eq!(witness[&1], BigInt::from(7));
+    assert_eq!(
+        parse_witness_from_reader(list.as_bytes(), 4).unwrap(),
+        witness
+    );
+
+    // A list must have a value for every signal, an object may leave some out
+    assert!(matches!(
+        parse_witness_from_reader(list.as_bytes(), 5),
+        Err(VerifierError::MalformedArtifact(_))
+    ));
+    assert!(parse_witness_from_reader(object.as_bytes(), 5).is_ok());
+    assert!(matches!(
+        parse_witness_from_reader(r#""1""#.as_bytes(), 1),
+        Err(VerifierError::Parse(_))
+    ));
+}
+
+#[test]
+fn test_parse_r1cs_prime() {
+    let prime = BigInt::from(0x1_0000_0001u64);
+    let (_, prime_bytes) = prime.to_bytes_le();
+
+    let mut r1cs = b"r1cs".to_vec();
+    r1cs.extend(1u32.to_le_bytes());
+    r1cs.extend(2u32.to_le_bytes());
+    // A constraints section (type 2) may come before the header section
+    r1cs.extend(2u32.to_le_bytes());
+    r1cs.extend(3u64.to_le_bytes());
+    r1cs.extend([0xff; 3]);
+    r1cs.extend(1u32.to_le_bytes());
+    r1cs.extend((4 + prime_bytes.len() as u64).to_le_bytes());
+    r1cs.extend((prime_bytes.len() as u32).to_le_bytes());
+    r1cs.extend(&prime_bytes);
+
+    assert_eq!(parse_r1cs_prime(io::Cursor::new(&r1cs)).unwrap(), prime);
+    assert!(matches!(
+        parse_r1cs_prime(io::Cursor::new(b"json{}".to_vec())),
+        Err(VerifierError::MalformedArtifact(_))
+    ));
+}
+
+#[test]
+fn test_resolve_field() {
+    let tree_constraints = TreeConstraints {
+        field: "17".to_string(),
+        ..Default::default()
+    };
+
+    // Tree constraints only
+    assert_eq!(
+        resolve_field(None, None, None, &tree_constraints).unwrap(),
+        BigInt::from(17)
+    );
+
+    // The .r1cs header takes precedence over the tree constraints
+    assert_eq!(
+        resolve_field(None, Some(BigInt::from(19)), None, &tree_constraints).unwrap(),
+        BigInt::from(19)
+    );
+
+    // So does the prime of a snarkjs constraints file, but not over the .r1cs header
+    assert_eq!(
+        resolve_field(None, None, Some(BigInt::from(13)), &tree_constraints).unwrap(),
+        BigInt::from(13)
+    );
+    assert_eq!(
+        resolve_field(
+            None,
+            Some(BigInt::from(19)),
+            Some(BigInt::from(13)),
+            &tree_constraints
+        )
+        .unwrap(),
+        BigInt::from(19)
+    );
+
+    // The prime given in the options takes precedence over everything else
+    assert_eq!(
+        resolve_field(
+            Some(&BigInt::from(23)),
+            Some(BigInt::from(19)),
+            None,
+            &tree_constraints
+        )
+        .unwrap(),
+        BigInt::from(23)
+    );
+
+    // An invalid prime in the tree constraints is only an error if there is no other source
+    let invalid_tree_constraints = TreeConstraints {
+        field: "p".to_string(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        resolve_field(None, None, None, &invalid_tree_constraints),
+        Err(VerifierError::Parse(_))
+    ));
+    assert_eq!(
+        resolve_field(
+            Some(&BigInt::from(23)),
+            None,
+            None,
+            &invalid_tree_constraints
+        )
+        .unwrap(),
+        BigInt::from(23)
+    );
+}
+
+#[test]
+fn test_matches_component_filters() {
+    // Returns whether the main component and its subcomponent match the filters
+    let matches = |filters: &[&str]| -> (bool, bool) {
+        let tree_constraints = TreeConstraints {
+            component_name: "main".to_string(),
+            template_name: "Main()".to_string(),
+            field: "101".to_string(),
+            subcomponents: vec![TreeConstraints {
+                component_name: "main.adders[1]".to_string(),
+                template_name: "Num2Bits(4)".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let options = Options {
+            component_filters: filters.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        };
+        let (context, _) = InputDataContext::from_data(
+            ConstraintStorage::new(),
+            None,
+            HashMap::new(),
+            tree_constraints,
+            options,
+        )
+        .unwrap();
+
+        let context = context.get_context_view();
+        let subcomponent_context = context.get_subcomponent_context_view(0);
+        (
+            context.matches_component_filters(),
+            subcomponent_context.matches_component_filters(),
+        )
+    };
+
+    assert_eq!(matches(&[]), (true, true));
+    // Brackets in component names are matched literally
+    assert_eq!(matches(&["main.adders[*]"]), (false, true));
+    assert_eq!(matches(&["*Bits*"]), (false, true));
+    assert_eq!(matches(&["Main*", "main.sub"]), (true, false));
+}
+
+#[test]
+fn test_artifact_paths() {
+    let folder = Path::new("artifacts");
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/safe_num2bits");
+
+    let default_paths = ArtifactPaths::new(folder, &Options::default());
+    assert_eq!(
+        default_paths.constraints,
+        folder.join("circuit_constraints.json")
+    );
+    assert_eq!(default_paths.witness, folder.join("witness.json"));
+
+    // Artifacts given individually are read even if the folder does not contain them
+    let empty_folder = std::env::temp_dir().join(format!("artifact-paths-{}", std::process::id()));
+    std::fs::create_dir_all(&empty_folder).unwrap();
+    let options = Options {
+        constraints_path: Some(fixture.join("circuit_constraints.json")),
+        witness_path: Some(fixture.join("witness.json")),
+        sym_path: Some(fixture.join("circuit_signals.sym")),
+        tree_path: Some(fixture.join("circuit_treeconstraints.json")),
+        ..Default::default()
+    };
+    let (context, _) = InputDataContext::parse_from_files(&empty_folder, options).unwrap();
+    assert_eq!(context.tree_constraints.template_name, "Num2Bits2()");
+    assert!(context.witness.is_some());
+
+    std::fs::remove_dir_all(empty_folder).unwrap();
+}
+
+#[test]
+fn test_component_list() {
+    let tree_constraints = TreeConstraints {
+        component_name: "main".to_string(),
+        template_name: "Main()".to_string(),
+        number_signals: 3,
+        no_constraints: 2,
+        subcomponents: vec![TreeConstraints {
+            component_name: "main.mux".to_string(),
+            template_name: "Mux(2, 3)".to_string(),
+            number_signals: 1,
+            no_constraints: 1,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let component_list = ComponentList::new(&tree_constraints);
+    assert_eq!(
+        crate::output_format::render_csv(&component_list),
+        "depth,component_name,template_name,number_inputs,number_outputs,number_signals,number_constraints\n\
+         0,main,Main(),0,0,3,2\n\
+         1,main.mux,\"Mux(2, 3)\",0,0,1,1"
+    );
+
+    let json = serde_json::to_value(&component_list).unwrap();
+    assert_eq!(json[1]["component_name"], "main.mux");
+    assert_eq!(json[1]["depth"], 1);
+}
+
+#[test]
+fn test_parse_gzipped_constraint_list() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/safe_num2bits");
+    let plain_path = fixture.join("circuit_constraints.json");
+    let folder = std::env::temp_dir().join(format!("gzipped-artifacts-{}", std::process::id()));
+    std::fs::create_dir_all(&folder).unwrap();
+
+    let gzipped_path = folder.join("circuit_constraints.json.gz");
+    let mut encoder = GzEncoder::new(File::create(&gzipped_path).unwrap(), Compression::default());
+    encoder
+        .write_all(&std::fs::read(&plain_path).unwrap())
+        .unwrap();
+    encoder.finish().unwrap();
+
+    // Only the compressed artifact exists in the folder, so its conventional path falls back to it
+    let artifact_paths = ArtifactPaths::new(&folder, &Options::default());
+    assert_eq!(artifact_paths.constraints, gzipped_path);
+    assert_eq!(artifact_paths.witness, folder.join("witness.json"));
+
+    let (plain, _) = parse_constraint_list(&plain_path, true).unwrap();
+    let (gzipped, _) = parse_constraint_list(&gzipped_path, true).unwrap();
+    assert_eq!(plain.get_ids(), gzipped.get_ids());
+    for id in plain.get_ids() {
+        let (plain_constraint, gzipped_constraint) = (
+            plain.read_constraint(id).unwrap(),
+            gzipped.read_constraint(id).unwrap(),
+        );
+        assert_eq!(plain_constraint.a(), gzipped_constraint.a());
+        assert_eq!(plain_constraint.b(), gzipped_constraint.b());
+        assert_eq!(plain_constraint.c(), gzipped_constraint.c());
+    }
+
+    std::fs::remove_dir_all(folder).unwrap();
+}
+
+#[test]
+fn test_component_witness_table() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, _) = InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    let context = context.get_context_view();
+
+    assert_eq!(
+        context.get_component_witness_table("main").unwrap(),
+        [
+            "            signal  witness value",
+            "    input   a       4",
+            "    output  out     0",
+        ]
+        .join("\n")
+    );
+    assert!(context
+        .get_component_witness_table("main.missing")
+        .is_none());
+}
+
+#[test]
+fn test_parse_snarkjs_constraint_list() {
+    // The snarkjs export of is_zero_1 is read as the same circuit
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures");
+    let (circom_context, circom_storage) =
+        InputDataContext::parse_from_files(&fixtures.join("is_zero_1"), Options::default())
+            .unwrap();
+    let (snarkjs_context, snarkjs_storage) =
+        InputDataContext::parse_from_files(&fixtures.join("snarkjs_is_zero_1"), Options::default())
+            .unwrap();
+    assert_eq!(snarkjs_context.field, circom_context.field);
+    assert_eq!(snarkjs_storage.get_ids(), circom_storage.get_ids());
+    for id in circom_storage.get_ids() {
+        let (circom_constraint, snarkjs_constraint) = (
+            circom_storage.read_constraint(id).unwrap(),
+            snarkjs_storage.read_constraint(id).unwrap(),
+        );
+        assert_eq!(snarkjs_constraint.a(), circom_constraint.a());
+        assert_eq!(snarkjs_constraint.b(), circom_constraint.b());
+        assert_eq!(snarkjs_constraint.c(), circom_constraint.c());
+    }
+
+    // Wires are replaced by their signal in the map
+    let json = serde_json::json!({
+        "prime": "11",
+        "constraints": [[{ "0": "1" }, { "1": "1" }, { "2": "3" }]],
+        "map": [0, 5, 7]
+    });
+    let (storage, prime) = parse_constraint_list_from_deserializer(json, true).unwrap();
+    assert_eq!(prime, Some(BigInt::from(11)));
+    let constraint = storage.read_constraint(0).unwrap();
+    assert_eq!(constraint.b(), &HashMap::from([(5, BigInt::from(1))]));
+    assert_eq!(constraint.c(), &HashMap::from([(7, BigInt::from(-3))]));
+
+    let json = serde_json::json!({
+        "prime": "11",
+        "constraints": [[{ "3": "1" }, {}, {}]],
+        "map": [0, 5, 7]
+    });
+    assert!(matches!(
+        parse_constraint_list_from_deserializer(json, true),
+        Err(VerifierError::Parse(_))
+    ));
+}
@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+
+// Library API of the verifier. It allows building the verification context from in-memory data
+//  structures (see InputDataContext::from_data) instead of parsing the artifacts folder, and
+//  generating the polynomial systems of a circuit apart from solving them (see
+//  verifier::collect_polynomial_systems and verifier::solve_polynomial_systems).
+
+// Prints like println!, unless the quiet option is set in the given options. The output of the
+//  verification goes through it, so that it can be replaced by a summary of the verdict. Defined
+//  before the modules so that they can use it
+macro_rules! println_unless_quiet {
+    ($options:expr) => {
+        if !$options.quiet {
+            println!();
+        }
+    };
+    ($options:expr, $($arg:tt)*) => {
+        if !$options.quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub mod benchmark;
+pub mod cli;
+pub mod component_tree_printer;
+pub mod error;
+pub mod graphml_printer;
+pub mod input_data;
+pub mod mermaid_printer;
+pub mod output_format;
+pub mod polynomial_system_fixer;
+pub mod reference;
+pub mod report;
+pub mod sarif;
+pub mod tree_constraint_graph_printer;
+pub mod verification_graph;
+pub mod verified_components;
+pub mod verifier;
+pub mod watch;
+
+pub use input_data::*;
+pub use tree_constraint_graph_printer::*;
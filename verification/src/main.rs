@@ -1,18 +1,21 @@
-#![allow(dead_code)]
-
-mod cli;
-mod input_data;
-mod polynomial_system_fixer;
-mod tree_constraint_graph_printer;
-mod verification_graph;
-mod verifier;
-
-use input_data::*;
-use tree_constraint_graph_printer::*;
-
-use crate::cli::parse_command_line_arguments;
+use colored::Colorize;
 use std::error::Error;
-use std::path::Path;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Instant;
+use verification::benchmark::benchmark_optimization_passes;
+use verification::cli::{parse_command_line_arguments, CliCommand};
+use verification::error::{VerifierError, VerifierResult};
+use verification::graphml_printer::write_verification_graph_graphml;
+use verification::mermaid_printer::write_verification_graph_mermaid;
+use verification::output_format::{print_analysis_output, OutputFormat};
+use verification::verification_graph::{
+    GraphMetrics, LinearRanks, RedundantConstraints, VerificationGraph,
+};
+use verification::verifier::VerificationOutcome;
+use verification::*;
+use verification::{cli, verifier, watch};
 
 // TODO: We should add an option for the user to prove strong safety for all inputs for a module
 //  that has === constraints (and therefore only handle the rest of the modules using our local
@@ -21,35 +24,241 @@ use std::path::Path;
 // TODO: We should apply some heuristics for quickly verifying modules without === constraints,
 //  such as the one published in Circom paper
 
-// TODO: When outputting constraints for Cocoa, try to simplify them using Gauss-Jordan first
+fn main() {
+    // Errors are printed with their message only, instead of their debug representation
+    if let Err(e) = run() {
+        println!("{}", format!("Error: {}", e).red());
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    match parse_command_line_arguments() {
+        CliCommand::Verify {
+            folder,
+            extra_folders,
+            options,
+        } => {
+            let base_path = folder.unwrap_or_else(|| {
+                // Hardcoded path for testing purposes if that flag was passed
+                let test_artifacts_path =
+                    Path::new(r"C:\Users\pedro\Documents\dev\CircomVerification\test-artifacts\");
+                let folder_name = r"interesting_systems\split_2_3";
 
-// TODO: When outputting constraints for Cocoa, first do a reachability analysis and remove all
-//  constraints not reachable by the outputs to fix
+                test_artifacts_path.join(folder_name)
+            });
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let (maybe_base_path, options) = parse_command_line_arguments();
+            if options.watch {
+                if !extra_folders.is_empty() {
+                    return Err(VerifierError::InvalidArgument(
+                        "Only one circuit can be watched at a time".to_string(),
+                    )
+                    .into());
+                }
+                let artifact_paths = ArtifactPaths::new(&base_path, &options);
+                return watch::watch_artifacts(&artifact_paths, || {
+                    verify_artifacts(&base_path, options.clone())?;
+                    Ok(())
+                });
+            }
 
-    let base_path = maybe_base_path.unwrap_or_else(|| {
-        // Hardcoded path for testing purposes if that flag was passed
-        let test_artifacts_path =
-            Path::new(r"C:\Users\pedro\Documents\dev\CircomVerification\test-artifacts\");
-        let folder_name = r"interesting_systems\split_2_3";
+            // The exit code tells scripts whether the circuit has been proved safe
+            let safe = if extra_folders.is_empty() && !options.summary_only {
+                verify_artifacts(&base_path, options)?.safe
+            } else {
+                let folders: Vec<PathBuf> = iter::once(base_path).chain(extra_folders).collect();
+                verify_batch(&folders, &options)
+            };
+            if !safe {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        CliCommand::Inspect { folder, options } => {
+            let (context, _) = InputDataContext::parse_from_files(&folder, options)?;
+            print_analysis_output(
+                &ComponentList::new(&context.tree_constraints),
+                context.options.output_format,
+            );
+            Ok(())
+        }
+        CliCommand::Export {
+            folder,
+            graphml_path,
+            mermaid_path,
+            options,
+        } => {
+            let (context, constraint_storage) =
+                InputDataContext::parse_from_files(&folder, options)?;
+            let context_view = context.get_context_view();
 
-        test_artifacts_path.join(folder_name)
-    });
+            let verification_graph = VerificationGraph::new(&context_view, &constraint_storage);
+            if let Some(graphml_path) = graphml_path {
+                write_verification_graph_graphml(
+                    &verification_graph,
+                    &context_view,
+                    &graphml_path,
+                )?;
+                println!("Verification graph written to {}", graphml_path.display());
+            }
+            if let Some(mermaid_path) = mermaid_path {
+                write_verification_graph_mermaid(
+                    &verification_graph,
+                    &context_view,
+                    &mermaid_path,
+                )?;
+                println!("Verification graph written to {}", mermaid_path.display());
+            }
+            Ok(())
+        }
+        CliCommand::Benchmark {
+            folders,
+            solve,
+            options,
+        } => {
+            let results = benchmark_optimization_passes(&folders, &options, solve)?;
+            print_analysis_output(&results, options.output_format);
+            Ok(())
+        }
+    }
+}
 
+// Verifies each circuit in turn, printing a line with its verdict after it and a total at the end.
+//  With the summary_only option, which implies the quiet one, the output of the verification itself
+//  is not printed. An error in a circuit is reported in its line and does not stop the batch.
+//  Returns true if all of them are safe
+fn verify_batch(folders: &[PathBuf], options: &cli::Options) -> bool {
+    let batch_start = Instant::now();
+    let mut num_safe_circuits = 0;
+    let mut num_unsafe_components = 0;
+    let mut num_exception_components = 0;
+
+    for folder in folders {
+        if !options.quiet {
+            println!("{}", format!("\nVerifying {}...", folder.display()).blue());
+        }
+
+        let start = Instant::now();
+        let result = verify_artifacts(folder, options.clone());
+        let seconds = start.elapsed().as_secs_f64();
+
+        let line = match result {
+            Ok(outcome) => {
+                num_unsafe_components += outcome.num_unsafe_components;
+                num_exception_components += outcome.num_exception_components;
+                let line = format!(
+                    "{}: {}, {} unsafe components, {} exceptions, {:.2}s",
+                    folder.display(),
+                    if outcome.safe { "SAFE" } else { "NOT SAFE" },
+                    outcome.num_unsafe_components,
+                    outcome.num_exception_components,
+                    seconds
+                );
+                if outcome.safe {
+                    num_safe_circuits += 1;
+                    line.green()
+                } else {
+                    line.red()
+                }
+            }
+            Err(e) => format!("{}: ERROR ({}), {:.2}s", folder.display(), e, seconds).red(),
+        };
+        println!("{}", line);
+    }
+
+    println!(
+        "Total: {} of {} circuits safe, {} unsafe components, {} exceptions, {:.2}s",
+        num_safe_circuits,
+        folders.len(),
+        num_unsafe_components,
+        num_exception_components,
+        batch_start.elapsed().as_secs_f64()
+    );
+    num_safe_circuits == folders.len()
+}
+
+// Returns whether the circuit (or the requested check) has been verified successfully
+fn verify_artifacts(
+    base_path: &Path,
+    options: cli::Options,
+) -> VerifierResult<VerificationOutcome> {
     // print_constraint_storage(&storage);
     // print_witness(&witness);
     // print_signal_name_map(&signal_name_map);
     // print_tree_constraints(&tree_constraints);
-    let (context, mut constraint_storage) =
-        InputDataContext::parse_from_files(&base_path, options)?;
+    let (context, mut constraint_storage) = InputDataContext::parse_from_files(base_path, options)?;
+
+    // Checks other than the verification do not count failed components
+    let outcome = |safe| VerificationOutcome {
+        safe,
+        ..Default::default()
+    };
+
+    if context.options.list_components {
+        print_analysis_output(
+            &ComponentList::new(&context.tree_constraints),
+            context.options.output_format,
+        );
+        return Ok(outcome(true));
+    }
+
     let global_context_view = context.get_context_view();
 
+    if context.options.graph_metrics {
+        let verification_graph = VerificationGraph::new(&global_context_view, &constraint_storage);
+        if context.options.output_format == OutputFormat::Table {
+            println!(
+                "Verification graph of '{}' ({}):",
+                context.tree_constraints.component_name, context.tree_constraints.template_name
+            );
+        }
+        print_analysis_output(
+            &GraphMetrics::new(
+                &verification_graph,
+                &global_context_view,
+                &constraint_storage,
+            ),
+            context.options.output_format,
+        );
+        return Ok(outcome(true));
+    }
+
+    if context.options.linear_rank {
+        print_analysis_output(
+            &LinearRanks::new(&global_context_view, &constraint_storage),
+            context.options.output_format,
+        );
+        return Ok(outcome(true));
+    }
+
+    if context.options.report_redundant {
+        print_analysis_output(
+            &RedundantConstraints::new(&global_context_view, &constraint_storage),
+            context.options.output_format,
+        );
+        return Ok(outcome(true));
+    }
+
+    if let Some(component_name) = &context.options.functional_uniqueness_component {
+        return verifier::verify_functional_uniqueness(
+            &global_context_view,
+            &constraint_storage,
+            component_name,
+        )
+        .map(outcome);
+    }
+
+    if !context.options.fix_signals.is_empty() {
+        return verifier::verify_fixed_signals(
+            &global_context_view,
+            &constraint_storage,
+            &context.options.fix_signals,
+        )
+        .map(outcome);
+    }
+
     let context_view = global_context_view;
     // let context_view = global_context_view.get_subcomponent_context_view(2);
 
-    verifier::verify(&context_view, &mut constraint_storage)?;
-
-    Ok(())
+    verifier::verify_with_outcome(&context_view, &mut constraint_storage)
 }
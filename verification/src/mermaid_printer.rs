@@ -0,0 +1,179 @@
+use crate::error::VerifierResult;
+use crate::verification_graph::{Node, VerificationGraph};
+use crate::InputDataContextView;
+use itertools::Itertools;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+// Writes the verification graph as a Mermaid flowchart, to be embedded in Markdown documents. It
+//  mirrors the SVG diagrams: signals are styled by kind and fixed status, '<==' assignments are
+//  edges from each RHS signal to the LHS signal, '===' constraints are an extra node connected to
+//  all their signals and subcomponents are subgraphs with their inputs and outputs.
+pub fn write_verification_graph_mermaid(
+    verification_graph: &VerificationGraph,
+    context: &InputDataContextView,
+    path: &Path,
+) -> VerifierResult<()> {
+    fs::write(
+        path,
+        get_verification_graph_mermaid(verification_graph, context),
+    )?;
+    Ok(())
+}
+
+pub fn get_verification_graph_mermaid(
+    verification_graph: &VerificationGraph,
+    context: &InputDataContextView,
+) -> String {
+    let mut out = String::new();
+
+    // Writing to a String never fails
+    let mut line = |indent: usize, s: String| {
+        writeln!(out, "{}{}", "    ".repeat(indent), s).unwrap();
+    };
+
+    line(0, "flowchart LR".to_string());
+    line(1, "classDef io stroke:orange,stroke-width:2px".to_string());
+    line(1, "classDef intermediate stroke:black".to_string());
+    line(1, "classDef subcomponentInput stroke:green".to_string());
+    line(1, "classDef subcomponentOutput stroke:blue".to_string());
+    line(1, "classDef constraint fill:none,stroke:none".to_string());
+    line(1, "classDef fixed fill:#8b1a1a,color:white".to_string());
+
+    let signal_node = |signal: &usize, class: &str| {
+        let label = escape_mermaid_label(&context.signal_name(*signal));
+        match class {
+            "io" => format!("s{}{{{{\"{}\"}}}}:::{}", signal, label, class),
+            _ => format!("s{}[\"{}\"]:::{}", signal, label, class),
+        }
+    };
+
+    // Signals of the component
+    for (signal, node) in &verification_graph.nodes {
+        let class = match node {
+            Node::InputSignal | Node::OutputSignal => "io",
+            Node::IntermediateSignal => "intermediate",
+            Node::SubComponentInputSignal(_) | Node::SubComponentOutputSignal(_) => continue,
+        };
+        line(1, signal_node(signal, class));
+    }
+
+    // Subcomponents, with an edge from their inputs to their outputs through an extra node if
+    //  there are both
+    for (cmp_index, subcomponent) in &verification_graph.subcomponents {
+        let tree_constraints = &context.tree_constraints.subcomponents[*cmp_index];
+        let (_, component_name) = tree_constraints.component_name.split_once('.').unwrap();
+        line(
+            1,
+            format!(
+                "subgraph cmp{}[\"{}\"]",
+                cmp_index,
+                escape_mermaid_label(&format!(
+                    "{}: {}",
+                    component_name, tree_constraints.template_name
+                ))
+            ),
+        );
+
+        for input in &subcomponent.input_signals {
+            line(2, signal_node(input, "subcomponentInput"));
+        }
+        for output in &subcomponent.output_signals {
+            line(2, signal_node(output, "subcomponentOutput"));
+        }
+
+        if !subcomponent.input_signals.is_empty() && !subcomponent.output_signals.is_empty() {
+            line(2, format!("cmp{}_body((\" \"))", cmp_index));
+            for input in &subcomponent.input_signals {
+                line(2, format!("s{} --- cmp{}_body", input, cmp_index));
+            }
+            for output in &subcomponent.output_signals {
+                line(2, format!("cmp{}_body --> s{}", cmp_index, output));
+            }
+        }
+
+        line(1, "end".to_string());
+    }
+
+    // Safe assignments '<=='
+    for safe_assignment in verification_graph
+        .safe_assignments
+        .iter()
+        .filter(|a| a.active)
+    {
+        for rhs_signal in &safe_assignment.rhs_signals {
+            line(
+                1,
+                format!(
+                    "s{} -->|\"{}\"| s{}",
+                    rhs_signal,
+                    escape_mermaid_label("<=="),
+                    safe_assignment.lhs_signal
+                ),
+            );
+        }
+    }
+
+    // Unsafe constraints '==='
+    for unsafe_constraint in verification_graph
+        .unsafe_constraints
+        .iter()
+        .filter(|c| c.active)
+    {
+        let constraint_node = format!("c{}", unsafe_constraint.associated_constraint);
+        line(1, format!("{}((\"===\")):::constraint", constraint_node));
+        for signal in &unsafe_constraint.signals {
+            line(1, format!("s{} --- {}", signal, constraint_node));
+        }
+    }
+
+    let fixed_nodes = verification_graph
+        .fixed_nodes
+        .iter()
+        .filter(|signal| verification_graph.nodes.contains_key(signal))
+        .map(|signal| format!("s{}", signal))
+        .join(",");
+    if !fixed_nodes.is_empty() {
+        line(1, format!("class {} fixed", fixed_nodes));
+    }
+
+    out
+}
+
+// Escapes the characters that Mermaid interprets inside quoted labels
+fn escape_mermaid_label(s: &str) -> String {
+    s.replace('&', "#amp;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[test]
+fn test_escape_mermaid_label() {
+    assert_eq!(escape_mermaid_label("in[0]"), "in[0]");
+    assert_eq!(escape_mermaid_label(r#"<== "x""#), "#lt;== #quot;x#quot;");
+}
+
+#[test]
+fn test_verification_graph_mermaid() {
+    use crate::cli::Options;
+    use crate::InputDataContext;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, constraint_storage) =
+        InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    let context = context.get_context_view();
+    let verification_graph = VerificationGraph::new(&context, &constraint_storage);
+
+    let mermaid = get_verification_graph_mermaid(&verification_graph, &context);
+    let lines: Vec<&str> = mermaid.lines().map(str::trim).collect();
+
+    assert_eq!(lines[0], "flowchart LR");
+    assert!(lines.contains(&"s2{{\"a\"}}:::io"));
+    assert!(lines.contains(&"s4[\"inv\"]:::intermediate"));
+    assert!(lines.contains(&"s3 -->|\"#lt;==\"| s1"));
+    assert!(lines.contains(&"c0((\"===\")):::constraint"));
+    assert!(lines.contains(&"s2 --- c0"));
+    assert_eq!(lines.last(), Some(&"class s2 fixed"));
+}
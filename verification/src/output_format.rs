@@ -0,0 +1,72 @@
+use itertools::Itertools;
+use serde::Serialize;
+use std::str::FromStr;
+
+// Format in which the inspection and analysis modes print their results
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OutputFormat {
+    // Human readable output
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "Unknown output format '{}', expected one of: table, json, csv",
+                s
+            )),
+        }
+    }
+}
+
+// Result of an analysis, which can be rendered in any output format. The JSON rendering is the
+//  serde serialization of the result
+pub trait AnalysisOutput: Serialize {
+    // Prints the human readable rendering
+    fn print_table(&self);
+
+    fn csv_header(&self) -> Vec<&'static str>;
+
+    // Rows of the CSV rendering, each with one value per column of the header
+    fn csv_rows(&self) -> Vec<Vec<String>>;
+}
+
+// Quotes a CSV value if it contains a separator, a quote or a line break
+fn escape_csv_value(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn render_csv<T: AnalysisOutput>(output: &T) -> String {
+    let header = output.csv_header().into_iter().map(String::from).collect();
+    std::iter::once(header)
+        .chain(output.csv_rows())
+        .map(|row: Vec<String>| row.iter().map(|value| escape_csv_value(value)).join(","))
+        .join("\n")
+}
+
+pub fn print_analysis_output<T: AnalysisOutput>(output: &T, format: OutputFormat) {
+    match format {
+        OutputFormat::Table => output.print_table(),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(output).unwrap()),
+        OutputFormat::Csv => println!("{}", render_csv(output)),
+    }
+}
+
+#[test]
+fn test_escape_csv_value() {
+    assert_eq!(escape_csv_value("main.a"), "main.a");
+    assert_eq!(escape_csv_value("Mux(2, 3)"), "\"Mux(2, 3)\"");
+    assert_eq!(escape_csv_value("say \"hi\""), "\"say \"\"hi\"\"\"");
+}
@@ -1,36 +1,61 @@
-use crate::input_data::SignalIndex;
+use crate::error::{VerifierError, VerifierResult};
+use crate::input_data::{ConstraintIndex, SignalIndex, TreeConstraints};
 use crate::verifier::PolynomialSystemFixedSignal;
 use crate::InputDataContextView;
-use circom_algebra::algebra::{ArithmeticExpression, Constraint};
+use circom_algebra::algebra::{ArithmeticExpression, Constraint, Substitution};
+use circom_algebra::constraint_storage::ConstraintStorage;
+use circom_algebra::modular_arithmetic;
 use colored::Colorize;
 use indoc::formatdoc;
 use itertools::Itertools;
-use num_bigint_dig::BigInt;
-use num_traits::One;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use num_bigint_dig::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write};
 use std::iter;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use which::which;
+use wildmatch::WildMatch;
 
 // This enum controls how each signal should be displayed: either as its name (which is human
-//  readable but may cause problems with Computer Algebra Systems), or as a signal index (which is
-//  not easily human readable but can be safely used by Computer Algebra Systems).
-#[derive(Copy, Clone)]
-enum SignalDisplayKind {
+//  readable but may cause problems with Computer Algebra Systems), as its name turned into a valid
+//  identifier (readable, and safe to paste into a Computer Algebra System), or as a signal index
+//  (which is not easily human readable but can be safely used by Computer Algebra Systems).
+//  The sanitized names are computed beforehand by get_sanitized_signal_names.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum SignalDisplayKind<'a> {
     Name,
+    SanitizedName(&'a BTreeMap<SignalIndex, String>),
     Index,
 }
 
 pub type PolSystemIndex = usize;
 
+// Maximum nesting of product definitions that will be followed when computing the domain of a
+//  signal. Guards against cyclic definitions in malformed systems.
+const MAX_DOMAIN_PRODUCT_DEPTH: usize = 8;
+
 #[derive(Clone)]
 pub struct SignalToFixData {
     // Is this signal a boolean signal?
     pub is_boolean: bool,
+
+    // If the constraints restrict this signal to a small finite domain (for example, a 2-bit
+    //  value constrained by x*(x-1)*(x-2)*(x-3) == 0), the set of values it can take
+    pub domain: Option<BTreeSet<BigInt>>,
 }
 
 // This structure represents an optimized polynomial system of constraints that should have
@@ -40,12 +65,36 @@ pub struct SignalToFixData {
 pub struct OptimizedPolynomialSystemFixedSignal {
     pub constraints: Vec<Constraint<usize>>,
 
+    // Original index of each of the constraints above
+    pub constraint_indices: Vec<ConstraintIndex>,
+
     // Signals to fix from the constraints given above
     pub signals_to_fix: BTreeMap<SignalIndex, SignalToFixData>,
 
     // Name of template and component associated to this polynomial system to be fixed
     pub template_name: String,
     pub component_name: String,
+
+    // Highest total degree of the constraints, once every optimization pass has been applied
+    pub max_degree: usize,
+}
+
+impl OptimizedPolynomialSystemFixedSignal {
+    // Keeps the constraints for which keep returns true, given their position and the constraint,
+    //  together with their original indices
+    fn retain_constraints(&mut self, mut keep: impl FnMut(usize, &Constraint<usize>) -> bool) {
+        let (constraints, constraint_indices) = self
+            .constraints
+            .drain(..)
+            .zip(self.constraint_indices.drain(..))
+            .enumerate()
+            .filter(|(position, (constraint, _))| keep(*position, constraint))
+            .map(|(_, pair)| pair)
+            .unzip();
+
+        self.constraints = constraints;
+        self.constraint_indices = constraint_indices;
+    }
 }
 
 pub struct ProhibitionPolynomial {
@@ -56,584 +105,6072 @@ pub struct ProhibitionPolynomial {
     pub num_vars: u32,
 }
 
-// Verifies a polynomial system generating a Cocoa5 file and executing it. Returns true if
-//  verification succeeded and false otherwise.
-pub fn verify_pol_systems(
-    pol_systems: &[PolynomialSystemFixedSignal],
-    context: &InputDataContextView,
-) -> Result<bool, Box<dyn Error>> {
-    assert!(!pol_systems.is_empty());
+// Returns a path for a new CoCoA script inside output_path, named after script_name and the current
+//  UNIX time. If a file with that name already exists, a numeric suffix is appended so no script is
+//  overwritten.
+fn get_timestamped_cocoa_file_path(output_path: &Path, script_name: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
 
-    let maybe_cocoa_path = which("CoCoAInterpreter");
-    if let Err(e) = maybe_cocoa_path {
-        let error_msg = format!("Couldn't find CocoA 5 interpreter in PATH: {}", e);
-        println!("{}", error_msg.red());
-        return Ok(false);
+    let mut path = output_path.join(format!("{}-{}.cocoa5", script_name, timestamp));
+    let mut suffix = 1;
+    while path.exists() {
+        path = output_path.join(format!("{}-{}-{}.cocoa5", script_name, timestamp, suffix));
+        suffix += 1;
     }
 
-    let cocoa_path = maybe_cocoa_path.unwrap();
-    let cocoa_base_folder = cocoa_path.parent().unwrap();
-    println!("Found CoCoA at {}", cocoa_path.to_str().unwrap());
+    path
+}
+
+// Writes a CoCoA script to the given path, together with its legend in a file with the same name
+//  and the .legend extension. The script itself only refers to signals by index
+fn write_cocoa_script(
+    script: &str,
+    cocoa_file_path: &Path,
+    context: &InputDataContextView,
+) -> VerifierResult<()> {
+    fs::write(cocoa_file_path, script)?;
+    fs::write(
+        cocoa_file_path.with_extension("legend"),
+        get_cocoa_script_legend(script, context),
+    )?;
+    Ok(())
+}
 
-    let cocoa_file_path = Path::new(context.base_path).join("groebner.cocoa5");
+// Returns a line "x_i -> name" for each signal variable x_i that appears in the code of the CoCoA
+//  script, sorted by index. Comments are skipped, as annotated scripts mention signal names in them
+fn get_cocoa_script_legend(script: &str, context: &InputDataContextView) -> String {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
 
-    let optimized_pol_systems: Vec<_> = pol_systems
-        .iter()
-        .map(|x| optimize_pol_system(x, context))
-        .collect();
+    let mut signals = BTreeSet::new();
+    for line in script.lines() {
+        let code = line.split("//").next().unwrap();
+        for (start, _) in code.match_indices("x_") {
+            let digits: String = code[start + 2..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            let end = start + 2 + digits.len();
+            let is_variable = !code[..start].ends_with(is_identifier_char)
+                && !code[end..].starts_with(is_identifier_char);
 
-    {
-        // Write Cocoa file
-        let mut cocoa_file = File::create(cocoa_file_path.as_path())?;
-        cocoa_file.write_all(
-            generate_cocoa_script(optimized_pol_systems.as_slice(), context).as_bytes(),
-        )?;
-        cocoa_file.flush()?;
+            if let (true, Ok(signal)) = (is_variable, digits.parse::<SignalIndex>()) {
+                signals.insert(signal);
+            }
+        }
     }
 
-    println!("{}", cocoa_file_path.display());
+    signals
+        .into_iter()
+        .map(|signal| format!("x_{} -> {}\n", signal, context.signal_name(signal)))
+        .collect()
+}
 
-    let mut child = Command::new(cocoa_path.as_path())
-        .arg("--no-preamble")
-        .arg(cocoa_file_path)
-        .current_dir(cocoa_base_folder)
-        .stdout(Stdio::piped())
-        .spawn()?;
+// CoCoA processes that are currently checking polynomial systems. They are kept here so they can be
+//  killed from other threads, for example when the user interrupts the verifier
+static RUNNING_COCOA: Mutex<Vec<Child>> = Mutex::new(Vec::new());
 
-    let stdout = child.stdout.take().unwrap();
-    let pol_systems_len = optimized_pol_systems.len();
+// Registers a running CoCoA process, returning its id
+fn add_running_cocoa(child: Child) -> u32 {
+    let id = child.id();
+    RUNNING_COCOA.lock().unwrap().push(child);
+    id
+}
 
-    display_ith_pol_system_progress(optimized_pol_systems.as_slice(), 0, context);
+// Removes the running CoCoA process with the given id. Returns None if it has already been killed
+fn take_running_cocoa(id: u32) -> Option<Child> {
+    let mut running_cocoa = RUNNING_COCOA.lock().unwrap();
+    let position = running_cocoa.iter().position(|child| child.id() == id)?;
+    Some(running_cocoa.swap_remove(position))
+}
 
-    let mut vec_timed_outs = Vec::new();
-    let mut vec_many_solutions = Vec::new();
+// Kills every running CoCoA process
+pub fn kill_running_cocoa() {
+    for mut child in RUNNING_COCOA.lock().unwrap().drain(..) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
 
-    for maybe_line in BufReader::new(stdout).lines() {
-        let line = maybe_line?;
-        let num: usize;
-        if let Some(num_str) = line.strip_prefix("OK: ") {
-            num = num_str.parse()?;
-            println!(
-                "\n{}",
-                format!(
-                    "Polynomial system {}/{} has only one solution!",
-                    num + 1,
-                    pol_systems_len
-                )
-                    .green()
-            );
-        } else if let Some(num_str) = line.strip_prefix("ERROR: ") {
-            num = num_str.parse()?;
-            println!(
-                "\n{}\n",
-                format!(
-                    "Polynomial system number {} possibly has many solutions!",
-                    num + 1
-                )
-                    .red()
-            );
-            vec_many_solutions.push(num);
-        } else if let Some(num_str) = line.strip_prefix("TIMEOUT: ") {
-            num = num_str.parse()?;
+// Set when the total timeout of the verification has been exceeded. From then on, CoCoA is killed
+//  as soon as it starts, so the remaining polynomial systems are left unchecked
+static TOTAL_TIMEOUT_EXCEEDED: AtomicBool = AtomicBool::new(false);
 
-            println!(
-                "\n{}\n",
-                format!("Polynomial system number {} has timed-out! ", num + 1).red()
-            );
-            vec_timed_outs.push(num);
-        } else if line.eq("FINISHED") {
-            if vec_timed_outs.is_empty() && vec_many_solutions.is_empty() {
-                return Ok(true);
-            }
-
-            // Print the number and modules that have failed
-            if !vec_many_solutions.is_empty() {
-                display_unverified_modules(
-                    pol_systems,
-                    &vec_many_solutions,
-                    "many solutions on Groebner basis",
-                );
-            }
+pub fn is_total_timeout_exceeded() -> bool {
+    TOTAL_TIMEOUT_EXCEEDED.load(Ordering::SeqCst)
+}
+
+// Enforces the total timeout of a verification while it is alive. When the timeout is exceeded,
+//  every running CoCoA process is killed
+pub struct TotalTimeoutWatchdog {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
 
-            if !vec_timed_outs.is_empty() {
-                display_unverified_modules(pol_systems, &vec_timed_outs, "timeout");
+impl TotalTimeoutWatchdog {
+    // Starts the watchdog, unless there is no timeout
+    pub fn start(timeout: Option<Duration>) -> TotalTimeoutWatchdog {
+        TOTAL_TIMEOUT_EXCEEDED.store(false, Ordering::SeqCst);
+        let Some(timeout) = timeout else {
+            return TotalTimeoutWatchdog {
+                stop: None,
+                thread: None,
+            };
+        };
+
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            // The verification has finished in time if the sender is dropped
+            if stopped.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                TOTAL_TIMEOUT_EXCEEDED.store(true, Ordering::SeqCst);
+                kill_running_cocoa();
             }
+        });
 
-            return Ok(false);
-        } else {
-            unreachable!();
+        TotalTimeoutWatchdog {
+            stop: Some(stop),
+            thread: Some(thread),
         }
+    }
+}
 
-        if num < pol_systems_len - 1 {
-            display_ith_pol_system_progress(optimized_pol_systems.as_slice(), num + 1, context);
+impl Drop for TotalTimeoutWatchdog {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
         }
     }
+}
 
-    // for pol_system in &pol_systems {
-    //     display_polynomial_system_readable(pol_system, context);
-    // }
+// Result of trying to fix the signals of a single polynomial system
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PolSystemVerdict {
+    // The polynomial system has a unique solution, so its signals are fixed
+    Fixed,
+    ManySolutions,
+    Timeout,
+    OutOfMemory,
 
-    unreachable!()
-}
+    // The polynomial system has more signals than allowed by the options, so it has been skipped
+    TooLarge,
 
-fn display_unverified_modules(
-    pol_systems: &[PolynomialSystemFixedSignal],
-    unverified_indices: &[usize],
-    unverified_reason: &str,
-) {
-    let mut unique_component_names = BTreeSet::new();
-    let mut component_name_to_template_name = BTreeMap::<&str, &str>::new();
+    // The polynomial system has constraints of higher degree than allowed by the options, so it
+    //  has been skipped
+    TooHighDegree,
 
-    // Get unique components and templates
-    for idx in unverified_indices {
-        let component_name = pol_systems[*idx].component_name.as_str();
-        let template_name = pol_systems[*idx].template_name.as_str();
-        unique_component_names.insert(component_name);
-        component_name_to_template_name.insert(component_name, template_name);
-    }
+    // CoCoA has not been run on this polynomial system
+    NotChecked,
+}
 
-    let display_str: String = itertools::Itertools::intersperse(
-        unique_component_names
-            .iter()
-            .map(|s| format!("{}: {}", s, component_name_to_template_name[s])),
-        ", ".to_string(),
-    )
-        .collect();
+pub struct PolSystemResult {
+    pub verdict: PolSystemVerdict,
 
-    println!(
-        "{}",
-        format!(
-            "Failed to verify due to {unverified_reason} {} polynomial systems in {} components: [{}]",
-            unverified_indices.len(),
-            unique_component_names.len(),
-            display_str
-        ).red()
-    );
+    // Time CoCoA has spent on this polynomial system, if it has been run
+    pub duration: Option<Duration>,
 }
 
-fn display_ith_pol_system_progress(
-    pol_systems: &[OptimizedPolynomialSystemFixedSignal],
-    index: usize,
+// Verifies a polynomial system generating a Cocoa5 file and executing it. Returns the result of
+//  each polynomial system, in the same order as pol_systems.
+pub fn verify_pol_systems(
+    pol_systems: &[PolynomialSystemFixedSignal],
     context: &InputDataContextView,
-) {
-    let pol_system = &pol_systems[index];
-    println!(
-        "\n{}",
-        format!(
-            "Fixing polynomial system {}/{} ({}: {})",
-            index + 1,
-            pol_systems.len(),
-            pol_system.component_name,
-            pol_system.template_name
-        )
-            .blue()
-    );
-    display_polynomial_system_readable(pol_system, context);
+) -> VerifierResult<Vec<PolSystemResult>> {
+    verify_pol_systems_with_script_name(pol_systems, context, "groebner")
 }
 
-// This function computes whether a given constraint is a binary constraint, that is, it specifies
-//  that a given signal must be binary. If it is, it returns the SignalIndex that this constraint
-//  specifies is binary. Else, it returns None
-fn is_constraint_binary_restriction(
-    constraint: &Constraint<usize>,
-    field_prime: &BigInt,
-) -> Option<SignalIndex> {
-    if !constraint.c().is_empty() {
-        return None;
-    }
-
-    if !constraint.has_constant_coefficient() {
-        return None;
-    }
+// Same as verify_pol_systems, but the CoCoA script is named after script_name, so several CoCoA
+//  processes can run at the same time on the same folder. When resuming, polynomial systems whose
+//  verdict has been recorded in the progress file are not sent to CoCoA again.
+fn verify_pol_systems_with_script_name(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    script_name: &str,
+) -> VerifierResult<Vec<PolSystemResult>> {
+    let keys: Vec<String> = pol_systems
+        .iter()
+        .map(|pol_system| get_pol_system_key(pol_system, context))
+        .collect();
 
-    let signals = constraint.take_signals();
+    let recorded_verdicts = if context.options.resume {
+        read_progress_file(Path::new(context.output_path))?
+    } else {
+        HashMap::new()
+    };
 
-    if signals.len() != 1 {
-        return None;
-    }
+    // Only conclusive verdicts are reused. Timeouts and interrupted systems are verified again
+    let mut results: Vec<Option<PolSystemResult>> = keys
+        .iter()
+        .map(|key| match recorded_verdicts.get(key) {
+            Some(&verdict @ (PolSystemVerdict::Fixed | PolSystemVerdict::ManySolutions)) => {
+                Some(PolSystemResult {
+                    verdict,
+                    duration: None,
+                })
+            }
+            _ => None,
+        })
+        .collect();
 
-    let single_signal;
-    let double_signals;
+    let pending_indices = sort_by_priority(
+        (0..pol_systems.len())
+            .filter(|idx| results[*idx].is_none())
+            .collect(),
+        pol_systems,
+        &context.options.priority_components,
+    );
 
-    if constraint.a().len() == 1 && constraint.b().len() == 2 {
-        single_signal = constraint.a();
-        double_signals = constraint.b();
-    } else if constraint.b().len() == 1 && constraint.a().len() == 2 {
-        single_signal = constraint.b();
-        double_signals = constraint.a();
-    } else {
-        return None;
+    if pending_indices.len() < pol_systems.len() {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            format!(
+                "Resuming verification: {} of {} polynomial systems have already been verified",
+                pol_systems.len() - pending_indices.len(),
+                pol_systems.len()
+            )
+            .blue()
+        );
     }
 
-    let signal_idx = *signals.iter().next().unwrap();
-
-    if !single_signal.get(signal_idx)?.is_one() {
-        return None;
-    }
+    if !pending_indices.is_empty() {
+        let pending_pol_systems: Vec<_> = pending_indices
+            .iter()
+            .map(|idx| pol_systems[*idx].clone())
+            .collect();
+        let pending_keys: Vec<_> = pending_indices
+            .iter()
+            .map(|idx| keys[*idx].clone())
+            .collect();
 
-    if !double_signals.get(signal_idx)?.is_one() {
-        return None;
+        let pending_results =
+            run_solver_on_pol_systems(&pending_pol_systems, &pending_keys, context, script_name)?;
+        for (idx, result) in pending_indices.into_iter().zip(pending_results) {
+            results[idx] = Some(result);
+        }
     }
 
-    let constant_coefficient_bigint =
-        double_signals.get(&Constraint::<usize>::constant_coefficient())?;
+    Ok(results.into_iter().flatten().collect())
+}
 
-    if !(field_prime - constant_coefficient_bigint).is_one() {
-        return None;
-    }
+// Sorts the given indices of polynomial systems so those of components matching the first
+//  priority pattern come first, followed by those matching the second one, and so on. The sort is
+//  stable, so the systems that match no pattern keep their order at the end
+fn sort_by_priority(
+    indices: Vec<usize>,
+    pol_systems: &[PolynomialSystemFixedSignal],
+    priority_patterns: &[String],
+) -> Vec<usize> {
+    let priority_patterns: Vec<_> = priority_patterns
+        .iter()
+        .map(|pattern| WildMatch::new(pattern))
+        .collect();
 
-    Some(*signal_idx)
+    indices
+        .into_iter()
+        .sorted_by_key(|idx| {
+            priority_patterns
+                .iter()
+                .position(|pattern| pattern.matches(&pol_systems[*idx].component_name))
+                .unwrap_or(priority_patterns.len())
+        })
+        .collect()
 }
 
-pub fn optimize_pol_system(
-    pol_system: &PolynomialSystemFixedSignal,
-    context: &InputDataContextView,
-) -> OptimizedPolynomialSystemFixedSignal {
-    // TODO: Perform Gauss-Jordan optimization
+// Program checking the polynomial systems. Instead of CoCoA, any command can be given in the
+//  options, as long as it follows the same protocol as the generated CoCoA scripts:
+//  - The polynomial systems are written to its standard input as the JSON document of
+//    dump_cas_json, which is then closed.
+//  - For each polynomial system, in increasing index order, it prints a line with its verdict and
+//    its index in the document: "OK: <index>" if the witness is its only solution, "ERROR: <index>"
+//    if it may have more and "TIMEOUT: <index>" if it couldn't be decided in timeout_seconds.
+//    Systems whose check is "skipped" are answered with the verdict given in the check.
+//  - Once every system has been answered, it prints "FINISHED" and exits.
+//  Any other line is reported as unexpected output. If it exits before printing FINISHED, the
+//  verification fails, unless it has run out of memory (see is_solver_out_of_memory).
+pub enum Solver {
+    // CoCoA interpreter at the given path, which runs a generated script
+    CoCoA(PathBuf),
 
-    let mut binary_signals = HashSet::new();
+    // Command run by the shell
+    External(String),
+}
 
-    for constraint in &pol_system.constraints {
-        if let Some(bin_signal) = is_constraint_binary_restriction(constraint, &context.field) {
-            binary_signals.insert(bin_signal);
+impl Solver {
+    // Returns the command given in the options, or CoCoA if there is none
+    fn find(context: &InputDataContextView) -> Result<Solver, which::Error> {
+        match &context.options.solver_cmd {
+            Some(command) => Ok(Solver::External(command.clone())),
+            None => which("CoCoAInterpreter").map(Solver::CoCoA),
         }
     }
 
-    // Remove constraints that are 0 == 0
-    let non_zero_constraints = pol_system.constraints.iter().filter(|x| !x.is_empty());
+    // Name of the solver in messages. External solvers are named after the program they run
+    fn name(&self) -> String {
+        match self {
+            Solver::CoCoA(_) => "CoCoA".to_string(),
+            Solver::External(command) => {
+                let program = command.split_whitespace().next().unwrap_or(command);
+                Path::new(program)
+                    .file_name()
+                    .map_or(program.to_string(), |name| {
+                        name.to_string_lossy().to_string()
+                    })
+            }
+        }
+    }
 
-    OptimizedPolynomialSystemFixedSignal {
-        constraints: non_zero_constraints.cloned().collect(),
-        signals_to_fix: pol_system
-            .signals_to_fix
-            .iter()
-            .map(|idx| -> (SignalIndex, SignalToFixData) {
-                (
-                    *idx,
-                    SignalToFixData {
-                        is_boolean: binary_signals.contains(idx),
-                    },
-                )
-            })
-            .collect(),
-        template_name: pol_system.template_name.clone(),
-        component_name: pol_system.component_name.clone(),
+    // Prints the settings of the solver. The version of CoCoA is also checked
+    fn check(&self, context: &InputDataContextView) -> VerifierResult<()> {
+        match self {
+            Solver::CoCoA(cocoa_path) => {
+                display_cocoa_settings(cocoa_path, context);
+                check_cocoa_version(cocoa_path, context)
+            }
+            Solver::External(command) => {
+                println_unless_quiet!(
+                    context.options,
+                    "Running solver '{}' with a timeout of {} seconds per polynomial system",
+                    command,
+                    context.options.groebner_cocoa_timeout_seconds
+                );
+                Ok(())
+            }
+        }
     }
-}
 
-pub fn generate_cocoa_script(
-    pol_systems: &[OptimizedPolynomialSystemFixedSignal],
-    context: &InputDataContextView,
-) -> String {
-    let pol_systems_str: String = Itertools::intersperse(
-        pol_systems
-            .iter()
-            .enumerate()
-            .map(|(idx, pol_system)| -> String { get_cocoa_subscript(pol_system, context, idx) }),
-        "\n".to_string(),
-    )
-        .collect();
+    // Starts checking the given polynomial systems, with the standard output and error piped.
+    //  CoCoA runs a script written to the output folder and named after script_name, while
+    //  external solvers receive the systems on their standard input.
+    fn spawn(
+        &self,
+        pol_systems: &[OptimizedPolynomialSystemFixedSignal],
+        context: &InputDataContextView,
+        script_name: &str,
+    ) -> VerifierResult<Child> {
+        match self {
+            Solver::CoCoA(cocoa_path) => {
+                let cocoa_file_path = if context.options.keep_scripts {
+                    get_timestamped_cocoa_file_path(Path::new(context.output_path), script_name)
+                } else {
+                    Path::new(context.output_path).join(format!("{}.cocoa5", script_name))
+                };
 
-    let field_prime = context.field.to_string();
+                let script = generate_cocoa_script(pol_systems, context);
+                write_cocoa_script(&script, &cocoa_file_path, context)?;
 
-    let s: String = formatdoc! {"
-        p := {field_prime};
-        use F ::= ZZ/(p);
+                println_unless_quiet!(
+                    context.options,
+                    "CoCoA script written to {}",
+                    cocoa_file_path.display()
+                );
 
-        {pol_systems_str}
+                Ok(get_cocoa_command(cocoa_path, &cocoa_file_path, context)?
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?)
+            }
+            Solver::External(command) => {
+                let input = serde_json::to_string(&get_cas_dump(pol_systems, context))?;
+                let mut child = get_shell_command(command)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
 
-        println \"FINISHED\";
-    "};
+                // The input is written on a separate thread, so the solver can print verdicts
+                //  before reading all of it. It may also exit without reading it, so write errors
+                //  are ignored
+                let mut stdin = child.stdin.take().unwrap();
+                thread::spawn(move || {
+                    let _ = stdin.write_all(input.as_bytes());
+                });
 
-    s
+                Ok(child)
+            }
+        }
+    }
 }
 
-pub fn display_polynomial_system_readable(
-    pol_system: &OptimizedPolynomialSystemFixedSignal,
-    context: &InputDataContextView,
-) {
-    let display_kind = SignalDisplayKind::Name;
-
-    println!("\nConstraints: ");
+// Returns the command running the given command line in the shell of the platform
+fn get_shell_command(command_line: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(command_line);
+    command
+}
 
-    for constraint in &pol_system.constraints {
-        println!(
-            "{} = 0",
-            get_constraint_polynomial(constraint, context, display_kind)
-        );
-    }
+// Runs the solver on the given polynomial systems, restarting it if it runs out of memory. The
+//  verdict of each system is appended to the progress file as soon as it is known, under the key in
+//  keys with the same index.
+fn run_solver_on_pol_systems(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    keys: &[String],
+    context: &InputDataContextView,
+    script_name: &str,
+) -> VerifierResult<Vec<PolSystemResult>> {
+    assert!(!pol_systems.is_empty());
 
-    let signals_to_fix_name_vec: Vec<String> = pol_system
-        .signals_to_fix
-        .keys()
-        .map(|idx| context.signal_name_map[idx].clone())
-        .collect();
+    let solver = match Solver::find(context) {
+        Ok(solver) => solver,
+        Err(e) => {
+            // The verification goes on without CoCoA instead of failing
+            let error_msg = VerifierError::CasNotFound(e).to_string();
+            println_unless_quiet!(context.options, "{}", error_msg.red());
+            return Ok(verify_pol_systems_without_cas(pol_systems, context));
+        }
+    };
+    let solver_name = solver.name();
 
-    let binary_signals_name_vec: Vec<String> = pol_system
-        .signals_to_fix
+    let optimized_pol_systems: Vec<_> = pol_systems
         .iter()
-        .filter_map(|(idx, data)| -> Option<String> {
-            if data.is_boolean {
-                Some(context.signal_name_map[idx].clone())
-            } else {
-                None
-            }
-        })
+        .map(|x| optimize_pol_system(x, context))
         .collect();
+    let pol_systems_len = optimized_pol_systems.len();
 
-    println!("Signals to fix: {:?}", signals_to_fix_name_vec);
-    println!("Binary signals: {:?}", binary_signals_name_vec);
+    // When only failures are requested, or several solver processes run at the same time, the
+    //  result of each polynomial system is not displayed. Failing ones are identified by their component
+    let show_results = !context.options.only_failures && context.options.jobs <= 1;
 
-    let prohibition_polynomial =
-        get_prohibition_witness_polynomial(&pol_system.signals_to_fix, context, display_kind);
+    // The system being fixed is only shown on interactive terminals, as it is redrawn in place
+    let progress_line = ProgressLine {
+        enabled: show_results
+            && !context.options.no_progress
+            && !context.options.quiet
+            && io::stdout().is_terminal(),
+    };
 
-    println!("Prohibition constraint: ");
-    println!("{} = 0", prohibition_polynomial.string);
-}
+    if context.options.jobs <= 1 {
+        solver.check(context)?;
+    }
 
-// Returns a String containing a subscript in the Cocoa5 CAS system for proving that the
-//  signals are fixed by the given constraints
-fn get_cocoa_subscript(
-    pol_system: &OptimizedPolynomialSystemFixedSignal,
-    context: &InputDataContextView,
-    pol_system_idx: PolSystemIndex,
-) -> String {
-    let mut used_signal_indices = BTreeSet::new();
+    let mut vec_timed_outs = Vec::new();
+    let mut vec_many_solutions = Vec::new();
+    let mut vec_out_of_memory = Vec::new();
+    let mut vec_too_large = Vec::new();
+    let mut vec_too_high_degree = Vec::new();
 
-    // The signals appearing in the constraints are used
+    let mut results: Vec<_> = (0..pol_systems_len)
+        .map(|_| PolSystemResult {
+            verdict: PolSystemVerdict::NotChecked,
+            duration: None,
+        })
+        .collect();
 
-    for constraint in &pol_system.constraints {
-        used_signal_indices.append(&mut constraint.take_cloned_signals_ordered());
-    }
+    // Index of the first polynomial system whose result has not been received yet. If the solver
+    //  runs out of memory, that system is marked as such and the solver is restarted on the next one
+    let mut first_unchecked = 0;
 
-    // The signals to be fixed are also used (even if they don't appear in any of the equations)
+    'solver_runs: while first_unchecked < pol_systems_len {
+        if is_total_timeout_exceeded() {
+            break;
+        }
 
-    for signal in pol_system.signals_to_fix.keys() {
-        used_signal_indices.insert(*signal);
-    }
+        // Indices in the script are relative to the first system included in it
+        let first_in_script = first_unchecked;
 
-    // let prohibition_vars = (0..pol_system.signals_to_fix.len()).map(|i| format!("u_{}", i));
+        let mut child = solver.spawn(
+            &optimized_pol_systems[first_in_script..],
+            context,
+            script_name,
+        )?;
+        progress_line.update(&optimized_pol_systems, first_in_script);
 
-    let prohibition_vars =
-        pol_system
-            .signals_to_fix
-            .iter()
-            .filter_map(|(idx, data)| -> Option<String> {
-                if data.is_boolean {
-                    None
-                } else {
-                    Some(format!("u_{}", idx))
-                }
-            });
+        // Instant at which the solver started working on the first unchecked polynomial system
+        let mut system_start = Instant::now();
 
-    let vars: String = Itertools::intersperse(
-        used_signal_indices
-            .iter()
-            .map(|i| format!("x_{}", i))
-            .chain(prohibition_vars),
-        ", ".to_string(),
-    )
-        .collect();
+        let stdout = child.stdout.take().unwrap();
 
-    let prohibition_polynomial = get_prohibition_witness_polynomial(
-        &pol_system.signals_to_fix,
-        context,
-        SignalDisplayKind::Index,
-    );
+        // Collect stderr on a separate thread, so the solver never blocks writing to a full pipe
+        let stderr = child.stderr.take().unwrap();
+        let child_id = add_running_cocoa(child);
+        if is_total_timeout_exceeded() {
+            // The watchdog may have killed the running processes before this one was registered
+            kill_running_cocoa();
+        }
+        let stderr_reader = thread::spawn(move || -> Vec<String> {
+            BufReader::new(stderr)
+                .lines()
+                .map_while(Result::ok)
+                .collect()
+        });
 
-    // Cocoa will struggle with prohibition polynomials containing a large amount of variables.
-    //  We will set a soft limit in order not to get stuck.
-    let var_limit = context
-        .options
-        .max_vars_prohibition_polynomial_before_timeout;
+        // Lines printed by the solver that are not part of our protocol, such as warnings or errors
+        let mut unexpected_output = Vec::new();
 
-    return if prohibition_polynomial.num_vars > var_limit {
-        formatdoc! {"
-            println \"TIMEOUT: {pol_system_idx}\";
-        "}
-    } else {
-        let pols: String = Itertools::intersperse(
-            pol_system
-                .constraints
-                .iter()
-                .map(|c| -> String {
-                    get_constraint_polynomial(c, context, SignalDisplayKind::Index)
-                })
-                .chain(iter::once(prohibition_polynomial.string)),
-            ",\n".to_string(),
-        )
-            .collect();
+        // Index of the polynomial system answered by a verdict line, which must be one of those
+        //  given to the solver. Otherwise, the solver is killed
+        let get_answered_pol_system = |num_str: &str, line: &str| -> VerifierResult<usize> {
+            let num = first_in_script + num_str.parse::<usize>()?;
+            if num >= pol_systems_len {
+                if let Some(mut child) = take_running_cocoa(child_id) {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                return Err(VerifierError::CasFailure(format!(
+                    "{} answered polynomial system {}, but there are only {}: {}",
+                    solver_name,
+                    num + 1,
+                    pol_systems_len,
+                    line
+                )));
+            }
+            Ok(num)
+        };
 
-        let timeout: u32 = context.options.groebner_cocoa_timeout_seconds;
+        for maybe_line in BufReader::new(stdout).lines() {
+            let line = maybe_line?;
+            progress_line.clear();
 
-        formatdoc! {"
-        use R ::= F[{vars}];
+            let num: usize;
+            let verdict;
+            if let Some(num_str) = line.strip_prefix("OK: ") {
+                num = get_answered_pol_system(num_str, &line)?;
+                verdict = PolSystemVerdict::Fixed;
+                if show_results && !progress_line.enabled {
+                    println_unless_quiet!(
+                        context.options,
+                        "{}",
+                        format!(
+                            "Polynomial system {}/{} has only one solution!",
+                            num + 1,
+                            pol_systems_len
+                        )
+                        .green()
+                    );
+                }
+            } else if let Some(num_str) = line.strip_prefix("ERROR: ") {
+                num = get_answered_pol_system(num_str, &line)?;
+                verdict = PolSystemVerdict::ManySolutions;
+                println_unless_quiet!(
+                    context.options,
+                    "\n{}\n",
+                    format!(
+                        "Polynomial system number {} possibly has many solutions!",
+                        describe_pol_system(pol_systems, num)
+                    )
+                    .red()
+                );
+                if context.options.print_witness_for_failures {
+                    if let Some(table) =
+                        context.get_component_witness_table(&pol_systems[num].component_name)
+                    {
+                        println_unless_quiet!(context.options, "{}\n", table);
+                    }
+                }
+                vec_many_solutions.push(num);
+            } else if let Some(num_str) = line.strip_prefix("TIMEOUT: ") {
+                num = get_answered_pol_system(num_str, &line)?;
+                verdict = PolSystemVerdict::Timeout;
 
-        I := ideal({pols});
+                println_unless_quiet!(
+                    context.options,
+                    "\n{}\n",
+                    format!(
+                        "Polynomial system number {} has timed-out! ",
+                        describe_pol_system(pol_systems, num)
+                    )
+                    .red()
+                );
+                vec_timed_outs.push(num);
+            } else if let Some(num_str) = line.strip_prefix("TOOLARGE: ") {
+                num = get_answered_pol_system(num_str, &line)?;
+                verdict = PolSystemVerdict::TooLarge;
 
-        Try
-            B := GBasisTimeout(I, {timeout});
+                println_unless_quiet!(
+                    context.options,
+                    "\n{}\n",
+                    format!(
+                        "Polynomial system number {} has too many signals to be verified! ",
+                        describe_pol_system(pol_systems, num)
+                    )
+                    .red()
+                );
+                vec_too_large.push(num);
+            } else if let Some(num_str) = line.strip_prefix("HIGHDEGREE: ") {
+                num = get_answered_pol_system(num_str, &line)?;
+                verdict = PolSystemVerdict::TooHighDegree;
 
-            If not(1 IsIn I) Then
-                println \"ERROR: {pol_system_idx}\";
-            Else;
-                println \"OK: {pol_system_idx}\";
-            EndIf;
-        UponError E Do
-            println \"TIMEOUT: {pol_system_idx}\";
-        EndTry;
-        "}
-    };
-}
+                println_unless_quiet!(
+                    context.options,
+                    "\n{}\n",
+                    format!(
+                        "Polynomial system number {} has constraints of degree {}, higher than allowed! ",
+                        describe_pol_system(pol_systems, num),
+                        optimized_pol_systems[num].max_degree
+                    )
+                    .red()
+                );
+                vec_too_high_degree.push(num);
+            } else if line.eq("FINISHED") {
+                // The solver exits right away after printing FINISHED
+                let exit_status = match take_running_cocoa(child_id) {
+                    Some(mut child) => Some(child.wait()?),
+                    None => None,
+                };
 
-fn get_prohibition_witness_polynomial(
-    signals_to_fix: &BTreeMap<SignalIndex, SignalToFixData>,
-    context: &InputDataContextView,
-    display_kind: SignalDisplayKind,
-) -> ProhibitionPolynomial {
-    if signals_to_fix.is_empty() {
-        // If signals_to_fix is empty, we pass the "0" ring element to cocoa instead
-        //  of an integer (otherwise cocoa complains ERROR: Expecting type LIST
-        //  or RINGELEM, but found type INT)
+                // Every system given to the solver must have been answered by then
+                let all_answered = results[first_in_script..]
+                    .iter()
+                    .all(|result| result.verdict != PolSystemVerdict::NotChecked);
+                if let Some(exit_status) = exit_status.filter(|_| !all_answered) {
+                    let stderr_output = stderr_reader.join().unwrap_or_default();
+                    return Err(VerifierError::CasFailure(
+                        get_unexpected_termination_message(
+                            &solver_name,
+                            &exit_status,
+                            &unexpected_output,
+                            &stderr_output,
+                        ),
+                    ));
+                }
+                break 'solver_runs;
+            } else {
+                println_unless_quiet!(
+                    context.options,
+                    "{}",
+                    format!("[{}] {}", solver_name, line).yellow()
+                );
+                unexpected_output.push(line);
+                progress_line.update(&optimized_pol_systems, first_unchecked);
+                continue;
+            }
 
-        let str = match display_kind {
-            SignalDisplayKind::Name => "0".to_string(),
-            SignalDisplayKind::Index => "RingElem(R, 0)".to_string(),
-        };
+            results[num] = PolSystemResult {
+                verdict,
+                duration: Some(system_start.elapsed()),
+            };
+            system_start = Instant::now();
+            append_to_progress_file(Path::new(context.output_path), &keys[num], verdict)?;
 
-        return ProhibitionPolynomial {
-            string: str,
-            num_vars: 0,
+            first_unchecked = num + 1;
+            if first_unchecked < pol_systems_len {
+                progress_line.update(&optimized_pol_systems, first_unchecked);
+            }
+        }
+
+        // The output stream has been closed without the solver printing FINISHED, so it must have
+        //  crashed or been killed.
+        let exit_status = match take_running_cocoa(child_id) {
+            Some(mut child) => child.wait()?,
+
+            // Killed because of the total timeout, which leaves the remaining systems unchecked
+            None if is_total_timeout_exceeded() => {
+                let _ = stderr_reader.join();
+                break;
+            }
+            None => {
+                return Err(VerifierError::CasFailure(format!(
+                    "{} has been interrupted",
+                    solver_name
+                )))
+            }
         };
-    }
+        let stderr_output = stderr_reader.join().unwrap_or_default();
 
-    let mut num_vars = 0;
+        // If every result has already been received, no system can be blamed for it
+        if first_unchecked == pol_systems_len
+            || !is_solver_out_of_memory(&exit_status, &unexpected_output, &stderr_output, context)
+        {
+            // Report everything we know about it.
+            return Err(VerifierError::CasFailure(
+                get_unexpected_termination_message(
+                    &solver_name,
+                    &exit_status,
+                    &unexpected_output,
+                    &stderr_output,
+                ),
+            ));
+        }
 
-    let str: String = Itertools::intersperse(
-        signals_to_fix.iter().map(|(signal_idx, data)| -> String {
-            let indexed_signal_kind = format!("x_{}", signal_idx);
-            let signal_name = match display_kind {
-                SignalDisplayKind::Name => &context.signal_name_map[signal_idx],
-                SignalDisplayKind::Index => &indexed_signal_kind,
-            };
-            let witness_value = &context.witness[signal_idx];
+        println_unless_quiet!(
+            context.options,
+            "\n{}\n",
+            format!(
+                "Polynomial system number {} has run out of memory! ",
+                describe_pol_system(pol_systems, first_unchecked)
+            )
+            .red()
+        );
+        vec_out_of_memory.push(first_unchecked);
+        results[first_unchecked] = PolSystemResult {
+            verdict: PolSystemVerdict::OutOfMemory,
+            duration: Some(system_start.elapsed()),
+        };
+        append_to_progress_file(
+            Path::new(context.output_path),
+            &keys[first_unchecked],
+            PolSystemVerdict::OutOfMemory,
+        )?;
+        first_unchecked += 1;
 
-            // Optimize  prohibition for binary variables. Instead of generating a new
-            // u_i value, just assert that they must be the opposite binary value.
-            if data.is_boolean {
-                num_vars += 1;
-                format!("({} - {})", signal_name, 1 - witness_value)
-            } else {
-                num_vars += 2;
+        if first_unchecked < pol_systems_len {
+            println_unless_quiet!(
+                context.options,
+                "{}",
                 format!(
-                    "(({} - {})*u_{} - 1)",
-                    signal_name, witness_value, signal_idx
+                    "Restarting {} on the remaining polynomial systems",
+                    solver_name
                 )
-            }
-        }),
-        " * ".to_string(),
-    )
-        .collect();
+                .yellow()
+            );
+        }
+    }
 
-    ProhibitionPolynomial {
-        string: str,
-        num_vars,
+    if context.options.quiet {
+        return Ok(results);
+    }
+
+    if vec_timed_outs.is_empty()
+        && vec_many_solutions.is_empty()
+        && vec_out_of_memory.is_empty()
+        && vec_too_large.is_empty()
+        && vec_too_high_degree.is_empty()
+    {
+        return Ok(results);
     }
+
+    // Print the number and modules that have failed
+    if !vec_many_solutions.is_empty() {
+        display_unverified_modules(
+            pol_systems,
+            &vec_many_solutions,
+            "many solutions on Groebner basis",
+        );
+    }
+
+    if !vec_timed_outs.is_empty() {
+        display_unverified_modules(pol_systems, &vec_timed_outs, "timeout");
+    }
+
+    if !vec_out_of_memory.is_empty() {
+        let reason = match context.options.cocoa_memory_limit_mb {
+            Some(limit) => format!("running out of memory (limit of {} MB)", limit),
+            None => "running out of memory".to_string(),
+        };
+        display_unverified_modules(pol_systems, &vec_out_of_memory, &reason);
+    }
+
+    if !vec_too_large.is_empty() {
+        display_unverified_modules(
+            pol_systems,
+            &vec_too_large,
+            "too many signals (too large to verify)",
+        );
+    }
+
+    if !vec_too_high_degree.is_empty() {
+        display_unverified_modules(
+            pol_systems,
+            &vec_too_high_degree,
+            "constraints of too high degree",
+        );
+    }
+
+    Ok(results)
 }
 
-fn get_constraint_polynomial(
-    constraint: &Constraint<usize>,
+// File inside the artifacts folder where the verdict of each polynomial system is appended as soon
+//  as CoCoA finishes it, so an interrupted verification can be resumed
+const PROGRESS_FILE_NAME: &str = "verification-progress.txt";
+
+// Returns a key identifying a polynomial system together with the witness values of its signals to
+//  fix. It does not depend on the order of the constraints or of their terms, and changes whenever
+//  the circuit or the witness change the system, so stale progress entries are never reused.
+pub fn get_pol_system_key(
+    pol_system: &PolynomialSystemFixedSignal,
     context: &InputDataContextView,
-    display_kind: SignalDisplayKind,
 ) -> String {
-    // We assume that the constraints are fixed (called Constraint::fix_constraint) before calling
-    //  these method. Right now, we do so everytime we update a constraint in
-    //  substitute_witness_signal_into_storage
+    let constraint_keys: BTreeSet<_> = pol_system
+        .constraints
+        .iter()
+        .filter(|constraint| !constraint.is_empty())
+        .map(get_constraint_key)
+        .collect();
 
-    let a = constraint.a();
-    let b = constraint.b();
-    let c = constraint.c();
+    let signals_to_fix: Vec<_> = pol_system
+        .signals_to_fix
+        .iter()
+        .map(|signal| (*signal, context.witness_value(*signal)))
+        .collect();
 
-    if a.is_empty() || b.is_empty() {
-        //  Only linear constraint c
-        linear_term_to_string(c, context, false, display_kind)
-    } else {
-        let a_str = linear_term_to_string(a, context, true, display_kind);
-        let b_str = linear_term_to_string(b, context, true, display_kind);
-        let c_str = linear_term_to_string(c, context, false, display_kind);
+    let description = format!(
+        "{}|{}|{:?}|{:?}",
+        context.field, pol_system.component_name, constraint_keys, signals_to_fix
+    );
 
-        if c_str.starts_with('-') {
-            format!(
-                "{} * {} - {}",
-                a_str,
-                b_str,
-                c_str.chars().skip(1).collect::<String>()
-            )
-        } else if c.is_empty() {
-            format!("{} * {}", a_str, b_str)
-        } else {
-            format!("{} * {} + {}", a_str, b_str, c_str)
+    get_stable_hash(&description)
+}
+
+// Returns the 64-bit FNV-1a hash of a string in hexadecimal. Unlike the hasher of the standard
+//  library, it is stable across builds, so it can be stored in files read by later runs
+pub fn get_stable_hash(description: &str) -> String {
+    let hash = description
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{:016x}", hash)
+}
+
+fn get_progress_verdict_name(verdict: PolSystemVerdict) -> &'static str {
+    match verdict {
+        PolSystemVerdict::Fixed => "OK",
+        PolSystemVerdict::ManySolutions => "ERROR",
+        PolSystemVerdict::Timeout => "TIMEOUT",
+        PolSystemVerdict::OutOfMemory => "OUTOFMEMORY",
+        PolSystemVerdict::TooLarge => "TOOLARGE",
+        PolSystemVerdict::TooHighDegree => "HIGHDEGREE",
+        PolSystemVerdict::NotChecked => "NOTCHECKED",
+    }
+}
+
+fn append_to_progress_file(
+    base_path: &Path,
+    key: &str,
+    verdict: PolSystemVerdict,
+) -> VerifierResult<()> {
+    // The file is opened for every verdict, so each line is written (and survives an interruption)
+    //  as soon as it is known, even with several CoCoA processes running at the same time
+    let mut progress_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(base_path.join(PROGRESS_FILE_NAME))?;
+    writeln!(
+        progress_file,
+        "{} {}",
+        key,
+        get_progress_verdict_name(verdict)
+    )?;
+    Ok(())
+}
+
+// Returns the last verdict recorded in the progress file for each polynomial system key. Lines
+//  that cannot be parsed, such as a line truncated by an interruption, are ignored.
+fn read_progress_file(base_path: &Path) -> VerifierResult<HashMap<String, PolSystemVerdict>> {
+    let path = base_path.join(PROGRESS_FILE_NAME);
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let all_verdicts = [
+        PolSystemVerdict::Fixed,
+        PolSystemVerdict::ManySolutions,
+        PolSystemVerdict::Timeout,
+        PolSystemVerdict::OutOfMemory,
+        PolSystemVerdict::TooLarge,
+        PolSystemVerdict::TooHighDegree,
+        PolSystemVerdict::NotChecked,
+    ];
+
+    let mut recorded_verdicts = HashMap::new();
+    for line in fs::read_to_string(path)?.lines() {
+        let Some((key, verdict_name)) = line.split_once(' ') else {
+            continue;
+        };
+        let maybe_verdict = all_verdicts
+            .into_iter()
+            .find(|v| get_progress_verdict_name(*v) == verdict_name);
+        if let Some(verdict) = maybe_verdict {
+            recorded_verdicts.insert(key.to_string(), verdict);
         }
     }
+
+    Ok(recorded_verdicts)
 }
 
-// Will surround with parenthesis if there is more than one summation term and surround_with_parenthesis is true
-fn linear_term_to_string(
-    linear_term: &HashMap<usize, BigInt>,
-    context: &InputDataContextView,
-    surround_with_parenthesis: bool,
-    display_kind: SignalDisplayKind,
-) -> String {
-    if linear_term.is_empty() {
-        return "0".to_string();
+// Removes the progress file of a previous verification, so a new one starts from scratch
+pub fn remove_progress_file(base_path: &Path) -> VerifierResult<()> {
+    let path = base_path.join(PROGRESS_FILE_NAME);
+    if path.exists() {
+        fs::remove_file(path)?;
     }
+    Ok(())
+}
 
-    let prime = &context.field;
+fn display_cocoa_settings(cocoa_path: &Path, context: &InputDataContextView) {
+    println_unless_quiet!(context.options, "Found CoCoA at {}", cocoa_path.display());
+    println_unless_quiet!(
+        context.options,
+        "Running CoCoA with a timeout of {} seconds per polynomial system and {}",
+        context.options.groebner_cocoa_timeout_seconds,
+        match context.options.cocoa_memory_limit_mb {
+            Some(limit) => format!("a memory limit of {} MB", limit),
+            None => "no memory limit".to_string(),
+        }
+    );
+}
 
-    let s: String = linear_term
-        .iter()
-        .sorted_by_key(|(&idx, _)| idx)
-        .map(|(&signal_idx, coeff)| -> String {
-            if signal_idx == ArithmeticExpression::<usize>::constant_coefficient() {
-                coefficient_to_string(coeff, prime)
-            } else {
-                let indexed_signal_name = format!("x_{}", signal_idx);
-                let signal_name = match display_kind {
-                    SignalDisplayKind::Name => &context.signal_name_map[&signal_idx],
-                    SignalDisplayKind::Index => &indexed_signal_name,
-                };
+// Versions of CoCoA the generated scripts are known to work with. The semantics of GBasisTimeout and
+//  UponError differ slightly in other versions
+pub const SUPPORTED_COCOA_VERSIONS: &str = "5.2..5.5";
 
-                if coeff.is_one() {
-                    signal_name.clone()
-                } else if coeff.eq(&(prime - &BigInt::one())) {
-                    format!("-{}", signal_name)
-                } else {
-                    format!("{}*{}", coefficient_to_string(coeff, prime), signal_name)
-                }
-            }
-        })
-        .fold("".to_string(), |curr, next| -> String {
-            if curr.is_empty() {
-                next
-            } else if next.starts_with('-') {
-                format!("{} - {}", curr, next.chars().skip(1).collect::<String>())
-            } else {
-                format!("{} + {}", curr, next)
-            }
-        });
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct CocoaVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
 
-    if surround_with_parenthesis && linear_term.len() > 1 {
-        format!("({})", s)
-    } else {
-        s
+impl FromStr for CocoaVersion {
+    type Err = String;
+
+    // Parses versions such as "5.4.1", or "5.4" for the first release of 5.4
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s
+            .split('.')
+            .map(|part| part.parse::<u32>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Invalid CoCoA version '{}'", s))?;
+
+        match parts.as_slice() {
+            [major, minor] => Ok(CocoaVersion {
+                major: *major,
+                minor: *minor,
+                patch: 0,
+            }),
+            [major, minor, patch] => Ok(CocoaVersion {
+                major: *major,
+                minor: *minor,
+                patch: *patch,
+            }),
+            _ => Err(format!("Invalid CoCoA version '{}'", s)),
+        }
     }
 }
 
-// Returns a prettified string of the given coefficient
-fn coefficient_to_string(coeff: &BigInt, prime_field: &BigInt) -> String {
-    if coeff > &(prime_field / 2) {
-        format!("-{}", (prime_field - coeff))
-    } else {
-        coeff.to_string()
+impl fmt::Display for CocoaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// Range of CoCoA versions given as MIN..MAX, from MIN (inclusive) to MAX (exclusive)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct CocoaVersionRange {
+    pub min: CocoaVersion,
+    pub max: CocoaVersion,
+}
+
+impl CocoaVersionRange {
+    pub fn contains(&self, version: CocoaVersion) -> bool {
+        self.min <= version && version < self.max
     }
 }
+
+impl FromStr for CocoaVersionRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = s.split_once("..").ok_or_else(|| {
+            format!(
+                "Invalid CoCoA version range '{}', expected MIN..MAX (for example, {})",
+                s, SUPPORTED_COCOA_VERSIONS
+            )
+        })?;
+
+        Ok(CocoaVersionRange {
+            min: min.parse()?,
+            max: max.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for CocoaVersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+// Runs a probe script printing the version of the CoCoA interpreter at cocoa_path. Returns None if
+//  the output does not contain a version
+fn get_cocoa_version(
+    cocoa_path: &Path,
+    context: &InputDataContextView,
+) -> VerifierResult<Option<CocoaVersion>> {
+    let probe_file_path = Path::new(context.output_path).join("cocoa-version.cocoa5");
+    fs::write(&probe_file_path, "println VersionInfo().CoCoAVersion;\n")?;
+
+    let output = get_cocoa_command(cocoa_path, &probe_file_path, context)?.output()?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find_map(|word| word.parse::<CocoaVersion>().ok()))
+}
+
+// Prints the version of CoCoA and checks it is in the range allowed by the options. A version out of
+//  the range, or one that cannot be detected, aborts the verification if requested in the options,
+//  and is otherwise reported as a warning
+fn check_cocoa_version(cocoa_path: &Path, context: &InputDataContextView) -> VerifierResult<()> {
+    let allowed_versions = context.options.allowed_cocoa_versions;
+
+    let problem = match get_cocoa_version(cocoa_path, context)? {
+        Some(version) => {
+            println_unless_quiet!(context.options, "Detected CoCoA version {}", version);
+            if allowed_versions.contains(version) {
+                return Ok(());
+            }
+            format!(
+                "CoCoA version {} is outside the supported range {}",
+                version, allowed_versions
+            )
+        }
+        None => "Couldn't detect the version of CoCoA".to_string(),
+    };
+
+    if context.options.abort_on_unsupported_cocoa {
+        Err(VerifierError::CasFailure(format!(
+            "{}. Use --allow-cocoa-version to accept other versions",
+            problem
+        )))
+    } else {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            format!("Warning: {}", problem).yellow()
+        );
+        Ok(())
+    }
+}
+
+// Verifies polynomial systems when CoCoA cannot be found. Linear systems are still decided by
+//  Gauss-Jordan elimination, while the rest are reported as not checked, so they can be told apart
+//  from the systems that have failed
+fn verify_pol_systems_without_cas(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+) -> Vec<PolSystemResult> {
+    let results: Vec<_> = pol_systems
+        .iter()
+        .enumerate()
+        .map(|(idx, pol_system)| {
+            let verdict = match get_unfixed_signals_of_linear_pol_system(pol_system, &context.field)
+            {
+                Some(unfixed_signals) if unfixed_signals.is_empty() => PolSystemVerdict::Fixed,
+                Some(_) => {
+                    println_unless_quiet!(
+                        context.options,
+                        "{}",
+                        format!(
+                            "Polynomial system {} has many solutions (linear, verified without CoCoA)",
+                            describe_pol_system(pol_systems, idx)
+                        )
+                        .red()
+                    );
+                    PolSystemVerdict::ManySolutions
+                }
+                None => {
+                    println_unless_quiet!(
+                        context.options,
+                        "{}",
+                        format!(
+                            "Polynomial system {} is unverified (no CAS): it is not linear",
+                            describe_pol_system(pol_systems, idx)
+                        )
+                        .yellow()
+                    );
+                    PolSystemVerdict::NotChecked
+                }
+            };
+
+            PolSystemResult {
+                verdict,
+                duration: None,
+            }
+        })
+        .collect();
+
+    let num_unverified = results
+        .iter()
+        .filter(|r| r.verdict == PolSystemVerdict::NotChecked)
+        .count();
+    println_unless_quiet!(
+        context.options,
+        "{} linear polynomial systems verified without CoCoA, {} left unverified (no CAS)",
+        pol_systems.len() - num_unverified,
+        num_unverified
+    );
+
+    results
+}
+
+// Polynomial systems of a single component, together with the index of the first one among all the
+//  polynomial systems of the circuit
+type PolSystemBatch = (PolSystemIndex, Vec<PolynomialSystemFixedSignal>);
+type BatchResults = (PolSystemIndex, Vec<PolSystemResult>);
+
+// Runs traverse, which must call the given dispatch function with the polynomial systems of each
+//  component as soon as they are known. They are verified by up to context.options.jobs CoCoA
+//  processes in parallel while the traversal goes on. Returns the result of traverse and the result
+//  of every dispatched polynomial system, in the order they were dispatched.
+pub fn verify_pol_systems_in_parallel<R, F>(
+    context: &InputDataContextView,
+    traverse: F,
+) -> VerifierResult<(R, Vec<PolSystemResult>)>
+where
+    F: FnOnce(&mut dyn FnMut(&[PolynomialSystemFixedSignal])) -> R,
+{
+    let jobs = context.options.jobs.max(1);
+    let not_checked = || PolSystemResult {
+        verdict: PolSystemVerdict::NotChecked,
+        duration: None,
+    };
+
+    let solver = match Solver::find(context) {
+        Ok(solver) => solver,
+        Err(e) => {
+            let error_msg = VerifierError::CasNotFound(e).to_string();
+            println_unless_quiet!(context.options, "{}", error_msg.red());
+
+            let mut all_pol_systems = Vec::new();
+            let res = traverse(&mut |pol_systems| all_pol_systems.extend_from_slice(pol_systems));
+            return Ok((
+                res,
+                verify_pol_systems_without_cas(&all_pol_systems, context),
+            ));
+        }
+    };
+    solver.check(context)?;
+    println_unless_quiet!(
+        context.options,
+        "Running up to {} {} processes in parallel",
+        jobs,
+        solver.name()
+    );
+
+    let (sender, receiver) = mpsc::channel::<PolSystemBatch>();
+    let receiver = Mutex::new(receiver);
+
+    thread::scope(|scope| {
+        // Each worker has its own CoCoA script
+        let workers: Vec<_> = (0..jobs)
+            .map(|worker_idx| {
+                let receiver = &receiver;
+                scope.spawn(move || -> VerifierResult<Vec<BatchResults>> {
+                    let script_name = format!("groebner-job{}", worker_idx);
+                    let mut worker_results = Vec::new();
+
+                    loop {
+                        // The lock must be released before running CoCoA, so other workers can
+                        //  receive batches in the meantime
+                        let batch = receiver.lock().unwrap().recv();
+                        let Ok((first_idx, pol_systems)) = batch else {
+                            break;
+                        };
+
+                        let results = verify_pol_systems_with_script_name(
+                            &pol_systems,
+                            context,
+                            &script_name,
+                        )?;
+                        worker_results.push((first_idx, results));
+                    }
+
+                    Ok(worker_results)
+                })
+            })
+            .collect();
+
+        let mut num_pol_systems = 0;
+        let res = traverse(&mut |pol_systems| {
+            if pol_systems.is_empty() {
+                return;
+            }
+
+            // Workers only stop receiving when they fail, in which case the error is reported below
+            let _ = sender.send((num_pol_systems, pol_systems.to_vec()));
+            num_pol_systems += pol_systems.len();
+        });
+
+        // Closing the channel makes the workers finish once every batch has been verified
+        drop(sender);
+
+        let mut results: Vec<_> = (0..num_pol_systems).map(|_| not_checked()).collect();
+        for worker in workers {
+            let worker_results = worker.join().map_err(|_| {
+                VerifierError::CasFailure("A CoCoA worker thread panicked".to_string())
+            })??;
+            for (first_idx, batch_results) in worker_results {
+                for (offset, result) in batch_results.into_iter().enumerate() {
+                    results[first_idx + offset] = result;
+                }
+            }
+        }
+
+        Ok((res, results))
+    })
+}
+
+// Returns the command running the CoCoA script at cocoa_file_path, with the extra arguments and
+//  memory limit given in the options
+fn get_cocoa_command(
+    cocoa_path: &Path,
+    cocoa_file_path: &Path,
+    context: &InputDataContextView,
+) -> VerifierResult<Command> {
+    let mut command = Command::new(cocoa_path);
+    command
+        .arg("--no-preamble")
+        .args(&context.options.cocoa_args)
+        .arg(cocoa_file_path)
+        .current_dir(cocoa_path.parent().unwrap());
+    if let Some(limit) = context.options.cocoa_memory_limit_mb {
+        set_memory_limit(&mut command, limit)?;
+    }
+    Ok(command)
+}
+
+// Returns the code given by the user to be run before any generated CoCoA code, such as package
+//  loading or settings. It always ends in a newline unless it is empty.
+fn get_cocoa_prelude(context: &InputDataContextView) -> String {
+    match &context.options.cocoa_prelude {
+        Some(prelude) if !prelude.is_empty() && !prelude.ends_with('\n') => {
+            format!("{}\n", prelude)
+        }
+        Some(prelude) => prelude.clone(),
+        None => String::new(),
+    }
+}
+
+// Limits the address space of the spawned process to limit_mb megabytes, so a pathological
+//  polynomial system makes CoCoA fail to allocate instead of exhausting the memory of the machine
+#[cfg(unix)]
+fn set_memory_limit(command: &mut Command, limit_mb: u64) -> VerifierResult<()> {
+    use std::os::unix::process::CommandExt;
+
+    let limit_bytes = limit_mb.saturating_mul(1024 * 1024) as libc::rlim_t;
+
+    // SAFETY: the closure runs in the forked child before exec, and only calls setrlimit, which is
+    //  async-signal-safe
+    unsafe {
+        command.pre_exec(move || {
+            let rlimit = libc::rlimit {
+                rlim_cur: limit_bytes,
+                rlim_max: limit_bytes,
+            };
+            if libc::setrlimit(libc::RLIMIT_AS, &rlimit) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_memory_limit(_command: &mut Command, _limit_mb: u64) -> VerifierResult<()> {
+    Err(VerifierError::InvalidArgument(
+        "Memory limits for CoCoA are only supported on Unix systems".to_string(),
+    ))
+}
+
+// Returns true if the solver terminated because it ran out of memory. Under an address space limit,
+//  CoCoA reports a failed allocation, while the kernel OOM killer terminates it with SIGKILL. A
+//  SIGKILL may also come from the user or any other process, so it is only blamed on memory if a
+//  memory limit has been given in the options
+fn is_solver_out_of_memory(
+    exit_status: &ExitStatus,
+    unexpected_output: &[String],
+    stderr_output: &[String],
+    context: &InputDataContextView,
+) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if context.options.cocoa_memory_limit_mb.is_some()
+            && exit_status.signal() == Some(libc::SIGKILL)
+        {
+            return true;
+        }
+    }
+
+    unexpected_output.iter().chain(stderr_output).any(|line| {
+        let line = line.to_lowercase();
+        line.contains("bad_alloc") || line.contains("out of memory")
+    })
+}
+
+// Maximum number of lines of CoCoA output included in error messages
+const MAX_REPORTED_CAS_OUTPUT_LINES: usize = 20;
+
+// Returns a message describing an unexpected termination of the solver, including the last lines it
+//  printed that did not follow the expected protocol and its stderr
+fn get_unexpected_termination_message(
+    solver_name: &str,
+    exit_status: &ExitStatus,
+    unexpected_output: &[String],
+    stderr_output: &[String],
+) -> String {
+    let last_lines = |lines: &[String]| -> String {
+        if lines.is_empty() {
+            "    <empty>".to_string()
+        } else {
+            lines
+                .iter()
+                .skip(lines.len().saturating_sub(MAX_REPORTED_CAS_OUTPUT_LINES))
+                .map(|l| format!("    {}", l))
+                .join("\n")
+        }
+    };
+
+    formatdoc! {"
+        {solver_name} terminated unexpectedly ({exit_status}) before finishing all polynomial systems.
+        Last unexpected output:
+        {}
+        Standard error:
+        {}",
+        last_lines(unexpected_output),
+        last_lines(stderr_output),
+    }
+}
+
+fn display_unverified_modules(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    unverified_indices: &[usize],
+    unverified_reason: &str,
+) {
+    let mut unique_component_names = BTreeSet::new();
+    let mut component_name_to_template_name = BTreeMap::<&str, &str>::new();
+
+    // Get unique components and templates
+    for idx in unverified_indices {
+        let component_name = pol_systems[*idx].component_name.as_str();
+        let template_name = pol_systems[*idx].template_name.as_str();
+        unique_component_names.insert(component_name);
+        component_name_to_template_name.insert(component_name, template_name);
+    }
+
+    let display_str: String = itertools::Itertools::intersperse(
+        unique_component_names
+            .iter()
+            .map(|s| format!("{}: {}", s, component_name_to_template_name[s])),
+        ", ".to_string(),
+    )
+        .collect();
+
+    println!(
+        "{}",
+        format!(
+            "Failed to verify due to {unverified_reason} {} polynomial systems in {} components: [{}]",
+            unverified_indices.len(),
+            unique_component_names.len(),
+            display_str
+        ).red()
+    );
+}
+
+// Returns the 1-based number of the given polynomial system, followed by the component it belongs
+//  to, for example "3 (main.sub: Template)"
+fn describe_pol_system(pol_systems: &[PolynomialSystemFixedSignal], index: usize) -> String {
+    format!(
+        "{} ({}: {})",
+        index + 1,
+        pol_systems[index].component_name,
+        pol_systems[index].template_name
+    )
+}
+
+// Terminal line showing the polynomial system CoCoA is working on. It is redrawn in place for each
+//  system, so it must be cleared before printing anything else
+struct ProgressLine {
+    enabled: bool,
+}
+
+impl ProgressLine {
+    fn update(&self, pol_systems: &[OptimizedPolynomialSystemFixedSignal], index: usize) {
+        if !self.enabled {
+            return;
+        }
+
+        let pol_system = &pol_systems[index];
+        print!(
+            "\r\x1B[2K{}",
+            format!(
+                "Fixing polynomial system {}/{} ({}: {})",
+                index + 1,
+                pol_systems.len(),
+                pol_system.component_name,
+                pol_system.template_name
+            )
+            .blue()
+        );
+        io::stdout().flush().ok();
+    }
+
+    fn clear(&self) {
+        if self.enabled {
+            print!("\r\x1B[2K");
+            io::stdout().flush().ok();
+        }
+    }
+}
+
+// This function computes whether a given constraint is a binary constraint, that is, it specifies
+//  that a given signal must be binary. If it is, it returns the SignalIndex that this constraint
+//  specifies is binary. Else, it returns None
+fn is_constraint_binary_restriction(
+    constraint: &Constraint<usize>,
+    field_prime: &BigInt,
+) -> Option<SignalIndex> {
+    if !constraint.c().is_empty() {
+        return None;
+    }
+
+    if !constraint.has_constant_coefficient() {
+        return None;
+    }
+
+    let signals = constraint.take_signals();
+
+    if signals.len() != 1 {
+        return None;
+    }
+
+    let single_signal;
+    let double_signals;
+
+    if constraint.a().len() == 1 && constraint.b().len() == 2 {
+        single_signal = constraint.a();
+        double_signals = constraint.b();
+    } else if constraint.b().len() == 1 && constraint.a().len() == 2 {
+        single_signal = constraint.b();
+        double_signals = constraint.a();
+    } else {
+        return None;
+    }
+
+    let signal_idx = *signals.iter().next().unwrap();
+
+    // As C is empty, the constraint can be scaled freely. Circom may have multiplied either side by
+    //  a scalar, so the single signal side only needs a nonzero coefficient, and the double signal
+    //  side is scaled by the modular inverse of the coefficient of the signal to make that
+    //  coefficient one. Zero coefficients have no inverse and are rejected
+    if single_signal.get(signal_idx)?.is_zero() {
+        return None;
+    }
+    let double_signals_scale =
+        modular_arithmetic::div(&BigInt::one(), double_signals.get(signal_idx)?, field_prime)
+            .ok()?;
+
+    // Check that the double signal side has the shape (s - 1)
+    let constant_coefficient_bigint = modular_arithmetic::mul(
+        double_signals.get(&Constraint::<usize>::constant_coefficient())?,
+        &double_signals_scale,
+        field_prime,
+    );
+
+    if !(field_prime - constant_coefficient_bigint).is_one() {
+        return None;
+    }
+
+    Some(*signal_idx)
+}
+
+// Given a map from signals to the constraints that define them as a product (t = A*B), computes
+//  whether a given linear expression only vanishes on a finite set of values of a single signal.
+//  If it does, returns that signal and the set of values. Else, it returns None
+fn get_linear_expression_roots(
+    linear_term: &HashMap<usize, BigInt>,
+    product_definitions: &HashMap<SignalIndex, &Constraint<usize>>,
+    field_prime: &BigInt,
+    depth: usize,
+) -> Option<(SignalIndex, BTreeSet<BigInt>)> {
+    let constant_coefficient_key = Constraint::<usize>::constant_coefficient();
+    let zero = BigInt::zero();
+
+    let mut signals = linear_term
+        .iter()
+        .filter(|(idx, coeff)| **idx != constant_coefficient_key && !coeff.is_zero());
+    let (signal_idx, signal_coeff) = signals.next()?;
+
+    if signals.next().is_some() {
+        return None;
+    }
+
+    let constant_coeff = linear_term.get(&constant_coefficient_key).unwrap_or(&zero);
+
+    // If the signal is itself defined as a product, it vanishes where any of its factors do
+    if constant_coeff.is_zero() && depth < MAX_DOMAIN_PRODUCT_DEPTH {
+        if let Some(definition) = product_definitions.get(signal_idx) {
+            if let Some(roots) = get_product_roots(
+                definition.a(),
+                definition.b(),
+                product_definitions,
+                field_prime,
+                depth + 1,
+            ) {
+                return Some(roots);
+            }
+        }
+    }
+
+    // Single linear factor a*x + b, which vanishes only at x = -b/a
+    let minus_constant = modular_arithmetic::sub(&zero, constant_coeff, field_prime);
+    let root = modular_arithmetic::div(&minus_constant, signal_coeff, field_prime).ok()?;
+
+    Some((*signal_idx, BTreeSet::from([root])))
+}
+
+// Computes whether the product A*B only vanishes on a finite set of values of a single signal
+fn get_product_roots(
+    a: &HashMap<usize, BigInt>,
+    b: &HashMap<usize, BigInt>,
+    product_definitions: &HashMap<SignalIndex, &Constraint<usize>>,
+    field_prime: &BigInt,
+    depth: usize,
+) -> Option<(SignalIndex, BTreeSet<BigInt>)> {
+    let (signal_a, mut roots_a) =
+        get_linear_expression_roots(a, product_definitions, field_prime, depth)?;
+    let (signal_b, mut roots_b) =
+        get_linear_expression_roots(b, product_definitions, field_prime, depth)?;
+
+    if signal_a != signal_b {
+        return None;
+    }
+
+    roots_a.append(&mut roots_b);
+    Some((signal_a, roots_a))
+}
+
+// If the given constraint defines a signal as a product of two linear expressions, that is, it is
+//  of the form A*B + k*t = 0 with k non-zero and t not appearing in A or B, returns t.
+fn get_product_definition_signal(constraint: &Constraint<usize>) -> Option<SignalIndex> {
+    if constraint.a().is_empty() || constraint.b().is_empty() || constraint.c().len() != 1 {
+        return None;
+    }
+
+    let (signal_idx, coeff) = constraint.c().iter().next().unwrap();
+
+    if *signal_idx == Constraint::<usize>::constant_coefficient()
+        || coeff.is_zero()
+        || constraint.a().contains_key(signal_idx)
+        || constraint.b().contains_key(signal_idx)
+    {
+        return None;
+    }
+
+    Some(*signal_idx)
+}
+
+// This function computes whether a given constraint restricts a signal to a small finite domain,
+//  that is, it is of the form A*B = 0 where A and B are (possibly nested through product
+//  definitions of other signals) products of linear factors of the same signal. As R1CS
+//  constraints are quadratic, domains larger than two values are expressed with auxiliary
+//  signals, for example t1 = x*(x-1), t2 = (x-2)*(x-3), t1*t2 = 0, which are followed using
+//  product_definitions. If it is, it returns the restricted signal and its domain. Else, None
+fn is_constraint_domain_restriction(
+    constraint: &Constraint<usize>,
+    product_definitions: &HashMap<SignalIndex, &Constraint<usize>>,
+    field_prime: &BigInt,
+) -> Option<(SignalIndex, BTreeSet<BigInt>)> {
+    if !constraint.c().is_empty() || constraint.a().is_empty() || constraint.b().is_empty() {
+        return None;
+    }
+
+    get_product_roots(
+        constraint.a(),
+        constraint.b(),
+        product_definitions,
+        field_prime,
+        0,
+    )
+}
+
+// Simplification passes that can be applied to a polynomial system before sending it to CoCoA.
+//  Each of them transforms the system into an equivalent one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OptimizationPass {
+    // Remove constraints whose polynomial A*B + C is identically zero over the field, which hold
+    //  for any value of their signals
+    Tautology,
+
+    // Detect signals restricted to be binary or to a small domain, to simplify the prohibition
+    //  polynomial
+    Binary,
+
+    // Gauss-Jordan elimination of the linear constraints
+    Gauss,
+
+    // Remove duplicated constraints
+    Dedup,
+
+    // Remove constraints that do not share any signal, directly or transitively, with the signals
+    //  to fix
+    Reach,
+}
+
+impl OptimizationPass {
+    // All passes, in the order they are applied by default. Tautologies are removed first, so the
+    //  rest of passes do not waste time on them. Binary detection runs next, as the other passes may
+    //  rewrite the constraints it looks for.
+    pub const ALL: [OptimizationPass; 5] = [
+        OptimizationPass::Tautology,
+        OptimizationPass::Binary,
+        OptimizationPass::Gauss,
+        OptimizationPass::Dedup,
+        OptimizationPass::Reach,
+    ];
+
+    // Name of the pass in the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizationPass::Tautology => "tautology",
+            OptimizationPass::Binary => "binary",
+            OptimizationPass::Gauss => "gauss",
+            OptimizationPass::Dedup => "dedup",
+            OptimizationPass::Reach => "reach",
+        }
+    }
+
+    pub fn apply(&self, pol_system: &mut OptimizedPolynomialSystemFixedSignal, field: &BigInt) {
+        match self {
+            OptimizationPass::Tautology => remove_tautological_constraints(pol_system, field),
+            OptimizationPass::Binary => detect_restricted_domain_signals(pol_system, field),
+            OptimizationPass::Gauss => gauss_jordan_linear_constraints(pol_system, field),
+            OptimizationPass::Dedup => remove_duplicated_constraints(pol_system),
+            OptimizationPass::Reach => remove_unreachable_constraints(pol_system),
+        }
+    }
+}
+
+impl FromStr for OptimizationPass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OptimizationPass::ALL
+            .into_iter()
+            .find(|pass| pass.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown optimization pass '{}', expected one of: {}",
+                    s,
+                    OptimizationPass::ALL
+                        .iter()
+                        .map(|pass| pass.name())
+                        .join(", ")
+                )
+            })
+    }
+}
+
+// How the witness is excluded from the solutions of a polynomial system, so that the system has no
+//  solution if and only if its signals are fixed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ProhibitionMode {
+    // Add the prohibition polynomial, where a signal x with witness value w differs from it if
+    //  (x - w)*u - 1 = 0 for some auxiliary variable u. Binary and small domain signals are
+    //  prohibited without auxiliary variables
+    UVar,
+
+    // Saturate the ideal of the constraints by the ideal of the witness point, which removes the
+    //  witness from the solutions without any auxiliary variable
+    Saturate,
+}
+
+impl FromStr for ProhibitionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uvar" => Ok(ProhibitionMode::UVar),
+            "saturate" => Ok(ProhibitionMode::Saturate),
+            _ => Err(format!(
+                "Unknown prohibition mode '{}', expected one of: uvar, saturate",
+                s
+            )),
+        }
+    }
+}
+
+// How the witness is excluded from polynomial systems whose signals to fix are all boolean, with
+//  the uvar prohibition mode. A boolean signal differs from its witness value w only if it is
+//  1 - w, so the system is fixed if no signal can be flipped
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BooleanProhibition {
+    // Add the product of the flips of every signal, (x - (1 - w)), as a single generator
+    Product,
+
+    // Check the flip of each signal in its own ideal. The product vanishes on the union of the
+    //  solutions of the flips, so the system is fixed if and only if every one of these ideals
+    //  contains 1. Each ideal has a linear generator instead of a high-degree one, which can be
+    //  faster, at the cost of a Groebner basis per signal
+    Separate,
+}
+
+impl FromStr for BooleanProhibition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(BooleanProhibition::Product),
+            "separate" => Ok(BooleanProhibition::Separate),
+            _ => Err(format!(
+                "Unknown boolean prohibition '{}', expected one of: product, separate",
+                s
+            )),
+        }
+    }
+}
+
+// How the generated CoCoA scripts decide whether the signals of a polynomial system are fixed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CocoaStrategy {
+    // Exclude the witness from the solutions as given by the prohibition mode, and check that 1
+    //  belongs to the Groebner basis of the resulting ideal
+    GBasis,
+
+    // Check that the ideal of the constraints is zero-dimensional and its radical has a single
+    //  point, the witness, which does not need any auxiliary variable. Otherwise, if every variable
+    //  is a signal to fix, the signals are not fixed. If not, the other points may only differ in
+    //  signals that do not need to be fixed, so the witness is excluded by saturation instead.
+    //  Only the Groebner basis of the constraints is bounded by the timeout: CoCoA has no timeout
+    //  for the radical nor the saturation, so a system may take longer than the timeout and is
+    //  only stopped by the total timeout
+    Dimension,
+}
+
+impl FromStr for CocoaStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gbasis" => Ok(CocoaStrategy::GBasis),
+            "dimension" => Ok(CocoaStrategy::Dimension),
+            _ => Err(format!(
+                "Unknown CoCoA strategy '{}', expected one of: gbasis, dimension",
+                s
+            )),
+        }
+    }
+}
+
+// Monomial ordering of the rings declared in the generated CoCoA scripts. It does not change the
+//  verdict of a polynomial system, but it can make a big difference in how long its Groebner basis
+//  takes to compute
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MonomialOrder {
+    // Degree reverse lexicographic ordering, the default ordering of CoCoA
+    DegRevLex,
+    DegLex,
+    Lex,
+
+    // Elimination ordering for every variable but the signals to fix, which are declared last
+    Elim,
+}
+
+impl FromStr for MonomialOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "degrevlex" => Ok(MonomialOrder::DegRevLex),
+            "deglex" => Ok(MonomialOrder::DegLex),
+            "lex" => Ok(MonomialOrder::Lex),
+            "elim" => Ok(MonomialOrder::Elim),
+            _ => Err(format!(
+                "Unknown monomial order '{}', expected one of: degrevlex, deglex, lex, elim",
+                s
+            )),
+        }
+    }
+}
+
+pub fn optimize_pol_system(
+    pol_system: &PolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> OptimizedPolynomialSystemFixedSignal {
+    let mut optimized_pol_system = OptimizedPolynomialSystemFixedSignal {
+        constraints: pol_system.constraints.clone(),
+        constraint_indices: pol_system.constraint_indices.clone(),
+        signals_to_fix: pol_system
+            .signals_to_fix
+            .iter()
+            .map(|idx| -> (SignalIndex, SignalToFixData) {
+                (
+                    *idx,
+                    SignalToFixData {
+                        is_boolean: false,
+                        domain: None,
+                    },
+                )
+            })
+            .collect(),
+        template_name: pol_system.template_name.clone(),
+        component_name: pol_system.component_name.clone(),
+        max_degree: 0,
+    };
+
+    // Constraints where A or B is a constant are turned into linear ones, so the passes detect them
+    //  as such, and then constraints that are 0 == 0 are removed
+    for constraint in &mut optimized_pol_system.constraints {
+        if let Some(linear_term) = get_constant_product_linear_term(constraint, &context.field) {
+            *constraint = Constraint::new(HashMap::new(), HashMap::new(), linear_term);
+        }
+    }
+    optimized_pol_system.retain_constraints(|_, constraint| !constraint.is_empty());
+
+    for pass in &context.options.optimization_passes {
+        pass.apply(&mut optimized_pol_system, &context.field);
+    }
+
+    // Passes may remove constraints, but never increase their degree, so it is computed at the end
+    optimized_pol_system.max_degree = get_max_constraint_degree(&optimized_pol_system.constraints);
+
+    optimized_pol_system
+}
+
+// Returns the total degree of the polynomial A*B + C of a constraint, which is 0 if it only
+//  contains constants. Degrees are a good predictor of the cost of Groebner bases.
+pub fn get_constraint_degree(constraint: &Constraint<usize>) -> usize {
+    let constant = 0;
+    let linear_term_degree = |linear_term: &HashMap<SignalIndex, BigInt>| -> usize {
+        if linear_term.keys().any(|signal| *signal != constant) {
+            1
+        } else {
+            0
+        }
+    };
+
+    // If A or B is empty, the product A*B is zero
+    let product_degree = if constraint.a().is_empty() || constraint.b().is_empty() {
+        0
+    } else {
+        linear_term_degree(constraint.a()) + linear_term_degree(constraint.b())
+    };
+
+    product_degree.max(linear_term_degree(constraint.c()))
+}
+
+// Returns the highest degree among the given constraints, or 0 if there are none
+pub fn get_max_constraint_degree(constraints: &[Constraint<usize>]) -> usize {
+    constraints
+        .iter()
+        .map(get_constraint_degree)
+        .max()
+        .unwrap_or(0)
+}
+
+// Optimization pass marking the signals to fix that are restricted to be binary or to lie in a
+//  small domain
+fn detect_restricted_domain_signals(
+    pol_system: &mut OptimizedPolynomialSystemFixedSignal,
+    field: &BigInt,
+) {
+    let mut binary_signals = HashSet::new();
+
+    for constraint in &pol_system.constraints {
+        if let Some(bin_signal) = is_constraint_binary_restriction(constraint, field) {
+            binary_signals.insert(bin_signal);
+        }
+    }
+
+    let product_definitions: HashMap<SignalIndex, &Constraint<usize>> = pol_system
+        .constraints
+        .iter()
+        .filter_map(|constraint| Some((get_product_definition_signal(constraint)?, constraint)))
+        .collect();
+
+    // If a signal is restricted by several constraints, it must lie in the intersection of all
+    //  the domains
+    let mut signal_domains = HashMap::<SignalIndex, BTreeSet<BigInt>>::new();
+
+    for constraint in &pol_system.constraints {
+        if let Some((signal, domain)) =
+            is_constraint_domain_restriction(constraint, &product_definitions, field)
+        {
+            let new_domain = match signal_domains.get(&signal) {
+                Some(previous_domain) => previous_domain.intersection(&domain).cloned().collect(),
+                None => domain,
+            };
+            signal_domains.insert(signal, new_domain);
+        }
+    }
+
+    for (idx, data) in pol_system.signals_to_fix.iter_mut() {
+        data.is_boolean |= binary_signals.contains(idx);
+        if let Some(domain) = signal_domains.remove(idx) {
+            data.domain = Some(domain);
+        }
+    }
+}
+
+// Optimization pass performing Gauss-Jordan elimination on the linear constraints. Each linear
+//  constraint is solved for one of its signals (the pivot), which is then substituted into every
+//  other constraint. Linear constraints that are linearly dependent on the rest become 0 == 0 and
+//  are removed.
+fn gauss_jordan_linear_constraints(
+    pol_system: &mut OptimizedPolynomialSystemFixedSignal,
+    field: &BigInt,
+) {
+    gauss_jordan_eliminate(&mut pol_system.constraints, field);
+    pol_system.retain_constraints(|_, constraint| !constraint.is_empty());
+}
+
+// Performs Gauss-Jordan elimination on the linear constraints, leaving them in reduced row echelon
+//  form. Returns the row of each pivot signal. Each pivot is substituted into every other row,
+//  including the rows of earlier pivots, so it only appears in its own row. A pivot row is never
+//  chosen again, and its pivot cannot be reintroduced by later substitutions, as no other row
+//  contains it. Other rows may become empty.
+fn gauss_jordan_eliminate(
+    constraints: &mut [Constraint<usize>],
+    field: &BigInt,
+) -> BTreeMap<SignalIndex, usize> {
+    let mut pivot_rows = BTreeMap::new();
+    let mut pivot_row_set = HashSet::new();
+
+    loop {
+        let constraint_signals: Vec<_> = constraints
+            .iter()
+            .map(|constraint| constraint.take_cloned_signals_ordered())
+            .collect();
+
+        // The pivot of a row is the signal appearing in the fewest constraints (the smallest
+        //  one in case of a tie), so substituting it rewrites as few constraints as possible
+        let maybe_pivot = constraints
+            .iter()
+            .enumerate()
+            .filter(|(row, constraint)| {
+                !pivot_row_set.contains(row) && Constraint::is_linear(constraint)
+            })
+            .find_map(|(row, _)| {
+                let pivot = constraint_signals[row].iter().min_by_key(|signal| {
+                    let appearances = constraint_signals
+                        .iter()
+                        .filter(|signals| signals.contains(signal))
+                        .count();
+                    (appearances, **signal)
+                })?;
+                Some((row, *pivot))
+            });
+
+        let Some((pivot_row, pivot)) = maybe_pivot else {
+            return pivot_rows;
+        };
+
+        let substitution =
+            Constraint::clear_signal_from_linear(constraints[pivot_row].clone(), &pivot, field);
+
+        for (row, constraint) in constraints.iter_mut().enumerate() {
+            if row != pivot_row {
+                Constraint::apply_substitution(constraint, &substitution, field);
+                Constraint::fix_constraint(constraint, field);
+            }
+        }
+
+        pivot_rows.insert(pivot, pivot_row);
+        pivot_row_set.insert(pivot_row);
+    }
+}
+
+// Returns the rank over the field of the linear constraints among the given ones, taking the known
+//  signals as constants. It is the number of the other signals that the linear constraints
+//  determine once the known ones are fixed
+pub fn get_linear_rank(
+    constraints: &[Constraint<usize>],
+    known_signals: &BTreeSet<SignalIndex>,
+    field: &BigInt,
+) -> usize {
+    let constant = Constraint::<usize>::constant_coefficient();
+    let mut linear_constraints: Vec<Constraint<usize>> = constraints
+        .iter()
+        .filter_map(|constraint| get_constant_product_linear_term(constraint, field))
+        .map(|mut linear_term| {
+            linear_term.retain(|signal, _| *signal != constant && !known_signals.contains(signal));
+            Constraint::new(HashMap::new(), HashMap::new(), linear_term)
+        })
+        .filter(|constraint| !constraint.is_empty())
+        .collect();
+
+    // Each pivot is a signal determined by the rest
+    gauss_jordan_eliminate(&mut linear_constraints, field).len()
+}
+
+// Returns the positions of the linear constraints among the given ones that are linear combinations
+//  of the others, so removing them does not change the solutions of the constraints. Only one of
+//  each set of dependent constraints is kept, as chosen by the elimination, so all the returned
+//  ones can be removed at once. Unlike in get_linear_rank, every signal is taken into account, as
+//  a constraint that also restricts the inputs is not redundant
+pub fn get_redundant_linear_constraints(
+    constraints: &[Constraint<usize>],
+    field: &BigInt,
+) -> Vec<usize> {
+    let (positions, mut linear_constraints): (Vec<usize>, Vec<Constraint<usize>>) = constraints
+        .iter()
+        .enumerate()
+        .filter_map(|(position, constraint)| {
+            let linear_term = get_constant_product_linear_term(constraint, field)?;
+            Some((
+                position,
+                Constraint::new(HashMap::new(), HashMap::new(), linear_term),
+            ))
+        })
+        .unzip();
+
+    // The rows that are not pivots are left empty if they are combinations of the pivot rows, and
+    //  with only a constant if they contradict them
+    let pivot_rows: HashSet<usize> = gauss_jordan_eliminate(&mut linear_constraints, field)
+        .into_values()
+        .collect();
+    linear_constraints
+        .iter()
+        .enumerate()
+        .filter(|(row, constraint)| !pivot_rows.contains(row) && constraint.is_empty())
+        .map(|(row, _)| positions[row])
+        .collect()
+}
+
+// Signals eliminated from a polynomial system by reduce_linear_block, each one mapped to the
+//  substitution giving its value in terms of the remaining signals
+pub type LinearSubstitutions = BTreeMap<SignalIndex, Substitution<usize>>;
+
+// Reduces the linear constraints of a polynomial system to reduced row echelon form, substituting
+//  each pivot into the quadratic constraints, and drops the pivot rows. Pivot signals no longer
+//  appear in the constraints, so they do not need to be declared as variables of the ring. As they
+//  may be signals to fix, the returned substitutions must still be applied to the prohibition.
+//  Pivots equal to a constant are kept, so the prohibition always contains some variable.
+pub fn reduce_linear_block(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    field: &BigInt,
+) -> (OptimizedPolynomialSystemFixedSignal, LinearSubstitutions) {
+    let mut constraints = pol_system.constraints.clone();
+    let pivot_rows = gauss_jordan_eliminate(&mut constraints, field);
+
+    let substitutions: LinearSubstitutions = pivot_rows
+        .iter()
+        .map(|(pivot, row)| {
+            let substitution =
+                Constraint::clear_signal_from_linear(constraints[*row].clone(), pivot, field);
+            (*pivot, substitution)
+        })
+        .filter(|(_, substitution)| !substitution.take_signals().is_empty())
+        .collect();
+
+    let pivot_row_set: HashSet<_> = substitutions
+        .keys()
+        .map(|pivot| pivot_rows[pivot])
+        .collect();
+    let mut reduced_pol_system = OptimizedPolynomialSystemFixedSignal {
+        constraints,
+        ..pol_system.clone()
+    };
+    reduced_pol_system.retain_constraints(|row, constraint| {
+        !constraint.is_empty() && !pivot_row_set.contains(&row)
+    });
+
+    (reduced_pol_system, substitutions)
+}
+
+// Returns a key identifying a constraint up to the order of its A and B factors
+fn get_constraint_key(constraint: &Constraint<usize>) -> Vec<Vec<(SignalIndex, BigInt)>> {
+    let sorted_terms = |linear_term: &HashMap<SignalIndex, BigInt>| -> Vec<(SignalIndex, BigInt)> {
+        linear_term
+            .iter()
+            .map(|(signal, coefficient)| (*signal, coefficient.clone()))
+            .sorted()
+            .collect()
+    };
+
+    let (a, b) = (sorted_terms(constraint.a()), sorted_terms(constraint.b()));
+    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+    vec![a, b, sorted_terms(constraint.c())]
+}
+
+// Returns whether the polynomial A*B + C of a constraint is identically zero over the field, such
+//  as in 2*(x + 3) - 2*x - 6 = 0. The product is expanded into monomials of degree at most 2, given
+//  as pairs of signals where the constant signal stands for a missing factor.
+fn is_constraint_tautology(constraint: &Constraint<usize>, field: &BigInt) -> bool {
+    let constant = Constraint::<usize>::constant_coefficient();
+    let mut coefficients = HashMap::<(SignalIndex, SignalIndex), BigInt>::new();
+
+    for (a_signal, a_coefficient) in constraint.a() {
+        for (b_signal, b_coefficient) in constraint.b() {
+            let monomial = (*a_signal.min(b_signal), *a_signal.max(b_signal));
+            *coefficients.entry(monomial).or_default() += a_coefficient * b_coefficient;
+        }
+    }
+
+    for (signal, coefficient) in constraint.c() {
+        *coefficients.entry((constant, *signal)).or_default() += coefficient;
+    }
+
+    coefficients
+        .values()
+        .all(|coefficient| (coefficient % field).is_zero())
+}
+
+// Optimization pass removing the constraints that are satisfied by any assignment of their signals
+fn remove_tautological_constraints(
+    pol_system: &mut OptimizedPolynomialSystemFixedSignal,
+    field: &BigInt,
+) {
+    pol_system.retain_constraints(|_, constraint| !is_constraint_tautology(constraint, field));
+}
+
+// Optimization pass removing duplicated constraints, keeping the first appearance of each one
+fn remove_duplicated_constraints(pol_system: &mut OptimizedPolynomialSystemFixedSignal) {
+    let mut seen_constraints = HashSet::new();
+    pol_system.retain_constraints(|_, constraint| {
+        seen_constraints.insert(get_constraint_key(constraint))
+    });
+}
+
+// Decides whether the signals of a polynomial system are fixed without Groebner bases, if all its
+//  constraints are linear. After Gauss-Jordan elimination, every remaining constraint contains a
+//  pivot signal that appears in no other constraint, together with signals that can take any value.
+//  Thus a signal is fixed if and only if some constraint contains no other signal. Returns the
+//  signals to fix that are not fixed, or None if some constraint is not linear.
+pub fn get_unfixed_signals_of_linear_pol_system(
+    pol_system: &PolynomialSystemFixedSignal,
+    field: &BigInt,
+) -> Option<Vec<SignalIndex>> {
+    if !pol_system.constraints.iter().all(Constraint::is_linear) {
+        return None;
+    }
+
+    let mut optimized_pol_system = OptimizedPolynomialSystemFixedSignal {
+        constraints: pol_system.constraints.clone(),
+        constraint_indices: pol_system.constraint_indices.clone(),
+        signals_to_fix: BTreeMap::new(),
+        template_name: pol_system.template_name.clone(),
+        component_name: pol_system.component_name.clone(),
+        max_degree: 1,
+    };
+    optimized_pol_system.retain_constraints(|_, c| !c.is_empty());
+    gauss_jordan_linear_constraints(&mut optimized_pol_system, field);
+
+    let fixed_signals: HashSet<_> = optimized_pol_system
+        .constraints
+        .iter()
+        .filter_map(|c| c.take_cloned_signals().into_iter().exactly_one().ok())
+        .collect();
+
+    Some(
+        pol_system
+            .signals_to_fix
+            .iter()
+            .filter(|s| !fixed_signals.contains(s))
+            .copied()
+            .collect(),
+    )
+}
+
+// Optimization pass removing the constraints that cannot affect the signals to fix, as they are
+//  not connected to them through a chain of constraints sharing signals. The witness is a
+//  solution of the removed constraints, so removing them does not change the values the signals
+//  to fix can take.
+fn remove_unreachable_constraints(pol_system: &mut OptimizedPolynomialSystemFixedSignal) {
+    let constraint_signals: Vec<BTreeSet<SignalIndex>> = pol_system
+        .constraints
+        .iter()
+        .map(|constraint| constraint.take_cloned_signals_ordered())
+        .collect();
+
+    let mut reachable_signals: BTreeSet<SignalIndex> =
+        pol_system.signals_to_fix.keys().copied().collect();
+    let mut reachable_constraints = vec![false; constraint_signals.len()];
+
+    // Fixpoint: add every constraint sharing a signal with the reachable ones
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (idx, signals) in constraint_signals.iter().enumerate() {
+            if !reachable_constraints[idx] && !signals.is_disjoint(&reachable_signals) {
+                reachable_constraints[idx] = true;
+                reachable_signals.extend(signals.iter().copied());
+                changed = true;
+            }
+        }
+    }
+
+    pol_system.retain_constraints(|idx, _| reachable_constraints[idx]);
+}
+
+// Generates the Cocoa5 script verifying all the given polynomial systems. The script only depends
+//  on its input: variables are declared in increasing index order (signals first, then prohibition
+//  variables) and linear terms are sorted by signal index, so the same systems always produce a
+//  byte-identical script. The golden test below guards this property.
+pub fn generate_cocoa_script(
+    pol_systems: &[OptimizedPolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+) -> String {
+    let pol_systems_str: String = Itertools::intersperse(
+        pol_systems
+            .iter()
+            .enumerate()
+            .map(|(idx, pol_system)| -> String { get_cocoa_subscript(pol_system, context, idx) }),
+        "\n".to_string(),
+    )
+        .collect();
+
+    let field_prime = context.field.to_string();
+    let prelude = get_cocoa_prelude(context);
+
+    let s: String = formatdoc! {"
+        {prelude}p := {field_prime};
+        use F ::= ZZ/(p);
+
+        {pol_systems_str}
+
+        println \"FINISHED\";
+    "};
+
+    s
+}
+
+pub fn display_polynomial_system_readable(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) {
+    println!();
+    write_polynomial_system_readable(&mut io::stdout(), pol_system, context)
+        .expect("could not write to stdout");
+}
+
+// Writes the constraints, signals to fix and prohibition of a polynomial system, using signal names.
+//  If sanitized names are requested in the options, they are preceded by a legend with the
+//  original name of every signal whose name has changed. Polynomials are written in Sage syntax,
+//  so the legend is made of Sage comments.
+fn write_polynomial_system_readable(
+    w: &mut impl Write,
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> io::Result<()> {
+    let sanitized_names = context
+        .options
+        .sanitized_names
+        .then(|| get_sanitized_signal_names(&get_used_signal_indices(pol_system), context));
+    let display_kind = match &sanitized_names {
+        Some(sanitized_names) => {
+            for (signal_idx, sanitized_name) in sanitized_names {
+                let name = context.signal_name(*signal_idx);
+                if *sanitized_name != name {
+                    writeln!(w, "# {} = {}", sanitized_name, name)?;
+                }
+            }
+            SignalDisplayKind::SanitizedName(sanitized_names)
+        }
+        None => SignalDisplayKind::Name,
+    };
+
+    writeln!(w, "Constraints: ")?;
+
+    for (constraint, constraint_idx) in pol_system
+        .constraints
+        .iter()
+        .zip(&pol_system.constraint_indices)
+    {
+        let polynomial = if context.options.expand_products {
+            get_expanded_constraint_polynomial(constraint, context, display_kind)
+        } else {
+            get_constraint_polynomial(constraint, context, display_kind)
+        };
+        writeln!(w, "{} = 0    (constraint #{})", polynomial, constraint_idx)?;
+    }
+
+    let signals_to_fix_name_vec: Vec<String> = pol_system
+        .signals_to_fix
+        .keys()
+        .map(|idx| get_signal_name(*idx, context, display_kind))
+        .collect();
+
+    let binary_signals_name_vec: Vec<String> = pol_system
+        .signals_to_fix
+        .iter()
+        .filter_map(|(idx, data)| -> Option<String> {
+            if data.is_boolean {
+                Some(get_signal_name(*idx, context, display_kind))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let domain_signals_name_vec: Vec<String> = pol_system
+        .signals_to_fix
+        .iter()
+        .filter_map(|(idx, data)| -> Option<String> {
+            match &data.domain {
+                Some(domain) if !data.is_boolean => Some(format!(
+                    "{}: {{{}}}",
+                    get_signal_name(*idx, context, display_kind),
+                    domain
+                        .iter()
+                        .map(|v| coefficient_to_string(v, &context.field))
+                        .join(", ")
+                )),
+                _ => None,
+            }
+        })
+        .collect();
+
+    writeln!(w, "Signals to fix: {:?}", signals_to_fix_name_vec)?;
+    writeln!(w, "Binary signals: {:?}", binary_signals_name_vec)?;
+    writeln!(w, "Domain signals: {:?}", domain_signals_name_vec)?;
+
+    if context.options.prohibition_mode == ProhibitionMode::Saturate {
+        writeln!(w, "Witness point removed by saturation: ")?;
+        return writeln!(
+            w,
+            "ideal({})",
+            get_witness_point_generators(
+                &pol_system.signals_to_fix,
+                &LinearSubstitutions::new(),
+                context,
+                display_kind,
+            )
+        );
+    }
+
+    let prohibition_polynomial = get_prohibition_witness_polynomial(
+        &pol_system.signals_to_fix,
+        &LinearSubstitutions::new(),
+        context,
+        display_kind,
+    );
+
+    if uses_separate_boolean_prohibition(&pol_system.signals_to_fix, context) {
+        writeln!(w, "Prohibited values, checked one at a time: ")?;
+        for generator in get_boolean_flip_generators(
+            &pol_system.signals_to_fix,
+            &LinearSubstitutions::new(),
+            context,
+            display_kind,
+        ) {
+            writeln!(w, "{} = 0", generator)?;
+        }
+        return Ok(());
+    }
+
+    writeln!(w, "Prohibition constraint: ")?;
+    writeln!(w, "{} = 0", prohibition_polynomial.string)
+}
+
+// Writes every polynomial system in readable form to a text file, to be reviewed without reading
+//  the CoCoA script. Systems are optimized as they would be before being sent to CoCoA, and grouped
+//  by the component they belong to.
+pub fn dump_pol_systems(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    path: &Path,
+) -> VerifierResult<()> {
+    let mut w = BufWriter::new(File::create(path)?);
+
+    let systems_by_component =
+        (0..pol_systems.len()).into_group_map_by(|idx| pol_systems[*idx].component_name.as_str());
+    let component_names = pol_systems
+        .iter()
+        .map(|pol_system| pol_system.component_name.as_str())
+        .unique();
+
+    for component_name in component_names {
+        let system_indices = &systems_by_component[component_name];
+        writeln!(
+            w,
+            "Component '{}' ({}), {} polynomial systems:",
+            component_name,
+            pol_systems[system_indices[0]].template_name,
+            system_indices.len()
+        )?;
+
+        for idx in system_indices {
+            writeln!(
+                w,
+                "\nPolynomial system {}",
+                describe_pol_system(pol_systems, *idx)
+            )?;
+            let optimized_pol_system = optimize_pol_system(&pol_systems[*idx], context);
+            write_polynomial_system_readable(&mut w, &optimized_pol_system, context)?;
+        }
+        writeln!(w)?;
+    }
+
+    w.flush()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct CasDumpEntry {
+    // Index of the polynomial system in the CoCoA script, or in the input of an external solver
+    index: PolSystemIndex,
+
+    #[serde(flatten)]
+    pol_system: CasPolSystem,
+}
+
+#[derive(Serialize)]
+struct CasDump {
+    prime: String,
+    timeout_seconds: u32,
+    pol_systems: Vec<CasDumpEntry>,
+}
+
+// Writes every polynomial system to a JSON file as it is sent to CoCoA, together with the field
+//  prime and the timeout of each Groebner basis. It is a structured form of the CoCoA script, to
+//  reproduce its checks in other tools.
+pub fn dump_cas_json(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    path: &Path,
+) -> VerifierResult<()> {
+    let optimized_pol_systems: Vec<_> = pol_systems
+        .iter()
+        .map(|pol_system| optimize_pol_system(pol_system, context))
+        .collect();
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, &get_cas_dump(&optimized_pol_systems, context))?;
+    writer.flush()?;
+    Ok(())
+}
+
+// Returns the document of dump_cas_json for polynomial systems that have already been optimized.
+//  It is also the input of external solvers
+fn get_cas_dump(
+    pol_systems: &[OptimizedPolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+) -> CasDump {
+    CasDump {
+        prime: context.field.to_string(),
+        timeout_seconds: context.options.groebner_cocoa_timeout_seconds,
+        pol_systems: pol_systems
+            .iter()
+            .enumerate()
+            .map(|(index, pol_system)| CasDumpEntry {
+                index,
+                pol_system: get_cas_pol_system(pol_system, context),
+            })
+            .collect(),
+    }
+}
+
+// Returns the signals used by a polynomial system: the signals appearing in the constraints and the
+//  signals to be fixed (even if they don't appear in any of the equations)
+fn get_used_signal_indices(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+) -> BTreeSet<SignalIndex> {
+    let mut used_signal_indices = BTreeSet::new();
+
+    for constraint in &pol_system.constraints {
+        used_signal_indices.append(&mut constraint.take_cloned_signals_ordered());
+    }
+
+    for signal in pol_system.signals_to_fix.keys() {
+        used_signal_indices.insert(*signal);
+    }
+
+    used_signal_indices
+}
+
+// Returns the auxiliary variables of the prohibition polynomial of a polynomial system. They are
+//  named after the signal they prohibit. As signals_to_fix is ordered, they are always declared in
+//  increasing signal index order.
+fn get_prohibition_vars(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+) -> impl Iterator<Item = String> + '_ {
+    pol_system
+        .signals_to_fix
+        .iter()
+        .filter(|(_, data)| !data.is_boolean && data.domain.is_none())
+        .map(|(idx, _)| format!("u_{}", idx))
+}
+
+// Size of a polynomial system as it is sent to CoCoA, after optimizing it and eliminating the
+//  pivots of its linear constraints
+pub struct PolSystemStats {
+    pub num_constraints: usize,
+    pub num_signals: usize,
+    pub num_prohibition_vars: u32,
+}
+
+pub fn get_pol_system_stats(
+    pol_system: &PolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> PolSystemStats {
+    let pol_system = optimize_pol_system(pol_system, context);
+    let (pol_system, eliminated_signals) = if context
+        .options
+        .optimization_passes
+        .contains(&OptimizationPass::Gauss)
+    {
+        reduce_linear_block(&pol_system, &context.field)
+    } else {
+        (pol_system, LinearSubstitutions::new())
+    };
+
+    let mut used_signal_indices = get_used_signal_indices(&pol_system);
+    used_signal_indices.retain(|signal| !eliminated_signals.contains_key(signal));
+
+    PolSystemStats {
+        num_constraints: pol_system.constraints.len(),
+        num_signals: used_signal_indices.len(),
+        num_prohibition_vars: get_prohibition_witness_polynomial(
+            &pol_system.signals_to_fix,
+            &eliminated_signals,
+            context,
+            SignalDisplayKind::Index,
+        )
+        .num_vars,
+    }
+}
+
+// Returns a String containing a subscript in the Cocoa5 CAS system for proving that the
+//  signals are fixed by the given constraints. If requested in the options, it is preceded by a
+//  comment with the witness value of each signal to fix.
+fn get_cocoa_subscript(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+    pol_system_idx: PolSystemIndex,
+) -> String {
+    let subscript = get_cocoa_check_subscript(pol_system, context, pol_system_idx);
+    if !context.options.annotate_scripts {
+        return subscript;
+    }
+
+    let witness_comments: String = pol_system
+        .signals_to_fix
+        .keys()
+        .map(|signal_idx| {
+            format!(
+                "// x_{} = {} ({})\n",
+                signal_idx,
+                context.witness_value(*signal_idx),
+                context.signal_name(*signal_idx)
+            )
+        })
+        .collect();
+    format!("{}{}", witness_comments, subscript)
+}
+
+// Polynomial system as it is sent to CoCoA. Its subscript is generated from it, so it can also be
+//  dumped to be reproduced in other tools
+#[derive(Serialize, Debug)]
+pub struct CasPolSystem {
+    pub component_name: String,
+    pub template_name: String,
+
+    // Variables of the ring, in the order they are declared in ring_declaration
+    pub vars: Vec<String>,
+    pub ring_declaration: String,
+
+    // Polynomial of each constraint, which must be zero, and the index of the constraint it comes
+    //  from
+    pub constraints: Vec<String>,
+    pub constraint_indices: Vec<ConstraintIndex>,
+
+    pub check: CasCheck,
+}
+
+// How the witness is excluded from the solutions of a CasPolSystem, which has no other solution if
+//  and only if 1 belongs to the resulting ideals
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CasCheck {
+    // The system is not sent to CoCoA, and gets this verdict (TOOLARGE, HIGHDEGREE or TIMEOUT)
+    //  right away
+    Skipped { verdict: &'static str },
+
+    // The prohibition polynomial is added to the constraints
+    Prohibition { prohibition_polynomial: String },
+
+    // Each flip is added to the constraints on its own
+    SeparateProhibition { flips: Vec<String> },
+
+    // The ideal of the constraints is saturated with respect to the ideal of the witness point
+    Saturation {
+        witness_point_generators: String,
+    },
+
+    // The ideal of the constraints has no other solution if it is zero-dimensional and its radical
+    //  has a single point. Otherwise, it has other solutions if all_vars_to_fix is true, and it is
+    //  saturated as in Saturation if not (see CocoaStrategy::Dimension)
+    Dimension {
+        witness_point_generators: String,
+        all_vars_to_fix: bool,
+    },
+}
+
+// Returns the polynomial system as it is sent to CoCoA
+pub fn get_cas_pol_system(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> CasPolSystem {
+    // Pivots of the linear constraints are replaced by their value in terms of the other signals,
+    //  so they are not declared as variables of the ring
+    let (pol_system, eliminated_signals) = if context
+        .options
+        .optimization_passes
+        .contains(&OptimizationPass::Gauss)
+    {
+        reduce_linear_block(pol_system, &context.field)
+    } else {
+        (pol_system.clone(), LinearSubstitutions::new())
+    };
+
+    let mut used_signal_indices = get_used_signal_indices(&pol_system);
+    used_signal_indices.retain(|signal| !eliminated_signals.contains_key(signal));
+
+    let signals_to_fix_vars: HashSet<String> = pol_system
+        .signals_to_fix
+        .keys()
+        .map(|i| format!("x_{}", i))
+        .collect();
+
+    let signal_vars = used_signal_indices.iter().map(|i| format!("x_{}", i));
+    let mut prohibition_polynomial = None;
+
+    let (vars, check) = if context.options.cocoa_strategy == CocoaStrategy::Dimension {
+        let check = CasCheck::Dimension {
+            witness_point_generators: get_witness_point_generators(
+                &pol_system.signals_to_fix,
+                &eliminated_signals,
+                context,
+                SignalDisplayKind::Index,
+            ),
+            all_vars_to_fix: used_signal_indices
+                .iter()
+                .all(|signal| pol_system.signals_to_fix.contains_key(signal)),
+        };
+        (signal_vars.collect(), check)
+    } else if context.options.prohibition_mode == ProhibitionMode::Saturate {
+        let check = CasCheck::Saturation {
+            witness_point_generators: get_witness_point_generators(
+                &pol_system.signals_to_fix,
+                &eliminated_signals,
+                context,
+                SignalDisplayKind::Index,
+            ),
+        };
+        (signal_vars.collect(), check)
+    } else if uses_separate_boolean_prohibition(&pol_system.signals_to_fix, context) {
+        let check = CasCheck::SeparateProhibition {
+            flips: get_boolean_flip_generators(
+                &pol_system.signals_to_fix,
+                &eliminated_signals,
+                context,
+                SignalDisplayKind::Index,
+            ),
+        };
+        (signal_vars.collect(), check)
+    } else {
+        let polynomial = get_prohibition_witness_polynomial(
+            &pol_system.signals_to_fix,
+            &eliminated_signals,
+            context,
+            SignalDisplayKind::Index,
+        );
+        let check = CasCheck::Prohibition {
+            prohibition_polynomial: polynomial.string,
+        };
+        prohibition_polynomial = Some(polynomial.num_vars);
+        (
+            signal_vars
+                .chain(get_prohibition_vars(&pol_system))
+                .collect(),
+            check,
+        )
+    };
+
+    // Systems with too many signals are hopeless for Groebner bases, so they are skipped upfront.
+    //  Likewise, constraints of high degree make Groebner bases explode, and Cocoa will struggle
+    //  with prohibition polynomials containing a large amount of variables, so a soft limit is set
+    //  in order not to get stuck
+    let too_many_signals = context
+        .options
+        .max_system_signals
+        .is_some_and(|limit| used_signal_indices.len() > limit as usize);
+    let too_high_degree = context
+        .options
+        .max_degree
+        .is_some_and(|limit| pol_system.max_degree > limit as usize);
+    let too_many_prohibition_vars = prohibition_polynomial.is_some_and(|num_vars| {
+        num_vars
+            > context
+                .options
+                .max_vars_prohibition_polynomial_before_timeout
+    });
+
+    let check = if too_many_signals {
+        CasCheck::Skipped {
+            verdict: "TOOLARGE",
+        }
+    } else if too_high_degree {
+        CasCheck::Skipped {
+            verdict: "HIGHDEGREE",
+        }
+    } else if too_many_prohibition_vars {
+        CasCheck::Skipped { verdict: "TIMEOUT" }
+    } else {
+        check
+    };
+
+    let (vars, ordering) = get_cocoa_ring_order(vars, &signals_to_fix_vars, context);
+    CasPolSystem {
+        component_name: pol_system.component_name.clone(),
+        template_name: pol_system.template_name.clone(),
+        ring_declaration: format_cocoa_ring_declaration(&vars, ordering.as_deref()),
+        vars,
+        constraints: pol_system
+            .constraints
+            .iter()
+            .map(|c| get_constraint_polynomial(c, context, SignalDisplayKind::Index))
+            .collect(),
+        constraint_indices: pol_system.constraint_indices.clone(),
+        check,
+    }
+}
+
+// Returns the subscript of get_cocoa_subscript, without comments
+fn get_cocoa_check_subscript(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+    pol_system_idx: PolSystemIndex,
+) -> String {
+    let cas_pol_system = get_cas_pol_system(pol_system, context);
+    let constraint_polynomials = cas_pol_system
+        .constraints
+        .iter()
+        .zip(&cas_pol_system.constraint_indices)
+        .map(|(polynomial, constraint_idx)| {
+            get_commented_constraint_polynomial(polynomial, *constraint_idx)
+        })
+        .collect();
+
+    match cas_pol_system.check {
+        CasCheck::Skipped { verdict } => formatdoc! {"
+            println \"{verdict}: {pol_system_idx}\";
+        "},
+        CasCheck::Prohibition {
+            prohibition_polynomial,
+        } => get_groebner_basis_check_subscript(
+            &cas_pol_system.ring_declaration,
+            constraint_polynomials,
+            prohibition_polynomial,
+            context,
+            pol_system_idx,
+        ),
+        CasCheck::SeparateProhibition { flips } => get_separate_prohibition_check_subscript(
+            &cas_pol_system.ring_declaration,
+            constraint_polynomials,
+            flips,
+            context,
+            pol_system_idx,
+        ),
+        CasCheck::Saturation {
+            witness_point_generators,
+        } => get_saturation_check_subscript(
+            &cas_pol_system.ring_declaration,
+            constraint_polynomials,
+            &witness_point_generators,
+            context,
+            pol_system_idx,
+        ),
+        CasCheck::Dimension {
+            witness_point_generators,
+            all_vars_to_fix,
+        } => get_dimension_check_subscript(
+            &cas_pol_system.ring_declaration,
+            constraint_polynomials,
+            &witness_point_generators,
+            all_vars_to_fix,
+            context,
+            pol_system_idx,
+        ),
+    }
+}
+
+// Returns the polynomial of each constraint of a polynomial system, preceded by a comment with the
+//  index of the constraint it comes from, so the generated script can be traced back to the circuit
+fn get_cocoa_constraint_polynomials(
+    pol_system: &OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> Vec<String> {
+    pol_system
+        .constraints
+        .iter()
+        .zip(&pol_system.constraint_indices)
+        .map(|(constraint, constraint_idx)| {
+            get_commented_constraint_polynomial(
+                &get_constraint_polynomial(constraint, context, SignalDisplayKind::Index),
+                *constraint_idx,
+            )
+        })
+        .collect()
+}
+
+fn get_commented_constraint_polynomial(
+    polynomial: &str,
+    constraint_idx: ConstraintIndex,
+) -> String {
+    format!("// constraint #{}\n{}", constraint_idx, polynomial)
+}
+
+// Returns the declaration of a ring with the given variables, in the monomial order given in the
+//  options
+fn get_cocoa_ring_declaration(
+    vars: Vec<String>,
+    last_block: &HashSet<String>,
+    context: &InputDataContextView,
+) -> String {
+    let (vars, ordering) = get_cocoa_ring_order(vars, last_block, context);
+    format_cocoa_ring_declaration(&vars, ordering.as_deref())
+}
+
+// Returns the given variables in the order they are declared in the ring, and the monomial order
+//  given in the options, if it is not the default one. With an elimination order, the variables in
+//  last_block are declared after the rest, which are the ones eliminated. If either block is empty
+//  there is nothing to eliminate, so the default order is used instead.
+fn get_cocoa_ring_order(
+    vars: Vec<String>,
+    last_block: &HashSet<String>,
+    context: &InputDataContextView,
+) -> (Vec<String>, Option<String>) {
+    match context.options.monomial_order {
+        MonomialOrder::DegRevLex => (vars, None),
+        MonomialOrder::DegLex => (vars, Some("DegLex".to_string())),
+        MonomialOrder::Lex => (vars, Some("Lex".to_string())),
+        MonomialOrder::Elim => {
+            let (last, eliminated): (Vec<_>, Vec<_>) =
+                vars.into_iter().partition(|var| last_block.contains(var));
+
+            let ordering = match eliminated.as_slice() {
+                _ if last.is_empty() => None,
+                [] => None,
+                [var] => Some(format!("Elim({})", var)),
+                [first, .., last_eliminated] => {
+                    Some(format!("Elim({}..{})", first, last_eliminated))
+                }
+            };
+            (eliminated.into_iter().chain(last).collect(), ordering)
+        }
+    }
+}
+
+fn format_cocoa_ring_declaration(vars: &[String], ordering: Option<&str>) -> String {
+    match ordering {
+        Some(ordering) => format!("use R ::= F[{}], {};", vars.join(", "), ordering),
+        None => format!("use R ::= F[{}];", vars.join(", ")),
+    }
+}
+
+// Returns a String containing a subscript that checks whether 1 belongs to the ideal generated by
+//  the given constraints and the prohibition polynomial, in the ring of the given declaration. It
+//  prints OK if it does, that is, if the polynomial system has no solution.
+fn get_groebner_basis_check_subscript(
+    ring_declaration: &str,
+    constraint_polynomials: Vec<String>,
+    prohibition_polynomial: String,
+    context: &InputDataContextView,
+    pol_system_idx: PolSystemIndex,
+) -> String {
+    let pols: String = Itertools::intersperse(
+        constraint_polynomials
+            .into_iter()
+            .chain(iter::once(prohibition_polynomial)),
+        ",\n".to_string(),
+    )
+    .collect();
+
+    let timeout: u32 = context.options.groebner_cocoa_timeout_seconds;
+
+    formatdoc! {"
+    {ring_declaration}
+
+    I := ideal(
+    {pols});
+
+    Try
+        B := GBasisTimeout(I, {timeout});
+
+        If not(1 IsIn I) Then
+            println \"ERROR: {pol_system_idx}\";
+        Else;
+            println \"OK: {pol_system_idx}\";
+        EndIf;
+    UponError E Do
+        println \"TIMEOUT: {pol_system_idx}\";
+    EndTry;
+    "}
+}
+
+// Returns a String containing a subscript that checks, for each of the given flips of the signals
+//  to fix, whether 1 belongs to the ideal generated by the constraints and that flip. It prints OK
+//  if it does for all of them, that is, if no signal can take another value than its witness.
+fn get_separate_prohibition_check_subscript(
+    ring_declaration: &str,
+    constraint_polynomials: Vec<String>,
+    flip_generators: Vec<String>,
+    context: &InputDataContextView,
+    pol_system_idx: PolSystemIndex,
+) -> String {
+    let pols = constraint_polynomials.join(",\n");
+    let flips = flip_generators.join(", ");
+    let timeout: u32 = context.options.groebner_cocoa_timeout_seconds;
+
+    formatdoc! {"
+    {ring_declaration}
+
+    Constraints := [
+    {pols}];
+    Fixed := true;
+
+    Try
+        Foreach Flip In [{flips}] Do
+            I := ideal(concat(Constraints, [Flip]));
+            B := GBasisTimeout(I, {timeout});
+
+            If not(1 IsIn I) Then
+                Fixed := false;
+                Break;
+            EndIf;
+        EndForeach;
+
+        If not(Fixed) Then
+            println \"ERROR: {pol_system_idx}\";
+        Else;
+            println \"OK: {pol_system_idx}\";
+        EndIf;
+    UponError E Do
+        println \"TIMEOUT: {pol_system_idx}\";
+    EndTry;
+    "}
+}
+
+// Returns a String containing a subscript that checks whether 1 belongs to the saturation of the
+//  ideal generated by the given constraints with respect to the ideal of the witness point, given by
+//  its generators. The saturation vanishes exactly on the solutions that differ from the witness,
+//  so it prints OK if there are none. The timeout only bounds the final Groebner basis, as CoCoA
+//  does not support a timeout for the saturation itself.
+fn get_saturation_check_subscript(
+    ring_declaration: &str,
+    constraint_polynomials: Vec<String>,
+    witness_point_generators: &str,
+    context: &InputDataContextView,
+    pol_system_idx: PolSystemIndex,
+) -> String {
+    let pols = if constraint_polynomials.is_empty() {
+        "RingElem(R, 0)".to_string()
+    } else {
+        constraint_polynomials.join(",\n")
+    };
+
+    let timeout: u32 = context.options.groebner_cocoa_timeout_seconds;
+
+    formatdoc! {"
+    {ring_declaration}
+
+    I := ideal(
+    {pols});
+    W := ideal({witness_point_generators});
+
+    Try
+        S := saturate(I, W);
+        B := GBasisTimeout(S, {timeout});
+
+        If not(1 IsIn S) Then
+            println \"ERROR: {pol_system_idx}\";
+        Else;
+            println \"OK: {pol_system_idx}\";
+        EndIf;
+    UponError E Do
+        println \"TIMEOUT: {pol_system_idx}\";
+    EndTry;
+    "}
+}
+
+// Returns a String containing a subscript that checks whether the ideal generated by the given
+//  constraints only vanishes at the witness, from the dimension of its quotient ring and the number
+//  of points of its radical. If it has more points and some variable does not need to be fixed,
+//  they may be the witness with other values for that variable, so the ideal is then saturated
+//  with respect to the ideal of the witness point as in get_saturation_check_subscript.
+// GBasisTimeout is the only function of CoCoA with a timeout, so radical, QuotientBasis and
+//  saturate are not bounded by it. They run after the Groebner basis of I has been computed in
+//  time, which makes the quotient basis cheap, but the radical and the saturation may still take
+//  arbitrarily long
+fn get_dimension_check_subscript(
+    ring_declaration: &str,
+    constraint_polynomials: Vec<String>,
+    witness_point_generators: &str,
+    all_vars_to_fix: bool,
+    context: &InputDataContextView,
+    pol_system_idx: PolSystemIndex,
+) -> String {
+    let pols = if constraint_polynomials.is_empty() {
+        "RingElem(R, 0)".to_string()
+    } else {
+        constraint_polynomials.join(",\n")
+    };
+
+    let timeout: u32 = context.options.groebner_cocoa_timeout_seconds;
+    let other_points = if all_vars_to_fix {
+        format!("println \"ERROR: {pol_system_idx}\";")
+    } else {
+        formatdoc! {"
+            S := saturate(I, W);
+                    B := GBasisTimeout(S, {timeout});
+
+                    If not(1 IsIn S) Then
+                        println \"ERROR: {pol_system_idx}\";
+                    Else;
+                        println \"OK: {pol_system_idx}\";
+                    EndIf;"}
+    };
+
+    formatdoc! {"
+    {ring_declaration}
+
+    I := ideal(
+    {pols});
+    W := ideal({witness_point_generators});
+
+    Try
+        B := GBasisTimeout(I, {timeout});
+
+        If 1 IsIn I Then
+            println \"OK: {pol_system_idx}\";
+        Elif IsZeroDim(I) And len(QuotientBasis(radical(I))) = 1 Then
+            println \"OK: {pol_system_idx}\";
+        Else
+            {other_points}
+        EndIf;
+    UponError E Do
+        println \"TIMEOUT: {pol_system_idx}\";
+    EndTry;
+    "}
+}
+
+// Returns the generators of the ideal of the witness point, x - w for each signal x to fix with
+//  witness value w. If there are no signals to fix, the unit ideal is returned instead, so the
+//  saturation does not remove any solution
+fn get_witness_point_generators(
+    signals_to_fix: &BTreeMap<SignalIndex, SignalToFixData>,
+    eliminated_signals: &LinearSubstitutions,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> String {
+    if signals_to_fix.is_empty() {
+        return match display_kind {
+            SignalDisplayKind::Name | SignalDisplayKind::SanitizedName(_) => "1".to_string(),
+            SignalDisplayKind::Index => "RingElem(R, 1)".to_string(),
+        };
+    }
+
+    signals_to_fix
+        .keys()
+        .map(|signal_idx| {
+            let signal_name =
+                get_signal_to_fix_string(*signal_idx, eliminated_signals, context, display_kind);
+            format!("{} - {}", signal_name, context.witness_value(*signal_idx))
+        })
+        .join(", ")
+}
+
+// Writes the ideal of each polynomial system to its own file inside dir, so the whole problem can be
+//  handed to an external Groebner basis tool. Files are named after the component of the system and
+//  the index of the system inside it, and contain three lines: the field prime, the comma-separated
+//  variables and the comma-separated generators of the ideal, that is, the optimized constraints
+//  and the prohibition polynomial. The signals of a system are fixed if and only if 1 belongs to its
+//  ideal.
+pub fn export_ideals(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    dir: &Path,
+) -> VerifierResult<()> {
+    fs::create_dir_all(dir)?;
+
+    let mut num_systems_per_component = HashMap::<&str, usize>::new();
+    for pol_system in pol_systems {
+        let index_in_component = num_systems_per_component
+            .entry(pol_system.component_name.as_str())
+            .or_default();
+
+        let optimized_pol_system = optimize_pol_system(pol_system, context);
+        let vars = get_used_signal_indices(&optimized_pol_system)
+            .iter()
+            .map(|i| format!("x_{}", i))
+            .chain(get_prohibition_vars(&optimized_pol_system))
+            .join(", ");
+
+        // Unlike in CoCoA scripts, an empty prohibition polynomial is written as a plain 0
+        let prohibition_polynomial = if optimized_pol_system.signals_to_fix.is_empty() {
+            "0".to_string()
+        } else {
+            get_prohibition_witness_polynomial(
+                &optimized_pol_system.signals_to_fix,
+                &LinearSubstitutions::new(),
+                context,
+                SignalDisplayKind::Index,
+            )
+            .string
+        };
+
+        let pols = optimized_pol_system
+            .constraints
+            .iter()
+            .map(|c| get_constraint_polynomial(c, context, SignalDisplayKind::Index))
+            .chain(iter::once(prohibition_polynomial))
+            .join(", ");
+
+        let path = dir.join(format!(
+            "{}-{}.ideal",
+            pol_system.component_name, index_in_component
+        ));
+        fs::write(path, format!("{}\n{}\n{}\n", context.field, vars, pols))?;
+
+        *index_in_component += 1;
+    }
+
+    Ok(())
+}
+
+// Polynomial system stating that a component is not functionally unique, that is, that there are
+//  two assignments of its signals which agree on the inputs but differ on some output. Unlike the
+//  polynomial systems of weak safety, it does not depend on any witness. The first copy of each
+//  signal keeps its index, while the second copy of every non-input signal s is the signal
+//  s + offset. Inputs are shared by both copies.
+pub struct FunctionalUniquenessSystem {
+    // Constraints of both copies of the component
+    pub constraints: Vec<Constraint<usize>>,
+
+    // The two copies of each output signal
+    pub output_pairs: Vec<(SignalIndex, SignalIndex)>,
+
+    pub template_name: String,
+    pub component_name: String,
+}
+
+// Builds the functional uniqueness system of a component. Subcomponents are not treated as black
+//  boxes: their constraints are duplicated as well.
+pub fn build_functional_uniqueness_system(
+    tree_constraints: &TreeConstraints,
+    constraint_storage: &ConstraintStorage,
+) -> FunctionalUniquenessSystem {
+    fn collect_constraints(
+        tree_constraints: &TreeConstraints,
+        constraint_storage: &ConstraintStorage,
+        constraints: &mut Vec<Constraint<usize>>,
+    ) {
+        let constraints_range = tree_constraints.initial_constraint
+            ..(tree_constraints.initial_constraint + tree_constraints.no_constraints);
+        constraints
+            .extend(constraints_range.filter_map(|idx| constraint_storage.read_constraint(idx)));
+
+        for subcomponent in &tree_constraints.subcomponents {
+            collect_constraints(subcomponent, constraint_storage, constraints);
+        }
+    }
+
+    let mut constraints = vec![];
+    collect_constraints(tree_constraints, constraint_storage, &mut constraints);
+
+    let output_signals = tree_constraints.initial_signal
+        ..(tree_constraints.initial_signal + tree_constraints.number_outputs);
+    let input_signals: BTreeSet<SignalIndex> =
+        (output_signals.end..(output_signals.end + tree_constraints.number_inputs)).collect();
+
+    // Any index above every signal of the component can be used as offset of the second copy
+    let offset = constraints
+        .iter()
+        .flat_map(|c| c.take_cloned_signals_ordered())
+        .chain(output_signals.clone())
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let copy_linear_expression = |expression: &HashMap<SignalIndex, BigInt>| {
+        expression
+            .iter()
+            .map(|(signal, coefficient)| {
+                let copied_signal = if *signal == Constraint::<usize>::constant_coefficient()
+                    || input_signals.contains(signal)
+                {
+                    *signal
+                } else {
+                    *signal + offset
+                };
+                (copied_signal, coefficient.clone())
+            })
+            .collect()
+    };
+
+    let second_copy: Vec<_> = constraints
+        .iter()
+        .map(|c| {
+            Constraint::new(
+                copy_linear_expression(c.a()),
+                copy_linear_expression(c.b()),
+                copy_linear_expression(c.c()),
+            )
+        })
+        .collect();
+    constraints.extend(second_copy);
+
+    FunctionalUniquenessSystem {
+        constraints,
+        output_pairs: output_signals.map(|s| (s, s + offset)).collect(),
+        template_name: tree_constraints.template_name.clone(),
+        component_name: tree_constraints.component_name.clone(),
+    }
+}
+
+// Builds a single polynomial system with every constraint of the circuit, ignoring the subcomponent
+//  structure, whose signals to fix are the outputs of the main component. As in the modular
+//  verification, the inputs of the main component are replaced by their witness values. It is used
+//  to cross-check the verdict of the modular verification on small circuits.
+pub fn build_monolithic_pol_system(
+    context: &InputDataContextView,
+    constraint_storage: &ConstraintStorage,
+) -> PolynomialSystemFixedSignal {
+    let tree_constraints = context.tree_constraints;
+    let output_signals = tree_constraints.initial_signal
+        ..(tree_constraints.initial_signal + tree_constraints.number_outputs);
+
+    build_whole_circuit_pol_system(context, constraint_storage, output_signals.collect())
+}
+
+// Same as build_monolithic_pol_system, but the signals to fix are the given ones instead of the
+//  outputs of the main component
+pub fn build_whole_circuit_pol_system(
+    context: &InputDataContextView,
+    constraint_storage: &ConstraintStorage,
+    signals_to_fix: BTreeSet<SignalIndex>,
+) -> PolynomialSystemFixedSignal {
+    fn collect_constraint_indices(
+        tree_constraints: &TreeConstraints,
+        constraint_indices: &mut Vec<ConstraintIndex>,
+    ) {
+        constraint_indices.extend(
+            tree_constraints.initial_constraint
+                ..(tree_constraints.initial_constraint + tree_constraints.no_constraints),
+        );
+
+        for subcomponent in &tree_constraints.subcomponents {
+            collect_constraint_indices(subcomponent, constraint_indices);
+        }
+    }
+
+    let tree_constraints = context.tree_constraints;
+    let mut constraint_indices = vec![];
+    collect_constraint_indices(tree_constraints, &mut constraint_indices);
+
+    let first_input = tree_constraints.initial_signal + tree_constraints.number_outputs;
+    let input_signals = first_input..(first_input + tree_constraints.number_inputs);
+
+    let mut constraints = vec![];
+    let mut kept_constraint_indices = vec![];
+    for idx in constraint_indices {
+        let Some(mut constraint) = constraint_storage.read_constraint(idx) else {
+            continue;
+        };
+
+        for input in input_signals.clone() {
+            let substitution = Substitution::<usize>::new(
+                input,
+                ArithmeticExpression::Linear {
+                    coefficients: HashMap::from([(
+                        Constraint::constant_coefficient(),
+                        context.witness_value(input).clone(),
+                    )]),
+                },
+            )
+            .unwrap();
+            Constraint::apply_substitution(&mut constraint, &substitution, &context.field);
+        }
+        Constraint::fix_constraint(&mut constraint, &context.field);
+
+        // Constraints only on the inputs are satisfied by the witness and vanish
+        if !constraint.is_empty() {
+            constraints.push(constraint);
+            kept_constraint_indices.push(idx);
+        }
+    }
+
+    PolynomialSystemFixedSignal {
+        constraints,
+        constraint_indices: kept_constraint_indices,
+        signals_to_fix,
+        template_name: tree_constraints.template_name.clone(),
+        component_name: tree_constraints.component_name.clone(),
+    }
+}
+
+// Checks the monolithic polynomial system of the circuit with CoCoA. Its script is written apart
+//  from the one of the modular verification, so both can be inspected. A circuit without outputs
+//  has nothing to fix, so CoCoA is not run
+pub fn verify_monolithic_pol_system(
+    pol_system: &PolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> VerifierResult<PolSystemVerdict> {
+    if pol_system.signals_to_fix.is_empty() {
+        return Ok(PolSystemVerdict::Fixed);
+    }
+
+    let results = verify_pol_systems_with_script_name(
+        std::slice::from_ref(pol_system),
+        context,
+        "groebner-monolithic",
+    )?;
+    Ok(results[0].verdict)
+}
+
+// Returns the signal index of the copy of a signal for the witness with the given index, when
+//  several witnesses are checked at once. The first witness uses the original signals, and the
+//  constant signal 0 is shared by all of them
+pub fn get_witness_copy_signal(
+    signal: SignalIndex,
+    witness_idx: usize,
+    signal_offset: usize,
+) -> SignalIndex {
+    if signal == Constraint::<usize>::constant_coefficient() {
+        signal
+    } else {
+        signal + witness_idx * signal_offset
+    }
+}
+
+fn rename_pol_system_signals(
+    pol_system: &PolynomialSystemFixedSignal,
+    witness_idx: usize,
+    signal_offset: usize,
+) -> PolynomialSystemFixedSignal {
+    let rename = |linear_term: &HashMap<SignalIndex, BigInt>| {
+        linear_term
+            .iter()
+            .map(|(signal, coeff)| {
+                (
+                    get_witness_copy_signal(*signal, witness_idx, signal_offset),
+                    coeff.clone(),
+                )
+            })
+            .collect()
+    };
+
+    PolynomialSystemFixedSignal {
+        constraints: pol_system
+            .constraints
+            .iter()
+            .map(|c| Constraint::new(rename(c.a()), rename(c.b()), rename(c.c())))
+            .collect(),
+        constraint_indices: pol_system.constraint_indices.clone(),
+        signals_to_fix: pol_system
+            .signals_to_fix
+            .iter()
+            .map(|signal| get_witness_copy_signal(*signal, witness_idx, signal_offset))
+            .collect(),
+        template_name: pol_system.template_name.clone(),
+        component_name: pol_system.component_name.clone(),
+    }
+}
+
+// Combines the polynomial systems obtained with each witness, so that each polynomial system is
+//  checked at every witness at once. Each witness has its own copy of the signals (see
+//  get_witness_copy_signal), and the systems with the same position among those of their component
+//  and the same signals to fix are merged. As their signals are disjoint, the prohibition polynomial
+//  of the merged system is the product of the prohibition polynomials of each witness, so it only
+//  has a solution if some witness is not the only solution of its system. Systems without a match
+//  in the first witness are kept on their own.
+pub fn combine_witness_pol_systems(
+    pol_systems_per_witness: &[Vec<PolynomialSystemFixedSignal>],
+    signal_offset: usize,
+) -> Vec<PolynomialSystemFixedSignal> {
+    let Some((first_pol_systems, other_pol_systems)) = pol_systems_per_witness.split_first() else {
+        return vec![];
+    };
+
+    // Pending polynomial systems of each of the other witnesses, by component
+    let mut pending: Vec<HashMap<&str, VecDeque<&PolynomialSystemFixedSignal>>> = other_pol_systems
+        .iter()
+        .map(|pol_systems| {
+            let mut by_component: HashMap<&str, VecDeque<_>> = HashMap::new();
+            for pol_system in pol_systems {
+                by_component
+                    .entry(pol_system.component_name.as_str())
+                    .or_default()
+                    .push_back(pol_system);
+            }
+            by_component
+        })
+        .collect();
+
+    let mut combined_pol_systems = vec![];
+    let mut unmatched_pol_systems = vec![];
+    for pol_system in first_pol_systems {
+        let mut combined = pol_system.clone();
+
+        for (idx, by_component) in pending.iter_mut().enumerate() {
+            let witness_idx = idx + 1;
+            let Some(other) = by_component
+                .get_mut(pol_system.component_name.as_str())
+                .and_then(|queue| queue.pop_front())
+            else {
+                continue;
+            };
+
+            let renamed = rename_pol_system_signals(other, witness_idx, signal_offset);
+            if other.signals_to_fix == pol_system.signals_to_fix {
+                combined.constraints.extend(renamed.constraints);
+                combined
+                    .constraint_indices
+                    .extend(renamed.constraint_indices);
+                combined.signals_to_fix.extend(renamed.signals_to_fix);
+            } else {
+                unmatched_pol_systems.push(renamed);
+            }
+        }
+
+        combined_pol_systems.push(combined);
+    }
+
+    for (idx, by_component) in pending.into_iter().enumerate() {
+        let witness_idx = idx + 1;
+        for pol_system in by_component.into_values().flatten() {
+            unmatched_pol_systems.push(rename_pol_system_signals(
+                pol_system,
+                witness_idx,
+                signal_offset,
+            ));
+        }
+    }
+
+    combined_pol_systems.extend(unmatched_pol_systems);
+    combined_pol_systems
+}
+
+// Returns a Cocoa5 script that prints OK if the functional uniqueness system has no solution, that
+//  is, if the outputs of the component are determined by its inputs.
+pub fn generate_functional_uniqueness_cocoa_script(
+    system: &FunctionalUniquenessSystem,
+    context: &InputDataContextView,
+) -> String {
+    let mut used_signal_indices = BTreeSet::new();
+    for constraint in &system.constraints {
+        used_signal_indices.append(&mut constraint.take_cloned_signals_ordered());
+    }
+    for (first, second) in &system.output_pairs {
+        used_signal_indices.insert(*first);
+        used_signal_indices.insert(*second);
+    }
+
+    let vars = used_signal_indices
+        .iter()
+        .map(|i| format!("x_{}", i))
+        .chain(system.output_pairs.iter().map(|(s, _)| format!("u_{}", s)))
+        .collect();
+
+    // Both copies of the outputs play the role of the signals to fix
+    let output_vars: HashSet<String> = system
+        .output_pairs
+        .iter()
+        .flat_map(|(first, second)| [format!("x_{}", first), format!("x_{}", second)])
+        .collect();
+
+    // Some output must differ between both copies. A component without outputs is trivially
+    //  functionally unique, which corresponds to an empty product.
+    let prohibition_polynomial = if system.output_pairs.is_empty() {
+        "RingElem(R, 1)".to_string()
+    } else {
+        system
+            .output_pairs
+            .iter()
+            .map(|(first, second)| format!("((x_{} - x_{})*u_{} - 1)", first, second, first))
+            .join(" * ")
+    };
+
+    // Each output contributes its two copies and a prohibition variable
+    let num_prohibition_vars = 3 * system.output_pairs.len() as u32;
+    let var_limit = context
+        .options
+        .max_vars_prohibition_polynomial_before_timeout;
+
+    let subscript = if num_prohibition_vars > var_limit {
+        "println \"TIMEOUT: 0\";\n".to_string()
+    } else {
+        get_groebner_basis_check_subscript(
+            &get_cocoa_ring_declaration(vars, &output_vars, context),
+            system
+                .constraints
+                .iter()
+                .map(|c| get_constraint_polynomial(c, context, SignalDisplayKind::Index))
+                .collect(),
+            prohibition_polynomial,
+            context,
+            0,
+        )
+    };
+
+    let field_prime = context.field.to_string();
+    let prelude = get_cocoa_prelude(context);
+
+    formatdoc! {"
+        {prelude}p := {field_prime};
+        use F ::= ZZ/(p);
+
+        {subscript}
+        println \"FINISHED\";
+    "}
+}
+
+// Checks the functional uniqueness system of a component with CoCoA. Returns Fixed if the outputs
+//  of the component are determined by its inputs, which is trivially the case if it has none.
+pub fn check_functional_uniqueness(
+    system: &FunctionalUniquenessSystem,
+    context: &InputDataContextView,
+) -> VerifierResult<PolSystemVerdict> {
+    if system.output_pairs.is_empty() {
+        return Ok(PolSystemVerdict::Fixed);
+    }
+
+    let output = run_cocoa_script(
+        &generate_functional_uniqueness_cocoa_script(system, context),
+        context,
+        "uniqueness",
+    )?;
+
+    let mut unexpected_output = Vec::new();
+    for line in &output.stdout {
+        if line.starts_with("OK: ") {
+            return Ok(PolSystemVerdict::Fixed);
+        } else if line.starts_with("ERROR: ") {
+            return Ok(PolSystemVerdict::ManySolutions);
+        } else if line.starts_with("TIMEOUT: ") {
+            return Ok(PolSystemVerdict::Timeout);
+        } else {
+            println_unless_quiet!(context.options, "{}", format!("[CoCoA] {}", line).yellow());
+            unexpected_output.push(line.clone());
+        }
+    }
+
+    // Killed because of the total timeout before deciding
+    let Some(status) = &output.status else {
+        return Ok(PolSystemVerdict::Timeout);
+    };
+
+    if is_solver_out_of_memory(status, &unexpected_output, &output.stderr, context) {
+        return Ok(PolSystemVerdict::OutOfMemory);
+    }
+
+    Err(VerifierError::CasFailure(
+        get_unexpected_termination_message("CoCoA", status, &unexpected_output, &output.stderr),
+    ))
+}
+
+struct SolverOutput {
+    // None if the solver has been killed because the total timeout has been exceeded
+    status: Option<ExitStatus>,
+    stdout: Vec<String>,
+    stderr: Vec<String>,
+}
+
+// Writes a CoCoA script to the artifacts folder, named after script_name, and runs it until it
+//  finishes. The script can only be run by CoCoA, so this fails if another solver is given in the
+//  options
+fn run_cocoa_script(
+    script: &str,
+    context: &InputDataContextView,
+    script_name: &str,
+) -> VerifierResult<SolverOutput> {
+    let cocoa_path = match Solver::find(context)? {
+        Solver::CoCoA(cocoa_path) => cocoa_path,
+        Solver::External(_) => {
+            return Err(VerifierError::InvalidArgument(format!(
+                "The {} check needs CoCoA, so it cannot be run with --solver-cmd",
+                script_name
+            )))
+        }
+    };
+    println_unless_quiet!(
+        context.options,
+        "Found CoCoA at {}",
+        cocoa_path.to_str().unwrap()
+    );
+
+    let cocoa_file_path = if context.options.keep_scripts {
+        get_timestamped_cocoa_file_path(Path::new(context.output_path), script_name)
+    } else {
+        Path::new(context.output_path).join(format!("{}.cocoa5", script_name))
+    };
+
+    write_cocoa_script(script, &cocoa_file_path, context)?;
+
+    println_unless_quiet!(
+        context.options,
+        "CoCoA script written to {}",
+        cocoa_file_path.display()
+    );
+
+    let child = get_cocoa_command(&cocoa_path, &cocoa_file_path, context)?
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    wait_for_solver(child, "CoCoA")
+}
+
+// Waits for a solver process with piped output to finish, collecting its output. Meanwhile, it is
+//  registered as running, so it is killed if the total timeout is exceeded. Unlike
+//  run_solver_on_pol_systems, the output is only processed once the solver has exited.
+fn wait_for_solver(mut child: Child, solver_name: &str) -> VerifierResult<SolverOutput> {
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    let child_id = add_running_cocoa(child);
+    if is_total_timeout_exceeded() {
+        // The watchdog may have killed the running processes before this one was registered
+        kill_running_cocoa();
+    }
+
+    // Collect stderr on a separate thread, so the solver never blocks writing to a full pipe
+    let stderr_reader = thread::spawn(move || -> Vec<String> {
+        BufReader::new(stderr)
+            .lines()
+            .map_while(Result::ok)
+            .collect()
+    });
+    let stdout_lines = BufReader::new(stdout)
+        .lines()
+        .collect::<io::Result<Vec<_>>>()?;
+    let stderr_lines = stderr_reader.join().unwrap_or_default();
+
+    let status = match take_running_cocoa(child_id) {
+        Some(mut child) => Some(child.wait()?),
+        None if is_total_timeout_exceeded() => None,
+        None => {
+            return Err(VerifierError::CasFailure(format!(
+                "{} has been interrupted",
+                solver_name
+            )))
+        }
+    };
+
+    Ok(SolverOutput {
+        status,
+        stdout: stdout_lines,
+        stderr: stderr_lines,
+    })
+}
+
+// Returns the verdict of each of the num_checks checks run by a solver, from the lines of its
+//  output made of a prefix in verdict_prefixes followed by the index of the check, such as
+//  "OK: 3". Every check must have been answered before the solver prints FINISHED, and any other
+//  line is shown as a warning. If the solver has been killed because of the total timeout, the
+//  checks it has not answered get unchecked_verdict.
+fn parse_solver_verdicts<V: Copy>(
+    output: &SolverOutput,
+    solver_name: &str,
+    verdict_prefixes: &[(&str, V)],
+    num_checks: usize,
+    unchecked_verdict: V,
+) -> VerifierResult<Vec<V>> {
+    let mut verdicts = vec![None; num_checks];
+    let mut unexpected_output = Vec::new();
+    let mut finished = false;
+
+    for line in &output.stdout {
+        let parsed_line = verdict_prefixes.iter().find_map(|(prefix, verdict)| {
+            let idx = line.strip_prefix(prefix)?.parse::<usize>().ok()?;
+            Some((idx, *verdict))
+        });
+
+        match parsed_line {
+            Some((idx, verdict)) if idx < num_checks => verdicts[idx] = Some(verdict),
+            Some((idx, _)) => {
+                return Err(VerifierError::CasFailure(format!(
+                    "{} answered check {}, but there are only {} checks: {}",
+                    solver_name, idx, num_checks, line
+                )))
+            }
+            None if line == "FINISHED" => finished = true,
+            None => {
+                println!("{}", format!("[{}] {}", solver_name, line).yellow());
+                unexpected_output.push(line.clone());
+            }
+        }
+    }
+
+    // Killed because of the total timeout, which leaves the remaining checks undecided
+    let Some(status) = &output.status else {
+        return Ok(verdicts
+            .into_iter()
+            .map(|verdict| verdict.unwrap_or(unchecked_verdict))
+            .collect());
+    };
+
+    if !finished || verdicts.iter().any(Option::is_none) {
+        return Err(VerifierError::CasFailure(
+            get_unexpected_termination_message(
+                solver_name,
+                status,
+                &unexpected_output,
+                &output.stderr,
+            ),
+        ));
+    }
+
+    Ok(verdicts.into_iter().flatten().collect())
+}
+
+// Assignment of the signals to fix of a polynomial system that differs from the witness, sampled
+//  at random to corroborate that the system is fixed
+pub struct SampledAssignment {
+    // Index of the polynomial system the assignment has been sampled for
+    pub pol_system_idx: PolSystemIndex,
+
+    pub values: BTreeMap<SignalIndex, BigInt>,
+}
+
+// Result of checking whether a sampled assignment can be extended to a solution of its polynomial
+//  system
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SampledAssignmentVerdict {
+    // The polynomial system has no solution taking these values, as expected of a fixed system
+    Rejected,
+
+    // The polynomial system may have a second solution taking these values. Groebner bases work
+    //  over the algebraic closure of the field, so the solution may not belong to the field itself
+    PossibleSolution,
+
+    Timeout,
+}
+
+// Samples up to num_samples random assignments of the signals to fix of each polynomial system.
+//  Binary signals and signals restricted to a small domain take values of their domain, and the
+//  remaining signals any value of the field. Samples that coincide with the witness are discarded,
+//  so fewer assignments may be returned.
+pub fn sample_assignments<R: Rng>(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    num_samples: u32,
+    rng: &mut R,
+) -> Vec<SampledAssignment> {
+    let mut sampled_assignments = vec![];
+
+    for (pol_system_idx, pol_system) in pol_systems.iter().enumerate() {
+        let optimized_pol_system = optimize_pol_system(pol_system, context);
+        if optimized_pol_system.signals_to_fix.is_empty() {
+            continue;
+        }
+
+        for _ in 0..num_samples {
+            let values: BTreeMap<SignalIndex, BigInt> = optimized_pol_system
+                .signals_to_fix
+                .iter()
+                .map(|(signal, data)| {
+                    let value = if data.is_boolean {
+                        BigInt::from(rng.gen_range(0, 2))
+                    } else if let Some(domain) = &data.domain {
+                        domain.iter().choose(rng).unwrap().clone()
+                    } else {
+                        rng.gen_bigint_range(&BigInt::zero(), &context.field)
+                    };
+                    (*signal, value)
+                })
+                .collect();
+
+            let is_witness = values
+                .iter()
+                .all(|(signal, value)| value == context.witness_value(*signal));
+            if !is_witness {
+                sampled_assignments.push(SampledAssignment {
+                    pol_system_idx,
+                    values,
+                });
+            }
+        }
+    }
+
+    sampled_assignments
+}
+
+// Returns a Cocoa5 script that checks, for each sampled assignment, whether its polynomial system
+//  has a solution taking those values. It prints OK with the index of the assignment if there is
+//  none, as expected of a fixed system.
+pub fn generate_sampled_assignments_cocoa_script(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    sampled_assignments: &[SampledAssignment],
+    context: &InputDataContextView,
+) -> String {
+    let field_prime = context.field.to_string();
+    let prelude = get_cocoa_prelude(context);
+    let mut cocoa_script = formatdoc! {"
+        {prelude}p := {field_prime};
+        use F ::= ZZ/(p);
+
+    "};
+
+    let mut optimized_pol_systems = HashMap::new();
+    for (sample_idx, sampled_assignment) in sampled_assignments.iter().enumerate() {
+        let pol_system_idx = sampled_assignment.pol_system_idx;
+        let optimized_pol_system = optimized_pol_systems
+            .entry(pol_system_idx)
+            .or_insert_with(|| optimize_pol_system(&pol_systems[pol_system_idx], context));
+
+        let vars = get_used_signal_indices(optimized_pol_system)
+            .iter()
+            .map(|i| format!("x_{}", i))
+            .collect();
+        let signals_to_fix_vars: HashSet<String> = optimized_pol_system
+            .signals_to_fix
+            .keys()
+            .map(|i| format!("x_{}", i))
+            .collect();
+
+        // The assignment is added to the ideal as the generators x - v of its point
+        let assignment_generators = sampled_assignment
+            .values
+            .iter()
+            .map(|(signal, value)| format!("x_{} - {}", signal, value))
+            .join(",\n");
+
+        cocoa_script.push_str(&get_groebner_basis_check_subscript(
+            &get_cocoa_ring_declaration(vars, &signals_to_fix_vars, context),
+            get_cocoa_constraint_polynomials(optimized_pol_system, context),
+            assignment_generators,
+            context,
+            sample_idx,
+        ));
+        cocoa_script.push('\n');
+    }
+
+    cocoa_script.push_str("println \"FINISHED\";\n");
+    cocoa_script
+}
+
+// Checks with CoCoA whether the polynomial system of each sampled assignment has a solution taking
+//  its values. Returns the verdict of each assignment, in the same order as sampled_assignments.
+pub fn check_sampled_assignments(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    sampled_assignments: &[SampledAssignment],
+    context: &InputDataContextView,
+) -> VerifierResult<Vec<SampledAssignmentVerdict>> {
+    let output = run_cocoa_script(
+        &generate_sampled_assignments_cocoa_script(pol_systems, sampled_assignments, context),
+        context,
+        "sampling",
+    )?;
+
+    parse_solver_verdicts(
+        &output,
+        "CoCoA",
+        &[
+            ("OK: ", SampledAssignmentVerdict::Rejected),
+            ("ERROR: ", SampledAssignmentVerdict::PossibleSolution),
+            ("TIMEOUT: ", SampledAssignmentVerdict::Timeout),
+        ],
+        sampled_assignments.len(),
+        SampledAssignmentVerdict::Timeout,
+    )
+}
+
+// Samples up to num_samples assignments of the signals to fix of each polynomial system other than
+//  the witness, and reports those that may be a second solution of their system. This is a cheap
+//  probabilistic corroboration of the algebraic result, as a fixed system rejects every
+//  assignment. Returns the number of possible second solutions found.
+pub fn report_sampled_ambiguities(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+    num_samples: u32,
+) -> VerifierResult<usize> {
+    let sampled_assignments =
+        sample_assignments(pol_systems, context, num_samples, &mut rand::thread_rng());
+
+    if sampled_assignments.is_empty() {
+        println_unless_quiet!(
+            context.options,
+            "No assignments other than the witness have been sampled"
+        );
+        return Ok(0);
+    }
+
+    println_unless_quiet!(
+        context.options,
+        "Checking {} sampled assignments of the signals to fix...",
+        sampled_assignments.len()
+    );
+    let verdicts = check_sampled_assignments(pol_systems, &sampled_assignments, context)?;
+
+    let mut num_ambiguities = 0;
+    let mut num_timeouts = 0;
+    for (sampled_assignment, verdict) in sampled_assignments.iter().zip(verdicts) {
+        match verdict {
+            SampledAssignmentVerdict::Rejected => {}
+            SampledAssignmentVerdict::PossibleSolution => {
+                num_ambiguities += 1;
+                println_unless_quiet!(
+                    context.options,
+                    "{}",
+                    format!(
+                        "[Sampling] Polynomial system {} may have a second solution with {}",
+                        describe_pol_system(pol_systems, sampled_assignment.pol_system_idx),
+                        sampled_assignment
+                            .values
+                            .iter()
+                            .map(|(signal, value)| format!(
+                                "{} = {}",
+                                context.signal_name(*signal),
+                                value
+                            ))
+                            .join(", ")
+                    )
+                    .red()
+                );
+            }
+            SampledAssignmentVerdict::Timeout => {
+                num_timeouts += 1;
+            }
+        }
+    }
+
+    if num_timeouts > 0 {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            format!(
+                "{} sampled assignments could not be checked before timing out",
+                num_timeouts
+            )
+            .yellow()
+        );
+    }
+
+    if num_ambiguities == 0 {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            "No sampled assignment is a second solution of its polynomial system".green()
+        );
+    } else {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            format!(
+                "{} sampled assignments may be a second solution of their polynomial system",
+                num_ambiguities
+            )
+            .red()
+        );
+    }
+
+    Ok(num_ambiguities)
+}
+
+// Splits a polynomial system into one system per signal to fix, with the same constraints, so the
+//  witness is only prohibited for that signal
+pub fn split_pol_system_by_signal(
+    pol_system: &PolynomialSystemFixedSignal,
+) -> Vec<PolynomialSystemFixedSignal> {
+    pol_system
+        .signals_to_fix
+        .iter()
+        .map(|signal| PolynomialSystemFixedSignal {
+            signals_to_fix: BTreeSet::from([*signal]),
+            ..pol_system.clone()
+        })
+        .collect()
+}
+
+// Checks whether each signal to fix of a polynomial system is fixed on its own, with the solver
+//  given in the options. CoCoA runs a script named after script_name. Returns the verdict of each
+//  signal, in the order of signals_to_fix, with the signals left unchecked by the total timeout
+//  marked as NotChecked
+pub fn check_signals_individually(
+    pol_system: &PolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+    script_name: &str,
+) -> VerifierResult<Vec<(SignalIndex, PolSystemVerdict)>> {
+    let optimized_pol_systems: Vec<_> = split_pol_system_by_signal(pol_system)
+        .iter()
+        .map(|single_signal_system| optimize_pol_system(single_signal_system, context))
+        .collect();
+
+    let solver = Solver::find(context)?;
+    let solver_name = solver.name();
+    let output = wait_for_solver(
+        solver.spawn(&optimized_pol_systems, context, script_name)?,
+        &solver_name,
+    )?;
+
+    let verdicts = parse_solver_verdicts(
+        &output,
+        &solver_name,
+        &[
+            ("OK: ", PolSystemVerdict::Fixed),
+            ("ERROR: ", PolSystemVerdict::ManySolutions),
+            ("TIMEOUT: ", PolSystemVerdict::Timeout),
+            ("TOOLARGE: ", PolSystemVerdict::TooLarge),
+            ("HIGHDEGREE: ", PolSystemVerdict::TooHighDegree),
+        ],
+        optimized_pol_systems.len(),
+        PolSystemVerdict::NotChecked,
+    )?;
+
+    Ok(pol_system
+        .signals_to_fix
+        .iter()
+        .copied()
+        .zip(verdicts)
+        .collect())
+}
+
+// Narrows down why a polynomial system has many solutions. Removing constraints can only add
+//  solutions, so instead of shrinking the set of constraints, each signal to fix is checked on its
+//  own. The signals that are not fixed are reported together with the constraints they appear in,
+//  which are the ones failing to determine them.
+pub fn report_failure_minimization(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    pol_system_idx: PolSystemIndex,
+    context: &InputDataContextView,
+) -> VerifierResult<()> {
+    let pol_system = &pol_systems[pol_system_idx];
+    println_unless_quiet!(
+        context.options,
+        "\nChecking each signal of polynomial system {} on its own...",
+        describe_pol_system(pol_systems, pol_system_idx)
+    );
+
+    let signal_verdicts = check_signals_individually(pol_system, context, "minimization")?;
+
+    for (signal, verdict) in &signal_verdicts {
+        let signal_name = context.signal_name(*signal);
+        match verdict {
+            PolSystemVerdict::Fixed => {}
+            PolSystemVerdict::ManySolutions => {
+                println_unless_quiet!(
+                    context.options,
+                    "{}",
+                    format!(
+                        "[Minimize] Signal '{}' is not fixed. It appears in these constraints of the system:",
+                        signal_name
+                    )
+                    .red()
+                );
+                for (constraint, constraint_idx) in pol_system
+                    .constraints
+                    .iter()
+                    .zip(&pol_system.constraint_indices)
+                    .filter(|(c, _)| c.take_signals().contains(signal))
+                {
+                    println_unless_quiet!(
+                        context.options,
+                        "    constraint #{}: {} = 0",
+                        constraint_idx,
+                        get_constraint_polynomial(constraint, context, SignalDisplayKind::Name)
+                    );
+                }
+            }
+            _ => println_unless_quiet!(
+                context.options,
+                "{}",
+                format!(
+                    "[Minimize] Signal '{}' could not be checked on its own ({:?})",
+                    signal_name, verdict
+                )
+                .yellow()
+            ),
+        }
+    }
+
+    let num_fixed = signal_verdicts
+        .iter()
+        .filter(|(_, verdict)| *verdict == PolSystemVerdict::Fixed)
+        .count();
+    println_unless_quiet!(
+        context.options,
+        "{} of {} signals to fix are fixed on their own",
+        num_fixed,
+        signal_verdicts.len()
+    );
+
+    Ok(())
+}
+
+fn get_prohibition_witness_polynomial(
+    signals_to_fix: &BTreeMap<SignalIndex, SignalToFixData>,
+    eliminated_signals: &LinearSubstitutions,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> ProhibitionPolynomial {
+    if signals_to_fix.is_empty() {
+        // If signals_to_fix is empty, we pass the "0" ring element to cocoa instead
+        //  of an integer (otherwise cocoa complains ERROR: Expecting type LIST
+        //  or RINGELEM, but found type INT)
+
+        let str = match display_kind {
+            SignalDisplayKind::Name | SignalDisplayKind::SanitizedName(_) => "0".to_string(),
+            SignalDisplayKind::Index => "RingElem(R, 0)".to_string(),
+        };
+
+        return ProhibitionPolynomial {
+            string: str,
+            num_vars: 0,
+        };
+    }
+
+    let mut num_vars = 0;
+
+    let str: String = Itertools::intersperse(
+        signals_to_fix.iter().map(|(signal_idx, data)| -> String {
+            let signal_name =
+                get_signal_to_fix_string(*signal_idx, eliminated_signals, context, display_kind);
+            let witness_value = context.witness_value(*signal_idx);
+
+            // Optimize  prohibition for binary variables. Instead of generating a new
+            // u_i value, just assert that they must be the opposite binary value.
+            if data.is_boolean {
+                num_vars += 1;
+                format!("({} - {})", signal_name, 1 - witness_value)
+            } else if let Some(domain) = &data.domain {
+                // Same optimization for signals restricted to a small domain: the signal must
+                //  take any other value of its domain.
+                num_vars += 1;
+                let factors = domain
+                    .iter()
+                    .filter(|v| *v != witness_value)
+                    .map(|v| format!("({} - {})", signal_name, v))
+                    .join("*");
+
+                if factors.is_empty() {
+                    // The domain only contains the witness value, so the signal cannot take any
+                    //  other value and the prohibition is unsatisfiable.
+                    "1".to_string()
+                } else {
+                    factors
+                }
+            } else {
+                num_vars += 2;
+                format!(
+                    "(({} - {})*u_{} - 1)",
+                    signal_name, witness_value, signal_idx
+                )
+            }
+        }),
+        " * ".to_string(),
+    )
+        .collect();
+
+    ProhibitionPolynomial {
+        string: str,
+        num_vars,
+    }
+}
+
+// Returns true if the signals to fix are all boolean and the options ask to prohibit their witness
+//  values one at a time (see BooleanProhibition)
+fn uses_separate_boolean_prohibition(
+    signals_to_fix: &BTreeMap<SignalIndex, SignalToFixData>,
+    context: &InputDataContextView,
+) -> bool {
+    context.options.prohibition_mode == ProhibitionMode::UVar
+        && context.options.boolean_prohibition == BooleanProhibition::Separate
+        && !signals_to_fix.is_empty()
+        && signals_to_fix.values().all(|data| data.is_boolean)
+}
+
+// Returns the flip of each boolean signal to fix, (x - (1 - w)) for witness value w, which
+//  vanishes only if the signal takes the other boolean value
+fn get_boolean_flip_generators(
+    signals_to_fix: &BTreeMap<SignalIndex, SignalToFixData>,
+    eliminated_signals: &LinearSubstitutions,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> Vec<String> {
+    signals_to_fix
+        .keys()
+        .map(|signal_idx| {
+            format!(
+                "({} - {})",
+                get_signal_to_fix_string(*signal_idx, eliminated_signals, context, display_kind),
+                1 - context.witness_value(*signal_idx)
+            )
+        })
+        .collect()
+}
+
+// Returns how a signal to fix is written in polynomials: its name or its indexed variable, or the
+//  expression giving its value if it has been eliminated from the polynomial system
+fn get_signal_to_fix_string(
+    signal_idx: SignalIndex,
+    eliminated_signals: &LinearSubstitutions,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> String {
+    match (eliminated_signals.get(&signal_idx), display_kind) {
+        (Some(substitution), _) => {
+            linear_term_to_string(substitution.to(), context, true, display_kind)
+        }
+        (None, _) => get_signal_name(signal_idx, context, display_kind),
+    }
+}
+
+// Returns how a signal is written in polynomials, according to display_kind
+fn get_signal_name(
+    signal_idx: SignalIndex,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> String {
+    match display_kind {
+        SignalDisplayKind::Name => context.signal_name(signal_idx),
+        SignalDisplayKind::SanitizedName(sanitized_names) => sanitized_names
+            .get(&signal_idx)
+            .cloned()
+            .unwrap_or_else(|| sanitize_signal_name(&context.signal_name(signal_idx))),
+        SignalDisplayKind::Index => format!("x_{}", signal_idx),
+    }
+}
+
+// Turns a signal name into a valid identifier for Computer Algebra Systems, replacing the array
+//  brackets and the dots before subcomponent signals by underscores. For example, "c.in[0]" becomes
+//  "c_in_0_"
+fn sanitize_signal_name(name: &str) -> String {
+    name.replace(['[', ']', '.'], "_")
+}
+
+// Returns the sanitized name of each of the given signals. Sanitizing is not injective ('a[1]' and a
+//  signal named 'a_1_' both give 'a_1_'), so a changed name that collides with the name of another
+//  of the signals is followed by the index of its signal, and by further underscores in the
+//  unlikely case that it still collides
+fn get_sanitized_signal_names(
+    signals: &BTreeSet<SignalIndex>,
+    context: &InputDataContextView,
+) -> BTreeMap<SignalIndex, String> {
+    let names: Vec<(SignalIndex, String, String)> = signals
+        .iter()
+        .map(|signal_idx| {
+            let name = context.signal_name(*signal_idx);
+            let sanitized_name = sanitize_signal_name(&name);
+            (*signal_idx, name, sanitized_name)
+        })
+        .collect();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (_, _, sanitized_name) in &names {
+        *counts.entry(sanitized_name).or_default() += 1;
+    }
+
+    let mut taken: HashSet<String> = counts.keys().map(|name| name.to_string()).collect();
+    names
+        .iter()
+        .map(|(signal_idx, name, sanitized_name)| {
+            if sanitized_name == name || counts[sanitized_name.as_str()] == 1 {
+                return (*signal_idx, sanitized_name.clone());
+            }
+
+            let mut unique_name = format!("{}_{}", sanitized_name, signal_idx);
+            while taken.contains(&unique_name) {
+                unique_name.push('_');
+            }
+            taken.insert(unique_name.clone());
+            (*signal_idx, unique_name)
+        })
+        .collect()
+}
+
+// Returns the constraint as an equation using signal names, for example "a * b - c = 0"
+pub fn get_readable_constraint(
+    constraint: &Constraint<usize>,
+    context: &InputDataContextView,
+) -> String {
+    let mut constraint = constraint.clone();
+    Constraint::fix_constraint(&mut constraint, &context.field);
+    format!(
+        "{} = 0",
+        get_constraint_polynomial(&constraint, context, SignalDisplayKind::Name)
+    )
+}
+
+fn get_constraint_polynomial(
+    constraint: &Constraint<usize>,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> String {
+    // We assume that the constraints are fixed (called Constraint::fix_constraint) before calling
+    //  these method. Right now, we do so everytime we update a constraint in
+    //  substitute_witness_signal_into_storage
+
+    let a = constraint.a();
+    let b = constraint.b();
+    let c = constraint.c();
+
+    if let Some(linear_term) = get_constant_product_linear_term(constraint, &context.field) {
+        // A or B is a constant, so the constraint is linear
+        linear_term_to_string(&linear_term, context, false, display_kind)
+    } else {
+        let a_str = linear_term_to_string(a, context, true, display_kind);
+        let b_str = linear_term_to_string(b, context, true, display_kind);
+        let c_str = linear_term_to_string(c, context, false, display_kind);
+
+        if c_str.starts_with('-') {
+            format!(
+                "{} * {} - {}",
+                a_str,
+                b_str,
+                c_str.chars().skip(1).collect::<String>()
+            )
+        } else if c.is_empty() {
+            format!("{} * {}", a_str, b_str)
+        } else {
+            format!("{} * {} + {}", a_str, b_str, c_str)
+        }
+    }
+}
+
+// If A or B is empty or only contains the constant signal, the polynomial A*B + C of the constraint
+//  is linear. In that case, returns it as a single linear term, with the constant distributed into
+//  the other side of the product and added to C. Else, returns None
+fn get_constant_product_linear_term(
+    constraint: &Constraint<usize>,
+    prime: &BigInt,
+) -> Option<HashMap<SignalIndex, BigInt>> {
+    let constant = Constraint::<usize>::constant_coefficient();
+    let get_constant_value = |linear_term: &HashMap<SignalIndex, BigInt>| -> Option<BigInt> {
+        match linear_term.len() {
+            0 => Some(BigInt::zero()),
+            1 => linear_term.get(&constant).cloned(),
+            _ => None,
+        }
+    };
+
+    let (factor, other) = if let Some(factor) = get_constant_value(constraint.a()) {
+        (factor, constraint.b())
+    } else {
+        (get_constant_value(constraint.b())?, constraint.a())
+    };
+
+    let mut linear_term = constraint.c().clone();
+    for (signal, coeff) in other {
+        let coeff = modular_arithmetic::mul(&factor, coeff, prime);
+        let sum = modular_arithmetic::add(
+            linear_term.get(signal).unwrap_or(&BigInt::zero()),
+            &coeff,
+            prime,
+        );
+        linear_term.insert(*signal, sum);
+    }
+    linear_term.retain(|_, coeff| !(&*coeff % prime).is_zero());
+
+    Some(linear_term)
+}
+
+// Same as get_constraint_polynomial, but the product A * B is expanded into a sum of monomials,
+//  adding up like terms, so that every quadratic term can be read. Only meant for display
+fn get_expanded_constraint_polynomial(
+    constraint: &Constraint<usize>,
+    context: &InputDataContextView,
+    display_kind: SignalDisplayKind,
+) -> String {
+    let prime = &context.field;
+    let constant = ArithmeticExpression::<usize>::constant_coefficient();
+
+    // Each monomial is given by its sorted signals, without the constant signal
+    let mut monomials: BTreeMap<Vec<SignalIndex>, BigInt> = BTreeMap::new();
+    let mut add_monomial = |signals: Vec<SignalIndex>, coeff: BigInt| {
+        let signals = signals
+            .into_iter()
+            .filter(|s| *s != constant)
+            .sorted()
+            .collect();
+        let monomial_coeff = monomials.entry(signals).or_insert_with(BigInt::zero);
+        *monomial_coeff = (&*monomial_coeff + coeff) % prime;
+    };
+
+    for (a_signal, a_coeff) in constraint.a() {
+        for (b_signal, b_coeff) in constraint.b() {
+            add_monomial(vec![*a_signal, *b_signal], a_coeff * b_coeff);
+        }
+    }
+    for (c_signal, c_coeff) in constraint.c() {
+        add_monomial(vec![*c_signal], c_coeff.clone());
+    }
+
+    let s = monomials
+        .iter()
+        .filter(|(_, coeff)| !coeff.is_zero())
+        .sorted_by_key(|(signals, _)| (Reverse(signals.len()), *signals))
+        .map(|(signals, coeff)| -> String {
+            if signals.is_empty() {
+                return coefficient_to_string(coeff, prime);
+            }
+
+            let monomial = match signals.as_slice() {
+                [x, y] if x == y => format!("{}^2", get_signal_name(*x, context, display_kind)),
+                _ => signals
+                    .iter()
+                    .map(|signal| get_signal_name(*signal, context, display_kind))
+                    .join("*"),
+            };
+
+            if coeff.is_one() {
+                monomial
+            } else if coeff.eq(&(prime - &BigInt::one())) {
+                format!("-{}", monomial)
+            } else {
+                format!("{}*{}", coefficient_to_string(coeff, prime), monomial)
+            }
+        })
+        .fold("".to_string(), |curr, next| -> String {
+            if curr.is_empty() {
+                next
+            } else if next.starts_with('-') {
+                format!("{} - {}", curr, next.chars().skip(1).collect::<String>())
+            } else {
+                format!("{} + {}", curr, next)
+            }
+        });
+
+    if s.is_empty() {
+        "0".to_string()
+    } else {
+        s
+    }
+}
+
+// Will surround with parenthesis if there is more than one summation term and surround_with_parenthesis is true
+fn linear_term_to_string(
+    linear_term: &HashMap<usize, BigInt>,
+    context: &InputDataContextView,
+    surround_with_parenthesis: bool,
+    display_kind: SignalDisplayKind,
+) -> String {
+    if linear_term.is_empty() {
+        return "0".to_string();
+    }
+
+    let prime = &context.field;
+
+    let s: String = linear_term
+        .iter()
+        .sorted_by_key(|(&idx, _)| idx)
+        .map(|(&signal_idx, coeff)| -> String {
+            if signal_idx == ArithmeticExpression::<usize>::constant_coefficient() {
+                coefficient_to_string(coeff, prime)
+            } else {
+                let signal_name = get_signal_name(signal_idx, context, display_kind);
+
+                if coeff.is_one() {
+                    signal_name
+                } else if coeff.eq(&(prime - &BigInt::one())) {
+                    format!("-{}", signal_name)
+                } else {
+                    format!("{}*{}", coefficient_to_string(coeff, prime), signal_name)
+                }
+            }
+        })
+        .fold("".to_string(), |curr, next| -> String {
+            if curr.is_empty() {
+                next
+            } else if next.starts_with('-') {
+                format!("{} - {}", curr, next.chars().skip(1).collect::<String>())
+            } else {
+                format!("{} + {}", curr, next)
+            }
+        });
+
+    if surround_with_parenthesis && linear_term.len() > 1 {
+        format!("({})", s)
+    } else {
+        s
+    }
+}
+
+// Returns a prettified string of the given coefficient
+fn coefficient_to_string(coeff: &BigInt, prime_field: &BigInt) -> String {
+    if coeff > &(prime_field / 2) {
+        format!("-{}", (prime_field - coeff))
+    } else {
+        coeff.to_string()
+    }
+}
+
+#[test]
+fn test_goldilocks_prohibition_polynomial() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    // Goldilocks prime, 2^64 - 2^32 + 1
+    let field = BigInt::from(18446744069414584321u64);
+    assert_eq!(coefficient_to_string(&(&field - 1u32), &field), "-1");
+    assert_eq!(
+        coefficient_to_string(&(&field / 2u32), &field),
+        "9223372034707292160"
+    );
+    assert_eq!(
+        coefficient_to_string(&(&field / 2u32 + 1u32), &field),
+        "-9223372034707292160"
+    );
+
+    let witness = HashMap::from([(1, BigInt::one()), (2, &field - 1u32)]);
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        ..Default::default()
+    };
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(witness),
+        HashMap::new(),
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+
+    let signals_to_fix = BTreeMap::from([
+        (
+            1,
+            SignalToFixData {
+                is_boolean: true,
+                domain: None,
+            },
+        ),
+        (
+            2,
+            SignalToFixData {
+                is_boolean: false,
+                domain: None,
+            },
+        ),
+    ]);
+    let prohibition_polynomial = get_prohibition_witness_polynomial(
+        &signals_to_fix,
+        &LinearSubstitutions::new(),
+        &context.get_context_view(),
+        SignalDisplayKind::Index,
+    );
+    assert_eq!(
+        prohibition_polynomial.string,
+        "(x_1 - 0) * ((x_2 - 18446744069414584320)*u_2 - 1)"
+    );
+    assert_eq!(prohibition_polynomial.num_vars, 3);
+}
+
+#[test]
+fn test_domain_restriction_detection() {
+    use std::str::FromStr;
+
+    let field = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let minus = |v: u32| -> BigInt { &field - BigInt::from(v) };
+    let constant = Constraint::<usize>::constant_coefficient();
+
+    // t1 = x * (x - 1), t2 = (x - 2) * (x - 3), t1 * t2 = 0, with x = 1, t1 = 2, t2 = 3
+    let t1_definition = Constraint::new(
+        HashMap::from([(1, BigInt::one())]),
+        HashMap::from([(1, BigInt::one()), (constant, minus(1))]),
+        HashMap::from([(2, minus(1))]),
+    );
+    let t2_definition = Constraint::new(
+        HashMap::from([(1, BigInt::one()), (constant, minus(2))]),
+        HashMap::from([(1, BigInt::one()), (constant, minus(3))]),
+        HashMap::from([(3, minus(1))]),
+    );
+    let domain_constraint = Constraint::new(
+        HashMap::from([(2, BigInt::one())]),
+        HashMap::from([(3, BigInt::one())]),
+        HashMap::new(),
+    );
+
+    let constraints = [t1_definition, t2_definition, domain_constraint];
+    let product_definitions: HashMap<SignalIndex, &Constraint<usize>> = constraints
+        .iter()
+        .filter_map(|constraint| Some((get_product_definition_signal(constraint)?, constraint)))
+        .collect();
+
+    assert_eq!(product_definitions.len(), 2);
+    assert!(
+        is_constraint_domain_restriction(&constraints[0], &product_definitions, &field).is_none()
+    );
+
+    let (signal, domain) =
+        is_constraint_domain_restriction(&constraints[2], &product_definitions, &field).unwrap();
+    assert_eq!(signal, 1);
+    assert_eq!(domain, (0..4u32).map(BigInt::from).collect());
+}
+
+#[test]
+fn test_scaled_binary_restriction_detection() {
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+    let x_minus_one = HashMap::from([(1, BigInt::one()), (constant, BigInt::from(100))]);
+
+    // 2x * (x - 1) = 0
+    let scaled_by_two = Constraint::new(
+        HashMap::from([(1, BigInt::from(2))]),
+        x_minus_one.clone(),
+        HashMap::new(),
+    );
+    assert_eq!(
+        is_constraint_binary_restriction(&scaled_by_two, &field),
+        Some(1)
+    );
+
+    // 0x * (x - 1) = 0 holds for any x
+    let zero_coefficient = Constraint::new(
+        HashMap::from([(1, BigInt::zero())]),
+        x_minus_one.clone(),
+        HashMap::new(),
+    );
+    assert_eq!(
+        is_constraint_binary_restriction(&zero_coefficient, &field),
+        None
+    );
+
+    // (x - 1) * (-x) = 0, with the sides swapped
+    let scaled_by_minus_one = Constraint::new(
+        x_minus_one,
+        HashMap::from([(1, BigInt::from(100))]),
+        HashMap::new(),
+    );
+    assert_eq!(
+        is_constraint_binary_restriction(&scaled_by_minus_one, &field),
+        Some(1)
+    );
+
+    // x * (3x - 3) = 0
+    let scaled_double_side = Constraint::new(
+        HashMap::from([(1, BigInt::one())]),
+        HashMap::from([(1, BigInt::from(3)), (constant, BigInt::from(98))]),
+        HashMap::new(),
+    );
+    assert_eq!(
+        is_constraint_binary_restriction(&scaled_double_side, &field),
+        Some(1)
+    );
+
+    // x * (x - 2) = 0 does not restrict x to be binary
+    let not_binary = Constraint::new(
+        HashMap::from([(1, BigInt::from(2))]),
+        HashMap::from([(1, BigInt::one()), (constant, BigInt::from(99))]),
+        HashMap::new(),
+    );
+    assert_eq!(is_constraint_binary_restriction(&not_binary, &field), None);
+}
+
+#[test]
+fn test_dedup_and_reach_optimization_passes() {
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+    let product = |x: usize, y: usize| {
+        Constraint::new(
+            HashMap::from([(x, BigInt::one())]),
+            HashMap::from([(y, BigInt::one())]),
+            HashMap::from([(constant, BigInt::from(94))]),
+        )
+    };
+
+    // 1*2 - 7 = 0 appears twice with swapped factors. 3*4 - 7 = 0 does not share any signal
+    //  with the signal to fix (1) or the constraints connected to it
+    let mut pol_system = OptimizedPolynomialSystemFixedSignal {
+        constraints: vec![product(1, 2), product(2, 1), product(3, 4)],
+        constraint_indices: vec![5, 6, 7],
+        signals_to_fix: BTreeMap::from([(
+            1,
+            SignalToFixData {
+                is_boolean: false,
+                domain: None,
+            },
+        )]),
+        template_name: "T()".to_string(),
+        component_name: "main".to_string(),
+        max_degree: 2,
+    };
+
+    OptimizationPass::Dedup.apply(&mut pol_system, &field);
+    assert_eq!(pol_system.constraints.len(), 2);
+    assert_eq!(pol_system.constraint_indices, vec![5, 7]);
+
+    OptimizationPass::Reach.apply(&mut pol_system, &field);
+    assert_eq!(pol_system.constraints.len(), 1);
+    assert!(pol_system.constraints[0].a().contains_key(&1));
+    assert_eq!(pol_system.constraint_indices, vec![5]);
+
+    assert_eq!(
+        "gauss".parse::<OptimizationPass>(),
+        Ok(OptimizationPass::Gauss)
+    );
+    assert!("gaus".parse::<OptimizationPass>().is_err());
+}
+
+#[test]
+fn test_remove_tautological_constraints() {
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+
+    // 2*(x + 3) - 2*x - 6 = 0 holds for any x, while 2*(x + 3) - x - 6 = 0 does not
+    let tautology = Constraint::new(
+        HashMap::from([(constant, BigInt::from(2))]),
+        HashMap::from([(1, BigInt::one()), (constant, BigInt::from(3))]),
+        HashMap::from([(1, &field - 2), (constant, &field - 6)]),
+    );
+    let tautology_with_product = Constraint::new(
+        HashMap::from([(1, BigInt::from(2))]),
+        HashMap::from([(2, BigInt::from(3))]),
+        HashMap::new(),
+    );
+    let mut pol_system = OptimizedPolynomialSystemFixedSignal {
+        constraints: vec![
+            tautology,
+            Constraint::new(
+                HashMap::from([(constant, BigInt::from(2))]),
+                HashMap::from([(1, BigInt::one()), (constant, BigInt::from(3))]),
+                HashMap::from([(1, &field - 1), (constant, &field - 6)]),
+            ),
+        ],
+        constraint_indices: vec![3, 4],
+        signals_to_fix: BTreeMap::new(),
+        template_name: "T()".to_string(),
+        component_name: "main".to_string(),
+        max_degree: 2,
+    };
+
+    assert!(!pol_system.constraints[0].is_empty());
+    OptimizationPass::Tautology.apply(&mut pol_system, &field);
+    assert_eq!(pol_system.constraint_indices, vec![4]);
+
+    // A non-zero product is never a tautology
+    assert!(!is_constraint_tautology(&tautology_with_product, &field));
+}
+
+#[cfg(test)]
+fn build_golden_test_pol_systems(field: &BigInt) -> Vec<PolynomialSystemFixedSignal> {
+    let constant = Constraint::<usize>::constant_coefficient();
+
+    // bit * (bit - 1) = 0, out - 2*bit - 3 = 0
+    let binary_system = PolynomialSystemFixedSignal {
+        constraints: vec![
+            Constraint::new(
+                HashMap::from([(2, BigInt::one())]),
+                HashMap::from([(2, BigInt::one()), (constant, field - 1)]),
+                HashMap::new(),
+            ),
+            Constraint::new(
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::from([(1, BigInt::one()), (2, field - 2), (constant, field - 3)]),
+            ),
+        ],
+        constraint_indices: vec![0, 1],
+        signals_to_fix: BTreeSet::from([2, 1]),
+        template_name: "Bin()".to_string(),
+        component_name: "main.bin".to_string(),
+    };
+
+    // a * b - 7 = 0
+    let generic_system = PolynomialSystemFixedSignal {
+        constraints: vec![Constraint::new(
+            HashMap::from([(3, BigInt::one())]),
+            HashMap::from([(4, BigInt::one())]),
+            HashMap::from([(constant, field - 7)]),
+        )],
+        constraint_indices: vec![2],
+        signals_to_fix: BTreeSet::from([4, 3]),
+        template_name: "Mul()".to_string(),
+        component_name: "main.mul".to_string(),
+    };
+
+    vec![binary_system, generic_system]
+}
+
+#[cfg(test)]
+fn generate_golden_test_cocoa_script(options: &crate::cli::Options) -> String {
+    use crate::input_data::{SignalIndexMap, TreeConstraints};
+    use crate::verified_components::VerifiedComponents;
+    use crate::DebugSVGPrinter;
+
+    let field = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let witness = HashMap::from([
+        (1, BigInt::from(5)),
+        (2, BigInt::one()),
+        (3, BigInt::one()),
+        (4, BigInt::from(7)),
+    ]);
+    let signal_name_map = HashMap::from([
+        (1, "bin.out".to_string()),
+        (2, "bin.bit".to_string()),
+        (3, "mul.a".to_string()),
+        (4, "mul.b".to_string()),
+    ]);
+    let signal_index_map = SignalIndexMap::new();
+    let tree_constraints = TreeConstraints::default();
+    let base_path = String::new();
+    let output_path = String::new();
+    let svg_printer = DebugSVGPrinter::disabled();
+
+    let context = InputDataContextView {
+        witness: Some(&witness),
+        extra_witnesses: &[],
+        signal_name_map: &signal_name_map,
+        signal_index_map: &signal_index_map,
+        tree_constraints: &tree_constraints,
+        field: field.clone(),
+        base_path: &base_path,
+        output_path: &output_path,
+        svg_printer: &svg_printer,
+        options,
+        reference_components: &[],
+        verified_components: &VerifiedComponents::default(),
+    };
+
+    // Each run builds the systems from scratch, so their hash maps have different iteration
+    //  orders
+    let generate = || -> String {
+        let optimized_pol_systems: Vec<_> = build_golden_test_pol_systems(&field)
+            .iter()
+            .map(|x| optimize_pol_system(x, &context))
+            .collect();
+        generate_cocoa_script(&optimized_pol_systems, &context)
+    };
+
+    let script = generate();
+    assert_eq!(script, generate());
+    script
+}
+
+#[test]
+fn test_generate_cocoa_script_golden() {
+    assert_eq!(
+        generate_golden_test_cocoa_script(&Default::default()),
+        include_str!("../../test/golden/generate_cocoa_script.cocoa5")
+    );
+}
+
+#[test]
+fn test_generate_cocoa_script_annotated() {
+    let options = crate::cli::Options {
+        annotate_scripts: true,
+        ..Default::default()
+    };
+
+    // Each polynomial system is preceded by the witness values of its signals to fix
+    let expected = include_str!("../../test/golden/generate_cocoa_script.cocoa5")
+        .replace(
+            "use R ::= F[x_2, u_1];",
+            "// x_1 = 5 (bin.out)\n// x_2 = 1 (bin.bit)\nuse R ::= F[x_2, u_1];",
+        )
+        .replace(
+            "use R ::= F[x_3, x_4, u_3, u_4];",
+            "// x_3 = 1 (mul.a)\n// x_4 = 7 (mul.b)\nuse R ::= F[x_3, x_4, u_3, u_4];",
+        );
+    assert_eq!(generate_golden_test_cocoa_script(&options), expected);
+}
+
+#[test]
+fn test_generate_cocoa_script_prelude() {
+    let options = crate::cli::Options {
+        cocoa_prelude: Some("MEMORY.PACKAGES := \"packages\";".to_string()),
+        ..Default::default()
+    };
+
+    // The prelude comes before the generated script, which is otherwise unchanged
+    assert_eq!(
+        generate_golden_test_cocoa_script(&options),
+        format!(
+            "MEMORY.PACKAGES := \"packages\";\n{}",
+            include_str!("../../test/golden/generate_cocoa_script.cocoa5")
+        )
+    );
+}
+
+#[test]
+fn test_generate_cocoa_script_saturate_golden() {
+    let options = crate::cli::Options {
+        prohibition_mode: ProhibitionMode::Saturate,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        generate_golden_test_cocoa_script(&options),
+        include_str!("../../test/golden/generate_cocoa_script_saturate.cocoa5")
+    );
+}
+
+#[test]
+fn test_generate_cocoa_script_dimension_golden() {
+    let options = crate::cli::Options {
+        cocoa_strategy: CocoaStrategy::Dimension,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        generate_golden_test_cocoa_script(&options),
+        include_str!("../../test/golden/generate_cocoa_script_dimension.cocoa5")
+    );
+}
+
+#[test]
+fn test_generate_cocoa_script_elim_golden() {
+    let options = crate::cli::Options {
+        monomial_order: MonomialOrder::Elim,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        generate_golden_test_cocoa_script(&options),
+        include_str!("../../test/golden/generate_cocoa_script_elim.cocoa5")
+    );
+}
+
+#[test]
+fn test_separate_boolean_prohibition() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+    let bit_constraint = |signal: SignalIndex| {
+        Constraint::new(
+            HashMap::from([(signal, BigInt::one())]),
+            HashMap::from([(signal, BigInt::one()), (constant, &field - 1)]),
+            HashMap::new(),
+        )
+    };
+
+    // b1 * (b1 - 1) = 0, b2 * (b2 - 1) = 0
+    let pol_system = PolynomialSystemFixedSignal {
+        constraints: vec![bit_constraint(1), bit_constraint(2)],
+        constraint_indices: vec![0, 1],
+        signals_to_fix: BTreeSet::from([1, 2]),
+        template_name: "Bits()".to_string(),
+        component_name: "main".to_string(),
+    };
+
+    let script = |boolean_prohibition: BooleanProhibition| {
+        let (context, _) = InputDataContext::from_data(
+            ConstraintStorage::new(),
+            Some(HashMap::from([(1, BigInt::one()), (2, BigInt::zero())])),
+            HashMap::new(),
+            TreeConstraints {
+                field: field.to_string(),
+                ..Default::default()
+            },
+            Options {
+                boolean_prohibition,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let context = context.get_context_view();
+        get_cocoa_check_subscript(&optimize_pol_system(&pol_system, &context), &context, 0)
+    };
+
+    // Each signal is flipped in its own ideal instead of multiplying the flips
+    assert!(script(BooleanProhibition::Product).contains("(x_1 - 0) * (x_2 - 1)"));
+    let separate_script = script(BooleanProhibition::Separate);
+    assert!(separate_script.contains("Foreach Flip In [(x_1 - 0), (x_2 - 1)] Do"));
+    assert!(separate_script.contains("use R ::= F[x_1, x_2];"));
+}
+
+#[test]
+fn test_cocoa_version_range() {
+    let range: CocoaVersionRange = SUPPORTED_COCOA_VERSIONS.parse().unwrap();
+    let version = |s: &str| s.parse::<CocoaVersion>().unwrap();
+
+    assert!(range.contains(version("5.2")));
+    assert!(range.contains(version("5.4.12")));
+    assert!(!range.contains(version("5.5.0")));
+    assert!(!range.contains(version("5.1.9")));
+    assert_eq!(version("5.4").to_string(), "5.4.0");
+
+    assert!("5".parse::<CocoaVersion>().is_err());
+    assert!("5.4.x".parse::<CocoaVersion>().is_err());
+    assert!("5.2-5.5".parse::<CocoaVersionRange>().is_err());
+}
+
+#[test]
+fn test_linear_pol_system_unfixed_signals() {
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+    let linear = |coefficients: &[(usize, i32)]| {
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            coefficients
+                .iter()
+                .map(|(signal, coefficient)| {
+                    (*signal, (BigInt::from(*coefficient) + &field) % &field)
+                })
+                .collect(),
+        )
+    };
+    let pol_system = |constraints: Vec<Constraint<usize>>| PolynomialSystemFixedSignal {
+        constraint_indices: (0..constraints.len()).collect(),
+        constraints,
+        signals_to_fix: BTreeSet::from([1, 2]),
+        template_name: "Linear()".to_string(),
+        component_name: "main".to_string(),
+    };
+    let unfixed_signals =
+        |constraints| get_unfixed_signals_of_linear_pol_system(&pol_system(constraints), &field);
+
+    // x1 + x2 = 3 and x1 - x2 = 1 determine both signals, but x1 + x2 = 3 alone does not
+    let sum = linear(&[(1, 1), (2, 1), (constant, -3)]);
+    let difference = linear(&[(1, 1), (2, -1), (constant, -1)]);
+    assert_eq!(
+        unfixed_signals(vec![sum.clone(), difference.clone()]),
+        Some(vec![])
+    );
+    assert_eq!(unfixed_signals(vec![sum.clone()]), Some(vec![1, 2]));
+
+    // x1 = 2 only determines x1
+    let x1 = linear(&[(1, 1), (constant, -2)]);
+    assert_eq!(unfixed_signals(vec![x1]), Some(vec![2]));
+
+    // Systems with non-linear constraints are left to Groebner bases
+    let product = Constraint::new(
+        HashMap::from([(1, BigInt::one())]),
+        HashMap::from([(2, BigInt::one())]),
+        HashMap::from([(constant, BigInt::from(98))]),
+    );
+    assert_eq!(unfixed_signals(vec![sum.clone(), product.clone()]), None);
+
+    // Without CoCoA, only linear systems are decided
+    let (context, _) = crate::input_data::InputDataContext::from_data(
+        ConstraintStorage::new(),
+        None,
+        HashMap::new(),
+        TreeConstraints {
+            field: field.to_string(),
+            ..Default::default()
+        },
+        crate::cli::Options::default(),
+    )
+    .unwrap();
+    let verdicts: Vec<_> = verify_pol_systems_without_cas(
+        &[
+            pol_system(vec![sum.clone(), difference]),
+            pol_system(vec![sum.clone()]),
+            pol_system(vec![sum, product]),
+        ],
+        &context.get_context_view(),
+    )
+    .into_iter()
+    .map(|result| result.verdict)
+    .collect();
+    assert_eq!(
+        verdicts,
+        vec![
+            PolSystemVerdict::Fixed,
+            PolSystemVerdict::ManySolutions,
+            PolSystemVerdict::NotChecked
+        ]
+    );
+}
+
+#[test]
+fn test_reduce_linear_block() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+    let witness = HashMap::from([
+        (1, BigInt::from(4)),
+        (2, BigInt::from(3)),
+        (3, BigInt::one()),
+    ]);
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        ..Default::default()
+    };
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(witness),
+        HashMap::new(),
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    // x1 - x2 - x3 = 0, x2 - 2*x3 - 1 = 0, x1 * x3 - 4 = 0
+    let pol_system = PolynomialSystemFixedSignal {
+        constraints: vec![
+            Constraint::new(
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::from([(1, BigInt::one()), (2, &field - 1), (3, &field - 1)]),
+            ),
+            Constraint::new(
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::from([(2, BigInt::one()), (3, &field - 2), (constant, &field - 1)]),
+            ),
+            Constraint::new(
+                HashMap::from([(1, BigInt::one())]),
+                HashMap::from([(3, BigInt::one())]),
+                HashMap::from([(constant, &field - 4)]),
+            ),
+        ],
+        constraint_indices: vec![0, 1, 2],
+        signals_to_fix: BTreeSet::from([1, 2, 3]),
+        template_name: "Reduce()".to_string(),
+        component_name: "main".to_string(),
+    };
+    let optimized_pol_system = optimize_pol_system(&pol_system, &context);
+
+    // Both linear constraints are solved for a pivot, leaving a single quadratic constraint
+    let (reduced_pol_system, eliminated_signals) =
+        reduce_linear_block(&optimized_pol_system, &field);
+    assert_eq!(reduced_pol_system.constraints.len(), 1);
+    assert_eq!(reduced_pol_system.constraint_indices, vec![2]);
+    assert_eq!(eliminated_signals.len(), 2);
+    assert!(reduced_pol_system
+        .constraints
+        .iter()
+        .flat_map(|c| c.take_cloned_signals())
+        .all(|signal| !eliminated_signals.contains_key(&signal)));
+    assert_eq!(
+        reduced_pol_system.signals_to_fix.len(),
+        optimized_pol_system.signals_to_fix.len()
+    );
+
+    // The ring of the script only declares the remaining signal and the prohibition variables
+    let script = get_cocoa_subscript(&optimized_pol_system, &context, 0);
+    assert!(script.starts_with("use R ::= F[x_3, u_1, u_2, u_3];"));
+
+    // The stats of the report describe the same reduced system
+    let stats = get_pol_system_stats(&pol_system, &context);
+    assert_eq!(stats.num_constraints, 1);
+    assert_eq!(stats.num_signals, 1);
+    assert_eq!(stats.num_prohibition_vars, 6);
+}
+
+#[test]
+fn test_constraint_degree() {
+    let linear_term = |terms: &[(SignalIndex, i32)]| -> HashMap<SignalIndex, BigInt> {
+        terms
+            .iter()
+            .map(|(signal, coefficient)| (*signal, BigInt::from(*coefficient)))
+            .collect()
+    };
+    let constraint =
+        |a: &[(SignalIndex, i32)], b: &[(SignalIndex, i32)], c: &[(SignalIndex, i32)]| {
+            Constraint::new(linear_term(a), linear_term(b), linear_term(c))
+        };
+
+    let quadratic = constraint(&[(1, 1)], &[(2, 1), (0, 3)], &[(3, 1)]);
+    let linear = constraint(&[], &[], &[(1, 1), (0, 5)]);
+    // A constant times a signal is linear, even if it comes from the product A*B
+    let scaled = constraint(&[(0, 2)], &[(1, 1)], &[]);
+    let constant = constraint(&[], &[], &[(0, 5)]);
+
+    assert_eq!(get_constraint_degree(&quadratic), 2);
+    assert_eq!(get_constraint_degree(&linear), 1);
+    assert_eq!(get_constraint_degree(&scaled), 1);
+    assert_eq!(get_constraint_degree(&constant), 0);
+    assert_eq!(get_max_constraint_degree(&[linear, quadratic]), 2);
+    assert_eq!(get_max_constraint_degree(&[]), 0);
+}
+
+#[test]
+fn test_export_ideals() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let witness = HashMap::from([(3, BigInt::one()), (4, BigInt::from(7))]);
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        ..Default::default()
+    };
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(witness),
+        HashMap::new(),
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+
+    // The generic system of the golden test, twice, so both files of the component are written
+    let pol_system = build_golden_test_pol_systems(&field).pop().unwrap();
+    let dir = std::env::temp_dir().join(format!("export-ideals-{}", std::process::id()));
+    export_ideals(
+        &[pol_system.clone(), pol_system],
+        &context.get_context_view(),
+        &dir,
+    )
+    .unwrap();
+
+    let expected =
+        "101\nx_3, x_4, u_3, u_4\nx_3 * x_4 - 7, ((x_3 - 1)*u_3 - 1) * ((x_4 - 7)*u_4 - 1)\n";
+    assert_eq!(
+        fs::read_to_string(dir.join("main.mul-0.ideal")).unwrap(),
+        expected
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("main.mul-1.ideal")).unwrap(),
+        expected
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_dump_pol_systems() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let witness = HashMap::from([(3, BigInt::one()), (4, BigInt::from(7))]);
+    let signal_name_map = HashMap::from([(3, "mul.a".to_string()), (4, "mul.b".to_string())]);
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        ..Default::default()
+    };
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(witness),
+        signal_name_map,
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+
+    // The generic system of the golden test, twice, so both are grouped under the same component
+    let pol_system = build_golden_test_pol_systems(&field).pop().unwrap();
+    let path = std::env::temp_dir().join(format!("dump-systems-{}.txt", std::process::id()));
+    dump_pol_systems(
+        &[pol_system.clone(), pol_system],
+        &context.get_context_view(),
+        &path,
+    )
+    .unwrap();
+
+    let system = indoc::indoc! {"
+        Constraints: 
+        mul.a * mul.b - 7 = 0    (constraint #2)
+        Signals to fix: [\"mul.a\", \"mul.b\"]
+        Binary signals: []
+        Domain signals: []
+        Prohibition constraint: 
+        ((mul.a - 1)*u_3 - 1) * ((mul.b - 7)*u_4 - 1) = 0
+    "};
+    let expected = format!(
+        "Component 'main.mul' (Mul()), 2 polynomial systems:\n\
+        \nPolynomial system 1 (main.mul: Mul())\n{}\
+        \nPolynomial system 2 (main.mul: Mul())\n{}\n",
+        system, system
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+
+    fs::remove_file(path).unwrap();
+
+    // Sanitized names are valid identifiers, and the legend maps them back to the original names
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(HashMap::from([(3, BigInt::one()), (4, BigInt::from(7))])),
+        HashMap::from([(3, "mul.a[0]".to_string()), (4, "b".to_string())]),
+        TreeConstraints {
+            field: field.to_string(),
+            ..Default::default()
+        },
+        Options {
+            sanitized_names: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let context = context.get_context_view();
+    let pol_system = build_golden_test_pol_systems(&field).pop().unwrap();
+
+    let mut output = Vec::new();
+    write_polynomial_system_readable(
+        &mut output,
+        &optimize_pol_system(&pol_system, &context),
+        &context,
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("# mul_a_0_ = mul.a[0]\nConstraints: \nmul_a_0_ * b - 7 = 0"));
+    assert!(output.contains("Signals to fix: [\"mul_a_0_\", \"b\"]"));
+}
+
+#[test]
+fn test_dump_cas_json() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(HashMap::from([(3, BigInt::one()), (4, BigInt::from(7))])),
+        HashMap::from([(3, "mul.a".to_string()), (4, "mul.b".to_string())]),
+        TreeConstraints {
+            field: field.to_string(),
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    let pol_system = build_golden_test_pol_systems(&field).pop().unwrap();
+    let path = std::env::temp_dir().join(format!("dump-cas-{}.json", std::process::id()));
+    dump_cas_json(&[pol_system], &context, &path).unwrap();
+
+    let dump: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(dump["prime"], "101");
+    assert_eq!(
+        dump["timeout_seconds"],
+        context.options.groebner_cocoa_timeout_seconds
+    );
+
+    let pol_system = &dump["pol_systems"][0];
+    assert_eq!(pol_system["index"], 0);
+    assert_eq!(pol_system["component_name"], "main.mul");
+    assert_eq!(pol_system["check"]["kind"], "prohibition");
+    assert_eq!(
+        pol_system["vars"],
+        serde_json::json!(["x_3", "x_4", "u_3", "u_4"])
+    );
+    assert_eq!(
+        pol_system["constraints"],
+        serde_json::json!(["x_3 * x_4 - 7"])
+    );
+    assert_eq!(
+        pol_system["check"]["prohibition_polynomial"],
+        "((x_3 - 1)*u_3 - 1) * ((x_4 - 7)*u_4 - 1)"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_external_solver() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let pol_systems = build_golden_test_pol_systems(&field);
+
+    // Runs the given solver command on the golden polynomial systems
+    let run_solver = |solver_cmd: &str| {
+        let (context, _) = InputDataContext::from_data(
+            ConstraintStorage::new(),
+            Some(HashMap::from([
+                (1, BigInt::from(5)),
+                (2, BigInt::one()),
+                (3, BigInt::one()),
+                (4, BigInt::from(7)),
+            ])),
+            HashMap::new(),
+            TreeConstraints {
+                field: field.to_string(),
+                ..Default::default()
+            },
+            Options {
+                solver_cmd: Some(solver_cmd.to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        verify_pol_systems(&pol_systems, &context.get_context_view())
+    };
+
+    // Answers every system with the given verdict, after some output outside of the protocol
+    let answer_all = |verdict: &str| {
+        format!(
+            "echo starting; grep -o '\"index\":[0-9]*' | sed 's/\"index\":/{}: /'; echo FINISHED",
+            verdict
+        )
+    };
+
+    let results = run_solver(&answer_all("OK")).unwrap();
+    assert_eq!(results.len(), pol_systems.len());
+    assert!(results.iter().all(|r| r.verdict == PolSystemVerdict::Fixed));
+
+    let results = run_solver(&answer_all("ERROR")).unwrap();
+    assert!(results
+        .iter()
+        .all(|r| r.verdict == PolSystemVerdict::ManySolutions));
+
+    // Exiting before answering every system fails the verification
+    let Err(VerifierError::CasFailure(message)) =
+        run_solver("echo 'OK: 0'; echo crashed >&2; exit 3")
+    else {
+        panic!("The solver should have failed");
+    };
+    assert!(message.contains("crashed"));
+
+    // So does answering a polynomial system that does not exist
+    assert!(matches!(
+        run_solver("cat >/dev/null; echo 'OK: 7'; echo FINISHED"),
+        Err(VerifierError::CasFailure(_))
+    ));
+
+    // Or finishing without answering every system
+    assert!(matches!(
+        run_solver("cat >/dev/null; echo 'OK: 1'; echo FINISHED"),
+        Err(VerifierError::CasFailure(_))
+    ));
+
+    // Being killed is not blamed on memory without a memory limit, unless the solver reports a
+    //  failed allocation, in which case it is restarted until every system has run out of memory
+    assert!(matches!(
+        run_solver("cat >/dev/null; kill -9 $$"),
+        Err(VerifierError::CasFailure(_))
+    ));
+    let results = run_solver("cat >/dev/null; echo std::bad_alloc >&2; kill -9 $$").unwrap();
+    assert!(results
+        .iter()
+        .all(|r| r.verdict == PolSystemVerdict::OutOfMemory));
+}
+
+#[test]
+fn test_sample_assignments() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let field = BigInt::from(101);
+    let witness = HashMap::from([(1, BigInt::from(5)), (2, BigInt::one())]);
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        ..Default::default()
+    };
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(witness),
+        HashMap::new(),
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    // The binary system of the golden test, whose bit signal 2 is sampled from {0, 1}
+    let pol_systems = vec![build_golden_test_pol_systems(&field).remove(0)];
+    let sampled_assignments =
+        sample_assignments(&pol_systems, &context, 20, &mut StdRng::seed_from_u64(0));
+
+    assert!(!sampled_assignments.is_empty());
+    for sampled_assignment in &sampled_assignments {
+        assert_eq!(sampled_assignment.pol_system_idx, 0);
+        assert!(sampled_assignment.values[&2] <= BigInt::one());
+        assert!(sampled_assignment.values[&1] < field);
+        assert!(sampled_assignment
+            .values
+            .iter()
+            .any(|(signal, value)| value != context.witness_value(*signal)));
+    }
+
+    let cocoa_script =
+        generate_sampled_assignments_cocoa_script(&pol_systems, &sampled_assignments, &context);
+    let first_values = &sampled_assignments[0].values;
+    assert!(cocoa_script.contains(&format!("x_2 - {}", first_values[&2])));
+    assert!(cocoa_script.contains("println \"OK: 0\";"));
+    assert!(cocoa_script.ends_with("println \"FINISHED\";\n"));
+}
+
+#[test]
+fn test_progress_file() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let make_context = |witness_value: u32| {
+        let tree_constraints = TreeConstraints {
+            field: field.to_string(),
+            ..Default::default()
+        };
+        let witness = HashMap::from([(3, BigInt::one()), (4, BigInt::from(witness_value))]);
+        InputDataContext::from_data(
+            ConstraintStorage::new(),
+            Some(witness),
+            HashMap::new(),
+            tree_constraints,
+            Options::default(),
+        )
+        .unwrap()
+        .0
+    };
+    let context = make_context(7);
+    let other_witness_context = make_context(8);
+
+    // The key does not depend on the order of the constraints, but does depend on the witness
+    let pol_system = build_golden_test_pol_systems(&field).pop().unwrap();
+    let mut reordered_pol_system = pol_system.clone();
+    reordered_pol_system.constraints.reverse();
+    let key = get_pol_system_key(&pol_system, &context.get_context_view());
+    assert_eq!(
+        key,
+        get_pol_system_key(&reordered_pol_system, &context.get_context_view())
+    );
+    assert_ne!(
+        key,
+        get_pol_system_key(&pol_system, &other_witness_context.get_context_view())
+    );
+
+    // The last verdict recorded for each key is the one kept, and truncated lines are ignored
+    let dir = std::env::temp_dir().join(format!("progress-file-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    append_to_progress_file(&dir, &key, PolSystemVerdict::Timeout).unwrap();
+    append_to_progress_file(&dir, &key, PolSystemVerdict::ManySolutions).unwrap();
+    append_to_progress_file(&dir, "0123", PolSystemVerdict::Fixed).unwrap();
+    fs::OpenOptions::new()
+        .append(true)
+        .open(dir.join(PROGRESS_FILE_NAME))
+        .unwrap()
+        .write_all(b"4567 TIM")
+        .unwrap();
+
+    assert_eq!(
+        read_progress_file(&dir).unwrap(),
+        HashMap::from([
+            (key, PolSystemVerdict::ManySolutions),
+            ("0123".to_string(), PolSystemVerdict::Fixed),
+        ])
+    );
+
+    remove_progress_file(&dir).unwrap();
+    assert!(read_progress_file(&dir).unwrap().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_split_pol_system_by_signal() {
+    let field = BigInt::from(101);
+    let pol_system = build_golden_test_pol_systems(&field).remove(0);
+
+    let single_signal_systems = split_pol_system_by_signal(&pol_system);
+    assert_eq!(
+        single_signal_systems
+            .iter()
+            .map(|system| system.signals_to_fix.iter().copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+        vec![vec![1], vec![2]]
+    );
+    for system in &single_signal_systems {
+        assert_eq!(system.constraints.len(), pol_system.constraints.len());
+        assert_eq!(system.component_name, pol_system.component_name);
+    }
+}
+
+#[test]
+fn test_functional_uniqueness_system() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+
+    // template IsZero() { signal input in; signal output out; signal inv;
+    //  inv <-- ...; out <== -in*inv + 1; in*out === 0; }
+    //  with out = 1, in = 2 and inv = 3
+    let mut constraint_storage = ConstraintStorage::new();
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::from([(2, BigInt::one())]),
+        HashMap::from([(3, BigInt::one())]),
+        HashMap::from([(1, BigInt::one()), (constant, BigInt::from(100))]),
+    ));
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::from([(2, BigInt::one())]),
+        HashMap::from([(1, BigInt::one())]),
+        HashMap::new(),
+    ));
+
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        no_constraints: 2,
+        template_name: "IsZero()".to_string(),
+        component_name: "main".to_string(),
+        number_inputs: 1,
+        number_outputs: 1,
+        number_signals: 3,
+        initial_signal: 1,
+        ..Default::default()
+    };
+
+    let system = build_functional_uniqueness_system(&tree_constraints, &constraint_storage);
+
+    // The input is shared, while out and inv are copied into signals 5 and 7
+    assert_eq!(system.output_pairs, vec![(1, 5)]);
+    assert_eq!(system.constraints.len(), 4);
+    assert_eq!(
+        system.constraints[2].take_cloned_signals_ordered(),
+        BTreeSet::from([2, 5, 7])
+    );
+    assert_eq!(
+        system.constraints[3].take_cloned_signals_ordered(),
+        BTreeSet::from([2, 5])
+    );
+
+    let signal_name_map = HashMap::from([
+        (1, "out".to_string()),
+        (2, "in".to_string()),
+        (3, "inv".to_string()),
+    ]);
+    let (context, _) = InputDataContext::from_data(
+        constraint_storage,
+        None,
+        signal_name_map,
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+
+    let script = generate_functional_uniqueness_cocoa_script(&system, &context.get_context_view());
+    assert!(script.contains("use R ::= F[x_1, x_2, x_3, x_5, x_7, u_1];"));
+    assert!(script.contains("((x_1 - x_5)*u_1 - 1)"));
+    assert!(script.contains("println \"OK: 0\";"));
+}
+
+#[test]
+fn test_build_monolithic_pol_system() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+    use std::path::Path;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, constraint_storage) =
+        InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    let context = context.get_context_view();
+
+    let pol_system = build_monolithic_pol_system(&context, &constraint_storage);
+
+    // The input a = 4 is replaced by its witness value in x * x === a
+    assert_eq!(pol_system.constraint_indices, vec![0, 1, 2]);
+    assert_eq!(pol_system.signals_to_fix, BTreeSet::from([1]));
+    assert!(pol_system
+        .constraints
+        .iter()
+        .all(|constraint| !constraint.take_signals().contains(&2)));
+}
+
+#[test]
+fn test_expanded_constraint_polynomial() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        None,
+        HashMap::from([(1, "x".to_string()), (2, "y".to_string())]),
+        TreeConstraints {
+            field: field.to_string(),
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    // (x + 2*y + 1) * (x - y) + 5*x + 3 = x^2 + x*y - 2*y^2 + 6*x - y + 3
+    let constraint = Constraint::new(
+        HashMap::from([(1, BigInt::one()), (2, BigInt::from(2)), (0, BigInt::one())]),
+        HashMap::from([(1, BigInt::one()), (2, BigInt::from(100))]),
+        HashMap::from([(1, BigInt::from(5)), (0, BigInt::from(3))]),
+    );
+    assert_eq!(
+        get_constraint_polynomial(&constraint, &context, SignalDisplayKind::Name),
+        "(1 + x + 2*y) * (x - y) + 3 + 5*x"
+    );
+    assert_eq!(
+        get_expanded_constraint_polynomial(&constraint, &context, SignalDisplayKind::Name),
+        "x^2 + x*y - 2*y^2 + 6*x - y + 3"
+    );
+
+    // Like terms that cancel out are removed: (x + 1) * (x - 1) + 1 = x^2
+    let constraint = Constraint::new(
+        HashMap::from([(1, BigInt::one()), (0, BigInt::one())]),
+        HashMap::from([(1, BigInt::one()), (0, BigInt::from(100))]),
+        HashMap::from([(0, BigInt::one())]),
+    );
+    assert_eq!(
+        get_expanded_constraint_polynomial(&constraint, &context, SignalDisplayKind::Name),
+        "x^2"
+    );
+}
+
+#[test]
+fn test_constant_product_constraint_polynomial() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let field = BigInt::from(101);
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        None,
+        HashMap::from([(1, "x".to_string()), (2, "y".to_string())]),
+        TreeConstraints {
+            field: field.to_string(),
+            ..Default::default()
+        },
+        Options {
+            optimization_passes: vec![],
+            ..Options::default()
+        },
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    // Constant times linear: 3 * (x + 2*y) + 5 = 3*x + 6*y + 5
+    let constant_times_linear = Constraint::new(
+        HashMap::from([(0, BigInt::from(3))]),
+        HashMap::from([(1, BigInt::one()), (2, BigInt::from(2))]),
+        HashMap::from([(0, BigInt::from(5))]),
+    );
+    assert_eq!(
+        get_constraint_polynomial(&constant_times_linear, &context, SignalDisplayKind::Name),
+        "5 + 3*x + 6*y"
+    );
+
+    // Constant times constant, where the constant terms cancel out: 2 * 3 + x - 6 = x
+    let constant_times_constant = Constraint::new(
+        HashMap::from([(0, BigInt::from(2))]),
+        HashMap::from([(0, BigInt::from(3))]),
+        HashMap::from([(1, BigInt::one()), (0, BigInt::from(95))]),
+    );
+    assert_eq!(
+        get_constraint_polynomial(&constant_times_constant, &context, SignalDisplayKind::Name),
+        "x"
+    );
+
+    // Both are linear constraints of the optimized system, even without optimization passes
+    let pol_system = PolynomialSystemFixedSignal {
+        constraints: vec![constant_times_linear, constant_times_constant],
+        constraint_indices: vec![0, 1],
+        signals_to_fix: BTreeSet::from([1, 2]),
+        template_name: "T".to_string(),
+        component_name: "main".to_string(),
+    };
+    let optimized_pol_system = optimize_pol_system(&pol_system, &context);
+    assert!(optimized_pol_system
+        .constraints
+        .iter()
+        .all(Constraint::is_linear));
+    assert_eq!(optimized_pol_system.max_degree, 1);
+}
+
+#[test]
+fn test_combine_witness_pol_systems() {
+    let pol_system = |component_name: &str, signals: &[SignalIndex]| PolynomialSystemFixedSignal {
+        constraints: signals
+            .iter()
+            .map(|signal| {
+                Constraint::new(
+                    HashMap::from([(*signal, BigInt::one())]),
+                    HashMap::from([(*signal, BigInt::one()), (0, BigInt::one())]),
+                    HashMap::new(),
+                )
+            })
+            .collect(),
+        constraint_indices: (0..signals.len()).collect(),
+        signals_to_fix: signals.iter().copied().collect(),
+        template_name: "T".to_string(),
+        component_name: component_name.to_string(),
+    };
+
+    let combined = combine_witness_pol_systems(
+        &[
+            vec![pol_system("main", &[1, 2]), pol_system("main.c", &[3])],
+            vec![pol_system("main", &[1, 2]), pol_system("main.c", &[4])],
+        ],
+        10,
+    );
+
+    // The systems of main are merged, while those of main.c fix different signals
+    assert_eq!(combined.len(), 3);
+    assert_eq!(combined[0].signals_to_fix, BTreeSet::from([1, 2, 11, 12]));
+    assert_eq!(combined[0].constraints.len(), 4);
+    assert!(combined[0].constraints[3].b().contains_key(&0));
+    assert!(combined[0].constraints[3].b().contains_key(&12));
+    assert_eq!(combined[1].signals_to_fix, BTreeSet::from([3]));
+    assert_eq!(combined[2].signals_to_fix, BTreeSet::from([14]));
+}
+
+#[test]
+fn test_dimension_check_subscript() {
+    let options = crate::cli::Options::default();
+    let tree_constraints = TreeConstraints::default();
+    let empty_path = String::new();
+    let context = InputDataContextView {
+        witness: None,
+        extra_witnesses: &[],
+        signal_name_map: &HashMap::new(),
+        signal_index_map: &crate::input_data::SignalIndexMap::new(),
+        tree_constraints: &tree_constraints,
+        field: BigInt::from(11),
+        base_path: &empty_path,
+        output_path: &empty_path,
+        svg_printer: &crate::DebugSVGPrinter::disabled(),
+        options: &options,
+        reference_components: &[],
+        verified_components: &crate::verified_components::VerifiedComponents::default(),
+    };
+    let subscript = |all_vars_to_fix: bool| {
+        get_dimension_check_subscript(
+            "use R ::= F[x_1, x_2];",
+            vec!["x_1 - x_2".to_string()],
+            "x_1 - 3",
+            all_vars_to_fix,
+            &context,
+            0,
+        )
+    };
+
+    // Other points are only conclusive if they differ in some signal to fix. If x_2 does not need
+    //  to be fixed, the ideal is saturated to find out
+    assert!(!subscript(true).contains("saturate"));
+    assert!(subscript(false).contains("S := saturate(I, W);"));
+    assert!(subscript(false).contains("Elif IsZeroDim(I) And len(QuotientBasis(radical(I))) = 1"));
+}
+
+// Verifies the given polynomial systems with CoCoA over F_101, with the witness of the golden
+//  systems and the given options, and returns their verdicts
+#[cfg(test)]
+fn get_cocoa_verdicts(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    options: crate::cli::Options,
+) -> Vec<PolSystemVerdict> {
+    use crate::input_data::InputDataContext;
+
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(HashMap::from([
+            (1, BigInt::from(5)),
+            (2, BigInt::one()),
+            (3, BigInt::one()),
+            (4, BigInt::from(7)),
+        ])),
+        HashMap::new(),
+        TreeConstraints {
+            field: "101".to_string(),
+            ..Default::default()
+        },
+        options,
+    )
+    .unwrap();
+
+    verify_pol_systems(pol_systems, &context.get_context_view())
+        .unwrap()
+        .into_iter()
+        .map(|result| result.verdict)
+        .collect()
+}
+
+#[test]
+fn test_cocoa_strategy_verdicts() {
+    use crate::cli::Options;
+
+    if which("CoCoAInterpreter").is_err() {
+        println!("CoCoA not found, skipping test");
+        return;
+    }
+
+    let field = BigInt::from(101);
+    let constant = Constraint::<usize>::constant_coefficient();
+
+    // (a - 1) * (a - 1) = 0, whose ideal is not radical but only vanishes at the witness
+    let mut pol_systems = build_golden_test_pol_systems(&field);
+    let a_minus_one = HashMap::from([(3, BigInt::one()), (constant, &field - 1)]);
+    pol_systems.push(PolynomialSystemFixedSignal {
+        constraints: vec![Constraint::new(
+            a_minus_one.clone(),
+            a_minus_one,
+            HashMap::new(),
+        )],
+        constraint_indices: vec![3],
+        signals_to_fix: BTreeSet::from([3]),
+        template_name: "Square()".to_string(),
+        component_name: "main.square".to_string(),
+    });
+
+    // Both strategies must agree on every system
+    for cocoa_strategy in [CocoaStrategy::GBasis, CocoaStrategy::Dimension] {
+        let options = Options {
+            cocoa_strategy,
+            ..Default::default()
+        };
+        assert_eq!(
+            get_cocoa_verdicts(&pol_systems, options),
+            [
+                PolSystemVerdict::ManySolutions,
+                PolSystemVerdict::ManySolutions,
+                PolSystemVerdict::Fixed
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_monomial_order_verdicts() {
+    use crate::cli::Options;
+
+    if which("CoCoAInterpreter").is_err() {
+        println!("CoCoA not found, skipping test");
+        return;
+    }
+
+    // The ordering may change how long CoCoA takes, but never its verdict
+    let pol_systems = build_golden_test_pol_systems(&BigInt::from(101));
+    for monomial_order in [
+        MonomialOrder::DegRevLex,
+        MonomialOrder::DegLex,
+        MonomialOrder::Lex,
+        MonomialOrder::Elim,
+    ] {
+        let options = Options {
+            monomial_order,
+            ..Default::default()
+        };
+        assert_eq!(
+            get_cocoa_verdicts(&pol_systems, options),
+            [
+                PolSystemVerdict::ManySolutions,
+                PolSystemVerdict::ManySolutions
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_total_timeout_watchdog() {
+    // The watchdog stops as soon as it is dropped, without waiting for the timeout. Exceeding the
+    //  timeout is not tested here, as it would kill the CoCoA processes of other tests
+    let start = Instant::now();
+    drop(TotalTimeoutWatchdog::start(Some(Duration::from_secs(60))));
+    assert!(start.elapsed() < Duration::from_secs(60));
+    assert!(!is_total_timeout_exceeded());
+}
+
+#[test]
+fn test_cocoa_script_legend() {
+    use crate::InputDataContext;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, _) =
+        InputDataContext::parse_from_files(&path, crate::cli::Options::default()).unwrap();
+    let context = context.get_context_view();
+
+    // Only the variables of the code are listed, once each, and not those mentioned in comments
+    //  nor other identifiers containing x_
+    let script = indoc::indoc! {"
+        // x_3 = 2 (x_4)
+        use R ::= F[x_3, x_2, u_3];
+        I := ideal(x_3 * x_3 - x_2, max_1 - 1, (x_3 - 2)*u_3 - 1);
+    "};
+    assert_eq!(
+        get_cocoa_script_legend(script, &context),
+        "x_2 -> a\nx_3 -> x\n"
+    );
+}
+
+#[test]
+fn test_sort_by_priority() {
+    let pol_systems: Vec<_> = ["main", "main.a[0]", "main.b", "main.a[1]", "main.c"]
+        .into_iter()
+        .map(|component_name| PolynomialSystemFixedSignal {
+            constraints: vec![],
+            constraint_indices: vec![],
+            signals_to_fix: BTreeSet::new(),
+            template_name: "T()".to_string(),
+            component_name: component_name.to_string(),
+        })
+        .collect();
+    let sort = |indices: Vec<usize>, patterns: &[&str]| {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        sort_by_priority(indices, &pol_systems, &patterns)
+    };
+
+    assert_eq!(sort(vec![0, 1, 2, 3, 4], &[]), vec![0, 1, 2, 3, 4]);
+    assert_eq!(
+        sort(vec![0, 1, 2, 3, 4], &["main.c", "main.a[*]"]),
+        vec![4, 1, 3, 0, 2]
+    );
+
+    // Only the given indices are sorted
+    assert_eq!(sort(vec![0, 2, 3], &["main.a[*]"]), vec![3, 0, 2]);
+}
+
+#[test]
+fn test_get_sanitized_signal_names() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let names = ["a[1]", "a_1_", "a.b", "a_b", "c.in[0]", "d[0].x"];
+    let (context, _) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        None,
+        names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (idx + 1, name.to_string()))
+            .collect(),
+        TreeConstraints {
+            field: "101".to_string(),
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    // Names that collide with another one once sanitized are followed by their signal index,
+    //  while the signals whose name has not changed keep it
+    let sanitized_names = get_sanitized_signal_names(&(1..=names.len()).collect(), &context);
+    assert_eq!(
+        sanitized_names.values().collect::<Vec<_>>(),
+        ["a_1__1", "a_1_", "a_b_3", "a_b", "c_in_0_", "d_0__x"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_parse_solver_verdicts() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let prefixes = [
+        ("OK: ", PolSystemVerdict::Fixed),
+        ("ERROR: ", PolSystemVerdict::ManySolutions),
+    ];
+    let parse = |status: Option<ExitStatus>, stdout: &[&str]| {
+        let output = SolverOutput {
+            status,
+            stdout: stdout.iter().map(|line| line.to_string()).collect(),
+            stderr: vec![],
+        };
+        parse_solver_verdicts(&output, "CoCoA", &prefixes, 2, PolSystemVerdict::NotChecked)
+    };
+    let exited = Some(ExitStatus::from_raw(0));
+
+    // Checks may be answered in any order, and other lines are only warnings
+    assert_eq!(
+        parse(exited, &["OK: 1", "Warning", "ERROR: 0", "FINISHED"]).unwrap(),
+        [PolSystemVerdict::ManySolutions, PolSystemVerdict::Fixed]
+    );
+
+    // Answering a check that does not exist, leaving some check unanswered or exiting before
+    //  FINISHED is an error of the solver
+    assert!(matches!(
+        parse(exited, &["OK: 0", "OK: 2", "FINISHED"]),
+        Err(VerifierError::CasFailure(_))
+    ));
+    assert!(matches!(
+        parse(exited, &["OK: 0", "FINISHED"]),
+        Err(VerifierError::CasFailure(_))
+    ));
+    assert!(matches!(
+        parse(exited, &["OK: 0", "OK: 1"]),
+        Err(VerifierError::CasFailure(_))
+    ));
+
+    // Killed because of the total timeout
+    assert_eq!(
+        parse(None, &["OK: 0"]).unwrap(),
+        [PolSystemVerdict::Fixed, PolSystemVerdict::NotChecked]
+    );
+}
@@ -0,0 +1,223 @@
+use crate::cli::Options;
+use crate::error::VerifierResult;
+use crate::input_data::{InputDataContext, SignalIndex, TreeConstraints};
+use crate::polynomial_system_fixer::get_stable_hash;
+use circom_algebra::constraint_storage::ConstraintStorage;
+use num_bigint_dig::BigInt;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// Linear terms of a constraint, with signals replaced by their canonical labels and sorted
+type CanonicalLinearTerm = Vec<(usize, BigInt)>;
+
+// Constraints of a component and all its subcomponents, up to signal renaming. Two components with
+//  the same canonical form have the same constraints once the inputs and outputs of one are mapped
+//  to the inputs and outputs of the other (in order) and the rest of signals are renamed, so the
+//  outputs of both are fixed by their inputs in the same way.
+#[derive(Debug, PartialEq)]
+pub struct CanonicalComponent {
+    field: BigInt,
+    number_outputs: usize,
+    number_inputs: usize,
+    constraints: Vec<[CanonicalLinearTerm; 3]>,
+}
+
+impl CanonicalComponent {
+    pub fn new(
+        tree_constraints: &TreeConstraints,
+        constraint_storage: &ConstraintStorage,
+        field: &BigInt,
+    ) -> CanonicalComponent {
+        // The constant signal is kept, and the outputs and inputs (laid out in this order from the
+        //  first signal of the component) are labeled by their position. The rest of signals are
+        //  labeled in order of appearance.
+        let constant = 0;
+        let number_public_signals =
+            tree_constraints.number_outputs + tree_constraints.number_inputs;
+        let mut labels: HashMap<SignalIndex, usize> = (0..number_public_signals)
+            .map(|position| (tree_constraints.initial_signal + position, position + 1))
+            .chain([(constant, 0)])
+            .collect();
+
+        let mut constraints = Vec::new();
+        for constraint_idx in get_subtree_constraint_indices(tree_constraints) {
+            let constraint = constraint_storage.read_constraint(constraint_idx).unwrap();
+
+            // Signals are labeled in increasing index order, which is preserved between instances
+            //  of the same template
+            for signal in constraint.take_cloned_signals_ordered() {
+                let next_label = labels.len();
+                labels.entry(signal).or_insert(next_label);
+            }
+
+            let canonical_linear_term = |linear_term: &HashMap<SignalIndex, BigInt>| {
+                let mut terms: CanonicalLinearTerm = linear_term
+                    .iter()
+                    .map(|(signal, coefficient)| (labels[signal], coefficient.clone()))
+                    .collect();
+                terms.sort();
+                terms
+            };
+
+            // A*B is the same constraint as B*A
+            let (a, b) = (
+                canonical_linear_term(constraint.a()),
+                canonical_linear_term(constraint.b()),
+            );
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
+            constraints.push([a, b, canonical_linear_term(constraint.c())]);
+        }
+
+        CanonicalComponent {
+            field: field.clone(),
+            number_outputs: tree_constraints.number_outputs,
+            number_inputs: tree_constraints.number_inputs,
+            constraints,
+        }
+    }
+
+    // Returns a hash of the canonical form, which is the same for components with the same
+    //  constraints up to signal renaming, and changes whenever any of their constraints changes
+    pub fn get_hash(&self) -> String {
+        get_stable_hash(&format!("{:?}", self))
+    }
+}
+
+// Returns the indices of the constraints of a component and all its subcomponents, in preorder.
+//  The ranges of a component may contain the ranges of its subcomponents, so each index is only
+//  returned once.
+fn get_subtree_constraint_indices(tree_constraints: &TreeConstraints) -> Vec<usize> {
+    fn collect(
+        tree_constraints: &TreeConstraints,
+        seen: &mut HashSet<usize>,
+        indices: &mut Vec<usize>,
+    ) {
+        let constraints_range = tree_constraints.initial_constraint
+            ..(tree_constraints.initial_constraint + tree_constraints.no_constraints);
+        indices.extend(constraints_range.filter(|idx| seen.insert(*idx)));
+
+        for subcomponent in &tree_constraints.subcomponents {
+            collect(subcomponent, seen, indices);
+        }
+    }
+
+    let mut indices = Vec::new();
+    collect(tree_constraints, &mut HashSet::new(), &mut indices);
+    indices
+}
+
+// Main component of a circuit verified in a previous run. Components of the circuit being verified
+//  that are structurally identical to it are considered safe without being verified again
+pub struct ReferenceComponent {
+    // Artifacts folder the reference has been loaded from
+    pub path: PathBuf,
+    pub template_name: String,
+    pub canonical_component: CanonicalComponent,
+}
+
+impl ReferenceComponent {
+    // Loads the main component of the circuit whose artifacts are in folder_base_path. The witness
+    //  is not needed
+    pub fn parse_from_files(
+        folder_base_path: &Path,
+        strict: bool,
+    ) -> VerifierResult<ReferenceComponent> {
+        let options = Options {
+            strict,
+            ..Default::default()
+        };
+        let (context, constraint_storage) =
+            InputDataContext::parse_from_files(folder_base_path, options)?;
+
+        Ok(ReferenceComponent {
+            path: folder_base_path.to_path_buf(),
+            template_name: context.tree_constraints.template_name.clone(),
+            canonical_component: CanonicalComponent::new(
+                &context.tree_constraints,
+                &constraint_storage,
+                &context.field,
+            ),
+        })
+    }
+}
+
+// Loads every reference component given in the options
+pub fn load_reference_components(options: &Options) -> VerifierResult<Vec<ReferenceComponent>> {
+    options
+        .reference_paths
+        .iter()
+        .map(|path| ReferenceComponent::parse_from_files(path, options.strict))
+        .collect()
+}
+
+#[test]
+fn test_canonical_component() {
+    use circom_algebra::algebra::Constraint;
+    use num_traits::One;
+
+    let field = BigInt::from(101);
+    let mut constraint_storage = ConstraintStorage::new();
+    let constant = 0;
+
+    // out === a * b, intermediate === a + 1, at signals [initial, initial + 4)
+    let mut add_component = |initial_signal: usize, swap_factors: bool| -> TreeConstraints {
+        let (out, a, b, intermediate) = (
+            initial_signal,
+            initial_signal + 1,
+            initial_signal + 2,
+            initial_signal + 3,
+        );
+        let (x, y) = if swap_factors { (b, a) } else { (a, b) };
+
+        let initial_constraint = constraint_storage.add_constraint(Constraint::new(
+            HashMap::from([(x, BigInt::one())]),
+            HashMap::from([(y, BigInt::one())]),
+            HashMap::from([(out, field.clone() - 1)]),
+        ));
+        constraint_storage.add_constraint(Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([
+                (intermediate, BigInt::one()),
+                (a, field.clone() - 1),
+                (constant, field.clone() - 1),
+            ]),
+        ));
+
+        TreeConstraints {
+            initial_signal,
+            number_outputs: 1,
+            number_inputs: 2,
+            number_signals: 4,
+            initial_constraint,
+            no_constraints: 2,
+            ..Default::default()
+        }
+    };
+
+    let first = add_component(1, false);
+    let translated = add_component(10, true);
+    let mut different_io = add_component(20, false);
+    different_io.number_inputs = 3;
+
+    let canonical =
+        |tree_constraints| CanonicalComponent::new(tree_constraints, &constraint_storage, &field);
+
+    // Instances of the same template only differ in their signal indices
+    assert_eq!(canonical(&first), canonical(&translated));
+    assert_ne!(canonical(&first), canonical(&different_io));
+    assert_ne!(
+        canonical(&first),
+        CanonicalComponent::new(&first, &constraint_storage, &BigInt::from(103))
+    );
+
+    // So are their hashes
+    assert_eq!(
+        canonical(&first).get_hash(),
+        canonical(&translated).get_hash()
+    );
+    assert_ne!(
+        canonical(&first).get_hash(),
+        canonical(&different_io).get_hash()
+    );
+}
@@ -0,0 +1,699 @@
+use crate::error::VerifierResult;
+use crate::polynomial_system_fixer::{
+    get_max_constraint_degree, get_pol_system_stats, PolSystemResult, PolSystemVerdict,
+};
+use crate::verifier::SubComponentVerificationResultKind::{
+    Exception, ModuleCachedSafe, ModuleConditionallySafe, ModuleEquivalentToReference,
+    ModuleTrusted, ModuleUnsafe,
+};
+use crate::verifier::{PolynomialSystemFixedSignal, SubComponentVerificationResult};
+use crate::InputDataContextView;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+// A polynomial system is considered to have regressed in timing if it now takes more than
+//  TIMING_REGRESSION_FACTOR times as long as in the baseline, and at least
+//  TIMING_REGRESSION_MIN_SECONDS more. The second condition filters out noise in fast systems.
+const TIMING_REGRESSION_FACTOR: f64 = 1.5;
+const TIMING_REGRESSION_MIN_SECONDS: f64 = 0.5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Safe,
+    Trusted,
+    Unsafe,
+    Exception,
+    ManySolutions,
+    Timeout,
+    OutOfMemory,
+    TooLarge,
+    TooHighDegree,
+
+    // The component is safe by itself, but some of its subcomponents are unsafe, so it is not
+    UnsafeSubcomponent,
+
+    // The component is safe as long as its subcomponents of trusted templates are
+    SafeIfTrusted,
+
+    // The component could not be fully checked, for example because verification was aborted
+    //  after finding an unsafe component elsewhere
+    NotChecked,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        !matches!(
+            self,
+            Verdict::Safe | Verdict::Trusted | Verdict::SafeIfTrusted
+        )
+    }
+}
+
+impl From<PolSystemVerdict> for Verdict {
+    fn from(verdict: PolSystemVerdict) -> Self {
+        match verdict {
+            PolSystemVerdict::Fixed => Verdict::Safe,
+            PolSystemVerdict::ManySolutions => Verdict::ManySolutions,
+            PolSystemVerdict::Timeout => Verdict::Timeout,
+            PolSystemVerdict::OutOfMemory => Verdict::OutOfMemory,
+            PolSystemVerdict::TooLarge => Verdict::TooLarge,
+            PolSystemVerdict::TooHighDegree => Verdict::TooHighDegree,
+            PolSystemVerdict::NotChecked => Verdict::NotChecked,
+        }
+    }
+}
+
+// Verdict of a component, not taking its subcomponents into account. Components are keyed by
+//  their fully-qualified name (for example, "main.sub"), which is stable across runs as long as
+//  the component hierarchy of the circuit does not change.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentReport {
+    pub component_name: String,
+    pub verdict: Verdict,
+
+    // Inputs of the component that none of its outputs depends on, if they have been requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dead_inputs: Vec<String>,
+}
+
+// Polynomial systems are keyed by the name of their component and their index among the
+//  polynomial systems of that component.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolSystemReport {
+    pub component_name: String,
+    pub template_name: String,
+    pub index_in_component: usize,
+    pub verdict: Verdict,
+    pub seconds: Option<f64>,
+
+    // Highest total degree of the constraints of the polynomial system
+    #[serde(default)]
+    pub max_degree: usize,
+
+    // Size of the polynomial system as it is sent to CoCoA
+    #[serde(default)]
+    pub num_constraints: usize,
+    #[serde(default)]
+    pub num_signals: usize,
+    #[serde(default)]
+    pub num_prohibition_vars: u32,
+}
+
+// Number of polynomial systems with each verdict, out of all the polynomial systems of a run
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolSystemSummary {
+    pub total: usize,
+    pub one_solution: usize,
+    pub many_solutions: usize,
+    pub timeout: usize,
+    pub too_large: usize,
+    #[serde(default)]
+    pub too_high_degree: usize,
+    pub out_of_memory: usize,
+    pub not_checked: usize,
+
+    // Polynomial system CoCoA has spent the most time on, if it has been run on any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowest: Option<PolSystemReport>,
+
+    // Highest total degree of a constraint, out of all the polynomial systems
+    #[serde(default)]
+    pub max_degree: usize,
+}
+
+impl PolSystemSummary {
+    pub fn new(pol_systems: &[PolSystemReport]) -> PolSystemSummary {
+        let mut summary = PolSystemSummary {
+            total: pol_systems.len(),
+            ..Default::default()
+        };
+
+        for pol_system in pol_systems {
+            let count = match pol_system.verdict {
+                Verdict::Safe => &mut summary.one_solution,
+                Verdict::ManySolutions => &mut summary.many_solutions,
+                Verdict::Timeout => &mut summary.timeout,
+                Verdict::TooLarge => &mut summary.too_large,
+                Verdict::TooHighDegree => &mut summary.too_high_degree,
+                Verdict::OutOfMemory => &mut summary.out_of_memory,
+                _ => &mut summary.not_checked,
+            };
+            *count += 1;
+        }
+
+        summary.slowest = pol_systems
+            .iter()
+            .filter(|s| s.seconds.is_some())
+            .max_by(|a, b| a.seconds.partial_cmp(&b.seconds).unwrap())
+            .cloned();
+
+        summary.max_degree = pol_systems.iter().map(|s| s.max_degree).max().unwrap_or(0);
+
+        summary
+    }
+
+    pub fn print(&self) {
+        println!("\n{}", "Polynomial systems summary:".blue());
+
+        let percentage = |count: usize| 100.0 * count as f64 / self.total.max(1) as f64;
+        let rows = [
+            ("One solution (OK)", self.one_solution),
+            ("Many solutions", self.many_solutions),
+            ("Timed out", self.timeout),
+            ("Too large (skipped)", self.too_large),
+            ("Degree too high", self.too_high_degree),
+            ("Out of memory", self.out_of_memory),
+            ("Not checked", self.not_checked),
+        ];
+
+        for (label, count) in rows {
+            // Verdicts that only appear in unusual runs are left out when there are none
+            if count == 0 && matches!(label, "Degree too high" | "Out of memory" | "Not checked") {
+                continue;
+            }
+            println!("  {:<20} {:>6} {:>6.1}%", label, count, percentage(count));
+        }
+        println!("  {:<20} {:>6} {:>6.1}%", "Total", self.total, 100.0);
+
+        if let Some(slowest) = &self.slowest {
+            println!(
+                "Slowest polynomial system: {} of '{}' ({}), {:.2}s",
+                slowest.index_in_component + 1,
+                slowest.component_name,
+                slowest.template_name,
+                slowest.seconds.unwrap_or_default()
+            );
+        }
+        println!("Highest constraint degree: {}", self.max_degree);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub safe: bool,
+    pub components: Vec<ComponentReport>,
+    pub pol_systems: Vec<PolSystemReport>,
+
+    // Counts of the verdicts of all polynomial systems. Reports that only contain failures still
+    //  summarize every polynomial system
+    #[serde(default)]
+    pub summary: PolSystemSummary,
+
+    // If present, the report only contains failing components and polynomial systems, and this is
+    //  the number of components that passed and have been left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_omitted_components: Option<usize>,
+
+    // True if the total timeout has been exceeded, so the polynomial systems that were not checked
+    //  by then, and their components, are reported as not checked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+}
+
+impl VerificationReport {
+    // Builds the report of a verification run. pol_system_results contains the result of each
+    //  polynomial system in pol_systems, and is empty if CoCoA has not been run.
+    pub fn new(
+        verification_result: &SubComponentVerificationResult,
+        pol_systems: &[PolynomialSystemFixedSignal],
+        pol_system_results: &[PolSystemResult],
+        safe: bool,
+        context: &InputDataContextView,
+    ) -> VerificationReport {
+        let pol_system_reports = get_pol_system_reports(pol_systems, pol_system_results, context);
+
+        let mut components = Vec::new();
+        verification_result.apply(&mut |res| {
+            let verdict = match &res.kind {
+                ModuleUnsafe(_) => Verdict::Unsafe,
+                Exception(_) => Verdict::Exception,
+                ModuleTrusted(_) => Verdict::Trusted,
+                ModuleEquivalentToReference(_) | ModuleCachedSafe => Verdict::Safe,
+                ModuleConditionallySafe(safety_conditions)
+                    if safety_conditions.skipped_by_filter =>
+                {
+                    Verdict::NotChecked
+                }
+                ModuleConditionallySafe(safety_conditions) => {
+                    // The component is as safe as the worst of its own polynomial systems, and
+                    //  only safe if its subcomponents are
+                    pol_system_reports
+                        .iter()
+                        .filter(|r| r.component_name == res.subcomponent_name)
+                        .map(|r| r.verdict)
+                        .find(|v| v.is_failure())
+                        .unwrap_or(if !safety_conditions.unsafe_subcomponents.is_empty() {
+                            Verdict::UnsafeSubcomponent
+                        } else if !safety_conditions.trusted_subcomponents.is_empty() {
+                            Verdict::SafeIfTrusted
+                        } else {
+                            Verdict::Safe
+                        })
+                }
+            };
+
+            let dead_inputs = match &res.kind {
+                ModuleConditionallySafe(safety_conditions) => safety_conditions.dead_inputs.clone(),
+                _ => vec![],
+            };
+
+            components.push(ComponentReport {
+                component_name: res.subcomponent_name.clone(),
+                verdict,
+                dead_inputs,
+            });
+        });
+
+        VerificationReport {
+            safe,
+            components,
+            summary: PolSystemSummary::new(&pol_system_reports),
+            pol_systems: pol_system_reports,
+            num_omitted_components: None,
+            timed_out: false,
+        }
+    }
+
+    // Returns a copy of this report without the components and polynomial systems that passed
+    pub fn only_failures(&self) -> VerificationReport {
+        let components: Vec<_> = self
+            .components
+            .iter()
+            .filter(|c| c.verdict.is_failure())
+            .cloned()
+            .collect();
+
+        VerificationReport {
+            safe: self.safe,
+            num_omitted_components: Some(
+                self.num_omitted_components.unwrap_or_default() + self.components.len()
+                    - components.len(),
+            ),
+            components,
+            pol_systems: self
+                .pol_systems
+                .iter()
+                .filter(|s| s.verdict.is_failure())
+                .cloned()
+                .collect(),
+            summary: self.summary.clone(),
+            timed_out: self.timed_out,
+        }
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> VerifierResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read_from_file(path: &Path) -> VerifierResult<VerificationReport> {
+        let f = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(f))?)
+    }
+}
+
+// Returns the report of each polynomial system in pol_systems. pol_system_results contains the
+//  result of each one, and is empty if CoCoA has not been run.
+pub fn get_pol_system_reports(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    pol_system_results: &[PolSystemResult],
+    context: &InputDataContextView,
+) -> Vec<PolSystemReport> {
+    let mut pol_system_reports = Vec::with_capacity(pol_systems.len());
+    let mut num_systems_per_component = HashMap::<&str, usize>::new();
+
+    for (idx, pol_system) in pol_systems.iter().enumerate() {
+        let index_in_component = num_systems_per_component
+            .entry(pol_system.component_name.as_str())
+            .or_default();
+
+        let (verdict, seconds) = match pol_system_results.get(idx) {
+            Some(result) => (
+                result.verdict.into(),
+                result.duration.map(|d| d.as_secs_f64()),
+            ),
+            None => (Verdict::NotChecked, None),
+        };
+
+        let stats = get_pol_system_stats(pol_system, context);
+        pol_system_reports.push(PolSystemReport {
+            component_name: pol_system.component_name.clone(),
+            template_name: pol_system.template_name.clone(),
+            index_in_component: *index_in_component,
+            verdict,
+            seconds,
+            max_degree: get_max_constraint_degree(&pol_system.constraints),
+            num_constraints: stats.num_constraints,
+            num_signals: stats.num_signals,
+            num_prohibition_vars: stats.num_prohibition_vars,
+        });
+
+        *index_in_component += 1;
+    }
+
+    pol_system_reports
+}
+
+// Changes between a baseline verification report and the report of the current run
+#[derive(Default, Debug)]
+pub struct ReportDiff {
+    // Components that passed in the baseline and fail now, with their old and new verdicts
+    pub newly_failing: Vec<(String, Verdict, Verdict)>,
+
+    // Components that failed in the baseline and pass now, with their old and new verdicts
+    pub newly_fixed: Vec<(String, Verdict, Verdict)>,
+
+    // Components that fail in both reports, but for a different reason
+    pub changed_failure: Vec<(String, Verdict, Verdict)>,
+
+    pub added_components: Vec<String>,
+    pub removed_components: Vec<String>,
+
+    // Polynomial systems that take noticeably longer than in the baseline, given as
+    //  (component name, index in component, baseline seconds, current seconds)
+    pub timing_regressions: Vec<(String, usize, f64, f64)>,
+}
+
+impl ReportDiff {
+    pub fn new(baseline: &VerificationReport, current: &VerificationReport) -> ReportDiff {
+        let mut diff = ReportDiff::default();
+
+        let baseline_components: BTreeMap<_, _> = baseline
+            .components
+            .iter()
+            .map(|c| (c.component_name.as_str(), c.verdict))
+            .collect();
+        let current_components: BTreeMap<_, _> = current
+            .components
+            .iter()
+            .map(|c| (c.component_name.as_str(), c.verdict))
+            .collect();
+
+        // Reports that only contain failures leave out passing components, so a component missing
+        //  from one of them is considered to have passed instead of being added or removed
+        for (name, &verdict) in &current_components {
+            match baseline_components.get(name) {
+                Some(&old_verdict) => diff.add_verdict_change(name, old_verdict, verdict),
+                None if baseline.num_omitted_components.is_some() => {
+                    diff.add_verdict_change(name, Verdict::Safe, verdict)
+                }
+                None => diff.added_components.push(name.to_string()),
+            }
+        }
+
+        for (name, &old_verdict) in &baseline_components {
+            if !current_components.contains_key(name) {
+                if current.num_omitted_components.is_some() {
+                    diff.add_verdict_change(name, old_verdict, Verdict::Safe);
+                } else {
+                    diff.removed_components.push(name.to_string());
+                }
+            }
+        }
+
+        let baseline_timings: HashMap<_, _> = baseline
+            .pol_systems
+            .iter()
+            .filter_map(|s| {
+                s.seconds
+                    .map(|t| ((s.component_name.as_str(), s.index_in_component), t))
+            })
+            .collect();
+
+        for pol_system in &current.pol_systems {
+            let key = (
+                pol_system.component_name.as_str(),
+                pol_system.index_in_component,
+            );
+            if let (Some(&old), Some(new)) = (baseline_timings.get(&key), pol_system.seconds) {
+                if new > old * TIMING_REGRESSION_FACTOR
+                    && new - old >= TIMING_REGRESSION_MIN_SECONDS
+                {
+                    diff.timing_regressions.push((
+                        pol_system.component_name.clone(),
+                        pol_system.index_in_component,
+                        old,
+                        new,
+                    ));
+                }
+            }
+        }
+
+        diff
+    }
+
+    fn add_verdict_change(&mut self, name: &str, old_verdict: Verdict, verdict: Verdict) {
+        if old_verdict == verdict {
+            return;
+        }
+
+        let change = (name.to_string(), old_verdict, verdict);
+        match (old_verdict.is_failure(), verdict.is_failure()) {
+            (false, true) => self.newly_failing.push(change),
+            (true, false) => self.newly_fixed.push(change),
+            (true, true) => self.changed_failure.push(change),
+            // Safe <-> Trusted changes are not interesting
+            (false, false) => {}
+        }
+    }
+
+    // Returns true if a component has started failing or a polynomial system has become slower
+    pub fn has_regressions(&self) -> bool {
+        !self.newly_failing.is_empty() || !self.timing_regressions.is_empty()
+    }
+
+    pub fn print(&self) {
+        println!("\n{}", "Changes with respect to baseline report:".blue());
+
+        if self.newly_failing.is_empty()
+            && self.newly_fixed.is_empty()
+            && self.changed_failure.is_empty()
+            && self.added_components.is_empty()
+            && self.removed_components.is_empty()
+            && self.timing_regressions.is_empty()
+        {
+            println!("{}", "No changes".green());
+            return;
+        }
+
+        for (name, old, new) in &self.newly_failing {
+            println!(
+                "{}",
+                format!("[Newly failing] '{}': {:?} -> {:?}", name, old, new).red()
+            );
+        }
+
+        for (name, old, new) in &self.newly_fixed {
+            println!(
+                "{}",
+                format!("[Newly fixed] '{}': {:?} -> {:?}", name, old, new).green()
+            );
+        }
+
+        for (name, old, new) in &self.changed_failure {
+            println!(
+                "{}",
+                format!("[Changed failure] '{}': {:?} -> {:?}", name, old, new).yellow()
+            );
+        }
+
+        for name in &self.added_components {
+            println!("[New component] '{}'", name);
+        }
+
+        for name in &self.removed_components {
+            println!("[Removed component] '{}'", name);
+        }
+
+        for (name, idx, old, new) in &self.timing_regressions {
+            println!(
+                "{}",
+                format!(
+                    "[Slower] Polynomial system {} of '{}': {:.2}s -> {:.2}s",
+                    idx + 1,
+                    name,
+                    old,
+                    new
+                )
+                .yellow()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+fn build_test_report(
+    components: &[(&str, Verdict)],
+    timings: &[(&str, f64)],
+) -> VerificationReport {
+    VerificationReport {
+        safe: components.iter().all(|(_, v)| !v.is_failure()),
+        components: components
+            .iter()
+            .map(|(name, verdict)| ComponentReport {
+                component_name: name.to_string(),
+                verdict: *verdict,
+                dead_inputs: vec![],
+            })
+            .collect(),
+        pol_systems: timings
+            .iter()
+            .map(|(name, seconds)| PolSystemReport {
+                component_name: name.to_string(),
+                template_name: "T".to_string(),
+                index_in_component: 0,
+                verdict: Verdict::Safe,
+                seconds: Some(*seconds),
+                max_degree: 2,
+                num_constraints: 3,
+                num_signals: 4,
+                num_prohibition_vars: 2,
+            })
+            .collect(),
+        summary: Default::default(),
+        num_omitted_components: None,
+        timed_out: false,
+    }
+}
+
+#[test]
+fn test_report_diff() {
+    let baseline = build_test_report(
+        &[
+            ("main", Verdict::Safe),
+            ("main.a", Verdict::Safe),
+            ("main.b", Verdict::Unsafe),
+            ("main.c", Verdict::Timeout),
+            ("main.old", Verdict::Safe),
+        ],
+        &[("main.a", 1.0), ("main.c", 0.1)],
+    );
+    let current = build_test_report(
+        &[
+            ("main", Verdict::Safe),
+            ("main.a", Verdict::Timeout),
+            ("main.b", Verdict::Safe),
+            ("main.c", Verdict::ManySolutions),
+            ("main.new", Verdict::Safe),
+        ],
+        &[("main.a", 5.0), ("main.c", 0.3)],
+    );
+
+    let diff = ReportDiff::new(&baseline, &current);
+    assert_eq!(
+        diff.newly_failing,
+        vec![("main.a".to_string(), Verdict::Safe, Verdict::Timeout)]
+    );
+    assert_eq!(
+        diff.newly_fixed,
+        vec![("main.b".to_string(), Verdict::Unsafe, Verdict::Safe)]
+    );
+    assert_eq!(
+        diff.changed_failure,
+        vec![(
+            "main.c".to_string(),
+            Verdict::Timeout,
+            Verdict::ManySolutions
+        )]
+    );
+    assert_eq!(diff.added_components, vec!["main.new".to_string()]);
+    assert_eq!(diff.removed_components, vec!["main.old".to_string()]);
+
+    // main.c is 3 times slower, but only by 0.2 seconds
+    assert_eq!(
+        diff.timing_regressions,
+        vec![("main.a".to_string(), 0, 1.0, 5.0)]
+    );
+    assert!(diff.has_regressions());
+}
+
+#[test]
+fn test_report_diff_only_failures() {
+    let baseline = build_test_report(
+        &[
+            ("main", Verdict::Safe),
+            ("main.a", Verdict::Safe),
+            ("main.b", Verdict::Unsafe),
+        ],
+        &[],
+    )
+    .only_failures();
+    assert_eq!(baseline.num_omitted_components, Some(2));
+    assert_eq!(baseline.components.len(), 1);
+
+    let current = build_test_report(
+        &[
+            ("main", Verdict::Safe),
+            ("main.a", Verdict::Timeout),
+            ("main.b", Verdict::Safe),
+        ],
+        &[],
+    );
+
+    // Components left out of the baseline are neither added nor removed, but passed
+    let diff = ReportDiff::new(&baseline, &current);
+    assert_eq!(
+        diff.newly_failing,
+        vec![("main.a".to_string(), Verdict::Safe, Verdict::Timeout)]
+    );
+    assert_eq!(
+        diff.newly_fixed,
+        vec![("main.b".to_string(), Verdict::Unsafe, Verdict::Safe)]
+    );
+    assert!(diff.added_components.is_empty());
+    assert!(diff.removed_components.is_empty());
+
+    // Leaving out passing components does not change anything
+    let diff = ReportDiff::new(&current, &current.only_failures());
+    assert!(diff.newly_failing.is_empty() && diff.newly_fixed.is_empty());
+    assert!(diff.removed_components.is_empty());
+}
+
+#[test]
+fn test_pol_system_summary() {
+    let pol_system = |verdict, seconds| PolSystemReport {
+        component_name: "main".to_string(),
+        template_name: "T".to_string(),
+        index_in_component: 0,
+        verdict,
+        seconds,
+        max_degree: 2,
+        num_constraints: 3,
+        num_signals: 4,
+        num_prohibition_vars: 2,
+    };
+    let pol_systems = [
+        pol_system(Verdict::Safe, Some(1.0)),
+        pol_system(Verdict::Safe, Some(0.5)),
+        pol_system(Verdict::ManySolutions, Some(2.0)),
+        pol_system(Verdict::Timeout, Some(5.0)),
+        pol_system(Verdict::TooLarge, None),
+    ];
+
+    let summary = PolSystemSummary::new(&pol_systems);
+    assert_eq!(
+        summary,
+        PolSystemSummary {
+            total: 5,
+            one_solution: 2,
+            many_solutions: 1,
+            timeout: 1,
+            too_large: 1,
+            too_high_degree: 0,
+            out_of_memory: 0,
+            not_checked: 0,
+            slowest: Some(pol_system(Verdict::Timeout, Some(5.0))),
+            max_degree: 2,
+        }
+    );
+
+    // Without timings there is no slowest polynomial system
+    assert_eq!(PolSystemSummary::new(&pol_systems[4..]).slowest, None);
+}
@@ -0,0 +1,447 @@
+use crate::error::VerifierResult;
+use crate::polynomial_system_fixer::{PolSystemResult, PolSystemVerdict};
+use crate::verifier::ModuleUnsafeReason::{
+    UnconstrainedOutputs, UnfixedOutputsAfterPropagation, UnfixedSignalsOfLinearSystem,
+};
+use crate::verifier::SubComponentVerificationResultKind::{Exception, ModuleUnsafe};
+use crate::verifier::VerificationException::{
+    NoUnsafeConstraintConnectedComponentWithoutCycles, PropagationIterationLimitExceeded,
+};
+use crate::verifier::{PolynomialSystemFixedSignal, SubComponentVerificationResult};
+use crate::InputDataContextView;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+// Id and description of each rule a result can break
+const SARIF_RULES: [(&str, &str); 7] = [
+    (
+        "unfixed-output",
+        "Outputs of a component are not fixed by its inputs",
+    ),
+    (
+        "unfixed-linear-signals",
+        "Signals of a component are not determined by its linear constraints",
+    ),
+    (
+        "unconstrained-output",
+        "Outputs of a component do not appear in any constraint",
+    ),
+    (
+        "cyclic-dependency",
+        "Cyclic dependencies between '===' constraints prevent deciding whether a component is safe",
+    ),
+    (
+        "propagation-limit-exceeded",
+        "The propagation of the fixed signals of a component did not converge",
+    ),
+    (
+        "many-solutions",
+        "A polynomial system of a component may have solutions other than the witness",
+    ),
+    (
+        "unverified-pol-system",
+        "A polynomial system of a component could not be verified",
+    ),
+];
+
+// Minimal SARIF 2.1.0 log, with a single run of the verifier
+#[derive(Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+pub struct SarifDriver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub rules: Vec<SarifRule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: &'static str,
+    pub short_description: SarifMessage,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: &'static str,
+    pub rule_index: usize,
+    pub level: &'static str,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+}
+
+// Location of a result: the component it has been found in, and the source location of its
+//  constraints if the tree constraints contain it
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub physical_location: Option<SarifPhysicalLocation>,
+    pub logical_locations: Vec<SarifLogicalLocation>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    pub region: SarifRegion,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLogicalLocation {
+    pub name: String,
+    pub fully_qualified_name: String,
+    pub kind: &'static str,
+}
+
+impl SarifLog {
+    // Builds the log of a verification run, with a result for each unsafe component, each
+    //  exception and each polynomial system that has not been fixed. pol_system_results contains
+    //  the result of each polynomial system in pol_systems, and is empty if CoCoA has not been run.
+    pub fn new(
+        verification_result: &SubComponentVerificationResult,
+        pol_systems: &[PolynomialSystemFixedSignal],
+        pol_system_results: &[PolSystemResult],
+        context: &InputDataContextView,
+    ) -> SarifLog {
+        let mut results = Vec::new();
+
+        verification_result.apply(&mut |res| {
+            let Some(message) = res.get_error_string() else {
+                return;
+            };
+
+            let (rule_id, level) = match &res.kind {
+                ModuleUnsafe(UnfixedOutputsAfterPropagation(_)) => ("unfixed-output", "error"),
+                ModuleUnsafe(UnfixedSignalsOfLinearSystem(_)) => {
+                    ("unfixed-linear-signals", "error")
+                }
+                ModuleUnsafe(UnconstrainedOutputs(_)) => ("unconstrained-output", "error"),
+                Exception(exception) => {
+                    let rule_id = match exception {
+                        NoUnsafeConstraintConnectedComponentWithoutCycles => "cyclic-dependency",
+                        PropagationIterationLimitExceeded { .. } => "propagation-limit-exceeded",
+                    };
+                    let level = if context.options.exceptions_as_warnings {
+                        "warning"
+                    } else {
+                        "error"
+                    };
+                    (rule_id, level)
+                }
+                _ => return,
+            };
+
+            results.push(get_sarif_result(
+                rule_id,
+                level,
+                message,
+                &res.subcomponent_name,
+                context,
+            ));
+        });
+
+        let mut num_systems_per_component = HashMap::<&str, usize>::new();
+        for (pol_system, result) in pol_systems.iter().zip(pol_system_results) {
+            let index_in_component = num_systems_per_component
+                .entry(pol_system.component_name.as_str())
+                .or_default();
+            *index_in_component += 1;
+
+            let (rule_id, level, problem) = match result.verdict {
+                PolSystemVerdict::Fixed => continue,
+                PolSystemVerdict::ManySolutions => {
+                    ("many-solutions", "warning", "possibly has many solutions")
+                }
+                PolSystemVerdict::Timeout => ("unverified-pol-system", "note", "has timed out"),
+                PolSystemVerdict::OutOfMemory => {
+                    ("unverified-pol-system", "note", "has run out of memory")
+                }
+                PolSystemVerdict::TooLarge => {
+                    ("unverified-pol-system", "note", "has too many signals")
+                }
+                PolSystemVerdict::TooHighDegree => (
+                    "unverified-pol-system",
+                    "note",
+                    "has constraints of too high degree",
+                ),
+                PolSystemVerdict::NotChecked => {
+                    ("unverified-pol-system", "note", "has not been checked")
+                }
+            };
+
+            let message = format!(
+                "Polynomial system {} of component '{}' ({}) {}, so signals {} may not be fixed",
+                index_in_component,
+                pol_system.component_name,
+                pol_system.template_name,
+                problem,
+                pol_system
+                    .signals_to_fix
+                    .iter()
+                    .map(|signal| format!("'{}'", context.signal_name(*signal)))
+                    .join(", ")
+            );
+            results.push(get_sarif_result(
+                rule_id,
+                level,
+                message,
+                &pol_system.component_name,
+                context,
+            ));
+        }
+
+        SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        rules: SARIF_RULES
+                            .iter()
+                            .map(|(id, description)| SarifRule {
+                                id,
+                                short_description: SarifMessage {
+                                    text: description.to_string(),
+                                },
+                            })
+                            .collect(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> VerifierResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn get_sarif_result(
+    rule_id: &'static str,
+    level: &'static str,
+    message: String,
+    component_name: &str,
+    context: &InputDataContextView,
+) -> SarifResult {
+    // The source location of a component is the one of its first constraint that has one
+    let physical_location = context
+        .component_by_name(component_name)
+        .ok()
+        .and_then(|tree_constraints| {
+            tree_constraints
+                .constraint_origins
+                .iter()
+                .min_by_key(|(constraint, _)| **constraint)
+        })
+        .and_then(|(_, origin)| parse_source_location(origin));
+
+    SarifResult {
+        rule_id,
+        rule_index: SARIF_RULES
+            .iter()
+            .position(|(id, _)| *id == rule_id)
+            .unwrap(),
+        level,
+        message: SarifMessage { text: message },
+        locations: vec![SarifLocation {
+            physical_location,
+            logical_locations: vec![SarifLogicalLocation {
+                name: component_name
+                    .rsplit_once('.')
+                    .map_or(component_name, |(_, name)| name)
+                    .to_string(),
+                fully_qualified_name: component_name.to_string(),
+                kind: "module",
+            }],
+        }],
+    }
+}
+
+// Parses a constraint origin of the form "file:line" or "file:line:column". Returns None for
+//  origins in any other form
+fn parse_source_location(origin: &str) -> Option<SarifPhysicalLocation> {
+    let (rest, last) = origin.rsplit_once(':')?;
+    let last = last.parse::<usize>().ok()?;
+    let (file, start_line, start_column) = match rest.rsplit_once(':') {
+        Some((file, line)) if line.parse::<usize>().is_ok() => {
+            (file, line.parse().unwrap(), Some(last))
+        }
+        _ => (rest, last, None),
+    };
+
+    Some(SarifPhysicalLocation {
+        artifact_location: SarifArtifactLocation {
+            uri: file.replace('\\', "/"),
+        },
+        region: SarifRegion {
+            start_line,
+            start_column,
+        },
+    })
+}
+
+#[test]
+fn test_parse_source_location() {
+    let location = parse_source_location("circuits/main.circom:12:5").unwrap();
+    assert_eq!(location.artifact_location.uri, "circuits/main.circom");
+    assert_eq!(location.region.start_line, 12);
+    assert_eq!(location.region.start_column, Some(5));
+
+    let location = parse_source_location("main.circom:7").unwrap();
+    assert_eq!(location.artifact_location.uri, "main.circom");
+    assert_eq!(location.region.start_line, 7);
+    assert_eq!(location.region.start_column, None);
+
+    assert!(parse_source_location("template Main").is_none());
+}
+
+#[test]
+fn test_sarif_log() {
+    use crate::cli::Options;
+    use crate::verification_graph::VerificationGraph;
+    use crate::verifier::SubComponentVerificationResultKind::ModuleConditionallySafe;
+    use crate::InputDataContext;
+
+    // Returns the SARIF log of a fixture, where every polynomial system gets the given verdict
+    let get_sarif_log = |fixture: &str, verdict: PolSystemVerdict| {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../test/fixtures")
+            .join(fixture);
+        let (context, mut constraint_storage) =
+            InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+        let context = context.get_context_view();
+        let res = VerificationGraph::new(&context, &constraint_storage)
+            .verify_subcomponents(&context, &mut constraint_storage);
+
+        let mut pol_systems = vec![];
+        res.apply(&mut |res| {
+            if let ModuleConditionallySafe(safety_conditions) = &res.kind {
+                pol_systems.extend(safety_conditions.pol_systems.iter().cloned());
+            }
+        });
+        let results: Vec<_> = pol_systems
+            .iter()
+            .map(|_| PolSystemResult {
+                verdict,
+                duration: None,
+            })
+            .collect();
+
+        let log = SarifLog::new(&res, &pol_systems, &results, &context);
+        (
+            log.runs.into_iter().next().unwrap().results,
+            context.signal_name(1),
+        )
+    };
+
+    let (results, _) = get_sarif_log("cyclic_exception", PolSystemVerdict::Fixed);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].rule_id, "cyclic-dependency");
+    assert_eq!(results[0].rule_index, 3);
+    assert_eq!(results[0].level, "error");
+    assert_eq!(
+        results[0].locations[0].logical_locations[0].fully_qualified_name,
+        "main"
+    );
+    assert!(results[0].locations[0].physical_location.is_none());
+
+    let (results, _) = get_sarif_log("unsafe_underconstrained", PolSystemVerdict::Fixed);
+    assert!(results.is_empty());
+
+    let (results, output_name) =
+        get_sarif_log("unsafe_underconstrained", PolSystemVerdict::ManySolutions);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].rule_id, "many-solutions");
+    assert!(results[0]
+        .message
+        .text
+        .contains(&format!("'{}'", output_name)));
+
+    // The log is valid SARIF, with every rule declared
+    let log = serde_json::to_value(SarifLog::new(
+        &SubComponentVerificationResult {
+            kind: ModuleUnsafe(UnconstrainedOutputs(vec!["out".to_string()])),
+            subcomponent_name: "main".to_string(),
+        },
+        &[],
+        &[],
+        &InputDataContext::from_data(
+            circom_algebra::constraint_storage::ConstraintStorage::new(),
+            None,
+            HashMap::new(),
+            crate::input_data::TreeConstraints {
+                field: "101".to_string(),
+                ..Default::default()
+            },
+            Options::default(),
+        )
+        .unwrap()
+        .0
+        .get_context_view(),
+    ))
+    .unwrap();
+    assert_eq!(log["$schema"], SARIF_SCHEMA);
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(
+        log["runs"][0]["tool"]["driver"]["rules"][2]["shortDescription"]["text"],
+        SARIF_RULES[2].1
+    );
+    assert_eq!(
+        log["runs"][0]["results"][0]["ruleId"],
+        "unconstrained-output"
+    );
+    assert!(log["runs"][0]["results"][0]["locations"][0]
+        .get("physicalLocation")
+        .is_none());
+}
@@ -1,26 +1,104 @@
+use crate::error::{VerifierError, VerifierResult};
+use crate::input_data::SignalIndex;
 use crate::verification_graph::VerificationGraph;
 use crate::InputDataContextView;
+use colored::Colorize;
 use graphviz_rust::cmd::Format;
 use graphviz_rust::dot_generator::*;
 use graphviz_rust::dot_structures::*;
 use graphviz_rust::exec;
 use graphviz_rust::printer::PrinterContext;
-use std::cell::RefCell;
-use std::error::Error;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::fs::File;
+use std::io;
 use std::io::Write;
-use std::path::Path;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 
 use crate::verification_graph::Node as VNode;
 
+// Snapshot of a verification graph to be rendered into the SVG file at path
+struct SvgRenderJob {
+    graph: Graph,
+    path: PathBuf,
+}
+
+// Threads rendering the enqueued graphs in the background, so the verification does not wait for
+//  Graphviz between propagation steps
+struct SvgRenderPool {
+    sender: mpsc::Sender<SvgRenderJob>,
+    workers: Vec<thread::JoinHandle<VerifierResult<()>>>,
+}
+
+impl SvgRenderPool {
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<SvgRenderJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let num_workers = thread::available_parallelism().map_or(1, |n| n.get());
+
+        let workers = (0..num_workers)
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || -> VerifierResult<()> {
+                    loop {
+                        // The lock must be released before rendering, so other workers can
+                        //  receive jobs in the meantime
+                        let job = receiver.lock().unwrap().recv();
+                        let Ok(job) = job else {
+                            return Ok(());
+                        };
+
+                        render_svg(job)?;
+                    }
+                })
+            })
+            .collect();
+
+        Self { sender, workers }
+    }
+}
+
+fn render_svg(job: SvgRenderJob) -> VerifierResult<()> {
+    let graph_svg = exec(
+        job.graph,
+        &mut PrinterContext::default(),
+        vec![Format::Svg.into()],
+    )?;
+
+    fs::create_dir_all(job.path.parent().unwrap())?;
+    let mut f = File::create(job.path)?;
+    f.write_all(graph_svg.as_bytes())?;
+
+    Ok(())
+}
+
+// Last graph drawn with a given file name, and the signals that were fixed in it
+struct PreviousFrame {
+    stmts: Vec<Stmt>,
+    fixed_nodes: BTreeSet<SignalIndex>,
+}
+
 pub struct DebugSVGPrinter {
-    // String containing the base filepath of the base SVG folder output
-    svg_folder_path: String,
+    // String containing the base filepath of the base SVG folder output. None if this printer is
+    //  disabled and never draws anything
+    svg_folder_path: Option<String>,
 
     // This index counts which SVG file is the next to be printed, to be able to have sequential
-    //  filenames
-    index: RefCell<i32>,
+    //  filenames. It is atomic so the printer can be shared with the threads running CoCoA. It is
+    //  incremented when a graph is enqueued, so the numbering follows the verification order
+    //  regardless of which render finishes first
+    index: AtomicI32,
+
+    // Started when the first graph is enqueued, so runs without SVG diagrams do not spawn threads
+    render_pool: Mutex<Option<SvgRenderPool>>,
+
+    // Last frame drawn with each file name, only kept if the layout of the following frames with
+    //  the same name has to be stable
+    previous_frames: Mutex<HashMap<String, PreviousFrame>>,
 }
 
 impl DebugSVGPrinter {
@@ -29,49 +107,131 @@ impl DebugSVGPrinter {
         delete_all_files(Path::new(svg_folder_path));
 
         Self {
-            svg_folder_path: String::from(svg_folder_path),
-            index: RefCell::new(0),
+            svg_folder_path: Some(String::from(svg_folder_path)),
+            index: AtomicI32::new(0),
+            render_pool: Mutex::new(None),
+            previous_frames: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Returns a printer that never draws anything nor touches the filesystem, for in-memory runs
+    pub fn disabled() -> Self {
+        Self {
+            svg_folder_path: None,
+            index: AtomicI32::new(0),
+            render_pool: Mutex::new(None),
+            previous_frames: Mutex::new(HashMap::new()),
         }
     }
 
+    // Enqueues a snapshot of the verification graph to be rendered in the background. Rendering
+    //  errors are returned by wait_for_renders. If highlight_unfixed_outputs is true, the outputs
+    //  that have not been fixed are drawn with a warning style, which is meant for the final frame
+    //  of a component
     pub fn print_verification_graph(
         &self,
         verification_graph: &VerificationGraph,
         context: &InputDataContextView,
         file_name: &str,
         graph_title: Option<&str>,
-    ) -> Result<(), Box<dyn Error>> {
+        highlight_unfixed_outputs: bool,
+    ) -> VerifierResult<()> {
         // If debug SVGs are deactivated, do not try to draw
-        if !context.options.generate_svg_diagrams {
-            return Ok(());
-        }
+        let svg_folder_path = match &self.svg_folder_path {
+            Some(path) if context.options.generate_svg_diagrams => path,
+            _ => return Ok(()),
+        };
+
+        // Frames with the same file name are the steps of the same process, such as the
+        //  propagation of the fixed nodes of a component
+        let previous_frame = if context.options.stable_svg_layout {
+            self.previous_frames.lock().unwrap().remove(file_name)
+        } else {
+            None
+        };
 
-        let g = construct_graphviz_graph_from_verification_graph(
+        let mut g = construct_graphviz_graph_from_verification_graph(
             verification_graph,
             context,
             graph_title,
+            highlight_unfixed_outputs,
+            previous_frame.as_ref().map(|frame| &frame.fixed_nodes),
         );
 
+        if context.options.stable_svg_layout {
+            let (Graph::Graph { stmts, .. } | Graph::DiGraph { stmts, .. }) = &mut g;
+            if let Some(previous_frame) = previous_frame {
+                *stmts = stabilize_frame(&previous_frame.stmts, std::mem::take(stmts));
+            }
+
+            self.previous_frames.lock().unwrap().insert(
+                file_name.to_string(),
+                PreviousFrame {
+                    stmts: stmts.clone(),
+                    fixed_nodes: verification_graph.fixed_nodes.clone(),
+                },
+            );
+        }
+
         // The following commented code prints the textual version of the graphviz code
         // let s = graphviz_rust::print(g.clone(), &mut PrinterContext::default());
         // println!("{}", s);
 
-        let graph_svg = exec(g, &mut PrinterContext::default(), vec![Format::Svg.into()])?;
-
         // Create a sequential filename: for example: svg/000-components.svg
 
-        let mut index = self.index.borrow_mut();
+        let index = self.index.fetch_add(1, Ordering::Relaxed);
+
+        let path =
+            Path::new(svg_folder_path.as_str()).join(format!("{:0>3}-{}.svg", index, file_name));
+
+        let mut render_pool = self.render_pool.lock().unwrap();
+        let job = SvgRenderJob { graph: g, path };
+        if render_pool
+            .get_or_insert_with(SvgRenderPool::new)
+            .sender
+            .send(job)
+            .is_err()
+        {
+            return Err(VerifierError::Io(io::Error::other(
+                "The SVG render threads have stopped",
+            )));
+        }
 
-        let path = Path::new(self.svg_folder_path.as_str())
-            .join(format!("{:0>3}-{}.svg", index, file_name));
+        Ok(())
+    }
 
-        *index += 1;
+    // Waits until every enqueued graph has been rendered. Returns the first rendering error, if
+    //  any. Graphs enqueued afterwards are rendered by new threads
+    pub fn wait_for_renders(&self) -> VerifierResult<()> {
+        let Some(render_pool) = self.render_pool.lock().unwrap().take() else {
+            return Ok(());
+        };
 
-        fs::create_dir_all(path.parent().unwrap())?;
-        let mut f = File::create(path)?;
-        f.write_all(graph_svg.as_bytes())?;
+        // Closing the channel makes the workers finish once every job has been rendered
+        drop(render_pool.sender);
+
+        let mut result = Ok(());
+        for worker in render_pool.workers {
+            let worker_result = worker.join().unwrap_or_else(|_| {
+                Err(VerifierError::Io(io::Error::other(
+                    "An SVG render thread panicked",
+                )))
+            });
+            if result.is_ok() {
+                result = worker_result;
+            }
+        }
 
-        Ok(())
+        result
+    }
+}
+
+impl Drop for DebugSVGPrinter {
+    // The program waits for the pending renders before exiting
+    fn drop(&mut self) {
+        if let Err(e) = self.wait_for_renders() {
+            println!("{}", format!("Error rendering SVG diagrams: {}", e).red());
+        }
     }
 }
 
@@ -83,10 +243,127 @@ fn delete_all_files(base_path: &Path) {
 }
 
 //noinspection SpellCheckingInspection
+// Returns the tooltip shown for a constraint in the SVG: its index and, if the artifacts include
+//  it, its origin in the circuit
+fn get_constraint_tooltip(context: &InputDataContextView, constraint: usize) -> String {
+    let tooltip = match context.tree_constraints.constraint_origins.get(&constraint) {
+        Some(origin) => format!("Constraint {}: {}", constraint, origin),
+        None => format!("Constraint {}", constraint),
+    };
+
+    // Tooltips are written as quoted DOT strings
+    tooltip.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Key identifying a statement of a graph across frames, or None for attributes, which are always
+//  taken from the last frame. The same edge may appear several times, so the key of an edge
+//  includes how many times it has appeared before
+fn get_stmt_key(stmt: &Stmt, edge_occurrences: &mut HashMap<String, usize>) -> Option<String> {
+    match stmt {
+        Stmt::Node(node) => Some(format!("node {:?}", node.id)),
+        Stmt::Subgraph(subgraph) => Some(format!("subgraph {:?}", subgraph.id)),
+        Stmt::Edge(edge) => {
+            let key = format!("edge {:?}", edge.ty);
+            let occurrence = edge_occurrences.entry(key.clone()).or_default();
+            *occurrence += 1;
+            Some(format!("{} {}", key, occurrence))
+        }
+        Stmt::Attribute(_) | Stmt::GAttribute(_) => None,
+    }
+}
+
+fn is_style_attribute(attribute: &Attribute) -> bool {
+    attribute.0 == id!("style")
+}
+
+// Hides a statement without removing it, so it still takes its space in the layout
+fn make_invisible(stmt: Stmt) -> Stmt {
+    let hide = |attributes: Vec<Attribute>| -> Vec<Attribute> {
+        attributes
+            .into_iter()
+            .filter(|attribute| !is_style_attribute(attribute))
+            .chain(iter::once(attr!("style", "invis")))
+            .collect()
+    };
+
+    match stmt {
+        Stmt::Node(node) => Stmt::Node(Node {
+            attributes: hide(node.attributes),
+            ..node
+        }),
+        Stmt::Edge(edge) => Stmt::Edge(Edge {
+            attributes: hide(edge.attributes),
+            ..edge
+        }),
+        Stmt::Subgraph(subgraph) => {
+            let stmts = iter::once(Stmt::Attribute(attr!("style", "invis")))
+                .chain(
+                    subgraph
+                        .stmts
+                        .into_iter()
+                        .filter(|stmt| {
+                            !matches!(stmt, Stmt::Attribute(attribute) if is_style_attribute(attribute))
+                        })
+                        .map(make_invisible),
+                )
+                .collect();
+            Stmt::Subgraph(Subgraph { stmts, ..subgraph })
+        }
+        Stmt::Attribute(_) | Stmt::GAttribute(_) => stmt,
+    }
+}
+
+// Merges the statements of a frame with those of the previous frame of the same graph, so
+//  Graphviz lays out both in the same way. The statements of the previous frame keep their order,
+//  and are replaced by their new version if they are still present or made invisible otherwise.
+//  Statements that are new in this frame go at the end, and attributes at the beginning, as
+//  default node attributes only apply to the nodes after them.
+fn stabilize_frame(previous_stmts: &[Stmt], stmts: Vec<Stmt>) -> Vec<Stmt> {
+    let mut stabilized_stmts = Vec::new();
+    let mut current_stmts = HashMap::new();
+    let mut current_keys = Vec::new();
+    let mut edge_occurrences = HashMap::new();
+    for stmt in stmts {
+        match get_stmt_key(&stmt, &mut edge_occurrences) {
+            Some(key) => {
+                current_keys.push(key.clone());
+                current_stmts.insert(key, stmt);
+            }
+            None => stabilized_stmts.push(stmt),
+        }
+    }
+
+    let mut edge_occurrences = HashMap::new();
+    for previous_stmt in previous_stmts {
+        let Some(key) = get_stmt_key(previous_stmt, &mut edge_occurrences) else {
+            continue;
+        };
+
+        stabilized_stmts.push(match (previous_stmt, current_stmts.remove(&key)) {
+            (Stmt::Subgraph(previous_subgraph), Some(Stmt::Subgraph(mut subgraph))) => {
+                subgraph.stmts = stabilize_frame(&previous_subgraph.stmts, subgraph.stmts);
+                Stmt::Subgraph(subgraph)
+            }
+            (_, Some(stmt)) => stmt,
+            (_, None) => make_invisible(previous_stmt.clone()),
+        });
+    }
+
+    stabilized_stmts.extend(
+        current_keys
+            .into_iter()
+            .filter_map(|key| current_stmts.remove(&key)),
+    );
+    stabilized_stmts
+}
+
+// If previous_fixed_nodes is given, the nodes fixed since the frame it belongs to are highlighted
 fn construct_graphviz_graph_from_verification_graph(
     verification_graph: &VerificationGraph,
     context: &InputDataContextView,
     graph_title: Option<&str>,
+    highlight_unfixed_outputs: bool,
+    previous_fixed_nodes: Option<&BTreeSet<SignalIndex>>,
 ) -> Graph {
     let mut g = graph!(di id!("id"));
 
@@ -99,6 +376,20 @@ fn construct_graphviz_graph_from_verification_graph(
         attr!("fontcolor", "white"),
     ];
 
+    // Extra-style attributes for nodes fixed since the previous frame
+    let newly_fixed_attrs = vec![attr!("color", "gold"), attr!("penwidth", "4")];
+    let is_newly_fixed = |s: &SignalIndex| {
+        verification_graph.fixed_nodes.contains(s)
+            && previous_fixed_nodes.is_some_and(|fixed_nodes| !fixed_nodes.contains(s))
+    };
+
+    // Extra-style attributes for outputs that remain unfixed in the final frame
+    let unfixed_output_attrs = vec![
+        attr!("style", "filled"),
+        attr!("fillcolor", "yellow"),
+        attr!("penwidth", "3"),
+    ];
+
     for (s, node) in verification_graph.nodes.iter().filter(|(_, n)| {
         matches!(
             **n,
@@ -113,13 +404,13 @@ fn construct_graphviz_graph_from_verification_graph(
 
         let mut attrs = match node {
             VNode::InputSignal | VNode::OutputSignal => vec![
-                attr!("label", esc context.signal_name_map.get(s).unwrap()),
+                attr!("label", esc context.signal_name(*s)),
                 attr!("color", esc if highlight_node {highlight_color} else {"orange"}),
                 attr!("shape", "Mdiamond"),
             ],
             VNode::IntermediateSignal => {
                 vec![
-                    attr!("label", esc context.signal_name_map.get(s).unwrap()),
+                    attr!("label", esc context.signal_name(*s)),
                     attr!("color", esc if highlight_node {highlight_color} else {"black"}),
                 ]
             }
@@ -130,6 +421,11 @@ fn construct_graphviz_graph_from_verification_graph(
         // Add style if this node has been fixed
         if verification_graph.fixed_nodes.contains(s) {
             attrs.append(&mut fixed_attrs.clone());
+            if is_newly_fixed(s) {
+                attrs.append(&mut newly_fixed_attrs.clone());
+            }
+        } else if highlight_unfixed_outputs && matches!(node, VNode::OutputSignal) {
+            attrs.append(&mut unfixed_output_attrs.clone());
         }
 
         g.add_stmt(Stmt::Node(node!(s.to_string(), attrs)));
@@ -189,13 +485,16 @@ fn construct_graphviz_graph_from_verification_graph(
                 .contains(output);
 
             let mut attrs = vec![
-                attr!("label", esc context.signal_name_map.get(output).unwrap()),
+                attr!("label", esc context.signal_name(*output)),
                 attr!("color", esc if highlight_node {highlight_color} else {"blue"}),
             ];
 
             // Add style if this node has been fixed
             if verification_graph.fixed_nodes.contains(output) {
                 attrs.append(&mut fixed_attrs.clone());
+                if is_newly_fixed(output) {
+                    attrs.append(&mut newly_fixed_attrs.clone());
+                }
             }
 
             v.push(Stmt::Node(node!(output.to_string(), attrs)));
@@ -214,12 +513,15 @@ fn construct_graphviz_graph_from_verification_graph(
                 .contains(input);
 
             let mut attrs = vec![
-                attr!("label", esc context.signal_name_map.get(input).unwrap()),
+                attr!("label", esc context.signal_name(*input)),
                 attr!("color", esc if highlight_node {highlight_color} else {"green"}),
             ];
             // Add style if this node has been fixed
             if verification_graph.fixed_nodes.contains(input) {
                 attrs.append(&mut fixed_attrs.clone());
+                if is_newly_fixed(input) {
+                    attrs.append(&mut newly_fixed_attrs.clone());
+                }
             }
 
             v.push(Stmt::Node(node!(input.to_string(), attrs)));
@@ -273,6 +575,7 @@ fn construct_graphviz_graph_from_verification_graph(
         // Highlight if chosen in connected component debug info
 
         let lhs = ass.lhs_signal;
+        let tooltip = get_constraint_tooltip(context, ass.associated_constraint);
 
         let highlight_edge = verification_graph
             .debug_polynomial_system_generator_data
@@ -292,7 +595,8 @@ fn construct_graphviz_graph_from_verification_graph(
                 node_id!(rhs.to_string()) => node_id!(lhs.to_string());
                 attr!("label", esc " <=="),
                 attr!("fontname", "Courier"),
-                attr!("color", esc edge_color)
+                attr!("color", esc edge_color),
+                attr!("tooltip", esc tooltip)
             )));
         } else {
             // Multiple sources, create intermediate node
@@ -301,17 +605,20 @@ fn construct_graphviz_graph_from_verification_graph(
                 intermediate_node_str;
                 attr!("shape", "point"),
                 attr!("fontname", "Courier"),
-                attr!("xlabel", esc "<==")
+                attr!("xlabel", esc "<=="),
+                attr!("tooltip", esc tooltip)
             )));
             g.add_stmt(Stmt::Edge(edge!(
                 node_id!(intermediate_node_str) => node_id!(lhs.to_string());
-                attr!("color", esc edge_color)
+                attr!("color", esc edge_color),
+                attr!("tooltip", esc tooltip)
             )));
 
             for rhs in &ass.rhs_signals {
                 g.add_stmt(Stmt::Edge(edge!(
                     node_id!(rhs.to_string()) => node_id!(intermediate_node_str);
-                    attr!("color", esc edge_color)
+                    attr!("color", esc edge_color),
+                    attr!("tooltip", esc tooltip)
                 )));
             }
         }
@@ -332,6 +639,7 @@ fn construct_graphviz_graph_from_verification_graph(
         } else {
             "green"
         };
+        let tooltip = get_constraint_tooltip(context, c.associated_constraint);
 
         if c.signals.len() == 1 {
             // Only one signal appears, make a loop
@@ -341,7 +649,8 @@ fn construct_graphviz_graph_from_verification_graph(
                 attr!("dir", "none"),
                 attr!("color", esc edge_color),
                 attr!("label", esc " ==="),
-                attr!("fontname", "Courier")
+                attr!("fontname", "Courier"),
+                attr!("tooltip", esc tooltip)
             )));
         } else {
             // TODO: Create a special case for === constraints where only 2 signals appear so we
@@ -353,14 +662,19 @@ fn construct_graphviz_graph_from_verification_graph(
             g.add_stmt(Stmt::Node(node!(
                 tmp_node_str;
                 attr!("shape", "point"),
-                attr!("xlabel", esc " ===")
+                attr!("xlabel", esc " ==="),
+                attr!("tooltip", esc tooltip)
             )));
 
             for signal in &c.signals {
                 // The direction of the edge matters for aesthetics in the graph.
                 // As a heuristic, if the node is an input, it will be the origin, else,
                 //   it will be a destination
-                let attrs = vec![attr!("dir", "none"), attr!("color",esc edge_color)];
+                let attrs = vec![
+                    attr!("dir", "none"),
+                    attr!("color", esc edge_color),
+                    attr!("tooltip", esc tooltip),
+                ];
 
                 if context.is_signal_public(*signal) {
                     // This signal is an input
@@ -384,3 +698,46 @@ fn construct_graphviz_graph_from_verification_graph(
 
     g
 }
+
+#[test]
+fn test_stabilize_frame() {
+    let previous_stmts = vec![
+        Stmt::Node(node!("a"; attr!("style", "filled"))),
+        Stmt::Node(node!("b")),
+        Stmt::Edge(edge!(node_id!("a") => node_id!("b"))),
+        Stmt::Subgraph(subgraph!("cluster_0"; attr!("style", "filled"), node!("c"))),
+    ];
+
+    // a has been removed, c has been fixed and d is new
+    let stmts = vec![
+        Stmt::Attribute(attr!("label", "title")),
+        Stmt::Node(node!("d")),
+        Stmt::Node(node!("b")),
+        Stmt::Subgraph(
+            subgraph!("cluster_0"; attr!("style", "filled"), node!("c"; attr!("style", "filled"))),
+        ),
+    ];
+
+    assert_eq!(
+        stabilize_frame(&previous_stmts, stmts),
+        vec![
+            Stmt::Attribute(attr!("label", "title")),
+            Stmt::Node(node!("a"; attr!("style", "invis"))),
+            Stmt::Node(node!("b")),
+            Stmt::Edge(edge!(node_id!("a") => node_id!("b"); attr!("style", "invis"))),
+            Stmt::Subgraph(
+                subgraph!("cluster_0"; attr!("style", "filled"), node!("c"; attr!("style", "filled")))
+            ),
+            Stmt::Node(node!("d")),
+        ]
+    );
+
+    // Removed subgraphs are hidden with all their contents
+    assert_eq!(
+        stabilize_frame(&previous_stmts[3..], vec![]),
+        vec![Stmt::Subgraph(subgraph!("cluster_0";
+            attr!("style", "invis"),
+            node!("c"; attr!("style", "invis"))
+        ))]
+    );
+}
@@ -1,3 +1,10 @@
+#[cfg(test)]
+use crate::output_format::render_csv;
+use crate::output_format::AnalysisOutput;
+use crate::polynomial_system_fixer::{
+    get_constraint_degree, get_linear_rank, get_readable_constraint,
+    get_redundant_linear_constraints, get_unfixed_signals_of_linear_pol_system,
+};
 use crate::verification_graph::Node::SubComponentInputSignal;
 use crate::verifier::{
     ModuleUnsafeReason, PolynomialSystemFixedSignal, SafetyConditions,
@@ -6,8 +13,11 @@ use crate::verifier::{
 use crate::{ComponentIndex, ConstraintIndex, InputDataContextView, SignalIndex};
 use circom_algebra::algebra::{ArithmeticExpression, Constraint, Substitution};
 use circom_algebra::constraint_storage::ConstraintStorage;
+use itertools::Itertools;
 use num_bigint_dig::BigInt;
 use num_traits::Zero;
+use serde::Serialize;
+use std::cmp::Reverse;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 #[allow(clippy::enum_variant_names)]
@@ -61,7 +71,6 @@ pub struct SubComponent {
 
     // original_input_signals and original_output_signals are the original input and output signals
     // of the component, before propagating and possibly removing inputs and outputs
-    // TODO: Maybe we don't need to store these, right now we don't use them.
     pub original_input_signals: BTreeSet<SignalIndex>,
     pub original_output_signals: BTreeSet<SignalIndex>,
 }
@@ -69,6 +78,54 @@ pub struct SubComponent {
 pub type SafeAssignmentIndex = usize;
 pub type UnsafeConstraintIndex = usize;
 
+// Reason why a signal has been fixed while traversing the verification graph
+#[derive(Clone, Debug, PartialEq)]
+pub enum FixingReason {
+    // Inputs of the component are fixed from the start
+    Input,
+
+    // LHS of the safe assignment (<==) of the given constraint, once every signal of its RHS is
+    //  fixed. This includes assignments of constants, such as out <== 2
+    SafeAssignment(ConstraintIndex),
+
+    // Only unfixed signal of the given linear === constraint, with a non-zero coefficient
+    LinearConstraint(ConstraintIndex),
+
+    // Signal of the polynomial system with the given constraints. It is only fixed if the
+    //  polynomial system is, which is checked once the graph has been traversed
+    PolynomialSystem(Vec<ConstraintIndex>),
+
+    // Output of a subcomponent, fixed once all of its inputs are fixed
+    SubComponentOutput(ComponentIndex),
+}
+
+impl FixingReason {
+    // Constraints of the component responsible for fixing the signal
+    pub fn constraint_indices(&self) -> &[ConstraintIndex] {
+        match self {
+            FixingReason::Input | FixingReason::SubComponentOutput(_) => &[],
+            FixingReason::SafeAssignment(constraint_index)
+            | FixingReason::LinearConstraint(constraint_index) => {
+                std::slice::from_ref(constraint_index)
+            }
+            FixingReason::PolynomialSystem(constraint_indices) => constraint_indices,
+        }
+    }
+}
+
+// Constraints involved in fixing an output of a component: the constraints fixing it and,
+//  recursively, the constraints fixing the signals those constraints depend on
+pub struct OutputCoverage {
+    pub output_name: String,
+
+    // Constraint index and readable form of each constraint
+    pub constraints: Vec<(ConstraintIndex, String)>,
+
+    // Names of the subcomponents whose outputs are involved. Their own constraints are part of the
+    //  coverage of those subcomponents
+    pub subcomponents: Vec<String>,
+}
+
 // NOTE: For reproducibility, I have declared the HashMaps as BTreeMap, so they are ordered.
 //      Explore whether it's a good idea to change them to HashMap
 pub struct VerificationGraph {
@@ -107,6 +164,17 @@ pub struct VerificationGraph {
     // List of subcomponents to verify in order for this component to be verified.
     pub sub_components_to_verify: Vec<ComponentIndex>,
 
+    // Order in which each signal has been fixed, and the reason why
+    pub fixing_reasons: BTreeMap<SignalIndex, (usize, FixingReason)>,
+
+    // Constraints of the component before any witness value is substituted into them. Only kept
+    //  if the constraint coverage or the dead inputs have been requested
+    pub original_constraints: HashMap<ConstraintIndex, Constraint<usize>>,
+
+    // Number of propagation steps performed so far, bounded by the propagation_max_iterations
+    //  option
+    pub propagation_iterations: u64,
+
     // Fields for Debug SVG printing
     pub debug_polynomial_system_generator_data: DebugPolynomialSystemGeneratorData,
 }
@@ -115,6 +183,433 @@ struct ConnectedComponent {
     nodes: BTreeSet<SignalIndex>,
 }
 
+// Signals, constraints and subcomponents an output depends on
+struct OutputDependencies {
+    signals: BTreeSet<SignalIndex>,
+    constraints: BTreeSet<ConstraintIndex>,
+    subcomponents: BTreeSet<ComponentIndex>,
+}
+
+// Size of a verification graph, to understand why a component is hard to verify
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct GraphMetrics {
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+    pub num_intermediates: usize,
+    pub num_subcomponent_inputs: usize,
+    pub num_subcomponent_outputs: usize,
+    pub num_subcomponents: usize,
+    pub num_safe_assignments: usize,
+    pub num_unsafe_constraints: usize,
+
+    // Rank of the linear constraints and number of signals they should determine, see
+    //  ComponentLinearRank
+    pub linear_rank: usize,
+    pub num_non_input_signals: usize,
+
+    // Number of signals of each connected component of '===' constraints, largest first. Signals
+    //  that do not appear in any '===' constraint are left out
+    pub unsafe_connected_component_sizes: Vec<usize>,
+}
+
+// Only the sizes of the largest connected components are printed
+const MAX_PRINTED_CONNECTED_COMPONENT_SIZES: usize = 10;
+
+impl GraphMetrics {
+    pub fn new(
+        verification_graph: &VerificationGraph,
+        context: &InputDataContextView,
+        constraint_storage: &ConstraintStorage,
+    ) -> GraphMetrics {
+        let linear_rank = ComponentLinearRank::new(verification_graph, context, constraint_storage);
+        let mut metrics = GraphMetrics {
+            num_subcomponents: verification_graph.subcomponents.len(),
+            num_safe_assignments: verification_graph
+                .safe_assignments
+                .iter()
+                .filter(|a| a.active)
+                .count(),
+            num_unsafe_constraints: verification_graph
+                .unsafe_constraints
+                .iter()
+                .filter(|c| c.active)
+                .count(),
+            linear_rank: linear_rank.rank,
+            num_non_input_signals: linear_rank.num_non_input_signals,
+            ..Default::default()
+        };
+
+        for node in verification_graph.nodes.values() {
+            match node {
+                Node::InputSignal => metrics.num_inputs += 1,
+                Node::OutputSignal => metrics.num_outputs += 1,
+                Node::IntermediateSignal => metrics.num_intermediates += 1,
+                Node::SubComponentInputSignal(_) => metrics.num_subcomponent_inputs += 1,
+                Node::SubComponentOutputSignal(_) => metrics.num_subcomponent_outputs += 1,
+            }
+        }
+
+        metrics.unsafe_connected_component_sizes = verification_graph
+            .compute_connected_components_unsafe_constraints()
+            .into_iter()
+            .filter(|component| {
+                component.nodes.iter().any(|signal| {
+                    verification_graph
+                        .edge_constraints
+                        .get(signal)
+                        .is_some_and(|constraints| !constraints.is_empty())
+                })
+            })
+            .map(|component| component.nodes.len())
+            .sorted_by_key(|size| Reverse(*size))
+            .collect();
+
+        metrics
+    }
+}
+
+impl AnalysisOutput for GraphMetrics {
+    fn print_table(&self) {
+        println!(
+            "Signals: {} ({} inputs, {} outputs, {} intermediates, {} subcomponent inputs, {} subcomponent outputs)",
+            self.num_inputs
+                + self.num_outputs
+                + self.num_intermediates
+                + self.num_subcomponent_inputs
+                + self.num_subcomponent_outputs,
+            self.num_inputs,
+            self.num_outputs,
+            self.num_intermediates,
+            self.num_subcomponent_inputs,
+            self.num_subcomponent_outputs
+        );
+        println!("Subcomponents: {}", self.num_subcomponents);
+        println!("Safe assignments '<==': {}", self.num_safe_assignments);
+        println!("Unsafe constraints '===': {}", self.num_unsafe_constraints);
+        println!(
+            "Rank of the linear constraints: {} for {} non-input signals{}",
+            self.linear_rank,
+            self.num_non_input_signals,
+            if self.linear_rank >= self.num_non_input_signals {
+                ""
+            } else {
+                " (needs non-linear constraints)"
+            }
+        );
+
+        let sizes = &self.unsafe_connected_component_sizes;
+        println!("Connected components of '===' constraints: {}", sizes.len());
+        if !sizes.is_empty() {
+            println!(
+                "Signals in the largest ones: {}{}",
+                sizes
+                    .iter()
+                    .take(MAX_PRINTED_CONNECTED_COMPONENT_SIZES)
+                    .join(", "),
+                if sizes.len() > MAX_PRINTED_CONNECTED_COMPONENT_SIZES {
+                    ", ..."
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+
+    fn csv_header(&self) -> Vec<&'static str> {
+        vec![
+            "num_inputs",
+            "num_outputs",
+            "num_intermediates",
+            "num_subcomponent_inputs",
+            "num_subcomponent_outputs",
+            "num_subcomponents",
+            "num_safe_assignments",
+            "num_unsafe_constraints",
+            "linear_rank",
+            "num_non_input_signals",
+            "unsafe_connected_component_sizes",
+        ]
+    }
+
+    // A single row. All the connected component sizes are written in the last column, separated
+    //  by spaces
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.num_inputs.to_string(),
+            self.num_outputs.to_string(),
+            self.num_intermediates.to_string(),
+            self.num_subcomponent_inputs.to_string(),
+            self.num_subcomponent_outputs.to_string(),
+            self.num_subcomponents.to_string(),
+            self.num_safe_assignments.to_string(),
+            self.num_unsafe_constraints.to_string(),
+            self.linear_rank.to_string(),
+            self.num_non_input_signals.to_string(),
+            self.unsafe_connected_component_sizes.iter().join(" "),
+        ]]
+    }
+}
+
+// Rank of the linear constraints of a component, to tell whether they alone determine its signals
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ComponentLinearRank {
+    pub component_name: String,
+    pub template_name: String,
+    pub num_linear_constraints: usize,
+
+    // Rank of the linear constraints over the field, with the inputs and the outputs of the
+    //  subcomponents taken as constants
+    pub rank: usize,
+
+    // Signals of the verification graph of the component that must be determined by its inputs.
+    //  The outputs of its subcomponents are not counted, as the subcomponents determine them
+    pub num_non_input_signals: usize,
+}
+
+impl ComponentLinearRank {
+    pub fn new(
+        verification_graph: &VerificationGraph,
+        context: &InputDataContextView,
+        constraint_storage: &ConstraintStorage,
+    ) -> ComponentLinearRank {
+        let tree_constraints = context.tree_constraints;
+        let known_signals: BTreeSet<SignalIndex> = verification_graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| {
+                matches!(node, Node::InputSignal | Node::SubComponentOutputSignal(_))
+            })
+            .map(|(signal, _)| *signal)
+            .collect();
+
+        let constraints: Vec<Constraint<usize>> = (tree_constraints.initial_constraint
+            ..(tree_constraints.initial_constraint + tree_constraints.no_constraints))
+            .map(|idx| constraint_storage.read_constraint(idx).unwrap())
+            .collect();
+
+        ComponentLinearRank {
+            component_name: tree_constraints.component_name.clone(),
+            template_name: tree_constraints.template_name.clone(),
+            num_linear_constraints: constraints
+                .iter()
+                .filter(|constraint| get_constraint_degree(constraint) <= 1)
+                .count(),
+            rank: get_linear_rank(&constraints, &known_signals, &context.field),
+            num_non_input_signals: verification_graph.nodes.len() - known_signals.len(),
+        }
+    }
+
+    // If the rank is lower than the number of non-input signals, the linear constraints leave some
+    //  of them free, so the component can only be safe thanks to its non-linear constraints or its
+    //  subcomponents
+    pub fn is_rank_sufficient(&self) -> bool {
+        self.rank >= self.num_non_input_signals
+    }
+}
+
+// Rank of the linear constraints of every component, listing each component after its parent
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct LinearRanks {
+    pub components: Vec<ComponentLinearRank>,
+}
+
+impl LinearRanks {
+    pub fn new(
+        context: &InputDataContextView,
+        constraint_storage: &ConstraintStorage,
+    ) -> LinearRanks {
+        fn add_component(
+            context: &InputDataContextView,
+            constraint_storage: &ConstraintStorage,
+            components: &mut Vec<ComponentLinearRank>,
+        ) {
+            let verification_graph = VerificationGraph::new(context, constraint_storage);
+            components.push(ComponentLinearRank::new(
+                &verification_graph,
+                context,
+                constraint_storage,
+            ));
+
+            for idx in 0..context.tree_constraints.subcomponents.len() {
+                add_component(
+                    &context.get_subcomponent_context_view(idx),
+                    constraint_storage,
+                    components,
+                );
+            }
+        }
+
+        let mut components = Vec::new();
+        add_component(context, constraint_storage, &mut components);
+        LinearRanks { components }
+    }
+}
+
+impl AnalysisOutput for LinearRanks {
+    // One component per line, marking those whose linear constraints do not determine them
+    fn print_table(&self) {
+        println!(
+            "{:<30} {:<20} {:>18} {:>6} {:>17}",
+            "component", "template", "linear constraints", "rank", "non-input signals"
+        );
+        for component in &self.components {
+            println!(
+                "{:<30} {:<20} {:>18} {:>6} {:>17}{}",
+                component.component_name,
+                component.template_name,
+                component.num_linear_constraints,
+                component.rank,
+                component.num_non_input_signals,
+                if component.is_rank_sufficient() {
+                    ""
+                } else {
+                    "  (needs non-linear constraints)"
+                }
+            );
+        }
+    }
+
+    fn csv_header(&self) -> Vec<&'static str> {
+        vec![
+            "component_name",
+            "template_name",
+            "num_linear_constraints",
+            "rank",
+            "num_non_input_signals",
+            "rank_sufficient",
+        ]
+    }
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        self.components
+            .iter()
+            .map(|c| {
+                vec![
+                    c.component_name.clone(),
+                    c.template_name.clone(),
+                    c.num_linear_constraints.to_string(),
+                    c.rank.to_string(),
+                    c.num_non_input_signals.to_string(),
+                    c.is_rank_sufficient().to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+// Linear constraint of a component that is a linear combination of its other constraints
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RedundantConstraint {
+    pub component_name: String,
+    pub template_name: String,
+    pub constraint_index: ConstraintIndex,
+    pub constraint: String,
+}
+
+// Linear constraints of every component that can be removed without changing the circuit, only
+//  adding proving cost. Unlike the dedup optimization pass, they need not be exact duplicates. The
+//  constraints of each component are only compared among themselves, listing each component after
+//  its parent
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct RedundantConstraints {
+    pub constraints: Vec<RedundantConstraint>,
+}
+
+impl RedundantConstraints {
+    pub fn new(
+        context: &InputDataContextView,
+        constraint_storage: &ConstraintStorage,
+    ) -> RedundantConstraints {
+        fn add_component(
+            context: &InputDataContextView,
+            constraint_storage: &ConstraintStorage,
+            redundant_constraints: &mut Vec<RedundantConstraint>,
+        ) {
+            let tree_constraints = context.tree_constraints;
+            let constraint_indices: Vec<ConstraintIndex> = (tree_constraints.initial_constraint
+                ..(tree_constraints.initial_constraint + tree_constraints.no_constraints))
+                .collect();
+            let constraints: Vec<Constraint<usize>> = constraint_indices
+                .iter()
+                .map(|idx| constraint_storage.read_constraint(*idx).unwrap())
+                .collect();
+
+            for position in get_redundant_linear_constraints(&constraints, &context.field) {
+                redundant_constraints.push(RedundantConstraint {
+                    component_name: tree_constraints.component_name.clone(),
+                    template_name: tree_constraints.template_name.clone(),
+                    constraint_index: constraint_indices[position],
+                    constraint: get_readable_constraint(&constraints[position], context),
+                });
+            }
+
+            for idx in 0..tree_constraints.subcomponents.len() {
+                add_component(
+                    &context.get_subcomponent_context_view(idx),
+                    constraint_storage,
+                    redundant_constraints,
+                );
+            }
+        }
+
+        let mut constraints = Vec::new();
+        add_component(context, constraint_storage, &mut constraints);
+        RedundantConstraints { constraints }
+    }
+}
+
+impl AnalysisOutput for RedundantConstraints {
+    // The redundant constraints of each component under its name
+    fn print_table(&self) {
+        if self.constraints.is_empty() {
+            println!("No redundant linear constraints found");
+            return;
+        }
+
+        for ((component_name, template_name), constraints) in &self
+            .constraints
+            .iter()
+            .group_by(|c| (&c.component_name, &c.template_name))
+        {
+            let constraints: Vec<_> = constraints.collect();
+            println!(
+                "{} ({}): {} redundant constraints",
+                component_name,
+                template_name,
+                constraints.len()
+            );
+            for c in constraints {
+                println!("  {}: {}", c.constraint_index, c.constraint);
+            }
+        }
+    }
+
+    fn csv_header(&self) -> Vec<&'static str> {
+        vec![
+            "component_name",
+            "template_name",
+            "constraint_index",
+            "constraint",
+        ]
+    }
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        self.constraints
+            .iter()
+            .map(|c| {
+                vec![
+                    c.component_name.clone(),
+                    c.template_name.clone(),
+                    c.constraint_index.to_string(),
+                    c.constraint.clone(),
+                ]
+            })
+            .collect()
+    }
+}
+
 #[derive(Default)]
 pub struct DebugPolynomialSystemGeneratorData {
     // Nodes in the polynomial system
@@ -246,7 +741,7 @@ impl VerificationGraph {
                 if rhs_signal != lhs_signal {
                     outgoing_safe_assignments
                         .entry(*rhs_signal)
-                        .or_insert(BTreeSet::new())
+                        .or_default()
                         .insert(safe_assignment_idx);
                 }
             }
@@ -271,7 +766,7 @@ impl VerificationGraph {
                 // let vector: BTreeSet<SignalIndex> = signals.iter().filter(|x| **x != signal).copied().collect();
                 edge_constraints
                     .entry(signal)
-                    .or_insert(BTreeSet::new())
+                    .or_default()
                     .insert(unsafe_constraint_index);
             }
 
@@ -289,12 +784,21 @@ impl VerificationGraph {
 
         // Input signals
         let mut fixed_nodes = BTreeSet::new();
-        fixed_nodes.append(&mut input_signals);
+        let mut fixing_reasons = BTreeMap::new();
+        for input in &input_signals {
+            fix_signal(
+                &mut fixed_nodes,
+                &mut fixing_reasons,
+                *input,
+                FixingReason::Input,
+            );
+        }
 
         // Safe assignments of only constants
         for ass in &mut safe_assignments {
             propagate_fixed_node_in_safe_assignment(
                 &mut fixed_nodes,
+                &mut fixing_reasons,
                 ass,
                 &mut incoming_safe_assignments,
             );
@@ -305,6 +809,7 @@ impl VerificationGraph {
             propagate_fixed_node_in_unsafe_constraint(
                 constraint_storage,
                 &mut fixed_nodes,
+                &mut fixing_reasons,
                 unsafe_constraint,
             );
         }
@@ -317,11 +822,26 @@ impl VerificationGraph {
                 sub_components_to_verify.push(*idx);
 
                 for output in &cmp.output_signals {
-                    fixed_nodes.insert(*output);
+                    fix_signal(
+                        &mut fixed_nodes,
+                        &mut fixing_reasons,
+                        *output,
+                        FixingReason::SubComponentOutput(*idx),
+                    );
                 }
             }
         }
 
+        let original_constraints = if context.options.coverage || context.options.report_dead_inputs
+        {
+            (tree_constraints.initial_constraint
+                ..(tree_constraints.initial_constraint + tree_constraints.no_constraints))
+                .map(|idx| (idx, constraint_storage.read_constraint(idx).unwrap()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
         VerificationGraph {
             nodes,
             incoming_safe_assignments,
@@ -333,6 +853,9 @@ impl VerificationGraph {
             fixed_nodes,
             number_of_outputs_not_yet_fixed: tree_constraints.number_outputs,
             sub_components_to_verify,
+            fixing_reasons,
+            original_constraints,
+            propagation_iterations: 0,
             debug_polynomial_system_generator_data: Default::default(),
         }
     }
@@ -342,8 +865,157 @@ impl VerificationGraph {
         context: &InputDataContextView,
         constraint_storage: &mut ConstraintStorage,
     ) -> SubComponentVerificationResult {
-        // TODO: Maybe there are some easy. common, special cases to consider before executing
-        //          the full algorithm.
+        self.verify_subcomponents_dispatching(context, constraint_storage, &mut |_| {})
+    }
+
+    // Reason why each signal of the graph has been fixed so far
+    fn get_fixing_reasons(&self) -> BTreeMap<SignalIndex, FixingReason> {
+        self.fixing_reasons
+            .iter()
+            .map(|(signal, (_, reason))| (*signal, reason.clone()))
+            .collect()
+    }
+
+    // Returns true if the component has no inputs and each of its outputs has been fixed to a
+    //  constant when building the graph, by a constant assignment such as out <== 2 or a linear
+    //  constraint such as 3*out === 1
+    fn are_outputs_constant(&self, context: &InputDataContextView) -> bool {
+        let tree_constraints = context.tree_constraints;
+        let mut outputs = tree_constraints.initial_signal
+            ..(tree_constraints.initial_signal + tree_constraints.number_outputs);
+
+        tree_constraints.number_inputs == 0
+            && outputs.all(|output| self.fixed_nodes.contains(&output))
+    }
+
+    // Returns the outputs of the component that have not been fixed and do not appear in any safe
+    //  assignment '<==' nor unsafe constraint '==='
+    fn get_unconstrained_outputs(&self, context: &InputDataContextView) -> Vec<SignalIndex> {
+        let tree_constraints = context.tree_constraints;
+        (tree_constraints.initial_signal
+            ..(tree_constraints.initial_signal + tree_constraints.number_outputs))
+            .filter(|output| {
+                !self.fixed_nodes.contains(output)
+                    && !self.incoming_safe_assignments.contains_key(output)
+                    && self
+                        .outgoing_safe_assignments
+                        .get(output)
+                        .is_none_or(|assignments| assignments.is_empty())
+                    && self
+                        .edge_constraints
+                        .get(output)
+                        .is_none_or(|constraints| constraints.is_empty())
+            })
+            .collect()
+    }
+
+    // Same as verify_subcomponents, but calls dispatch with the polynomial systems of each
+    //  conditionally safe component as soon as they are known, before verifying its subcomponents.
+    //  Components are dispatched in the same order as SubComponentVerificationResult::apply visits
+    //  them
+    pub fn verify_subcomponents_dispatching(
+        &mut self,
+        context: &InputDataContextView,
+        constraint_storage: &mut ConstraintStorage,
+        dispatch: &mut dyn FnMut(&[PolynomialSystemFixedSignal]),
+    ) -> SubComponentVerificationResult {
+        // Components not matching the filter are not verified, but their subcomponents may match
+        if !context.matches_component_filters() {
+            dispatch(&[]);
+
+            let subcomponents = (0..context.tree_constraints.subcomponents.len())
+                .map(|subcomponent_idx| {
+                    verify_subcomponent(context, subcomponent_idx, constraint_storage, dispatch)
+                })
+                .collect::<Vec<_>>();
+            let (unsafe_subcomponents, trusted_subcomponents) =
+                SafetyConditions::get_unverified_subcomponents(&subcomponents);
+
+            return SubComponentVerificationResult {
+                kind: SubComponentVerificationResultKind::ModuleConditionallySafe(
+                    SafetyConditions {
+                        subcomponents,
+                        pol_systems: vec![],
+                        verified_by_linear_algebra: false,
+                        skipped_by_filter: true,
+                        output_coverage: vec![],
+                        fixing_reasons: BTreeMap::new(),
+                        dead_inputs: vec![],
+                        component_hash: None,
+                        unsafe_subcomponents,
+                        trusted_subcomponents,
+                    },
+                ),
+                subcomponent_name: context.tree_constraints.component_name.clone(),
+            };
+        }
+
+        // Components found safe by a previous run are not verified again while their constraints
+        //  do not change
+        let component_hash = context.get_component_hash(constraint_storage);
+        if component_hash
+            .as_ref()
+            .is_some_and(|hash| context.verified_components.contains(hash))
+        {
+            dispatch(&[]);
+
+            return SubComponentVerificationResult {
+                kind: SubComponentVerificationResultKind::ModuleCachedSafe,
+                subcomponent_name: context.tree_constraints.component_name.clone(),
+            };
+        }
+
+        // Sink components (without outputs) have nothing to fix, and the outputs of constant
+        //  components (without inputs) fixed to a constant do not depend on anything else. Neither
+        //  of them needs polynomial systems, but their subcomponents are still verified, as they
+        //  may be unsafe themselves
+        if context.tree_constraints.number_outputs == 0 || self.are_outputs_constant(context) {
+            dispatch(&[]);
+
+            let subcomponents = (0..context.tree_constraints.subcomponents.len())
+                .map(|subcomponent_idx| {
+                    verify_subcomponent(context, subcomponent_idx, constraint_storage, dispatch)
+                })
+                .collect::<Vec<_>>();
+            let (unsafe_subcomponents, trusted_subcomponents) =
+                SafetyConditions::get_unverified_subcomponents(&subcomponents);
+
+            return SubComponentVerificationResult {
+                kind: SubComponentVerificationResultKind::ModuleConditionallySafe(
+                    SafetyConditions {
+                        subcomponents,
+                        pol_systems: vec![],
+                        verified_by_linear_algebra: false,
+                        skipped_by_filter: false,
+                        output_coverage: self.get_output_coverage(context),
+                        fixing_reasons: self.get_fixing_reasons(),
+                        // Sink components have no outputs for their inputs to influence, and
+                        //  constant components have no inputs
+                        dead_inputs: vec![],
+                        component_hash,
+                        unsafe_subcomponents,
+                        trusted_subcomponents,
+                    },
+                ),
+                subcomponent_name: context.tree_constraints.component_name.clone(),
+            };
+        }
+
+        // Outputs without any constraint can never be fixed, so there is no point in propagating
+        let unconstrained_outputs = self.get_unconstrained_outputs(context);
+        if !unconstrained_outputs.is_empty() {
+            return SubComponentVerificationResult {
+                kind: SubComponentVerificationResultKind::ModuleUnsafe(
+                    ModuleUnsafeReason::UnconstrainedOutputs(
+                        unconstrained_outputs
+                            .into_iter()
+                            .map(|idx| context.signal_name(idx))
+                            .collect(),
+                    ),
+                ),
+                subcomponent_name: context.tree_constraints.component_name.clone(),
+            };
+        }
 
         context
             .svg_printer
@@ -359,43 +1031,94 @@ impl VerificationGraph {
                     )
                         .as_str(),
                 ),
+                false,
             )
             .unwrap();
 
         let mut pol_systems_to_be_fixed: Vec<PolynomialSystemFixedSignal> = vec![];
 
         loop {
-            self.propagate_fixed_nodes(context, constraint_storage);
+            if !self.propagate_fixed_nodes(context, constraint_storage) {
+                return self.get_propagation_limit_exception(context);
+            }
 
             if self.number_of_outputs_not_yet_fixed == 0 {
+                // If every polynomial system is linear, Gauss-Jordan elimination decides whether
+                //  their signals are fixed, so Groebner Basis are not needed
+                let maybe_linear_unfixed_signals: Option<Vec<Vec<SignalIndex>>> =
+                    pol_systems_to_be_fixed
+                        .iter()
+                        .map(|pol_system| {
+                            get_unfixed_signals_of_linear_pol_system(pol_system, &context.field)
+                        })
+                        .collect();
+
+                let mut verified_by_linear_algebra = false;
+                if let Some(linear_unfixed_signals) = maybe_linear_unfixed_signals {
+                    if !pol_systems_to_be_fixed.is_empty() {
+                        let unfixed_signals: Vec<SignalIndex> =
+                            linear_unfixed_signals.into_iter().flatten().collect();
+
+                        if !unfixed_signals.is_empty() {
+                            return SubComponentVerificationResult {
+                                kind: SubComponentVerificationResultKind::ModuleUnsafe(
+                                    ModuleUnsafeReason::UnfixedSignalsOfLinearSystem(
+                                        unfixed_signals
+                                            .iter()
+                                            .map(|idx| context.signal_name(*idx))
+                                            .collect(),
+                                    ),
+                                ),
+                                subcomponent_name: context.tree_constraints.component_name.clone(),
+                            };
+                        }
+
+                        pol_systems_to_be_fixed.clear();
+                        verified_by_linear_algebra = true;
+                    }
+                }
+
                 // Verification complete, next subcomponents
+                dispatch(&pol_systems_to_be_fixed);
 
                 let mut subcomponent_verification_results: Vec<SubComponentVerificationResult> =
-                    vec![];
-
-                subcomponent_verification_results.reserve(self.sub_components_to_verify.len());
+                    Vec::with_capacity(self.sub_components_to_verify.len());
 
                 // let num_subcomponents = context.tree_constraints.subcomponents.len();
                 // subcomponent_verification_results.reserve(num_subcomponents);
                 //for subcomponent_idx in 0..num_subcomponents {
 
                 for &subcomponent_idx in &self.sub_components_to_verify {
-                    let subcomponent_context =
-                        context.get_subcomponent_context_view(subcomponent_idx);
-                    let mut subcomponent_verification_graph =
-                        VerificationGraph::new(&subcomponent_context, constraint_storage);
-
-                    subcomponent_verification_results.push(
-                        subcomponent_verification_graph
-                            .verify_subcomponents(&subcomponent_context, constraint_storage),
-                    );
+                    subcomponent_verification_results.push(verify_subcomponent(
+                        context,
+                        subcomponent_idx,
+                        constraint_storage,
+                        dispatch,
+                    ));
                 }
 
+                let output_coverage = self.get_output_coverage(context);
+
+                // The subcomponents have been concluded before this module, which is only safe
+                //  if all of them are
+                let (unsafe_subcomponents, trusted_subcomponents) =
+                    SafetyConditions::get_unverified_subcomponents(
+                        &subcomponent_verification_results,
+                    );
+
                 return SubComponentVerificationResult {
                     kind: SubComponentVerificationResultKind::ModuleConditionallySafe(
                         SafetyConditions {
                             subcomponents: subcomponent_verification_results,
                             pol_systems: pol_systems_to_be_fixed,
+                            verified_by_linear_algebra,
+                            skipped_by_filter: false,
+                            output_coverage,
+                            fixing_reasons: self.get_fixing_reasons(),
+                            dead_inputs: self.get_dead_inputs(context),
+                            component_hash,
+                            unsafe_subcomponents,
+                            trusted_subcomponents,
                         },
                     ),
                     subcomponent_name: context.tree_constraints.component_name.clone(),
@@ -408,6 +1131,8 @@ impl VerificationGraph {
                 self.edge_constraints.iter().any(|(_, set)| !set.is_empty());
 
             if !is_there_any_unsafe_constraint_remaining {
+                self.draw_final_svg(context);
+
                 let unsafe_outputs = self
                     .nodes
                     .iter()
@@ -418,7 +1143,7 @@ impl VerificationGraph {
                     kind: SubComponentVerificationResultKind::ModuleUnsafe(
                         ModuleUnsafeReason::UnfixedOutputsAfterPropagation(
                             unsafe_outputs
-                                .map(|idx| context.signal_name_map[idx].clone())
+                                .map(|idx| context.signal_name(*idx))
                                 .collect(),
                         ),
                     ),
@@ -428,6 +1153,10 @@ impl VerificationGraph {
 
             // Else, if there are === constraints remaining, we should merge all === constraint
             //  cycles until there are no more connected components that can be merged
+            if !self.count_propagation_iteration(context) {
+                return self.get_propagation_limit_exception(context);
+            }
+
             let maybe_pol_system =
                 self.merge_unsafe_constraints_connected_component(context, constraint_storage);
 
@@ -439,6 +1168,8 @@ impl VerificationGraph {
 
                 // TODO: Maybe use some heuristic to make a bigger connected component?
                 // TODO: If <== from unfixed signal, add it to connected component.
+                self.draw_final_svg(context);
+
                 return SubComponentVerificationResult {
                     kind: SubComponentVerificationResultKind::Exception(
                         VerificationException::NoUnsafeConstraintConnectedComponentWithoutCycles,
@@ -449,6 +1180,95 @@ impl VerificationGraph {
         }
     }
 
+    // Returns true if the connected component has a === constraint and no incoming directed
+    //  constraint (that is, <== or component constraint) from a signal outside of it
+    fn is_connected_component_selectable(&self, comp: &ConnectedComponent) -> bool {
+        let any_incoming_assignments_from_outside_component =
+            comp.nodes
+                .iter()
+                .any(|signal| match self.incoming_safe_assignments.get(signal) {
+                    None => false,
+                    Some(safe_assignment_idx) => {
+                        let rhs_signals = &self.safe_assignments[*safe_assignment_idx].rhs_signals;
+                        let any_rhs_signal_outside_connected_component =
+                            rhs_signals.iter().any(|s| !comp.nodes.contains(s));
+                        any_rhs_signal_outside_connected_component
+                    }
+                });
+
+        let any_incoming_component_edges = comp.nodes.iter().any(|signal| {
+            match self.nodes[signal] {
+                // Only collect components with inputs from outside the component
+                Node::SubComponentOutputSignal(cmp_index) => {
+                    let cmp_inputs = &self.subcomponents[&cmp_index].input_signals;
+                    let any_cmp_inputs_outside_connected_component =
+                        cmp_inputs.iter().any(|s| !comp.nodes.contains(s));
+
+                    any_cmp_inputs_outside_connected_component
+                }
+                _ => false,
+            }
+        });
+
+        // Check that the chosen connected component has at least one === constraint
+        let any_unsafe_constraint =
+            comp.nodes
+                .iter()
+                .any(|signal| match self.edge_constraints.get(signal) {
+                    None => false,
+                    Some(x) => !x.is_empty(),
+                });
+
+        !(any_incoming_assignments_from_outside_component || any_incoming_component_edges)
+            && any_unsafe_constraint
+    }
+
+    // Hints are signals computed with <-- that are only used in the RHS of safe assignments, such
+    //  as inv in the IsZero gadget: out <== -in*inv + 1; in*out === 0. Even if inv is never fixed,
+    //  the constraints may still fix out, which is checked by adding inv to the polynomial system.
+    //  Returns the connected component extended with the hints that assign its signals, or None
+    //  if there are no such hints.
+    fn add_hint_signals_to_connected_component(
+        &self,
+        mut comp: ConnectedComponent,
+    ) -> Option<ConnectedComponent> {
+        let is_hint_signal = |signal: &SignalIndex| {
+            matches!(self.nodes.get(signal), Some(Node::IntermediateSignal))
+                && !self.incoming_safe_assignments.contains_key(signal)
+                && self
+                    .edge_constraints
+                    .get(signal)
+                    .is_none_or(|constraints| constraints.is_empty())
+                && self
+                    .outgoing_safe_assignments
+                    .get(signal)
+                    .is_some_and(|safe_assignments| {
+                        safe_assignments.iter().all(|safe_assignment_idx| {
+                            let lhs = self.safe_assignments[*safe_assignment_idx].lhs_signal;
+                            comp.nodes.contains(&lhs)
+                        })
+                    })
+        };
+
+        let hint_signals: BTreeSet<SignalIndex> = comp
+            .nodes
+            .iter()
+            .filter_map(|signal| self.incoming_safe_assignments.get(signal))
+            .flat_map(|safe_assignment_idx| {
+                &self.safe_assignments[*safe_assignment_idx].rhs_signals
+            })
+            .filter(|signal| !comp.nodes.contains(signal) && is_hint_signal(signal))
+            .copied()
+            .collect();
+
+        if hint_signals.is_empty() {
+            return None;
+        }
+
+        comp.nodes.extend(hint_signals);
+        Some(comp)
+    }
+
     // This function looks for a connected of === constraints that can be reduced using Groebner
     //  bases. Returns true if it has been able to merge such a connected component, false otherwise
     fn merge_unsafe_constraints_connected_component(
@@ -464,66 +1284,30 @@ impl VerificationGraph {
         // 1. Compute connected components of === constraints
         // FIXME: Check how to we handle components inputs / outputs if we allow components in
         //  each connected component.
-        let connected_components = self.compute_connected_components_unsafe_constraints();
+        let mut connected_components = self.compute_connected_components_unsafe_constraints();
 
         // 2. Look for a connected component of === that does not have any incoming directed constraint
         //  (that is, <== or component constraint) from a signal outside the connected component.
 
         // FIXME: Now we only select a connected component where at least there is a ===
         //  constraint? Maybe there are cases were that is not appropriate.
-        let mut filtered_connected_components = connected_components.iter().filter(|&comp| {
-            let any_incoming_assignments_from_outside_component =
-                comp.nodes
-                    .iter()
-                    .any(|signal| match self.incoming_safe_assignments.get(signal) {
-                        None => false,
-                        Some(safe_assignment_idx) => {
-                            let rhs_signals =
-                                &self.safe_assignments[*safe_assignment_idx].rhs_signals;
-                            let any_rhs_signal_outside_connected_component =
-                                rhs_signals.iter().any(|s| !comp.nodes.contains(s));
-                            any_rhs_signal_outside_connected_component
-                        }
-                    });
-
-            let any_incoming_component_edges = comp.nodes.iter().any(|signal| {
-                match self.nodes[signal] {
-                    // Only collect components with inputs from outside the component
-                    Node::SubComponentOutputSignal(cmp_index) => {
-                        let cmp_inputs = &self.subcomponents[&cmp_index].input_signals;
-                        let any_cmp_inputs_outside_connected_component =
-                            cmp_inputs.iter().any(|s| !comp.nodes.contains(s));
-
-                        any_cmp_inputs_outside_connected_component
-                    }
-                    _ => false,
-                }
-            });
-
-            // Check that the chosen connected component has at least one === constraint
-            let any_unsafe_constraint =
-                comp.nodes
-                    .iter()
-                    .any(|signal| match self.edge_constraints.get(signal) {
-                        None => false,
-                        Some(x) => !x.is_empty(),
-                    });
 
-            !(any_incoming_assignments_from_outside_component || any_incoming_component_edges)
-                && any_unsafe_constraint
-        });
-
-        // The following comments print the number of connected components found before and
-        //  after filtering them for the conditions above.
-
-        // let filtered_vec: Vec<_> = filtered_connected_components.clone().collect();
-        // println!("Unfiltered: {}, Filtered: {}", connected_components.len(), filtered_vec.len());
-
-        let maybe_connected_component = filtered_connected_components.next();
-        let connected_component = maybe_connected_component?;
+        // If there is no such connected component, look for one that only has incoming safe
+        //  assignments from hint signals, and add those hints to it (as in the IsZero gadget)
+        let connected_component = match connected_components
+            .iter()
+            .position(|comp| self.is_connected_component_selectable(comp))
+        {
+            Some(idx) => connected_components.swap_remove(idx),
+            None => connected_components
+                .into_iter()
+                .filter_map(|comp| self.add_hint_signals_to_connected_component(comp))
+                .find(|comp| self.is_connected_component_selectable(comp))?,
+        };
 
         let mut already_added_unsafe_constraints = HashSet::<UnsafeConstraintIndex>::new();
         let mut polynomial_constraints = vec![];
+        let mut polynomial_constraint_indices = vec![];
 
         // Used for debug graph printing
         let mut debug_polynomial_safe_assignments = BTreeSet::new();
@@ -541,6 +1325,7 @@ impl VerificationGraph {
                             .unwrap();
 
                         polynomial_constraints.push(constraint);
+                        polynomial_constraint_indices.push(unsafe_constraint.associated_constraint);
                         debug_polynomial_unsafe_constraints.insert(*unsafe_constraint_index);
                         already_added_unsafe_constraints.insert(*unsafe_constraint_index);
                     }
@@ -556,6 +1341,7 @@ impl VerificationGraph {
 
                 polynomial_constraints
                     .push(constraint_storage.read_constraint(constraint_idx).unwrap());
+                polynomial_constraint_indices.push(constraint_idx);
                 debug_polynomial_safe_assignments.insert(*safe_assignment_index);
             }
 
@@ -576,7 +1362,7 @@ impl VerificationGraph {
         // Compute the signals to fix, which are the signals which have dependencies outside the
         //  connected component
 
-        let mut signals_to_fix = connected_component
+        let signals_to_fix: Vec<SignalIndex> = connected_component
             .nodes
             .iter()
             .filter(|signal_index| {
@@ -620,7 +1406,14 @@ impl VerificationGraph {
             .collect();
 
         // Fix all the nodes that should be fixed
-        self.fixed_nodes.append(&mut signals_to_fix);
+        for signal in signals_to_fix {
+            fix_signal(
+                &mut self.fixed_nodes,
+                &mut self.fixing_reasons,
+                signal,
+                FixingReason::PolynomialSystem(polynomial_constraint_indices.clone()),
+            );
+        }
 
         // Draw the state of the component graph now
 
@@ -652,6 +1445,7 @@ impl VerificationGraph {
                     )
                         .as_str(),
                 ),
+                false,
             )
             .unwrap();
 
@@ -734,11 +1528,23 @@ impl VerificationGraph {
                     )
                         .as_str(),
                 ),
+                false,
             )
             .unwrap();
 
+        // Intermediate signals determined by a linear constraint do not need to be part of the
+        //  system sent to the Computer Algebra System
+        let (polynomial_constraints, polynomial_constraint_indices) =
+            eliminate_linear_intermediate_signals(
+                polynomial_constraints,
+                polynomial_constraint_indices,
+                &self.fixed_nodes,
+                &context.field,
+            );
+
         let polynomial_system = PolynomialSystemFixedSignal {
             constraints: polynomial_constraints,
+            constraint_indices: polynomial_constraint_indices,
             signals_to_fix: self.fixed_nodes.clone(),
             template_name: context.tree_constraints.template_name.clone(),
             component_name: context.tree_constraints.component_name.clone(),
@@ -747,6 +1553,118 @@ impl VerificationGraph {
         Some(polynomial_system)
     }
 
+    // Returns the signals, constraints and subcomponents the given output depends on, by following
+    //  the reasons why each signal has been fixed. A signal only depends on the signals of its
+    //  fixing constraints that were fixed before it. Requires the original constraints
+    fn get_output_dependencies(&self, output: SignalIndex) -> OutputDependencies {
+        let mut constraints = BTreeSet::new();
+        let mut subcomponents = BTreeSet::new();
+        let mut visited = BTreeSet::from([output]);
+        let mut pending = vec![output];
+
+        while let Some(signal) = pending.pop() {
+            let Some((order, reason)) = self.fixing_reasons.get(&signal) else {
+                continue;
+            };
+
+            let dependencies: BTreeSet<SignalIndex> = match reason {
+                FixingReason::SubComponentOutput(cmp_index) => {
+                    subcomponents.insert(*cmp_index);
+                    self.subcomponents[cmp_index].original_input_signals.clone()
+                }
+                _ => {
+                    let constraint_indices = reason.constraint_indices();
+                    constraints.extend(constraint_indices);
+                    constraint_indices
+                        .iter()
+                        .flat_map(|idx| {
+                            self.original_constraints[idx].take_cloned_signals_ordered()
+                        })
+                        .collect()
+                }
+            };
+
+            for dependency in dependencies {
+                let fixed_before = self
+                    .fixing_reasons
+                    .get(&dependency)
+                    .is_some_and(|(dependency_order, _)| dependency_order < order);
+                if fixed_before && visited.insert(dependency) {
+                    pending.push(dependency);
+                }
+            }
+        }
+
+        OutputDependencies {
+            signals: visited,
+            constraints,
+            subcomponents,
+        }
+    }
+
+    // Returns the constraints involved in fixing each output of the component. Returns an empty
+    //  list if the coverage has not been requested, as the original constraints are needed
+    pub fn get_output_coverage(&self, context: &InputDataContextView) -> Vec<OutputCoverage> {
+        if !context.options.coverage {
+            return vec![];
+        }
+
+        let tree_constraints = context.tree_constraints;
+        let outputs = tree_constraints.initial_signal
+            ..(tree_constraints.initial_signal + tree_constraints.number_outputs);
+
+        outputs
+            .map(|output| {
+                let OutputDependencies {
+                    constraints,
+                    subcomponents,
+                    ..
+                } = self.get_output_dependencies(output);
+
+                OutputCoverage {
+                    output_name: context.signal_name(output),
+                    constraints: constraints
+                        .into_iter()
+                        .map(|idx| {
+                            (
+                                idx,
+                                get_readable_constraint(&self.original_constraints[&idx], context),
+                            )
+                        })
+                        .collect(),
+                    subcomponents: subcomponents
+                        .into_iter()
+                        .map(|idx| tree_constraints.subcomponents[idx].component_name.clone())
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    // Returns the names of the inputs of the component that no output depends on, which usually
+    //  means a constraint using them has been forgotten. Unlike unused signals, these inputs may
+    //  still appear in constraints. Returns an empty list if the dead inputs have not been
+    //  requested, as the original constraints are needed
+    pub fn get_dead_inputs(&self, context: &InputDataContextView) -> Vec<String> {
+        if !context.options.report_dead_inputs {
+            return vec![];
+        }
+
+        let tree_constraints = context.tree_constraints;
+        let outputs = tree_constraints.initial_signal
+            ..(tree_constraints.initial_signal + tree_constraints.number_outputs);
+        let inputs = outputs.end..(outputs.end + tree_constraints.number_inputs);
+
+        let live_signals: BTreeSet<SignalIndex> = outputs
+            .flat_map(|output| self.get_output_dependencies(output).signals)
+            .collect();
+
+        inputs
+            .filter(|input| !live_signals.contains(input))
+            .map(|input| context.signal_name(input))
+            .collect()
+    }
+
     fn compute_connected_components_unsafe_constraints(&self) -> Vec<ConnectedComponent> {
         let mut remaining_nodes = self.nodes.clone();
         let mut connected_components = Vec::new();
@@ -810,12 +1728,18 @@ impl VerificationGraph {
 
     // This function will propagate the fixed_nodes through the different type of constraints by
     // substituting the fixed value into all the appearing constraints, fixing
+    // Returns false if the maximum number of propagation iterations is exceeded before every fixed
+    //  node has been propagated
     fn propagate_fixed_nodes(
         &mut self,
         context: &InputDataContextView,
         constraint_storage: &mut ConstraintStorage,
-    ) {
+    ) -> bool {
         while !self.fixed_nodes.is_empty() {
+            if !self.count_propagation_iteration(context) {
+                return false;
+            }
+
             let node = self.fixed_nodes.pop_last().unwrap();
             self.propagate_fixed_node(node, context, constraint_storage);
 
@@ -827,6 +1751,60 @@ impl VerificationGraph {
         if context.options.generate_svg_diagrams {
             self.draw_propagation_svg(context);
         }
+
+        true
+    }
+
+    // Counts a new propagation step, returning false if it exceeds the maximum number of steps
+    fn count_propagation_iteration(&mut self, context: &InputDataContextView) -> bool {
+        self.propagation_iterations += 1;
+        self.propagation_iterations <= context.options.propagation_max_iterations
+    }
+
+    fn get_propagation_limit_exception(
+        &self,
+        context: &InputDataContextView,
+    ) -> SubComponentVerificationResult {
+        self.draw_final_svg(context);
+
+        let unfixed_signals = self
+            .nodes
+            .keys()
+            .filter(|signal| !self.fixed_nodes.contains(signal))
+            .map(|signal| context.signal_name(*signal))
+            .collect();
+
+        SubComponentVerificationResult {
+            kind: SubComponentVerificationResultKind::Exception(
+                VerificationException::PropagationIterationLimitExceeded {
+                    max_iterations: context.options.propagation_max_iterations,
+                    unfixed_signals,
+                },
+            ),
+            subcomponent_name: context.tree_constraints.component_name.clone(),
+        }
+    }
+
+    // Draws the last state of the graph of a component that could not be verified, highlighting
+    //  the outputs that remain unfixed
+    fn draw_final_svg(&self, context: &InputDataContextView) {
+        context
+            .svg_printer
+            .print_verification_graph(
+                self,
+                context,
+                format!("final-{}", context.tree_constraints.component_name).as_str(),
+                Some(
+                    format!(
+                        "{}: {}",
+                        context.tree_constraints.component_name,
+                        context.tree_constraints.template_name
+                    )
+                        .as_str(),
+                ),
+                true,
+            )
+            .unwrap();
     }
 
     fn draw_propagation_svg(&self, context: &InputDataContextView) {
@@ -844,6 +1822,7 @@ impl VerificationGraph {
                     )
                         .as_str(),
                 ),
+                false,
             )
             .unwrap();
     }
@@ -928,6 +1907,7 @@ impl VerificationGraph {
 
                 propagate_fixed_node_in_safe_assignment(
                     &mut self.fixed_nodes,
+                    &mut self.fixing_reasons,
                     ass,
                     &mut self.incoming_safe_assignments,
                 );
@@ -958,6 +1938,7 @@ impl VerificationGraph {
                 propagate_fixed_node_in_unsafe_constraint(
                     constraint_storage,
                     &mut self.fixed_nodes,
+                    &mut self.fixing_reasons,
                     unsafe_constraint,
                 );
             }
@@ -978,7 +1959,12 @@ impl VerificationGraph {
                 self.sub_components_to_verify.push(cmp_index);
 
                 for output_signal in &cmp.output_signals {
-                    self.fixed_nodes.insert(*output_signal);
+                    fix_signal(
+                        &mut self.fixed_nodes,
+                        &mut self.fixing_reasons,
+                        *output_signal,
+                        FixingReason::SubComponentOutput(cmp_index),
+                    );
                 }
             }
         }
@@ -1014,7 +2000,7 @@ fn substitute_witness_signal_into_storage(
     let mut substitution_to_coefficients = HashMap::new();
     substitution_to_coefficients.insert(
         Constraint::constant_coefficient(),
-        context.witness[&fixed_signal].clone(),
+        context.witness_value(fixed_signal).clone(),
     );
 
     let substitution = Substitution::<usize>::new(
@@ -1035,16 +2021,130 @@ fn substitute_witness_signal_into_storage(
     constraint
 }
 
+// Eliminates from a polynomial system every signal not in signals_to_keep that is determined by a
+//  linear constraint, by substituting its value in terms of the other signals into the remaining
+//  constraints and dropping that linear constraint. As each eliminated signal is a function of the
+//  remaining ones, the set of values the kept signals can take does not change. The original index
+//  of each remaining constraint is returned alongside it.
+fn eliminate_linear_intermediate_signals(
+    mut constraints: Vec<Constraint<usize>>,
+    mut constraint_indices: Vec<ConstraintIndex>,
+    signals_to_keep: &BTreeSet<SignalIndex>,
+    field: &BigInt,
+) -> (Vec<Constraint<usize>>, Vec<ConstraintIndex>) {
+    loop {
+        // Choose the smallest eliminable signal, so the resulting system is deterministic
+        let maybe_elimination = constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, constraint)| Constraint::is_linear(constraint))
+            .filter_map(|(constraint_idx, constraint)| {
+                constraint
+                    .c()
+                    .iter()
+                    .filter(|(signal, coefficient)| {
+                        **signal != Constraint::<usize>::constant_coefficient()
+                            && !signals_to_keep.contains(signal)
+                            && !coefficient.is_zero()
+                    })
+                    .map(|(signal, _)| (*signal, constraint_idx))
+                    .min()
+            })
+            .min();
+
+        let Some((signal, constraint_idx)) = maybe_elimination else {
+            return (constraints, constraint_indices);
+        };
+
+        let linear_constraint = constraints.remove(constraint_idx);
+        constraint_indices.remove(constraint_idx);
+        let substitution = Constraint::clear_signal_from_linear(linear_constraint, &signal, field);
+
+        for constraint in &mut constraints {
+            Constraint::apply_substitution(constraint, &substitution, field);
+            Constraint::fix_constraint(constraint, field);
+        }
+
+        // Constraints that have become 0 = 0 are trivially satisfied
+        (constraints, constraint_indices) = constraints
+            .into_iter()
+            .zip(constraint_indices)
+            .filter(|(constraint, _)| !constraint.is_empty())
+            .unzip();
+    }
+}
+
+// Verifies the subcomponent with the given index of the component of context, assuming that its
+//  inputs are fixed
+fn verify_subcomponent(
+    context: &InputDataContextView,
+    subcomponent_idx: ComponentIndex,
+    constraint_storage: &mut ConstraintStorage,
+    dispatch: &mut dyn FnMut(&[PolynomialSystemFixedSignal]),
+) -> SubComponentVerificationResult {
+    let subcomponent_context = context.get_subcomponent_context_view(subcomponent_idx);
+    let subcomponent_name = subcomponent_context.tree_constraints.component_name.clone();
+
+    // Trusted components are black boxes: their outputs have already been fixed because their
+    //  inputs are, so there is nothing else to verify
+    if subcomponent_context.is_template_trusted() {
+        return SubComponentVerificationResult {
+            kind: SubComponentVerificationResultKind::ModuleTrusted(
+                subcomponent_context.tree_constraints.template_name.clone(),
+            ),
+            subcomponent_name,
+        };
+    }
+
+    // Components identical to a reference are as safe as it, so neither them nor their
+    //  subcomponents are verified
+    if let Some(reference) = subcomponent_context.find_equivalent_reference(constraint_storage) {
+        return SubComponentVerificationResult {
+            kind: SubComponentVerificationResultKind::ModuleEquivalentToReference(
+                reference.path.display().to_string(),
+            ),
+            subcomponent_name,
+        };
+    }
+
+    let mut subcomponent_verification_graph =
+        VerificationGraph::new(&subcomponent_context, constraint_storage);
+    subcomponent_verification_graph.verify_subcomponents_dispatching(
+        &subcomponent_context,
+        constraint_storage,
+        dispatch,
+    )
+}
+
 // This function checks a safe assignment. If all RHS values have been fixed, the LHS will
 // also be fixed. Called both on creation of the VerificationGraph and on fixed node propagation
+// Marks a signal as fixed, so it is propagated, and records why. If the signal was already fixed,
+//  the first reason is kept
+fn fix_signal(
+    fixed_nodes: &mut BTreeSet<SignalIndex>,
+    fixing_reasons: &mut BTreeMap<SignalIndex, (usize, FixingReason)>,
+    signal: SignalIndex,
+    reason: FixingReason,
+) {
+    fixed_nodes.insert(signal);
+    let order = fixing_reasons.len();
+    fixing_reasons.entry(signal).or_insert((order, reason));
+}
+
 fn propagate_fixed_node_in_safe_assignment(
     fixed_nodes: &mut BTreeSet<SignalIndex>,
+    fixing_reasons: &mut BTreeMap<SignalIndex, (usize, FixingReason)>,
     assignment: &mut SafeAssignment,
     incoming_safe_assignments: &mut BTreeMap<SignalIndex, SafeAssignmentIndex>,
 ) {
     // Fix the LHS of a '<==' assignment if the RHS does not have any signals (are constants)
     if assignment.rhs_signals.is_empty() {
-        fixed_nodes.insert(assignment.lhs_signal);
+        fix_signal(
+            fixed_nodes,
+            fixing_reasons,
+            assignment.lhs_signal,
+            FixingReason::SafeAssignment(assignment.associated_constraint),
+        );
 
         // Clean up constraint
         incoming_safe_assignments.remove(&assignment.lhs_signal);
@@ -1057,6 +2157,7 @@ fn propagate_fixed_node_in_safe_assignment(
 fn propagate_fixed_node_in_unsafe_constraint(
     constraint_storage: &ConstraintStorage,
     fixed_nodes: &mut BTreeSet<SignalIndex>,
+    fixing_reasons: &mut BTreeMap<SignalIndex, (usize, FixingReason)>,
     unsafe_constraint: &mut UnsafeConstraint,
 ) {
     // Fix the only signal of a === constraint if it is the only signal, the constraint is
@@ -1079,7 +2180,12 @@ fn propagate_fixed_node_in_unsafe_constraint(
             let coefficient = constraint.c().get(signal).unwrap_or(&zero);
 
             if !coefficient.is_zero() {
-                fixed_nodes.insert(*signal);
+                fix_signal(
+                    fixed_nodes,
+                    fixing_reasons,
+                    *signal,
+                    FixingReason::LinearConstraint(unsafe_constraint.associated_constraint),
+                );
 
                 // Clean up constraint
                 unsafe_constraint.active = false;
@@ -1087,3 +2193,681 @@ fn propagate_fixed_node_in_unsafe_constraint(
         }
     }
 }
+
+#[test]
+fn test_eliminate_linear_intermediate_signals() {
+    use std::str::FromStr;
+
+    let field = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let minus_one = &field - BigInt::from(1);
+    let constant = Constraint::<usize>::constant_coefficient();
+
+    // Signals 1 (x) and 5 (out) have to be fixed, while 2 (a), 3 (b) and 4 (c) are intermediate
+    //  signals defined by a chain of linear relations: a = x + 1, b = 2a, c = b + 3, out = c*c
+    let linear = |coefficients: Vec<(usize, BigInt)>| {
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            coefficients.into_iter().collect(),
+        )
+    };
+    let constraints = vec![
+        linear(vec![
+            (2, BigInt::from(1)),
+            (1, minus_one.clone()),
+            (constant, minus_one.clone()),
+        ]),
+        linear(vec![(3, BigInt::from(1)), (2, &field - BigInt::from(2))]),
+        linear(vec![
+            (4, BigInt::from(1)),
+            (3, minus_one.clone()),
+            (constant, &field - BigInt::from(3)),
+        ]),
+        Constraint::new(
+            HashMap::from([(4, BigInt::from(1))]),
+            HashMap::from([(4, BigInt::from(1))]),
+            HashMap::from([(5, minus_one.clone())]),
+        ),
+    ];
+
+    let signals_to_keep = BTreeSet::from([1, 5]);
+    let (eliminated, eliminated_indices) = eliminate_linear_intermediate_signals(
+        constraints,
+        vec![10, 11, 12, 13],
+        &signals_to_keep,
+        &field,
+    );
+
+    // Only out = (2x + 5)^2 remains, which comes from out = c*c
+    assert_eq!(eliminated.len(), 1);
+    assert_eq!(eliminated_indices, vec![13]);
+    let signals: BTreeSet<_> = eliminated[0]
+        .a()
+        .keys()
+        .chain(eliminated[0].b().keys())
+        .chain(eliminated[0].c().keys())
+        .copied()
+        .collect();
+    assert_eq!(signals, BTreeSet::from([constant, 1, 5]));
+
+    // The remaining constraint must hold for x = 1, out = 49 and not for x = 1, out = 48
+    let evaluate = |lc: &HashMap<usize, BigInt>, out: u32| -> BigInt {
+        lc.iter()
+            .map(|(signal, coefficient)| {
+                let value = match *signal {
+                    1 => BigInt::from(1),
+                    5 => BigInt::from(out),
+                    _ => BigInt::from(1),
+                };
+                coefficient * value
+            })
+            .sum()
+    };
+    let holds = |out: u32| {
+        let c = &eliminated[0];
+        let value = evaluate(c.a(), out) * evaluate(c.b(), out) + evaluate(c.c(), out);
+        (value % &field).is_zero()
+    };
+    assert!(holds(49));
+    assert!(!holds(48));
+}
+
+#[test]
+fn test_degenerate_components() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+
+    let field = BigInt::from(101);
+
+    // Subcomponent with an input and an output that is not constrained at all, so it is unsafe
+    let unconstrained_subcomponent =
+        |initial_signal: usize, number_inputs: usize| TreeConstraints {
+            template_name: "Unconstrained()".to_string(),
+            component_name: "main.sub".to_string(),
+            number_inputs,
+            number_outputs: 1,
+            number_signals: 1 + number_inputs,
+            initial_signal,
+            ..Default::default()
+        };
+
+    let verify_circuit = |constraint: Constraint<usize>, tree_constraints: TreeConstraints| {
+        let mut constraint_storage = ConstraintStorage::new();
+        constraint_storage.add_constraint(constraint);
+        let witness = (0..4).map(|signal| (signal, BigInt::from(5))).collect();
+        let signal_name_map = (0..4)
+            .map(|signal| (signal, format!("s{}", signal)))
+            .collect();
+
+        let (context, mut constraint_storage) = InputDataContext::from_data(
+            constraint_storage,
+            Some(witness),
+            signal_name_map,
+            TreeConstraints {
+                field: field.to_string(),
+                template_name: "Main()".to_string(),
+                component_name: "main".to_string(),
+                no_constraints: 1,
+                initial_signal: 1,
+                number_signals: 3,
+                ..tree_constraints
+            },
+            Options::default(),
+        )
+        .unwrap();
+        let context = context.get_context_view();
+
+        VerificationGraph::new(&context, &constraint_storage)
+            .verify_subcomponents(&context, &mut constraint_storage)
+            .kind
+    };
+    // The degenerate component itself needs no polynomial systems, but its unsafe subcomponent
+    //  must still be verified and reported
+    let reports_unsafe_subcomponent = |kind: SubComponentVerificationResultKind| match kind {
+        SubComponentVerificationResultKind::ModuleConditionallySafe(conditions) => {
+            conditions.pol_systems.is_empty()
+                && conditions.subcomponents.len() == 1
+                && conditions.unsafe_subcomponents == vec!["main.sub"]
+        }
+        _ => false,
+    };
+
+    // Sink component: signal input in; sub.in === in;
+    let sink = verify_circuit(
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(3, BigInt::from(1)), (1, &field - 1)]),
+        ),
+        TreeConstraints {
+            number_inputs: 1,
+            subcomponents: vec![unconstrained_subcomponent(2, 1)],
+            ..Default::default()
+        },
+    );
+    assert!(reports_unsafe_subcomponent(sink));
+
+    // Constant component: signal output out; out <== 5; with a subcomponent without inputs
+    let constant = verify_circuit(
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(1, BigInt::from(1)), (0, &field - 5)]),
+        ),
+        TreeConstraints {
+            number_outputs: 1,
+            are_double_arrow: vec![(0, 1)],
+            subcomponents: vec![unconstrained_subcomponent(2, 0)],
+            ..Default::default()
+        },
+    );
+    assert!(reports_unsafe_subcomponent(constant));
+}
+
+#[test]
+fn test_unsafe_subcomponent_taints_parent() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+    use crate::report::{Verdict, VerificationReport};
+
+    let field = BigInt::from(101);
+    let one = || BigInt::from(1);
+    let (out, input, sub_out, sub_in) = (1, 2, 3, 4);
+
+    // out <== sub.out; sub.in <== in; where the output of sub is not constrained at all
+    let verify = |options: Options| {
+        let mut constraint_storage = ConstraintStorage::new();
+        constraint_storage.add_constraint(Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(out, one()), (sub_out, &field - 1)]),
+        ));
+        constraint_storage.add_constraint(Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(sub_in, one()), (input, &field - 1)]),
+        ));
+
+        let (context, mut constraint_storage) = InputDataContext::from_data(
+            constraint_storage,
+            Some((0..5).map(|signal| (signal, BigInt::from(5))).collect()),
+            (1..5)
+                .map(|signal| (signal, format!("s{}", signal)))
+                .collect(),
+            TreeConstraints {
+                field: field.to_string(),
+                template_name: "Main()".to_string(),
+                component_name: "main".to_string(),
+                number_outputs: 1,
+                number_inputs: 1,
+                number_signals: 2,
+                initial_signal: 1,
+                no_constraints: 2,
+                are_double_arrow: vec![(0, out), (1, sub_in)],
+                subcomponents: vec![TreeConstraints {
+                    template_name: "Broken()".to_string(),
+                    component_name: "main.sub".to_string(),
+                    number_outputs: 1,
+                    number_inputs: 1,
+                    number_signals: 2,
+                    initial_signal: sub_out,
+                    initial_constraint: 2,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            options,
+        )
+        .unwrap();
+        let context = context.get_context_view();
+
+        let res = VerificationGraph::new(&context, &constraint_storage)
+            .verify_subcomponents(&context, &mut constraint_storage);
+        let report = VerificationReport::new(&res, &[], &[], false, &context);
+        let verdicts: Vec<(String, Verdict)> = report
+            .components
+            .into_iter()
+            .map(|component| (component.component_name, component.verdict))
+            .collect();
+        let SubComponentVerificationResultKind::ModuleConditionallySafe(safety_conditions) =
+            res.kind
+        else {
+            panic!("main should only depend on its subcomponent")
+        };
+        (safety_conditions, verdicts)
+    };
+
+    // Main fixes its output by itself, but relies on its broken subcomponent to do so
+    let (safety_conditions, verdicts) = verify(Options::default());
+    assert_eq!(safety_conditions.unsafe_subcomponents, vec!["main.sub"]);
+    assert!(safety_conditions.trusted_subcomponents.is_empty());
+    assert_eq!(
+        verdicts,
+        vec![
+            ("main".to_string(), Verdict::UnsafeSubcomponent),
+            ("main.sub".to_string(), Verdict::Unsafe)
+        ]
+    );
+
+    // Trusting the broken subcomponent makes main safe only under that assumption
+    let (safety_conditions, verdicts) = verify(Options {
+        trusted_templates: vec!["Broken()".to_string()],
+        ..Default::default()
+    });
+    assert!(safety_conditions.unsafe_subcomponents.is_empty());
+    assert_eq!(safety_conditions.trusted_subcomponents, vec!["main.sub"]);
+    assert_eq!(
+        verdicts,
+        vec![
+            ("main".to_string(), Verdict::SafeIfTrusted),
+            ("main.sub".to_string(), Verdict::Trusted)
+        ]
+    );
+
+    // A template trusted by its bare name is trusted whatever its parameters
+    let (_, bare_name_verdicts) = verify(Options {
+        trusted_templates: vec!["Broken".to_string()],
+        ..Default::default()
+    });
+    assert_eq!(bare_name_verdicts, verdicts);
+    assert!(!Verdict::SafeIfTrusted.is_failure());
+}
+
+#[test]
+fn test_graph_metrics() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+
+    let field = BigInt::from(101);
+    let one = || BigInt::from(1);
+    let (out, input, a, b, c) = (1, 2, 3, 4, 5);
+
+    // a <== in; out === a*b; c*c === c
+    let mut constraint_storage = ConstraintStorage::new();
+    for constraint in [
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(a, one()), (input, &field - 1)]),
+        ),
+        Constraint::new(
+            HashMap::from([(a, one())]),
+            HashMap::from([(b, one())]),
+            HashMap::from([(out, &field - 1)]),
+        ),
+        Constraint::new(
+            HashMap::from([(c, one())]),
+            HashMap::from([(c, one())]),
+            HashMap::from([(c, &field - 1)]),
+        ),
+    ] {
+        constraint_storage.add_constraint(constraint);
+    }
+
+    let (context, constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        None,
+        (1..6)
+            .map(|signal| (signal, format!("s{}", signal)))
+            .collect(),
+        TreeConstraints {
+            field: field.to_string(),
+            template_name: "Main()".to_string(),
+            component_name: "main".to_string(),
+            number_outputs: 1,
+            number_inputs: 1,
+            number_signals: 5,
+            initial_signal: 1,
+            no_constraints: 3,
+            are_double_arrow: vec![(0, a)],
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    let verification_graph = VerificationGraph::new(&context, &constraint_storage);
+    assert_eq!(
+        GraphMetrics::new(&verification_graph, &context, &constraint_storage),
+        GraphMetrics {
+            num_inputs: 1,
+            num_outputs: 1,
+            num_intermediates: 3,
+            num_safe_assignments: 1,
+            num_unsafe_constraints: 2,
+            linear_rank: 1,
+            num_non_input_signals: 4,
+            unsafe_connected_component_sizes: vec![3, 1],
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        render_csv(&GraphMetrics::new(
+            &verification_graph,
+            &context,
+            &constraint_storage
+        ))
+        .lines()
+        .nth(1),
+        Some("1,1,3,0,0,0,1,2,1,4,3 1")
+    );
+}
+
+#[test]
+fn test_linear_ranks() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+
+    let field = BigInt::from(101);
+    let one = || BigInt::from(1);
+    let minus = |value: u32| &field - value;
+    let (out, input, a, b) = (1, 2, 3, 4);
+
+    // a === in + 1; b === 2*a; a + b === 3*in + 3, which follows from the other two; out === a*b
+    let mut constraint_storage = ConstraintStorage::new();
+    for constraint in [
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(a, one()), (input, minus(1)), (0, minus(1))]),
+        ),
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(b, one()), (a, minus(2))]),
+        ),
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(a, one()), (b, one()), (input, minus(3)), (0, minus(3))]),
+        ),
+        Constraint::new(
+            HashMap::from([(a, one())]),
+            HashMap::from([(b, one())]),
+            HashMap::from([(out, minus(1))]),
+        ),
+    ] {
+        constraint_storage.add_constraint(constraint);
+    }
+
+    let (context, constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        None,
+        (1..5)
+            .map(|signal| (signal, format!("s{}", signal)))
+            .collect(),
+        TreeConstraints {
+            field: field.to_string(),
+            template_name: "Main()".to_string(),
+            component_name: "main".to_string(),
+            number_outputs: 1,
+            number_inputs: 1,
+            number_signals: 4,
+            initial_signal: 1,
+            no_constraints: 4,
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+
+    // The linear constraints determine a and b, but not out
+    let ranks = LinearRanks::new(&context.get_context_view(), &constraint_storage);
+    assert_eq!(
+        ranks.components,
+        vec![ComponentLinearRank {
+            component_name: "main".to_string(),
+            template_name: "Main()".to_string(),
+            num_linear_constraints: 3,
+            rank: 2,
+            num_non_input_signals: 3,
+        }]
+    );
+    assert!(!ranks.components[0].is_rank_sufficient());
+}
+
+#[test]
+fn test_redundant_constraints() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+
+    let field = BigInt::from(101);
+    let one = || BigInt::from(1);
+    let minus = |value: u32| &field - value;
+    let (out, input, a, b) = (1, 2, 3, 4);
+
+    // a === in + 1; b === 2*a; a + b === 3*in + 3, which follows from the first two;
+    //  2 * (a - 2*in + 1) === 0, which also restricts the input; out === a*b
+    let mut constraint_storage = ConstraintStorage::new();
+    for constraint in [
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(a, one()), (input, minus(1)), (0, minus(1))]),
+        ),
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(b, one()), (a, minus(2))]),
+        ),
+        Constraint::new(
+            HashMap::new(),
+            HashMap::new(),
+            HashMap::from([(a, one()), (b, one()), (input, minus(3)), (0, minus(3))]),
+        ),
+        Constraint::new(
+            HashMap::from([(0, BigInt::from(2))]),
+            HashMap::from([(a, one()), (input, minus(2)), (0, one())]),
+            HashMap::new(),
+        ),
+        Constraint::new(
+            HashMap::from([(a, one())]),
+            HashMap::from([(b, one())]),
+            HashMap::from([(out, minus(1))]),
+        ),
+    ] {
+        constraint_storage.add_constraint(constraint);
+    }
+
+    let (context, constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        None,
+        HashMap::from([
+            (out, "main.out".to_string()),
+            (input, "main.in".to_string()),
+            (a, "main.a".to_string()),
+            (b, "main.b".to_string()),
+        ]),
+        TreeConstraints {
+            field: field.to_string(),
+            template_name: "Main()".to_string(),
+            component_name: "main".to_string(),
+            number_outputs: 1,
+            number_inputs: 1,
+            number_signals: 4,
+            initial_signal: 1,
+            no_constraints: 5,
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+
+    let redundant = RedundantConstraints::new(&context.get_context_view(), &constraint_storage);
+    assert_eq!(
+        redundant.constraints,
+        vec![RedundantConstraint {
+            component_name: "main".to_string(),
+            template_name: "Main()".to_string(),
+            constraint_index: 2,
+            constraint: "-3 - 3*main.in + main.a + main.b = 0".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_output_coverage() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+    use std::path::Path;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let options = Options {
+        coverage: true,
+        ..Default::default()
+    };
+    let (context, mut constraint_storage) =
+        InputDataContext::parse_from_files(&path, options).unwrap();
+    let context = context.get_context_view();
+
+    let mut verification_graph = VerificationGraph::new(&context, &constraint_storage);
+    let result = verification_graph.verify_subcomponents(&context, &mut constraint_storage);
+
+    // out is fixed by the polynomial system of x*out === 0, which also contains x*x === a and the
+    //  assignment of out
+    let SubComponentVerificationResultKind::ModuleConditionallySafe(safety_conditions) =
+        result.kind
+    else {
+        panic!("Expected the component to be conditionally safe");
+    };
+    let [coverage] = safety_conditions.output_coverage.as_slice() else {
+        panic!("Expected the coverage of a single output");
+    };
+    assert_eq!(coverage.output_name, "out");
+    assert_eq!(
+        coverage.constraints,
+        vec![
+            (0, "x * x - a = 0".to_string()),
+            (1, "x * inv - 1 + out = 0".to_string()),
+            (2, "x * out = 0".to_string()),
+        ]
+    );
+    assert!(coverage.subcomponents.is_empty());
+    assert_eq!(
+        verification_graph.fixing_reasons[&2],
+        (0, FixingReason::Input)
+    );
+}
+
+#[test]
+fn test_propagation_iteration_limit() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+    use std::path::Path;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let options = Options {
+        propagation_max_iterations: 1,
+        ..Default::default()
+    };
+    let (context, mut constraint_storage) =
+        InputDataContext::parse_from_files(&path, options).unwrap();
+    let context = context.get_context_view();
+
+    // Propagating the input a takes the only iteration, so merging the === connected component
+    //  exceeds the limit
+    let mut verification_graph = VerificationGraph::new(&context, &constraint_storage);
+    let result = verification_graph.verify_subcomponents(&context, &mut constraint_storage);
+
+    let SubComponentVerificationResultKind::Exception(
+        VerificationException::PropagationIterationLimitExceeded {
+            max_iterations,
+            unfixed_signals,
+        },
+    ) = result.kind
+    else {
+        panic!("Expected the propagation iteration limit to be exceeded");
+    };
+    assert_eq!(max_iterations, 1);
+    assert_eq!(unfixed_signals, vec!["out", "x", "inv"]);
+}
+
+#[test]
+fn test_dead_inputs() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+    use std::path::Path;
+
+    let dead_inputs = |fixture: &str| {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../test/fixtures")
+            .join(fixture);
+        let options = Options {
+            report_dead_inputs: true,
+            ..Default::default()
+        };
+        let (context, mut constraint_storage) =
+            InputDataContext::parse_from_files(&path, options).unwrap();
+        let context = context.get_context_view();
+
+        let mut verification_graph = VerificationGraph::new(&context, &constraint_storage);
+        let result = verification_graph.verify_subcomponents(&context, &mut constraint_storage);
+        let SubComponentVerificationResultKind::ModuleConditionallySafe(safety_conditions) =
+            result.kind
+        else {
+            panic!("Expected the component to be conditionally safe");
+        };
+        safety_conditions.dead_inputs
+    };
+
+    // b appears in b * (b - 1) === 0, but out <== a does not depend on it
+    assert_eq!(dead_inputs("dead_input"), vec!["b"]);
+    assert!(dead_inputs("is_zero_1").is_empty());
+}
+
+#[test]
+fn test_unconstrained_outputs() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+
+    // template Main() { signal input in; signal output out1, out2; out1 <== in; } forgets to
+    //  constrain out2
+    let mut constraint_storage = ConstraintStorage::new();
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::from(1)), (3, BigInt::from(-1))]),
+    ));
+    let (context, mut constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        Some(HashMap::from([
+            (0, BigInt::from(1)),
+            (1, BigInt::from(5)),
+            (2, BigInt::from(0)),
+            (3, BigInt::from(5)),
+        ])),
+        HashMap::from([
+            (1, "out1".to_string()),
+            (2, "out2".to_string()),
+            (3, "in".to_string()),
+        ]),
+        TreeConstraints {
+            field: "11".to_string(),
+            template_name: "Main()".to_string(),
+            component_name: "main".to_string(),
+            number_inputs: 1,
+            number_outputs: 2,
+            number_signals: 3,
+            initial_signal: 1,
+            no_constraints: 1,
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    let mut verification_graph = VerificationGraph::new(&context, &constraint_storage);
+    let result = verification_graph.verify_subcomponents(&context, &mut constraint_storage);
+    let SubComponentVerificationResultKind::ModuleUnsafe(ModuleUnsafeReason::UnconstrainedOutputs(
+        unconstrained_outputs,
+    )) = result.kind
+    else {
+        panic!("Expected out2 to be reported as unconstrained");
+    };
+    assert_eq!(unconstrained_outputs, vec!["out2"]);
+}
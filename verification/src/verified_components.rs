@@ -0,0 +1,220 @@
+use crate::cli::Options;
+use crate::error::VerifierResult;
+use crate::polynomial_system_fixer::{PolSystemResult, PolSystemVerdict};
+use crate::verifier::SubComponentVerificationResultKind::{
+    Exception, ModuleCachedSafe, ModuleConditionallySafe, ModuleEquivalentToReference,
+    ModuleTrusted, ModuleUnsafe,
+};
+use crate::verifier::{PolynomialSystemFixedSignal, SubComponentVerificationResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+// File inside the output folder where the components found safe are recorded, so later runs with
+//  the skip_verified option do not verify them again
+pub const VERIFIED_COMPONENTS_FILE_NAME: &str = "verified.json";
+
+// Components found safe by previous runs. Each of them is identified by the hash of its canonical
+//  form (see CanonicalComponent::get_hash), so it is recognized even if the rest of the circuit
+//  changes, and is no longer recognized as soon as any of its constraints or those of its
+//  subcomponents change
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct VerifiedComponents {
+    // Name of the component each hash has first been recorded for, only meant for inspecting the
+    //  file
+    pub components: BTreeMap<String, String>,
+}
+
+impl VerifiedComponents {
+    pub fn contains(&self, component_hash: &str) -> bool {
+        self.components.contains_key(component_hash)
+    }
+
+    // Returns the components recorded in the given folder, or none if it has no verified
+    //  components file
+    pub fn read_from_folder(base_path: &Path) -> VerifierResult<VerifiedComponents> {
+        let path = base_path.join(VERIFIED_COMPONENTS_FILE_NAME);
+        if !path.exists() {
+            return Ok(VerifiedComponents::default());
+        }
+
+        let f = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(f))?)
+    }
+
+    pub fn write_to_folder(&self, base_path: &Path) -> VerifierResult<()> {
+        let path = base_path.join(VERIFIED_COMPONENTS_FILE_NAME);
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // Records every verified component of the verification result that is safe: all its
+    //  polynomial systems have been fixed and all its subcomponents are safe. pol_system_results
+    //  contains the result of each polynomial system in pol_systems, and is empty if CoCoA has not
+    //  been run. Returns the number of components that were not recorded yet
+    pub fn record_safe_components(
+        &mut self,
+        verification_result: &SubComponentVerificationResult,
+        pol_systems: &[PolynomialSystemFixedSignal],
+        pol_system_results: &[PolSystemResult],
+    ) -> usize {
+        let num_components = self.components.len();
+        self.record_if_safe(verification_result, pol_systems, pol_system_results);
+        self.components.len() - num_components
+    }
+
+    // Returns true if the component of the verification result is safe, recording it and its safe
+    //  subcomponents
+    fn record_if_safe(
+        &mut self,
+        verification_result: &SubComponentVerificationResult,
+        pol_systems: &[PolynomialSystemFixedSignal],
+        pol_system_results: &[PolSystemResult],
+    ) -> bool {
+        match &verification_result.kind {
+            ModuleCachedSafe | ModuleEquivalentToReference(_) => true,
+
+            // Trusted components have not been verified, so neither have the components that
+            //  contain them
+            ModuleUnsafe(_) | Exception(_) | ModuleTrusted(_) => false,
+            ModuleConditionallySafe(safety_conditions) => {
+                // Every subcomponent is visited, so the safe ones are recorded even if others
+                //  are not
+                let mut safe = true;
+                for subcomponent in &safety_conditions.subcomponents {
+                    safe &= self.record_if_safe(subcomponent, pol_systems, pol_system_results);
+                }
+
+                safe &= !safety_conditions.skipped_by_filter
+                    && pol_systems
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, pol_system)| {
+                            pol_system.component_name == verification_result.subcomponent_name
+                        })
+                        .all(|(idx, _)| {
+                            pol_system_results
+                                .get(idx)
+                                .is_some_and(|result| result.verdict == PolSystemVerdict::Fixed)
+                        });
+
+                match &safety_conditions.component_hash {
+                    Some(component_hash) if safe => {
+                        self.components
+                            .entry(component_hash.clone())
+                            .or_insert_with(|| verification_result.subcomponent_name.clone());
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+// Loads the components recorded in the output folder if the skip_verified option is set
+pub fn load_verified_components(
+    options: &Options,
+    output_path: &Path,
+) -> VerifierResult<VerifiedComponents> {
+    if options.skip_verified {
+        VerifiedComponents::read_from_folder(output_path)
+    } else {
+        Ok(VerifiedComponents::default())
+    }
+}
+
+#[test]
+fn test_verified_components() {
+    use crate::verification_graph::VerificationGraph;
+    use crate::InputDataContext;
+    use circom_algebra::algebra::Constraint;
+    use num_bigint_dig::BigInt;
+    use num_traits::One;
+    use std::collections::HashMap;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let out_dir = std::env::temp_dir().join(format!("verified-components-{}", std::process::id()));
+    let options = Options {
+        skip_verified: true,
+        out_dir: Some(out_dir.clone()),
+        ..Default::default()
+    };
+
+    // Verifies the circuit, optionally replacing its first constraint, and returns the result
+    //  together with its polynomial systems
+    let verify = |replaced_constraint: Option<Constraint<usize>>| {
+        let (context, mut constraint_storage) =
+            InputDataContext::parse_from_files(&path, options.clone()).unwrap();
+        if let Some(constraint) = replaced_constraint {
+            constraint_storage.replace(0, constraint);
+        }
+        let context = context.get_context_view();
+        let res = VerificationGraph::new(&context, &constraint_storage)
+            .verify_subcomponents(&context, &mut constraint_storage);
+
+        let mut pol_systems = vec![];
+        res.apply(&mut |res| {
+            if let ModuleConditionallySafe(safety_conditions) = &res.kind {
+                pol_systems.extend(safety_conditions.pol_systems.iter().cloned());
+            }
+        });
+        (res, pol_systems)
+    };
+    let results = |num_pol_systems: usize, verdict: PolSystemVerdict| -> Vec<PolSystemResult> {
+        (0..num_pol_systems)
+            .map(|_| PolSystemResult {
+                verdict,
+                duration: None,
+            })
+            .collect()
+    };
+
+    let (res, pol_systems) = verify(None);
+    assert!(!pol_systems.is_empty());
+
+    // Components with a polynomial system that is not fixed are not recorded
+    let mut verified_components = VerifiedComponents::default();
+    let many_solutions = results(pol_systems.len(), PolSystemVerdict::ManySolutions);
+    assert_eq!(
+        verified_components.record_safe_components(&res, &pol_systems, &many_solutions),
+        0
+    );
+    assert_eq!(
+        verified_components.record_safe_components(&res, &pol_systems, &[]),
+        0
+    );
+
+    let fixed = results(pol_systems.len(), PolSystemVerdict::Fixed);
+    assert_eq!(
+        verified_components.record_safe_components(&res, &pol_systems, &fixed),
+        1
+    );
+    assert_eq!(
+        verified_components.components.values().collect::<Vec<_>>(),
+        vec!["main"]
+    );
+    verified_components.write_to_folder(&out_dir).unwrap();
+    assert_eq!(
+        VerifiedComponents::read_from_folder(&out_dir).unwrap(),
+        verified_components
+    );
+
+    // The next run skips the component, unless one of its constraints changes
+    let (res, pol_systems) = verify(None);
+    assert!(matches!(res.kind, ModuleCachedSafe));
+    assert!(pol_systems.is_empty());
+
+    let (res, _) = verify(Some(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::one()), (2, BigInt::from(3))]),
+    )));
+    assert!(!matches!(res.kind, ModuleCachedSafe));
+
+    std::fs::remove_dir_all(out_dir).unwrap();
+}
@@ -1,23 +1,53 @@
-use crate::input_data::{InputDataContextView, SignalIndex};
-use crate::polynomial_system_fixer::verify_pol_systems;
-use crate::verification_graph::VerificationGraph;
-use crate::verifier::ModuleUnsafeReason::UnfixedOutputsAfterPropagation;
+use crate::component_tree_printer::write_component_tree_svg;
+use crate::error::{VerifierError, VerifierResult};
+use crate::graphml_printer::write_verification_graph_graphml;
+use crate::input_data::{
+    ArtifactPaths, ConstraintIndex, InputDataContextView, SignalIndex, SignalNameMap, Witness,
+};
+use crate::mermaid_printer::write_verification_graph_mermaid;
+use crate::polynomial_system_fixer::{
+    build_functional_uniqueness_system, build_monolithic_pol_system,
+    build_whole_circuit_pol_system, check_functional_uniqueness, check_signals_individually,
+    combine_witness_pol_systems, dump_cas_json, dump_pol_systems, export_ideals,
+    get_pol_system_stats, get_witness_copy_signal, is_total_timeout_exceeded, remove_progress_file,
+    report_failure_minimization, report_sampled_ambiguities, verify_monolithic_pol_system,
+    verify_pol_systems, verify_pol_systems_in_parallel, PolSystemResult, PolSystemVerdict,
+    TotalTimeoutWatchdog,
+};
+use crate::report::{get_pol_system_reports, PolSystemSummary, ReportDiff, VerificationReport};
+use crate::sarif::SarifLog;
+use crate::tree_constraint_graph_printer::DebugSVGPrinter;
+use crate::verification_graph::{FixingReason, OutputCoverage, VerificationGraph};
+use crate::verified_components::VERIFIED_COMPONENTS_FILE_NAME;
+use crate::verifier::ModuleUnsafeReason::{
+    UnconstrainedOutputs, UnfixedOutputsAfterPropagation, UnfixedSignalsOfLinearSystem,
+};
 use crate::verifier::SubComponentVerificationResultKind::{
-    Exception, ModuleConditionallySafe, ModuleUnsafe,
+    Exception, ModuleCachedSafe, ModuleConditionallySafe, ModuleEquivalentToReference,
+    ModuleTrusted, ModuleUnsafe,
+};
+use crate::verifier::VerificationException::{
+    NoUnsafeConstraintConnectedComponentWithoutCycles, PropagationIterationLimitExceeded,
 };
-use crate::verifier::VerificationException::NoUnsafeConstraintConnectedComponentWithoutCycles;
 use circom_algebra::algebra::Constraint;
 use circom_algebra::constraint_storage::ConstraintStorage;
 use colored::Colorize;
 use itertools::Itertools;
-use std::collections::BTreeSet;
-use std::error::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
+use std::path::Path;
+use std::time::Duration;
 
 // This structure represents a polynomial system of constraints that should have their output fixed
 #[derive(Clone)]
 pub struct PolynomialSystemFixedSignal {
     pub constraints: Vec<Constraint<usize>>,
 
+    // Index in the constraint storage of each of the constraints above, which is its index in
+    //  circuit_constraints.json. Constraints rewritten by substitutions keep the index they came
+    //  from
+    pub constraint_indices: Vec<ConstraintIndex>,
+
     // Signals to fix from the constraints given above
     pub signals_to_fix: BTreeSet<SignalIndex>,
 
@@ -33,16 +63,92 @@ pub struct SafetyConditions {
 
     // Polynomial systems to be fixed using Groebner Basis for this module to be safe
     pub pol_systems: Vec<PolynomialSystemFixedSignal>,
+
+    // True if the polynomial systems of this module were linear, and have already been proved to
+    //  be fixed by linear algebra instead of Groebner Basis
+    pub verified_by_linear_algebra: bool,
+
+    // True if this module does not match the component filters given by the user, so it has not
+    //  been verified and its outputs are assumed to be fixed
+    pub skipped_by_filter: bool,
+
+    // Constraints involved in fixing each output of this module. Only computed if requested in
+    //  the options
+    pub output_coverage: Vec<OutputCoverage>,
+
+    // Reason why each signal of the verification graph of this module has been fixed, including
+    //  the inputs and outputs of its subcomponents
+    pub fixing_reasons: BTreeMap<SignalIndex, FixingReason>,
+
+    // Names of the inputs of this module that none of its outputs depends on. Only computed if
+    //  requested in the options
+    pub dead_inputs: Vec<String>,
+
+    // Hash identifying the constraints of this module and its subcomponents, under which it is
+    //  recorded as verified if it turns out to be safe. Only computed with the skip_verified option
+    pub component_hash: Option<String>,
+
+    // Subcomponents, direct or nested, found to be unsafe. This module assumes that the outputs of
+    //  its subcomponents are fixed, so it is unsafe as well
+    pub unsafe_subcomponents: Vec<String>,
+
+    // Subcomponents, direct or nested, of a trusted template. This module is only safe if they are
+    pub trusted_subcomponents: Vec<String>,
+}
+
+impl SafetyConditions {
+    // Returns the unsafe and the trusted subcomponents among the given verification results of the
+    //  subcomponents of a module, including those of their own subcomponents. They must have been
+    //  verified before, so components are concluded in post-order
+    pub fn get_unverified_subcomponents(
+        subcomponents: &[SubComponentVerificationResult],
+    ) -> (Vec<String>, Vec<String>) {
+        let mut unsafe_subcomponents = Vec::new();
+        let mut trusted_subcomponents = Vec::new();
+        for subcomponent in subcomponents {
+            match &subcomponent.kind {
+                ModuleUnsafe(_) => {
+                    unsafe_subcomponents.push(subcomponent.subcomponent_name.clone())
+                }
+                ModuleTrusted(_) => {
+                    trusted_subcomponents.push(subcomponent.subcomponent_name.clone())
+                }
+                ModuleConditionallySafe(safety_conditions) => {
+                    unsafe_subcomponents
+                        .extend(safety_conditions.unsafe_subcomponents.iter().cloned());
+                    trusted_subcomponents
+                        .extend(safety_conditions.trusted_subcomponents.iter().cloned());
+                }
+                ModuleEquivalentToReference(_) | ModuleCachedSafe | Exception(_) => {}
+            }
+        }
+        (unsafe_subcomponents, trusted_subcomponents)
+    }
 }
 
 pub enum VerificationException {
     NoUnsafeConstraintConnectedComponentWithoutCycles,
+
+    // The graph propagation has not converged after the maximum number of iterations given in
+    //  the options. Contains the names of the signals not yet fixed
+    PropagationIterationLimitExceeded {
+        max_iterations: u64,
+        unfixed_signals: Vec<String>,
+    },
 }
 
 pub enum ModuleUnsafeReason {
     // A vector of signal names have not been fixed after finishing all possible propagation
     //  and no === remaining
     UnfixedOutputsAfterPropagation(Vec<String>),
+
+    // A vector of signal names that can take several values, as they are not determined by the
+    //  linear constraints of the module
+    UnfixedSignalsOfLinearSystem(Vec<String>),
+
+    // A vector of output names that do not appear in any constraint of the module, so nothing can
+    //  fix them. It usually means that a constraint has been forgotten
+    UnconstrainedOutputs(Vec<String>),
 }
 
 pub enum SubComponentVerificationResultKind {
@@ -52,6 +158,18 @@ pub enum SubComponentVerificationResultKind {
 
     ModuleConditionallySafe(SafetyConditions),
 
+    // The template of this module (given as the String) has been trusted by the user, so the
+    //  module has not been verified
+    ModuleTrusted(String),
+
+    // This module has the same constraints, up to signal renaming, as the reference component
+    //  loaded from the given folder, so it is safe and has not been verified
+    ModuleEquivalentToReference(String),
+
+    // This module has the same constraints as a component found safe by a previous run, recorded
+    //  in the verified components file, so it has not been verified again
+    ModuleCachedSafe,
+
     Exception(VerificationException),
 }
 
@@ -63,10 +181,29 @@ pub struct SubComponentVerificationResult {
 impl SubComponentVerificationResult {
     // If this SubComponentVerificationResult is an error or exception, returns a string message
     //  describing the error. If not, returns none. Does not recurse to subcomponents.
-    fn get_error_string(&self) -> Option<String> {
+    pub fn get_error_string(&self) -> Option<String> {
         match &self.kind {
-            ModuleConditionallySafe(_) => None,
+            ModuleConditionallySafe(_)
+            | ModuleTrusted(_)
+            | ModuleEquivalentToReference(_)
+            | ModuleCachedSafe => None,
             ModuleUnsafe(unsafe_reason) => match unsafe_reason {
+                UnfixedSignalsOfLinearSystem(unfixed_signals) => Some(format!(
+                    "[Unsafe] Component '{}' is unsafe. Signals {} are not determined by its linear constraints",
+                    self.subcomponent_name,
+                    unfixed_signals
+                        .iter()
+                        .map(|s| { format!("'{}'", s) })
+                        .join(", ")
+                )),
+                UnconstrainedOutputs(unconstrained_outputs) => Some(format!(
+                    "[Unsafe] Component '{}' is unsafe. Outputs {} are completely unconstrained, they do not appear in any constraint",
+                    self.subcomponent_name,
+                    unconstrained_outputs
+                        .iter()
+                        .map(|s| { format!("'{}'", s) })
+                        .join(", ")
+                )),
                 UnfixedOutputsAfterPropagation(unfixed_outputs) => {
                     if unfixed_outputs.len() == 1 {
                         Some(format!(
@@ -93,12 +230,48 @@ impl SubComponentVerificationResult {
                         self.subcomponent_name
                     ))
                 }
+                PropagationIterationLimitExceeded {
+                    max_iterations,
+                    unfixed_signals,
+                } => Some(format!(
+                    "[Exception] Graph propagation in component '{}' did not converge after {} iterations, cannot determine safety. Signals not yet fixed: {}",
+                    self.subcomponent_name,
+                    max_iterations,
+                    unfixed_signals
+                        .iter()
+                        .map(|s| { format!("'{}'", s) })
+                        .join(", ")
+                )),
             },
         }
     }
 }
 
 impl SubComponentVerificationResult {
+    // Returns why the given signal has been fixed in this module or its subcomponents, or None if
+    //  it has not been fixed. Signals shared with a subcomponent are reported with the reason of
+    //  the innermost module, except for the inputs of a subcomponent, which are reported with the
+    //  reason of the module they are fixed in
+    pub fn get_fixing_reason(&self, signal: SignalIndex) -> Option<&FixingReason> {
+        let ModuleConditionallySafe(safety_conditions) = &self.kind else {
+            return None;
+        };
+
+        let mut subcomponent_input_reason = None;
+        for subcomponent in &safety_conditions.subcomponents {
+            match subcomponent.get_fixing_reason(signal) {
+                Some(FixingReason::Input) => subcomponent_input_reason = Some(&FixingReason::Input),
+                Some(reason) => return Some(reason),
+                None => {}
+            }
+        }
+
+        safety_conditions
+            .fixing_reasons
+            .get(&signal)
+            .or(subcomponent_input_reason)
+    }
+
     pub fn apply<F>(&self, f: &mut F)
     where
         F: FnMut(&SubComponentVerificationResult),
@@ -113,79 +286,1092 @@ impl SubComponentVerificationResult {
     }
 }
 
+fn check_witness_provided(context: &InputDataContextView) -> VerifierResult<()> {
+    if context.witness.is_none() {
+        return Err(VerifierError::MalformedArtifact(format!(
+            "Weak safety verification requires a witness, but '{}' does not exist",
+            ArtifactPaths::new(Path::new(context.base_path), context.options)
+                .witness
+                .display()
+        )));
+    }
+    Ok(())
+}
+
+// Traverses the circuit and returns the polynomial systems that must only have the witness as
+//  solution for the circuit to be safe, without running CoCoA, so that the caller can decide how
+//  to solve them (for example, with solve_polynomial_systems). Only the first witness is used.
+//  Returns a TraversalFailed error if some component is unsafe or raises an exception.
+pub fn collect_polynomial_systems(
+    context: &InputDataContextView,
+    constraint_storage: &mut ConstraintStorage,
+) -> VerifierResult<Vec<PolynomialSystemFixedSignal>> {
+    check_witness_provided(context)?;
+
+    let res = VerificationGraph::new(context, constraint_storage)
+        .verify_subcomponents(context, constraint_storage);
+
+    flatten_verification_result_and_report_errors(&res, context).ok_or_else(|| {
+        let mut errors = vec![];
+        res.apply(&mut |res| {
+            let is_warning =
+                context.options.exceptions_as_warnings && matches!(res.kind, Exception(_));
+            if let Some(s) = res.get_error_string().filter(|_| !is_warning) {
+                errors.push(s);
+            }
+        });
+        VerifierError::TraversalFailed(errors.join("\n"))
+    })
+}
+
+// Checks with CoCoA whether each polynomial system has the witness as its only solution, returning
+//  the verdict of each of them in order. This is the solving step of verify, for polynomial systems
+//  obtained with collect_polynomial_systems.
+pub fn solve_polynomial_systems(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+) -> VerifierResult<Vec<PolSystemResult>> {
+    check_witness_provided(context)?;
+    verify_pol_systems(pol_systems, context)
+}
+
+// Verdict of a circuit together with the number of components that failed verification, as
+//  summarized in a line per circuit with the summary_only option
+#[derive(Clone, Debug, Default)]
+pub struct VerificationOutcome {
+    pub safe: bool,
+
+    // Components that are unsafe, or have a polynomial system with many solutions
+    pub num_unsafe_components: usize,
+
+    // Components that raised an exception, or have a polynomial system CoCoA has not decided
+    pub num_exception_components: usize,
+}
+
+impl VerificationOutcome {
+    // pol_system_results contains the result of each polynomial system in pol_systems, and is
+    //  empty if CoCoA has not been run
+    pub fn new(
+        verification_result: &SubComponentVerificationResult,
+        pol_systems: &[PolynomialSystemFixedSignal],
+        pol_system_results: &[PolSystemResult],
+        safe: bool,
+    ) -> VerificationOutcome {
+        let mut outcome = VerificationOutcome {
+            safe,
+            ..Default::default()
+        };
+
+        verification_result.apply(&mut |res| {
+            let verdicts = || {
+                pol_systems
+                    .iter()
+                    .zip(pol_system_results)
+                    .filter(|(pol_system, _)| pol_system.component_name == res.subcomponent_name)
+                    .map(|(_, result)| result.verdict)
+            };
+
+            match &res.kind {
+                ModuleUnsafe(_) => outcome.num_unsafe_components += 1,
+                Exception(_) => outcome.num_exception_components += 1,
+                ModuleConditionallySafe(_) => {
+                    if verdicts().any(|v| v == PolSystemVerdict::ManySolutions) {
+                        outcome.num_unsafe_components += 1;
+                    } else if verdicts().any(|v| v != PolSystemVerdict::Fixed) {
+                        outcome.num_exception_components += 1;
+                    }
+                }
+                ModuleTrusted(_) | ModuleEquivalentToReference(_) | ModuleCachedSafe => {}
+            }
+        });
+
+        outcome
+    }
+}
+
+// Verifies the whole circuit: traverses it, solves the polynomial systems it needs with CoCoA and
+//  prints and reports the results as requested in the options. Returns true if it is safe
 pub fn verify(
     context: &InputDataContextView,
     constraint_storage: &mut ConstraintStorage,
-) -> Result<bool, Box<dyn Error>> {
+) -> VerifierResult<bool> {
+    Ok(verify_with_outcome(context, constraint_storage)?.safe)
+}
+
+// Same as verify, but also returns the number of components that failed verification
+pub fn verify_with_outcome(
+    context: &InputDataContextView,
+    constraint_storage: &mut ConstraintStorage,
+) -> VerifierResult<VerificationOutcome> {
+    check_witness_provided(context)?;
+
+    if context.is_template_trusted() {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            format!(
+                "The template '{}' of the main component is trusted, but the main component is always verified",
+                context.tree_constraints.template_name
+            )
+            .yellow()
+        );
+    }
+
+    // CoCoA is killed when the total timeout is exceeded, until the watchdog is dropped on return
+    let _watchdog = TotalTimeoutWatchdog::start(
+        context
+            .options
+            .total_timeout_seconds
+            .map(Duration::from_secs),
+    );
+
+    // Verdicts of a previous run are only kept if it is being resumed
+    if !context.options.resume {
+        remove_progress_file(Path::new(context.output_path))?;
+    }
+
+    // The monolithic system is built before the modular verification substitutes witness values
+    //  into the constraint storage
+    let monolithic_pol_system = context
+        .options
+        .monolithic
+        .then(|| build_monolithic_pol_system(context, constraint_storage));
+
+    // The verification substitutes the values of the witness into the constraint storage, so each
+    //  of the other witnesses is verified on a copy of the original constraints
+    let original_constraint_storage =
+        (!context.extra_witnesses.is_empty()).then(|| copy_constraint_storage(constraint_storage));
+
     let mut verification_graph = VerificationGraph::new(context, constraint_storage);
-    let res = verification_graph.verify_subcomponents(context, constraint_storage);
 
-    let maybe_pol_systems = flatten_verification_result_and_report_errors(&res);
-    if let Some(pol_systems) = maybe_pol_systems {
+    if let Some(graphml_path) = &context.options.graphml_path {
+        write_verification_graph_graphml(&verification_graph, context, graphml_path)?;
+        println_unless_quiet!(
+            context.options,
+            "Verification graph written to {}",
+            graphml_path.display()
+        );
+    }
+
+    if let Some(mermaid_path) = &context.options.mermaid_path {
+        write_verification_graph_mermaid(&verification_graph, context, mermaid_path)?;
+        println_unless_quiet!(
+            context.options,
+            "Verification graph written to {}",
+            mermaid_path.display()
+        );
+    }
+
+    // With several jobs, polynomial systems are verified while the circuit is being traversed. This
+    //  is not possible with several witnesses, as the systems of every witness are combined first
+    let verify_in_parallel = context.options.jobs > 1 && original_constraint_storage.is_none();
+    let (res, parallel_pol_system_results) = if verify_in_parallel {
+        let (res, results) = verify_pol_systems_in_parallel(context, |dispatch| {
+            verification_graph.verify_subcomponents_dispatching(
+                context,
+                constraint_storage,
+                dispatch,
+            )
+        })?;
+        (res, Some(results))
+    } else {
+        (
+            verification_graph.verify_subcomponents(context, constraint_storage),
+            None,
+        )
+    };
+
+    // The main component is verified first, and its subcomponents are only traversed if its
+    //  outputs can be fixed, so no polynomial system has been checked yet
+    if context.options.require_outputs_fixed {
+        if let ModuleUnsafe(
+            UnfixedOutputsAfterPropagation(unfixed_outputs) | UnconstrainedOutputs(unfixed_outputs),
+        ) = &res.kind
+        {
+            println_unless_quiet!(
+                context.options,
+                "{}",
+                format!(
+                    "[Unfixed outputs] No constraint can fix the outputs {} of the main component '{}'. Aborting verification...",
+                    unfixed_outputs.join(", "),
+                    res.subcomponent_name
+                )
+                .red()
+            );
+            return Ok(VerificationOutcome::new(&res, &[], &[], false));
+        }
+    }
+
+    if context.options.coverage && !context.options.quiet {
+        print_output_coverage(&res);
+    }
+
+    if context.options.report_dead_inputs && !context.options.quiet {
+        print_dead_inputs(&res);
+    }
+
+    let mut maybe_pol_systems = flatten_verification_result_and_report_errors(&res, context);
+
+    // The polynomial systems of every witness are combined, so that they are checked at all of them
+    //  in a single CoCoA run. From now on, the context has a copy of each signal for every witness
+    let mut combined_witness = None;
+    if let (Some(systems), Some(storage)) = (&maybe_pol_systems, &original_constraint_storage) {
+        maybe_pol_systems = match get_extra_witness_pol_systems(context, storage) {
+            Some(extra_pol_systems) => {
+                let signal_offset = context.witness.unwrap().keys().max().unwrap() + 1;
+                let pol_systems_per_witness: Vec<_> = iter::once(systems.clone())
+                    .chain(extra_pol_systems)
+                    .collect();
+                combined_witness = Some(get_combined_witness(context, signal_offset));
+                Some(combine_witness_pol_systems(
+                    &pol_systems_per_witness,
+                    signal_offset,
+                ))
+            }
+            None => None,
+        };
+    }
+
+    let combined_context;
+    let context = match &combined_witness {
+        Some((witness, signal_name_map)) => {
+            combined_context = InputDataContextView {
+                witness: Some(witness),
+                signal_name_map,
+                field: context.field.clone(),
+                ..*context
+            };
+            &combined_context
+        }
+        None => context,
+    };
+
+    if combined_witness.is_some() {
+        if let Some(systems) = &maybe_pol_systems {
+            warn_about_large_combined_pol_systems(systems, context);
+        }
+    }
+
+    let mut pol_systems = vec![];
+    let mut pol_system_results = vec![];
+
+    let safe = if let Some(systems) = maybe_pol_systems {
+        pol_systems = systems;
+
+        if let Some(export_ideals_path) = &context.options.export_ideals_path {
+            export_ideals(&pol_systems, context, export_ideals_path)?;
+            println_unless_quiet!(
+                context.options,
+                "Ideals of {} polynomial systems written to {}",
+                pol_systems.len(),
+                export_ideals_path.display()
+            );
+        }
+
+        if let Some(dump_systems_path) = &context.options.dump_systems_path {
+            dump_pol_systems(&pol_systems, context, dump_systems_path)?;
+            println_unless_quiet!(
+                context.options,
+                "{} polynomial systems written to {}",
+                pol_systems.len(),
+                dump_systems_path.display()
+            );
+        }
+        if let Some(dump_cas_json_path) = &context.options.dump_cas_json_path {
+            dump_cas_json(&pol_systems, context, dump_cas_json_path)?;
+            println_unless_quiet!(
+                context.options,
+                "CoCoA input of {} polynomial systems written to {}",
+                pol_systems.len(),
+                dump_cas_json_path.display()
+            );
+        }
+
         if pol_systems.is_empty() {
             // We don't have any polynomial systems to fix using Groebner Basis, finished.
-            println!(
+            println_unless_quiet!(
+                context.options,
                 "{}",
                 "No polynomial systems to fix. Finished. Module is safe!".green()
             );
-            return Ok(true);
+            true
         } else {
-            println!(
-                "{}",
-                "No exceptions or errors reported when traversing tree. Fixing polynomial systems...\n".green()
-            );
+            pol_system_results = match parallel_pol_system_results {
+                Some(results) => {
+                    assert_eq!(results.len(), pol_systems.len());
+                    results
+                }
+                None => {
+                    println_unless_quiet!(
+                        context.options,
+                        "{}",
+                        "No exceptions or errors reported when traversing tree. Fixing polynomial systems...\n".green()
+                    );
+                    solve_polynomial_systems(&pol_systems, context)?
+                }
+            };
+            let all_fixed = pol_system_results
+                .iter()
+                .all(|r| r.verdict == PolSystemVerdict::Fixed);
 
-            let res = verify_pol_systems(&pol_systems, context)?;
+            if !context.options.quiet {
+                PolSystemSummary::new(&get_pol_system_reports(
+                    &pol_systems,
+                    &pol_system_results,
+                    context,
+                ))
+                .print();
+            }
 
-            if res {
-                println!(
+            if combined_witness.is_some() {
+                print_ambiguous_witness_pol_systems(&pol_systems, &pol_system_results, context);
+            }
+
+            if is_total_timeout_exceeded() {
+                println_unless_quiet!(
+                    context.options,
+                    "{}",
+                    format!(
+                        "\n[Timeout] The total timeout of {} seconds has been exceeded. {} polynomial systems have not been checked, so the verdict is partial",
+                        context.options.total_timeout_seconds.unwrap_or_default(),
+                        pol_system_results
+                            .iter()
+                            .filter(|r| r.verdict == PolSystemVerdict::NotChecked)
+                            .count()
+                    )
+                    .red()
+                );
+            }
+
+            if all_fixed {
+                println_unless_quiet!(
+                    context.options,
                     "{}",
                     "\nMODULE SAFE: all polynomials systems have been fixed".green()
                 );
             } else {
-                println!(
+                println_unless_quiet!(
+                    context.options,
                     "{}",
                     "\nCouldn't fix a polynomial system. Aborting verification...".red()
                 );
             }
 
-            return Ok(res);
+            // Further CoCoA runs would be killed right away after the total timeout
+            if context.options.minimize_failure && !is_total_timeout_exceeded() {
+                for (idx, result) in pol_system_results.iter().enumerate() {
+                    if result.verdict == PolSystemVerdict::ManySolutions {
+                        report_failure_minimization(&pol_systems, idx, context)?;
+                    }
+                }
+            }
+
+            if let Some(num_samples) = context
+                .options
+                .sample_inputs
+                .filter(|_| !is_total_timeout_exceeded())
+            {
+                println_unless_quiet!(
+                    context.options,
+                    "\nSampling assignments of the signals to fix..."
+                );
+                report_sampled_ambiguities(&pol_systems, context, num_samples)?;
+            }
+
+            all_fixed
+        }
+    } else {
+        false
+    };
+
+    if let Some(monolithic_pol_system) = monolithic_pol_system
+        .as_ref()
+        .filter(|_| !is_total_timeout_exceeded())
+    {
+        cross_check_with_monolithic_pol_system(monolithic_pol_system, safe, context)?;
+    }
+
+    if context.options.skip_verified {
+        let mut verified_components = context.verified_components.clone();
+        let num_recorded =
+            verified_components.record_safe_components(&res, &pol_systems, &pol_system_results);
+        verified_components.write_to_folder(Path::new(context.output_path))?;
+        if num_recorded > 0 {
+            println_unless_quiet!(
+                context.options,
+                "{} components recorded as verified in {}",
+                num_recorded,
+                Path::new(context.output_path)
+                    .join(VERIFIED_COMPONENTS_FILE_NAME)
+                    .display()
+            );
+        }
+    }
+
+    let options = context.options;
+    let mut regressed = false;
+    if options.report_path.is_some()
+        || options.baseline_report_path.is_some()
+        || options.only_failures
+    {
+        let report = VerificationReport {
+            timed_out: is_total_timeout_exceeded(),
+            ..VerificationReport::new(&res, &pol_systems, &pol_system_results, safe, context)
+        };
+        let failures_report = report.only_failures();
+
+        if options.only_failures {
+            println_unless_quiet!(
+                context.options,
+                "{}",
+                format!(
+                    "{} components passed verification and are not shown",
+                    failures_report.num_omitted_components.unwrap_or_default()
+                )
+                .green()
+            );
+        }
+
+        if let Some(report_path) = &options.report_path {
+            if options.only_failures {
+                failures_report.write_to_file(report_path)?;
+            } else {
+                report.write_to_file(report_path)?;
+            }
+            println_unless_quiet!(
+                context.options,
+                "Verification report written to {}",
+                report_path.display()
+            );
+        }
+
+        if let Some(baseline_path) = &options.baseline_report_path {
+            let baseline = VerificationReport::read_from_file(baseline_path)?;
+            let diff = ReportDiff::new(&baseline, &report);
+            if !options.quiet {
+                diff.print();
+            }
+
+            if diff.has_regressions() {
+                println_unless_quiet!(
+                    context.options,
+                    "{}",
+                    "Regressions found with respect to the baseline report".red()
+                );
+                regressed = options.fail_on_regression;
+            }
+        }
+    }
+
+    if let Some(sarif_path) = &options.sarif_path {
+        SarifLog::new(&res, &pol_systems, &pol_system_results, context)
+            .write_to_file(sarif_path)?;
+        println_unless_quiet!(
+            context.options,
+            "SARIF log written to {}",
+            sarif_path.display()
+        );
+    }
+
+    if let Some(tree_svg_path) = &options.tree_svg_path {
+        let report =
+            VerificationReport::new(&res, &pol_systems, &pol_system_results, safe, context);
+        write_component_tree_svg(context.tree_constraints, &report.components, tree_svg_path)?;
+        println_unless_quiet!(
+            context.options,
+            "Component tree written to {}",
+            tree_svg_path.display()
+        );
+    }
+
+    // Regressions only make the run fail, and not the verdicts in the reports
+    Ok(VerificationOutcome::new(
+        &res,
+        &pol_systems,
+        &pol_system_results,
+        safe && !regressed,
+    ))
+}
+
+// ConstraintStorage is not Clone, so it is copied by adding each of its constraints to a new
+//  storage, which keeps their indices
+fn copy_constraint_storage(constraint_storage: &ConstraintStorage) -> ConstraintStorage {
+    let mut copy = ConstraintStorage::new();
+    for id in constraint_storage.get_ids() {
+        copy.add_constraint(constraint_storage.read_constraint(id).unwrap());
+    }
+    copy
+}
+
+// Traverses the circuit with each of the extra witnesses, on a copy of the original constraints,
+//  and returns the polynomial systems found with each of them. Returns None if some component is
+//  unsafe or raises an exception at any of the witnesses
+fn get_extra_witness_pol_systems(
+    context: &InputDataContextView,
+    original_constraint_storage: &ConstraintStorage,
+) -> Option<Vec<Vec<PolynomialSystemFixedSignal>>> {
+    // The graphs of the other witnesses would overwrite the ones drawn for the first witness
+    let svg_printer = DebugSVGPrinter::disabled();
+    let mut safe = true;
+
+    let pol_systems_per_witness = context
+        .extra_witnesses
+        .iter()
+        .zip(&context.options.extra_witness_paths)
+        .map(|(witness, witness_path)| {
+            let witness_context = InputDataContextView {
+                witness: Some(witness),
+                svg_printer: &svg_printer,
+                field: context.field.clone(),
+                ..*context
+            };
+            let mut constraint_storage = copy_constraint_storage(original_constraint_storage);
+            let res = VerificationGraph::new(&witness_context, &constraint_storage)
+                .verify_subcomponents(&witness_context, &mut constraint_storage);
+
+            let mut pol_systems = vec![];
+            res.apply(&mut |res| {
+                if let Some(s) = res.get_error_string() {
+                    let message = format!("[Witness {}] {}", witness_path.display(), s);
+                    if context.options.exceptions_as_warnings && matches!(res.kind, Exception(_)) {
+                        println_unless_quiet!(context.options, "{}", message.yellow());
+                    } else {
+                        println_unless_quiet!(context.options, "{}", message.red());
+                        safe = false;
+                    }
+                }
+
+                if let ModuleConditionallySafe(safety_conditions) = &res.kind {
+                    pol_systems.extend(safety_conditions.pol_systems.iter().cloned());
+                }
+            });
+            pol_systems
+        })
+        .collect();
+
+    if safe {
+        Some(pol_systems_per_witness)
+    } else {
+        println_unless_quiet!(
+            context.options,
+            "{}",
+            "The circuit is not safe at some of the other witnesses. Aborting safety verification..."
+                .red()
+        );
+        None
+    }
+}
+
+// Returns the witness and signal names of the combined polynomial systems, with a copy of every
+//  signal for each witness (see get_witness_copy_signal). The copies of the signals for the k-th
+//  extra witness are named "w<k>.<name>"
+fn get_combined_witness(
+    context: &InputDataContextView,
+    signal_offset: usize,
+) -> (Witness, SignalNameMap) {
+    let mut witness = context.witness.unwrap().clone();
+    let mut signal_name_map = context.signal_name_map.clone();
+
+    for (idx, extra_witness) in context.extra_witnesses.iter().enumerate() {
+        let witness_idx = idx + 1;
+        for (signal, value) in extra_witness {
+            let copy_signal = get_witness_copy_signal(*signal, witness_idx, signal_offset);
+            if copy_signal == *signal {
+                continue;
+            }
+
+            witness.insert(copy_signal, value.clone());
+            signal_name_map.insert(
+                copy_signal,
+                format!("w{}.{}", witness_idx, context.signal_name(*signal)),
+            );
+        }
+    }
+
+    (witness, signal_name_map)
+}
+
+// Warns about the combined polynomial systems whose prohibition polynomial has more variables than
+//  the limit, as they will be reported as timed-out without being checked
+fn warn_about_large_combined_pol_systems(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    context: &InputDataContextView,
+) {
+    let num_witnesses = context.extra_witnesses.len() + 1;
+    println_unless_quiet!(
+        context.options,
+        "Checking {} polynomial systems at {} witnesses at once",
+        pol_systems.len(),
+        num_witnesses
+    );
+
+    let var_limit = context
+        .options
+        .max_vars_prohibition_polynomial_before_timeout;
+    for (idx, pol_system) in pol_systems.iter().enumerate() {
+        let num_vars = get_pol_system_stats(pol_system, context).num_prohibition_vars;
+        if num_vars > var_limit {
+            println_unless_quiet!(
+                context.options,
+                "{}",
+                format!(
+                    "[Warning] Polynomial system {} of component '{}' has {} prohibition variables at {} witnesses, above the limit of {}. It will not be checked: use fewer witnesses or raise --maxvars",
+                    idx, pol_system.component_name, num_vars, num_witnesses, var_limit
+                )
+                .yellow()
+            );
+        }
+    }
+}
+
+// Reports the components whose combined polynomial systems have several solutions, which means
+//  that their outputs are not fixed at some of the witnesses
+fn print_ambiguous_witness_pol_systems(
+    pol_systems: &[PolynomialSystemFixedSignal],
+    pol_system_results: &[PolSystemResult],
+    context: &InputDataContextView,
+) {
+    for (idx, (pol_system, result)) in pol_systems.iter().zip(pol_system_results).enumerate() {
+        if result.verdict == PolSystemVerdict::ManySolutions {
+            println_unless_quiet!(
+                context.options,
+                "{}",
+                format!(
+                    "Polynomial system {} of component '{}' is ambiguous at some of the {} witnesses",
+                    idx,
+                    pol_system.component_name,
+                    context.extra_witnesses.len() + 1
+                )
+                .red()
+            );
         }
     }
+}
+
+// Checks that the outputs of the given component are a deterministic function of its inputs, for
+//  any input and not only the one in the witness. Returns true if they are.
+pub fn verify_functional_uniqueness(
+    context: &InputDataContextView,
+    constraint_storage: &ConstraintStorage,
+    component_name: &str,
+) -> VerifierResult<bool> {
+    let component = context.component_by_name(component_name)?;
+    let system = build_functional_uniqueness_system(component, constraint_storage);
+
+    println_unless_quiet!(
+        context.options,
+        "Checking functional uniqueness of component '{}' ({}) with {} constraints",
+        component.component_name,
+        component.template_name,
+        system.constraints.len() / 2
+    );
+
+    let verdict = check_functional_uniqueness(&system, context)?;
+    let message = match verdict {
+        PolSystemVerdict::Fixed => format!(
+            "Component '{}' is functionally unique: its outputs are determined by its inputs",
+            component.component_name
+        )
+        .green(),
+        PolSystemVerdict::ManySolutions => format!(
+            "Outputs of component '{}' are possibly not determined by its inputs",
+            component.component_name
+        )
+        .red(),
+        PolSystemVerdict::Timeout => format!(
+            "Functional uniqueness check of component '{}' has timed-out",
+            component.component_name
+        )
+        .red(),
+        PolSystemVerdict::OutOfMemory => format!(
+            "Functional uniqueness check of component '{}' has run out of memory",
+            component.component_name
+        )
+        .red(),
+        PolSystemVerdict::TooLarge
+        | PolSystemVerdict::TooHighDegree
+        | PolSystemVerdict::NotChecked => {
+            unreachable!("CoCoA never reports this verdict for functional uniqueness")
+        }
+    };
+    println_unless_quiet!(context.options, "{}", message);
+
+    Ok(verdict == PolSystemVerdict::Fixed)
+}
+
+// Returns the indices of the signals with the given names, which must be signals of the main
+//  component or its subcomponents other than the inputs of the main component
+fn resolve_signals_to_fix(
+    context: &InputDataContextView,
+    signal_names: &[String],
+) -> VerifierResult<BTreeSet<SignalIndex>> {
+    let tree_constraints = context.tree_constraints;
+    let first_input = tree_constraints.initial_signal + tree_constraints.number_outputs;
+    let input_signals = first_input..(first_input + tree_constraints.number_inputs);
+    let component_signals = tree_constraints.initial_signal
+        ..(tree_constraints.initial_signal + tree_constraints.number_signals);
+
+    signal_names
+        .iter()
+        .map(|name| {
+            let signal = context.signal_index_by_name(name)?;
+            if input_signals.contains(&signal) {
+                return Err(VerifierError::InvalidArgument(format!(
+                    "Signal '{}' is an input of '{}', whose value is given by the witness",
+                    name, tree_constraints.component_name
+                )));
+            }
+            if !component_signals.contains(&signal) {
+                return Err(VerifierError::InvalidArgument(format!(
+                    "Signal '{}' does not belong to component '{}'",
+                    name, tree_constraints.component_name
+                )));
+            }
+            Ok(signal)
+        })
+        .collect()
+}
+
+// Checks that each of the given signals is fixed by the inputs of the main component, instead of
+//  its outputs. The polynomial system contains every constraint of the circuit, as in the
+//  monolithic verification, and each signal is checked on its own. Returns true if all of them
+//  are fixed
+pub fn verify_fixed_signals(
+    context: &InputDataContextView,
+    constraint_storage: &ConstraintStorage,
+    signal_names: &[String],
+) -> VerifierResult<bool> {
+    check_witness_provided(context)?;
+    let signals_to_fix = resolve_signals_to_fix(context, signal_names)?;
+    let pol_system = build_whole_circuit_pol_system(context, constraint_storage, signals_to_fix);
+
+    println_unless_quiet!(
+        context.options,
+        "Checking that {} signals of '{}' are fixed by its inputs, with {} constraints",
+        pol_system.signals_to_fix.len(),
+        context.tree_constraints.component_name,
+        pol_system.constraints.len()
+    );
+
+    let mut all_fixed = true;
+    for (signal, verdict) in check_signals_individually(&pol_system, context, "groebner-signals")? {
+        let signal_name = context.signal_name(signal);
+        let message = match verdict {
+            PolSystemVerdict::Fixed => format!("Signal '{}' is fixed", signal_name).green(),
+            PolSystemVerdict::ManySolutions => {
+                format!("Signal '{}' is possibly not fixed", signal_name).red()
+            }
+            verdict => format!(
+                "Signal '{}' could not be checked ({:?})",
+                signal_name, verdict
+            )
+            .yellow(),
+        };
+        println_unless_quiet!(context.options, "{}", message);
+        all_fixed &= verdict == PolSystemVerdict::Fixed;
+    }
+
+    Ok(all_fixed)
+}
+
+// Verifies the monolithic polynomial system of the whole circuit, and warns if its verdict
+//  disagrees with the one of the modular verification
+fn cross_check_with_monolithic_pol_system(
+    monolithic_pol_system: &PolynomialSystemFixedSignal,
+    modular_safe: bool,
+    context: &InputDataContextView,
+) -> VerifierResult<()> {
+    println_unless_quiet!(
+        context.options,
+        "\nCross-checking with the monolithic polynomial system of the whole circuit ({} constraints)...",
+        monolithic_pol_system.constraints.len()
+    );
+
+    let message = match verify_monolithic_pol_system(monolithic_pol_system, context)? {
+        PolSystemVerdict::Fixed if modular_safe => {
+            "The monolithic verification agrees: the outputs are fixed".green()
+        }
+        PolSystemVerdict::ManySolutions if !modular_safe => {
+            "The monolithic verification agrees: the outputs are not fixed".green()
+        }
+        PolSystemVerdict::Fixed => {
+            "Warning: the monolithic verification fixes the outputs, but the modular verification could not prove it".yellow()
+        }
+        PolSystemVerdict::ManySolutions => {
+            "Warning: the monolithic verification finds several values for the outputs, but the modular verification reported the circuit as safe".red()
+        }
+        verdict => format!(
+            "Warning: the monolithic verification is inconclusive ({:?})",
+            verdict
+        )
+        .yellow(),
+    };
+    println_unless_quiet!(context.options, "{}", message);
+
+    Ok(())
+}
+
+// Prints the constraints involved in fixing each output of every component whose coverage has
+//  been computed
+fn print_output_coverage(res: &SubComponentVerificationResult) {
+    res.apply(&mut |res| {
+        let ModuleConditionallySafe(safety_conditions) = &res.kind else {
+            return;
+        };
+        if safety_conditions.output_coverage.is_empty() {
+            return;
+        }
+
+        println!("Constraint coverage of '{}':", res.subcomponent_name);
+        for coverage in &safety_conditions.output_coverage {
+            println!(
+                "  {}: {} constraints",
+                coverage.output_name,
+                coverage.constraints.len()
+            );
+            for (constraint_idx, constraint) in &coverage.constraints {
+                println!("    #{}: {}", constraint_idx, constraint);
+            }
+            for subcomponent_name in &coverage.subcomponents {
+                println!("    outputs of subcomponent '{}'", subcomponent_name);
+            }
+        }
+    });
+    println!();
+}
+
+// Prints the inputs of every component that none of its outputs depends on
+fn print_dead_inputs(res: &SubComponentVerificationResult) {
+    let mut num_components_with_dead_inputs = 0;
+    res.apply(&mut |res| {
+        let ModuleConditionallySafe(safety_conditions) = &res.kind else {
+            return;
+        };
+        if safety_conditions.dead_inputs.is_empty() {
+            return;
+        }
 
-    Ok(false)
+        num_components_with_dead_inputs += 1;
+        println!(
+            "{}",
+            format!(
+                "[Dead inputs] No output of component '{}' depends on inputs {}. A constraint using them may be missing",
+                res.subcomponent_name,
+                safety_conditions
+                    .dead_inputs
+                    .iter()
+                    .map(|s| format!("'{}'", s))
+                    .join(", ")
+            )
+            .yellow()
+        );
+    });
+
+    if num_components_with_dead_inputs == 0 {
+        println!("{}", "No dead inputs found".green());
+    }
+    println!();
 }
 
-// Returns true if any error or exception was found. False otherwise
+// Returns the polynomial systems to fix if no error or exception was found, or None otherwise. If
+//  only_failures is set, trusted components are only reported as a count. If
+//  exceptions_as_warnings is set, exceptions are reported as warnings and do not count as errors.
 fn flatten_verification_result_and_report_errors(
     verification_result: &SubComponentVerificationResult,
+    context: &InputDataContextView,
 ) -> Option<Vec<PolynomialSystemFixedSignal>> {
+    let options = context.options;
     let mut num_unsafe_found = 0;
     let mut num_exceptions_found = 0;
+    let mut num_trusted_found = 0;
+    let mut num_linear_verified = 0;
+    let mut num_equivalent_found = 0;
+    let mut num_cached_safe = 0;
+    let mut num_skipped_by_filter = 0;
 
     let mut polynomial_systems_to_prove = vec![];
 
     verification_result.apply(&mut |res| {
         if let Some(s) = res.get_error_string() {
-            println!("{}", s.red());
+            if options.exceptions_as_warnings && matches!(res.kind, Exception(_)) {
+                println_unless_quiet!(options, "{}", format!("[Warning] {}", s).yellow());
+            } else {
+                println_unless_quiet!(options, "{}", s.red());
+            }
+
+            if options.print_witness_for_failures && matches!(res.kind, ModuleUnsafe(_)) {
+                if let Some(table) = context.get_component_witness_table(&res.subcomponent_name) {
+                    println_unless_quiet!(options, "{}", table);
+                }
+            }
         }
 
         match &res.kind {
             ModuleUnsafe(_) => {
                 num_unsafe_found += 1;
             }
+            ModuleConditionallySafe(safety_conditions) if safety_conditions.skipped_by_filter => {
+                num_skipped_by_filter += 1;
+                if !options.only_failures {
+                    println_unless_quiet!(
+                        options,
+                        "{}",
+                        format!(
+                            "[Skipped] Component '{}' does not match the filter and has not been verified. Its outputs are assumed to be fixed",
+                            res.subcomponent_name
+                        )
+                        .yellow()
+                    );
+                }
+            }
             ModuleConditionallySafe(safety_conditions) => {
+                if safety_conditions.verified_by_linear_algebra {
+                    num_linear_verified += 1;
+                    if !options.only_failures {
+                        println_unless_quiet!(
+                            options,
+                            "{}",
+                            format!(
+                                "[Linear] Component '{}' has been verified by linear algebra",
+                                res.subcomponent_name
+                            )
+                            .green()
+                        );
+                    }
+                }
+
                 // Add polynomial systems to a vector to further verify
                 polynomial_systems_to_prove.append(&mut safety_conditions.pol_systems.clone())
             }
+            ModuleTrusted(_) if options.only_failures => {
+                num_trusted_found += 1;
+            }
+            ModuleTrusted(template_name) => {
+                num_trusted_found += 1;
+                println_unless_quiet!(
+                    options,
+                    "{}",
+                    format!(
+                        "[Trusted] Component '{}' of trusted template '{}' has not been verified",
+                        res.subcomponent_name, template_name
+                    )
+                    .cyan()
+                );
+            }
+            ModuleEquivalentToReference(reference_path) => {
+                num_equivalent_found += 1;
+                if !options.only_failures {
+                    println_unless_quiet!(
+                        options,
+                        "{}",
+                        format!(
+                            "[Equivalent] Component '{}' is identical to the reference in '{}', so it is safe and has not been verified",
+                            res.subcomponent_name, reference_path
+                        )
+                        .green()
+                    );
+                }
+            }
+            ModuleCachedSafe => {
+                num_cached_safe += 1;
+                if !options.only_failures {
+                    println_unless_quiet!(
+                        options,
+                        "{}",
+                        format!(
+                            "[Cached safe] Component '{}' was found safe by a previous run and its constraints have not changed, so it has not been verified again",
+                            res.subcomponent_name
+                        )
+                        .green()
+                    );
+                }
+            }
             Exception(_) => {
                 num_exceptions_found += 1;
             }
         }
     });
 
+    if num_skipped_by_filter > 0 {
+        println_unless_quiet!(
+            options,
+            "{}",
+            format!(
+                "{} components do not match the filter and have not been verified. The circuit is only safe if they are",
+                num_skipped_by_filter
+            )
+            .yellow()
+        );
+    }
+
+    // Components that do not match any reference have been verified as usual
+    if !options.reference_paths.is_empty() {
+        let mut num_components = 0;
+        verification_result.apply(&mut |_| num_components += 1);
+        println_unless_quiet!(
+            options,
+            "{}",
+            format!(
+                "{} components matched a reference, {} components did not",
+                num_equivalent_found,
+                num_components - num_equivalent_found - num_trusted_found
+            )
+            .green()
+        );
+    }
+
+    if num_cached_safe > 0 {
+        println_unless_quiet!(
+            options,
+            "{}",
+            format!(
+                "{} components are cached safe from previous runs",
+                num_cached_safe
+            )
+            .green()
+        );
+    }
+
+    if num_trusted_found > 0 {
+        println_unless_quiet!(
+            options,
+            "{}",
+            format!(
+                "{} components have been trusted without verification",
+                num_trusted_found
+            )
+            .cyan()
+        );
+    }
+
+    if num_linear_verified > 0 {
+        println_unless_quiet!(
+            options,
+            "{}",
+            format!(
+                "{} components have been verified by linear algebra, without Groebner Basis",
+                num_linear_verified
+            )
+            .green()
+        );
+    }
+
+    if options.exceptions_as_warnings && num_exceptions_found > 0 {
+        println_unless_quiet!(
+            options,
+            "{}",
+            format!(
+                "{} exceptions found on verification graph traversal. Those components are not verified, but do not make the circuit unsafe",
+                num_exceptions_found
+            )
+            .yellow()
+        );
+        num_exceptions_found = 0;
+    }
+
     if num_unsafe_found + num_exceptions_found > 0 {
-        println!(
+        println_unless_quiet!(
+            options,
             "{}",
             format!(
                 "{} unsafe modules found, {} exceptions found on verification graph traversal. Aborting safety verification...",
@@ -202,6 +1388,9 @@ fn flatten_verification_result_and_report_errors(
 
 #[test]
 fn test_verification_result_error_printing() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
     let a = SubComponentVerificationResult {
         kind: ModuleConditionallySafe(SafetyConditions {
             subcomponents: vec![
@@ -223,14 +1412,540 @@ fn test_verification_result_error_printing() {
                             subcomponent_name: "main.third.one".to_string(),
                         }],
                         pol_systems: vec![],
+                        verified_by_linear_algebra: false,
+                        skipped_by_filter: false,
+                        output_coverage: vec![],
+                        fixing_reasons: BTreeMap::new(),
+                        dead_inputs: vec![],
+                        component_hash: None,
+                        unsafe_subcomponents: vec![],
+                        trusted_subcomponents: vec![],
                     }),
                     subcomponent_name: "main.third".to_string(),
                 },
             ],
             pol_systems: vec![],
+            verified_by_linear_algebra: false,
+            skipped_by_filter: false,
+            output_coverage: vec![],
+            fixing_reasons: BTreeMap::new(),
+            dead_inputs: vec![],
+            component_hash: None,
+            unsafe_subcomponents: vec![],
+            trusted_subcomponents: vec![],
+        }),
+        subcomponent_name: "main".to_string(),
+    };
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, _) = InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    flatten_verification_result_and_report_errors(&a, &context.get_context_view());
+}
+
+#[test]
+fn test_exceptions_as_warnings() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let result = SubComponentVerificationResult {
+        kind: ModuleConditionallySafe(SafetyConditions {
+            subcomponents: vec![SubComponentVerificationResult {
+                kind: Exception(NoUnsafeConstraintConnectedComponentWithoutCycles),
+                subcomponent_name: "main.first".to_string(),
+            }],
+            pol_systems: vec![],
+            verified_by_linear_algebra: false,
+            skipped_by_filter: false,
+            output_coverage: vec![],
+            fixing_reasons: BTreeMap::new(),
+            dead_inputs: vec![],
+            component_hash: None,
+            unsafe_subcomponents: vec![],
+            trusted_subcomponents: vec![],
         }),
         subcomponent_name: "main".to_string(),
     };
 
-    flatten_verification_result_and_report_errors(&a);
+    let flatten = |options: Options| {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+        let (context, _) = InputDataContext::parse_from_files(&path, options).unwrap();
+        flatten_verification_result_and_report_errors(&result, &context.get_context_view())
+    };
+
+    assert!(flatten(Options::default()).is_none());
+    assert!(flatten(Options {
+        exceptions_as_warnings: true,
+        ..Default::default()
+    })
+    .is_some());
+}
+
+#[test]
+fn test_verify_in_memory_circuit() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+    use num_bigint_dig::BigInt;
+    use std::collections::HashMap;
+
+    let field = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+    let minus_one = BigInt::parse_bytes(field.as_bytes(), 10).unwrap() - 1;
+
+    // template Main() { signal input in; signal output out; out <== in; }
+    let mut constraint_storage = ConstraintStorage::new();
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::from(1)), (2, minus_one)]),
+    ));
+
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        no_constraints: 1,
+        template_name: "Main()".to_string(),
+        component_name: "main".to_string(),
+        number_inputs: 1,
+        number_outputs: 1,
+        number_signals: 2,
+        initial_signal: 1,
+        are_double_arrow: vec![(0, 1)],
+        ..Default::default()
+    };
+    let witness = HashMap::from([
+        (0, BigInt::from(1)),
+        (1, BigInt::from(5)),
+        (2, BigInt::from(5)),
+    ]);
+    let signal_name_map = HashMap::from([(1, "out".to_string()), (2, "in".to_string())]);
+
+    let (context, mut constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        Some(witness),
+        signal_name_map,
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+
+    assert!(verify(&context.get_context_view(), &mut constraint_storage).unwrap());
+}
+
+#[test]
+fn test_verify_degenerate_circuits() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+    use crate::polynomial_system_fixer::{
+        build_functional_uniqueness_system, build_monolithic_pol_system,
+        check_functional_uniqueness, verify_monolithic_pol_system,
+    };
+    use num_bigint_dig::BigInt;
+    use std::collections::HashMap;
+
+    // Circuits without constraints: an empty one, and another one that only has inputs
+    for number_inputs in [0, 2] {
+        let (context, mut constraint_storage) = InputDataContext::from_data(
+            ConstraintStorage::new(),
+            Some((0..=number_inputs).map(|s| (s, BigInt::from(1))).collect()),
+            (1..=number_inputs)
+                .map(|s| (s, format!("in{}", s)))
+                .collect(),
+            TreeConstraints {
+                field: "11".to_string(),
+                template_name: "Main()".to_string(),
+                component_name: "main".to_string(),
+                number_inputs,
+                number_signals: number_inputs,
+                initial_signal: 1,
+                ..Default::default()
+            },
+            Options {
+                monolithic: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let context = context.get_context_view();
+
+        // Without outputs there is nothing to fix, so CoCoA is not needed
+        let monolithic_pol_system = build_monolithic_pol_system(&context, &constraint_storage);
+        assert_eq!(
+            verify_monolithic_pol_system(&monolithic_pol_system, &context).unwrap(),
+            PolSystemVerdict::Fixed
+        );
+        let system =
+            build_functional_uniqueness_system(context.tree_constraints, &constraint_storage);
+        assert_eq!(
+            check_functional_uniqueness(&system, &context).unwrap(),
+            PolSystemVerdict::Fixed
+        );
+
+        assert!(
+            collect_polynomial_systems(&context, &mut constraint_storage)
+                .unwrap()
+                .is_empty()
+        );
+        let outcome = verify_with_outcome(&context, &mut constraint_storage).unwrap();
+        assert!(outcome.safe);
+        assert_eq!(outcome.num_unsafe_components, 0);
+        assert_eq!(outcome.num_exception_components, 0);
+    }
+
+    // A single assignment fixes the output without any polynomial system: out <== in;
+    let mut constraint_storage = ConstraintStorage::new();
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::from(1)), (2, BigInt::from(10))]),
+    ));
+    let (context, mut constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        Some(HashMap::from([
+            (0, BigInt::from(1)),
+            (1, BigInt::from(3)),
+            (2, BigInt::from(3)),
+        ])),
+        HashMap::from([(1, "out".to_string()), (2, "in".to_string())]),
+        TreeConstraints {
+            field: "11".to_string(),
+            no_constraints: 1,
+            template_name: "Main()".to_string(),
+            component_name: "main".to_string(),
+            number_inputs: 1,
+            number_outputs: 1,
+            number_signals: 2,
+            initial_signal: 1,
+            are_double_arrow: vec![(0, 1)],
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    assert!(
+        collect_polynomial_systems(&context, &mut constraint_storage)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(verify(&context, &mut constraint_storage).unwrap());
+}
+
+#[test]
+fn test_collect_polynomial_systems() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+    use num_bigint_dig::BigInt;
+    use std::collections::HashMap;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, mut constraint_storage) =
+        InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    let context = context.get_context_view();
+
+    let pol_systems = collect_polynomial_systems(&context, &mut constraint_storage).unwrap();
+    assert_eq!(pol_systems.len(), 1);
+    assert_eq!(pol_systems[0].component_name, "main");
+    assert_eq!(pol_systems[0].template_name, "IsZeroOfRoot()");
+    assert_eq!(pol_systems[0].constraint_indices, vec![2, 1, 0]);
+
+    // template Main() { signal input in; signal output out; } does not constrain its output
+    let (context, mut constraint_storage) = InputDataContext::from_data(
+        ConstraintStorage::new(),
+        Some(HashMap::from([
+            (0, BigInt::from(1)),
+            (1, BigInt::from(5)),
+            (2, BigInt::from(5)),
+        ])),
+        HashMap::from([(1, "out".to_string()), (2, "in".to_string())]),
+        TreeConstraints {
+            field: "11".to_string(),
+            template_name: "Main()".to_string(),
+            component_name: "main".to_string(),
+            number_inputs: 1,
+            number_outputs: 1,
+            number_signals: 2,
+            initial_signal: 1,
+            ..Default::default()
+        },
+        Options::default(),
+    )
+    .unwrap();
+    assert!(matches!(
+        collect_polynomial_systems(&context.get_context_view(), &mut constraint_storage),
+        Err(VerifierError::TraversalFailed(_))
+    ));
+}
+
+#[test]
+fn test_resolve_signals_to_fix() {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test/fixtures/is_zero_1");
+    let (context, constraint_storage) =
+        InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    let context = context.get_context_view();
+
+    let names = |names: &[&str]| -> Vec<String> { names.iter().map(|n| n.to_string()).collect() };
+    assert_eq!(
+        resolve_signals_to_fix(&context, &names(&["inv", "main.out"])).unwrap(),
+        BTreeSet::from([1, 4])
+    );
+    assert!(resolve_signals_to_fix(&context, &names(&["a"])).is_err());
+    assert!(resolve_signals_to_fix(&context, &names(&["b"])).is_err());
+
+    let pol_system = build_whole_circuit_pol_system(&context, &constraint_storage, [4].into());
+    assert_eq!(pol_system.signals_to_fix, BTreeSet::from([4]));
+    assert_eq!(pol_system.constraints.len(), 3);
+}
+
+#[test]
+fn test_goldilocks_field_pipeline() {
+    use crate::cli::Options;
+    use crate::input_data::{InputDataContext, TreeConstraints};
+    use crate::polynomial_system_fixer::{generate_cocoa_script, optimize_pol_system};
+    use num_bigint_dig::BigInt;
+    use std::collections::HashMap;
+
+    // Goldilocks prime, 2^64 - 2^32 + 1
+    let field = BigInt::from(18446744069414584321u64);
+    let minus = |v: u32| -> BigInt { &field - v };
+
+    // template Main() {
+    //     signal input in; signal output out; signal bit;
+    //     bit * (bit - 1) === 0; out === 2 * bit + in;
+    // }
+    let mut constraint_storage = ConstraintStorage::new();
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::from([(3, BigInt::from(1))]),
+        HashMap::from([(3, BigInt::from(1)), (0, minus(1))]),
+        HashMap::new(),
+    ));
+    constraint_storage.add_constraint(Constraint::new(
+        HashMap::new(),
+        HashMap::new(),
+        HashMap::from([(1, BigInt::from(1)), (2, minus(1)), (3, minus(2))]),
+    ));
+
+    let tree_constraints = TreeConstraints {
+        field: field.to_string(),
+        no_constraints: 2,
+        template_name: "Main()".to_string(),
+        component_name: "main".to_string(),
+        number_inputs: 1,
+        number_outputs: 1,
+        number_signals: 3,
+        initial_signal: 1,
+        ..Default::default()
+    };
+    let witness = HashMap::from([
+        (0, BigInt::from(1)),
+        (1, BigInt::from(9)),
+        (2, BigInt::from(7)),
+        (3, BigInt::from(1)),
+    ]);
+    let signal_name_map = HashMap::from([
+        (1, "out".to_string()),
+        (2, "in".to_string()),
+        (3, "bit".to_string()),
+    ]);
+
+    let (context, mut constraint_storage) = InputDataContext::from_data(
+        constraint_storage,
+        Some(witness),
+        signal_name_map,
+        tree_constraints,
+        Options::default(),
+    )
+    .unwrap();
+    let context = context.get_context_view();
+
+    let mut verification_graph = VerificationGraph::new(&context, &constraint_storage);
+    let result = verification_graph.verify_subcomponents(&context, &mut constraint_storage);
+    let pol_systems = flatten_verification_result_and_report_errors(&result, &context).unwrap();
+
+    let optimized_pol_systems: Vec<_> = pol_systems
+        .iter()
+        .map(|pol_system| optimize_pol_system(pol_system, &context))
+        .collect();
+    let script = generate_cocoa_script(&optimized_pol_systems, &context);
+
+    // Substituting bit = (out - in) / 2 leaves a single system on out, which can take the values 7
+    //  and 9. Its prohibition only allows 7, the value other than the witness
+    assert!(script.starts_with("p := 18446744069414584321;\nuse F ::= ZZ/(p);\n"));
+    assert!(script.contains("use R ::= F[x_1];"));
+    assert!(script.contains("(x_1 - 7));"));
+
+    // Coefficients are written as elements of the field, in balanced form
+    let half_field = &field / 2u32;
+    for number in script
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+    {
+        let number = BigInt::parse_bytes(number.as_bytes(), 10).unwrap();
+        assert!(number <= field);
+        assert!(number == field || number <= half_field);
+    }
+}
+
+// Decides whether the signals to fix of a polynomial system are fixed by enumerating every
+//  assignment of its signals, replacing CoCoA in tests. Only feasible for tiny fields and systems
+#[cfg(test)]
+fn is_pol_system_fixed_by_enumeration(
+    pol_system: &crate::polynomial_system_fixer::OptimizedPolynomialSystemFixedSignal,
+    context: &InputDataContextView,
+) -> bool {
+    use num_bigint_dig::BigInt;
+    use num_traits::{ToPrimitive, Zero};
+    use std::collections::HashMap;
+
+    let constant = Constraint::<usize>::constant_coefficient();
+    let signals: Vec<SignalIndex> = pol_system
+        .constraints
+        .iter()
+        .flat_map(|constraint| constraint.take_cloned_signals_ordered())
+        .chain(pol_system.signals_to_fix.keys().copied())
+        .filter(|signal| *signal != constant)
+        .sorted()
+        .dedup()
+        .collect();
+
+    let field_size = context.field.to_u64().unwrap();
+    let evaluate = |linear_term: &HashMap<SignalIndex, BigInt>,
+                    values: &HashMap<SignalIndex, BigInt>| {
+        linear_term
+            .iter()
+            .map(|(signal, coefficient)| coefficient * &values[signal])
+            .sum::<BigInt>()
+    };
+
+    signals
+        .iter()
+        .map(|_| 0..field_size)
+        .multi_cartesian_product()
+        .all(|assignment| {
+            let values: HashMap<SignalIndex, BigInt> = signals
+                .iter()
+                .copied()
+                .zip(assignment.into_iter().map(BigInt::from))
+                .chain([(constant, BigInt::from(1))])
+                .collect();
+
+            let is_solution = pol_system.constraints.iter().all(|constraint| {
+                let value = evaluate(constraint.a(), &values) * evaluate(constraint.b(), &values)
+                    + evaluate(constraint.c(), &values);
+                (value % &context.field).is_zero()
+            });
+            let is_witness = pol_system
+                .signals_to_fix
+                .keys()
+                .all(|signal| values[signal] == *context.witness_value(*signal));
+
+            !is_solution || is_witness
+        })
+}
+
+// Runs the whole verification pipeline on the circuit in the given folder of test/fixtures. Its
+//  polynomial systems are optimized as usual, but decided by enumeration instead of CoCoA, so the
+//  fixtures use a tiny field
+#[cfg(test)]
+fn verify_fixture(name: &str) -> crate::report::Verdict {
+    use crate::cli::Options;
+    use crate::input_data::InputDataContext;
+    use crate::polynomial_system_fixer::optimize_pol_system;
+    use crate::report::Verdict;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../test/fixtures")
+        .join(name);
+    let (context, mut constraint_storage) =
+        InputDataContext::parse_from_files(&path, Options::default()).unwrap();
+    let context = context.get_context_view();
+
+    let mut verification_graph = VerificationGraph::new(&context, &constraint_storage);
+    let result = verification_graph.verify_subcomponents(&context, &mut constraint_storage);
+
+    let Some(pol_systems) = flatten_verification_result_and_report_errors(&result, &context) else {
+        let mut verdict = Verdict::Exception;
+        result.apply(&mut |res| {
+            if matches!(res.kind, ModuleUnsafe(_)) {
+                verdict = Verdict::Unsafe;
+            }
+        });
+        return verdict;
+    };
+
+    let all_fixed = pol_systems.iter().all(|pol_system| {
+        is_pol_system_fixed_by_enumeration(&optimize_pol_system(pol_system, &context), &context)
+    });
+    if all_fixed {
+        Verdict::Safe
+    } else {
+        Verdict::ManySolutions
+    }
+}
+
+// Regression test of the core algorithm: every fixture must keep its known verdict
+#[test]
+fn test_fixture_verdicts() {
+    use crate::report::Verdict;
+
+    assert_eq!(verify_fixture("safe_num2bits"), Verdict::Safe);
+    assert_eq!(
+        verify_fixture("unsafe_underconstrained"),
+        Verdict::ManySolutions
+    );
+    assert_eq!(verify_fixture("cyclic_exception"), Verdict::Exception);
+    assert_eq!(verify_fixture("is_zero_1"), Verdict::Safe);
+    assert_eq!(verify_fixture("dead_input"), Verdict::Safe);
+}
+
+#[test]
+fn test_get_fixing_reason() {
+    let safety_conditions = |subcomponents, fixing_reasons| SafetyConditions {
+        subcomponents,
+        pol_systems: vec![],
+        verified_by_linear_algebra: false,
+        skipped_by_filter: false,
+        output_coverage: vec![],
+        fixing_reasons,
+        dead_inputs: vec![],
+        component_hash: None,
+        unsafe_subcomponents: vec![],
+        trusted_subcomponents: vec![],
+    };
+
+    // Signal 2 is the output of the subcomponent, fixed in it by a linear constraint, and signal 3
+    //  is its input, fixed in main by a safe assignment
+    let sub = SubComponentVerificationResult {
+        kind: ModuleConditionallySafe(safety_conditions(
+            vec![],
+            BTreeMap::from([
+                (2, FixingReason::LinearConstraint(7)),
+                (3, FixingReason::Input),
+            ]),
+        )),
+        subcomponent_name: "main.sub".to_string(),
+    };
+    let result = SubComponentVerificationResult {
+        kind: ModuleConditionallySafe(safety_conditions(
+            vec![sub],
+            BTreeMap::from([
+                (1, FixingReason::Input),
+                (2, FixingReason::SubComponentOutput(0)),
+                (3, FixingReason::SafeAssignment(4)),
+            ]),
+        )),
+        subcomponent_name: "main".to_string(),
+    };
+
+    assert_eq!(result.get_fixing_reason(1), Some(&FixingReason::Input));
+    assert_eq!(
+        result.get_fixing_reason(2),
+        Some(&FixingReason::LinearConstraint(7))
+    );
+    assert_eq!(
+        result.get_fixing_reason(3),
+        Some(&FixingReason::SafeAssignment(4))
+    );
+    assert_eq!(result.get_fixing_reason(5), None);
 }
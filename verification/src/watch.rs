@@ -0,0 +1,89 @@
+use crate::input_data::ArtifactPaths;
+use crate::polynomial_system_fixer::kill_running_cocoa;
+use colored::Colorize;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+// Compilers write artifacts in several steps, so changes closer than this in time are grouped
+//  into a single verification
+const WATCH_DEBOUNCE_MILLIS: u64 = 500;
+
+// Artifacts that trigger a new verification when they change
+fn get_watched_files(artifact_paths: &ArtifactPaths) -> [&Path; 2] {
+    [&artifact_paths.constraints, &artifact_paths.witness]
+}
+
+fn get_modification_times(artifact_paths: &ArtifactPaths) -> Vec<Option<SystemTime>> {
+    get_watched_files(artifact_paths)
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+// Calls run once, and again every time the constraints or the witness change. Errors returned by
+//  run are printed and do not stop watching. Only returns if their folders cannot be watched: the
+//  process exits when interrupted with Ctrl-C, killing CoCoA if it is running.
+pub fn watch_artifacts<F>(artifact_paths: &ArtifactPaths, mut run: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut() -> Result<(), Box<dyn Error>>,
+{
+    ctrlc::set_handler(|| {
+        kill_running_cocoa();
+        std::process::exit(130);
+    })?;
+
+    let (sender, receiver) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(Duration::from_millis(WATCH_DEBOUNCE_MILLIS), sender)?;
+
+    // The folders of the files are watched, as the files may not exist yet or be replaced
+    let watched_folders: BTreeSet<PathBuf> = get_watched_files(artifact_paths)
+        .iter()
+        .map(|path| match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        })
+        .collect();
+    for folder in &watched_folders {
+        debouncer
+            .watcher()
+            .watch(folder, RecursiveMode::NonRecursive)?;
+    }
+
+    loop {
+        // Clear the screen and move the cursor to the top left corner
+        print!("\x1B[2J\x1B[1;1H");
+        println!(
+            "{}\n",
+            format!(
+                "Watching {} and {} for changes. Press Ctrl-C to exit",
+                artifact_paths.constraints.display(),
+                artifact_paths.witness.display()
+            )
+            .blue()
+        );
+
+        let modification_times = get_modification_times(artifact_paths);
+        if let Err(e) = run() {
+            println!("{}", format!("Error: {}", e).red());
+        }
+
+        // Wait until one of the watched files changes. Events do not tell whether a file has been
+        //  written or just read (as the verifier itself does), so modification times are compared
+        loop {
+            receiver.recv()?.map_err(|e| e.to_string())?;
+            if get_modification_times(artifact_paths) != modification_times {
+                break;
+            }
+        }
+    }
+}